@@ -2,8 +2,15 @@ use {
     crate::MarginQuerier,
     anyhow::ensure,
     dango_auth::authenticate_tx,
-    dango_types::{account::InstantiateMsg, DangoQuerier},
-    grug::{AuthCtx, AuthResponse, MutableCtx, NumberConst, Response, StdResult, Tx, Udec128},
+    dango_types::{
+        account::{margin::ExecuteMsg, InstantiateMsg},
+        config::AppConfig,
+        lending, DangoQuerier,
+    },
+    grug::{
+        Addr, AuthCtx, AuthResponse, Coins, JsonDeExt, Message, MsgExecute, MutableCtx,
+        NumberConst, QuerierWrapper, Response, StdResult, Tx, Udec128,
+    },
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
@@ -19,14 +26,21 @@ pub fn instantiate(ctx: MutableCtx, _msg: InstantiateMsg) -> anyhow::Result<Resp
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn authenticate(ctx: AuthCtx, tx: Tx) -> anyhow::Result<AuthResponse> {
+    // Repaying debts can't make the account less healthy, so there's no need
+    // to check its health afterwards. This ensures debts can be repaid even if
+    // the health can't be computed, e.g. if a price is stale.
+    let request_backrun = !only_repays_debts(&ctx.querier, &tx)?;
+
     authenticate_tx(ctx, tx, None)?;
 
-    Ok(AuthResponse::new().request_backrun(true))
+    Ok(AuthResponse::new().request_backrun(request_backrun))
 }
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn backrun(ctx: AuthCtx, _tx: Tx) -> anyhow::Result<Response> {
-    let health = ctx.querier.query_health(ctx.contract)?;
+    let health = ctx
+        .querier
+        .query_health(ctx.contract, ctx.block.timestamp)?;
 
     // After executing all messages in the transactions, the account must have
     // a utilization rate no greater than one. Otherwise, we throw an error to
@@ -42,6 +56,46 @@ pub fn backrun(ctx: AuthCtx, _tx: Tx) -> anyhow::Result<Response> {
     Ok(Response::new())
 }
 
+/// Returns whether all messages in the transaction are repaying debts to the
+/// lending contract.
+fn only_repays_debts(querier: &QuerierWrapper, tx: &Tx) -> anyhow::Result<bool> {
+    let app_cfg: AppConfig = querier.query_app_config()?;
+
+    Ok(tx.msgs.iter().all(|msg| match msg {
+        Message::Execute(MsgExecute { contract, msg, .. }) => {
+            *contract == app_cfg.addresses.lending
+                && matches!(
+                    msg.clone().deserialize_json(),
+                    Ok(lending::ExecuteMsg::Repay {})
+                )
+        },
+        _ => false,
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::SeizeCollateral { liquidator, coins } => {
+            seize_collateral(ctx, liquidator, coins)
+        },
+    }
+}
+
+fn seize_collateral(ctx: MutableCtx, liquidator: Addr, coins: Coins) -> anyhow::Result<Response> {
+    let app_cfg: AppConfig = ctx.querier.query_app_config()?;
+
+    // Only the lending contract can seize collateral. It's responsible for
+    // ensuring the account is undercollateralized, and that the liquidator
+    // has repaid the corresponding debt.
+    ensure!(
+        ctx.sender == app_cfg.addresses.lending,
+        "only the lending contract can seize collateral"
+    );
+
+    Ok(Response::new().add_message(Message::transfer(liquidator, coins)?))
+}
+
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn receive(_ctx: MutableCtx) -> StdResult<Response> {
     // Do nothing, accept all transfers.
@@ -1,38 +1,48 @@
 use {
-    dango_lending::DEBTS,
     dango_oracle::OracleQuerier,
-    dango_types::{account::margin::HealthResponse, config::AppConfig},
-    grug::{Addr, BorshDeExt, Coins, Inner, IsZero, Number, NumberConst, QuerierWrapper, Udec128},
+    dango_types::{account::margin::HealthResponse, config::AppConfig, lending::QueryDebtRequest},
+    grug::{
+        Addr, Coin, Coins, Inner, IsZero, Number, NumberConst, QuerierWrapper, Timestamp, Udec128,
+    },
 };
 
 /// Margin account query methods.
 pub trait MarginQuerier {
-    fn query_health(&self, account: Addr) -> anyhow::Result<HealthResponse>;
+    /// Queries the health of a margin account, using oracle prices as of the
+    /// given time.
+    fn query_health(
+        &self,
+        account: Addr,
+        current_time: Timestamp,
+    ) -> anyhow::Result<HealthResponse>;
 }
 
 impl MarginQuerier for QuerierWrapper<'_> {
-    fn query_health(&self, account: Addr) -> anyhow::Result<HealthResponse> {
+    fn query_health(
+        &self,
+        account: Addr,
+        current_time: Timestamp,
+    ) -> anyhow::Result<HealthResponse> {
         let app_cfg: AppConfig = self.query_app_config()?;
 
-        // Query all debts for the account.
-        let debts = self
-            .query_wasm_raw(app_cfg.addresses.lending, DEBTS.path(account))?
-            .map(|coins| coins.deserialize_borsh::<Coins>())
-            .transpose()?
-            .unwrap_or_default();
+        // Query all debts for the account, including accrued interest.
+        let debts =
+            self.query_wasm_smart(app_cfg.addresses.lending, QueryDebtRequest { account })?;
 
         // Calculate the total value of the debts.
         let mut total_debt_value = Udec128::ZERO;
         for debt in debts {
-            let price = self.query_price(app_cfg.addresses.oracle, &debt.denom)?;
+            let price = self.query_price(app_cfg.addresses.oracle, &debt.denom, current_time)?;
             let value = price.value_of_unit_amount(debt.amount)?;
 
             total_debt_value.checked_add_assign(value)?;
         }
 
-        // Calculate the total value of the account's collateral adjusted for the
-        // collateral power.
+        // Calculate the total value of the account's collateral, both as is and
+        // adjusted for the collateral power.
+        let mut total_collateral_value = Udec128::ZERO;
         let mut total_adjusted_collateral_value = Udec128::ZERO;
+        let mut collaterals = Coins::new();
         for (denom, power) in app_cfg.collateral_powers {
             let collateral_balance = self.query_balance(account, denom.clone())?;
 
@@ -42,11 +52,13 @@ impl MarginQuerier for QuerierWrapper<'_> {
                 continue;
             }
 
-            let price = self.query_price(app_cfg.addresses.oracle, &denom)?;
+            let price = self.query_price(app_cfg.addresses.oracle, &denom, current_time)?;
             let value = price.value_of_unit_amount(collateral_balance)?;
             let adjusted_value = value.checked_mul(power.into_inner())?;
 
+            total_collateral_value.checked_add_assign(value)?;
             total_adjusted_collateral_value.checked_add_assign(adjusted_value)?;
+            collaterals.insert(Coin::new(denom, collateral_balance)?)?;
         }
 
         // Calculate the utilization rate.
@@ -66,6 +78,8 @@ impl MarginQuerier for QuerierWrapper<'_> {
             utilization_rate,
             total_debt_value,
             total_adjusted_collateral_value,
+            total_collateral_value,
+            collaterals,
         })
     }
 }
@@ -1,25 +1,28 @@
 use {
     crate::MarginQuerier,
-    dango_auth::NEXT_NONCE,
+    dango_auth::load_seen_nonces,
     dango_types::account::margin::QueryMsg,
     grug::{ImmutableCtx, Json, JsonSerExt, StdResult, Storage},
+    std::collections::BTreeSet,
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
     match msg {
-        QueryMsg::Nonce {} => {
-            let res = query_nonce(ctx.storage)?;
+        QueryMsg::SeenNonces {} => {
+            let res = query_seen_nonces(ctx.storage)?;
             res.to_json_value()
         },
         QueryMsg::Health {} => {
-            let res = ctx.querier.query_health(ctx.contract)?;
+            let res = ctx
+                .querier
+                .query_health(ctx.contract, ctx.block.timestamp)?;
             res.to_json_value()
         },
     }
     .map_err(Into::into)
 }
 
-fn query_nonce(storage: &dyn Storage) -> StdResult<u32> {
-    NEXT_NONCE.current(storage)
+fn query_seen_nonces(storage: &dyn Storage) -> StdResult<BTreeSet<u32>> {
+    load_seen_nonces(storage)
 }
@@ -312,6 +312,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -354,6 +355,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -458,6 +460,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -1,12 +1,12 @@
 use {
     crate::{PROPOSALS, VOTES},
-    dango_auth::NEXT_NONCE,
+    dango_auth::load_seen_nonces,
     dango_types::{
         account::multi::{Proposal, ProposalId, QueryMsg, Status, Vote},
         account_factory::Username,
     },
     grug::{Bound, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage},
-    std::collections::BTreeMap,
+    std::collections::{BTreeMap, BTreeSet},
 };
 
 const DEFAULT_PAGE_LIMIT: u32 = 30;
@@ -14,8 +14,8 @@ const DEFAULT_PAGE_LIMIT: u32 = 30;
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
-        QueryMsg::Nonce {} => {
-            let res = query_nonce(ctx.storage)?;
+        QueryMsg::SeenNonces {} => {
+            let res = query_seen_nonces(ctx.storage)?;
             res.to_json_value()
         },
         QueryMsg::Proposal { proposal_id } => {
@@ -40,8 +40,8 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     }
 }
 
-fn query_nonce(storage: &dyn Storage) -> StdResult<u32> {
-    NEXT_NONCE.current(storage)
+fn query_seen_nonces(storage: &dyn Storage) -> StdResult<BTreeSet<u32>> {
+    load_seen_nonces(storage)
 }
 
 fn query_proposal(ctx: ImmutableCtx, proposal_id: ProposalId) -> StdResult<Proposal> {
@@ -1,16 +1,17 @@
 use {
-    dango_auth::NEXT_NONCE,
+    dango_auth::load_seen_nonces,
     dango_types::account::spot::QueryMsg,
     grug::{ImmutableCtx, Json, JsonSerExt, StdResult, Storage},
+    std::collections::BTreeSet,
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
-        QueryMsg::Nonce {} => query_nonce(ctx.storage)?.to_json_value(),
+        QueryMsg::SeenNonces {} => query_seen_nonces(ctx.storage)?.to_json_value(),
     }
 }
 
-fn query_nonce(storage: &dyn Storage) -> StdResult<u32> {
-    NEXT_NONCE.current(storage)
+fn query_seen_nonces(storage: &dyn Storage) -> StdResult<BTreeSet<u32>> {
+    load_seen_nonces(storage)
 }
@@ -1,16 +1,19 @@
 use {
-    crate::{perform_swap, PoolExt, PoolInit, CONFIG, NEXT_POOL_ID, POOLS},
-    anyhow::{anyhow, ensure},
+    crate::{
+        perform_swap, record_observation, ConcentratedPoolExt, PoolExt, PoolInit, Ticks, CONFIG,
+        NEXT_POOL_ID, POOLS, POSITIONS,
+    },
+    anyhow::{anyhow, bail, ensure},
     dango_types::{
         amm::{
-            ConcentratedPool, ExecuteMsg, InstantiateMsg, Pool, PoolId, PoolParams, XykPool,
-            MINIMUM_LIQUIDITY, NAMESPACE, SUBNAMESPACE,
+            ConcentratedPool, ExecuteMsg, InstantiateMsg, Pool, PoolId, PoolParams, Position, Tick,
+            XykPool, MINIMUM_LIQUIDITY, NAMESPACE, SUBNAMESPACE,
         },
         bank, taxman,
     },
     grug::{
-        Coins, Denom, Inner, IsZero, Message, MutableCtx, Number, Part, Response, StdResult,
-        Uint128, UniqueVec,
+        Addr, CoinPair, Coins, Denom, Inner, IsZero, Message, MutableCtx, Number, Part, Response,
+        StdResult, Storage, Uint128, UniqueVec,
     },
 };
 
@@ -34,6 +37,23 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             minimum_output,
         } => provide_liquidity(ctx, pool_id, minimum_output),
         ExecuteMsg::WithdrawLiquidity { pool_id } => withdraw_liquidity(ctx, pool_id),
+        ExecuteMsg::IncreasePosition {
+            pool_id,
+            lower_tick,
+            upper_tick,
+            minimum_liquidity,
+        } => increase_position(ctx, pool_id, lower_tick, upper_tick, minimum_liquidity),
+        ExecuteMsg::DecreasePosition {
+            pool_id,
+            lower_tick,
+            upper_tick,
+            liquidity,
+        } => decrease_position(ctx, pool_id, lower_tick, upper_tick, liquidity),
+        ExecuteMsg::CollectFees {
+            pool_id,
+            lower_tick,
+            upper_tick,
+        } => collect_fees(ctx, pool_id, lower_tick, upper_tick),
     }
 }
 
@@ -55,56 +75,95 @@ fn create_pool(ctx: MutableCtx, params: PoolParams) -> anyhow::Result<Response>
 
     let (pool_id, _) = NEXT_POOL_ID.increment(ctx.storage)?;
 
-    let (shares_to_mint, pool) = match params {
+    let cfg = ctx.querier.query_config()?;
+    let denom = denom_of(pool_id)?;
+
+    let msgs = match params {
         PoolParams::Xyk(params) => {
             let xyk = XykPool::initialize(liquidity.try_into()?, params)?;
-            (xyk.shares, Pool::Xyk(xyk))
+            let shares_to_mint = withhold_minimum_liquidity(xyk.shares)?;
+
+            let pool = Pool::Xyk(xyk);
+
+            record_observation(ctx.storage, ctx.block.timestamp, pool_id, &pool)?;
+
+            POOLS.save(ctx.storage, pool_id, &pool)?;
+
+            // 1. Mint self the withheld liquidity tokens.
+            // 2. Mint the creator the remaining liquidity tokens.
+            vec![
+                Message::execute(
+                    cfg.bank,
+                    &bank::ExecuteMsg::Mint {
+                        to: ctx.contract,
+                        denom: denom.clone(),
+                        amount: MINIMUM_LIQUIDITY,
+                    },
+                    Coins::new(),
+                )?,
+                Message::execute(
+                    cfg.bank,
+                    &bank::ExecuteMsg::Mint {
+                        to: ctx.sender,
+                        denom,
+                        amount: shares_to_mint,
+                    },
+                    Coins::new(),
+                )?,
+            ]
         },
         PoolParams::Concentracted(params) => {
-            let concentrated = ConcentratedPool::initialize(liquidity.try_into()?, params)?;
-            (concentrated.shares, Pool::Concentrated(concentrated))
+            let deposit: CoinPair = liquidity.try_into()?;
+            let mut concentrated = ConcentratedPool::initialize(deposit.clone(), params)?;
+
+            // Provide the deposit as liquidity over the full price range.
+            let range = concentrated.params.full_range();
+            let mut position = concentrated.empty_position();
+            let mut ticks = Ticks::new(ctx.storage, pool_id);
+            let (liquidity, refunds) = concentrated.increase_position(
+                &mut ticks,
+                &mut position,
+                range.0,
+                range.1,
+                deposit,
+            )?;
+
+            ticks.into_changes().commit(ctx.storage)?;
+
+            let liquidity_to_own = withhold_minimum_liquidity(liquidity)?;
+
+            // Concentrated liquidity pools don't issue liquidity tokens. The
+            // initial liquidity is a full range position instead, which is
+            // split between self and the creator the same way.
+            for (owner, liquidity) in [
+                (ctx.contract, MINIMUM_LIQUIDITY),
+                (ctx.sender, liquidity_to_own),
+            ] {
+                let position = Position {
+                    liquidity,
+                    ..concentrated.empty_position()
+                };
+
+                POSITIONS.save(ctx.storage, (pool_id, &owner, range), &position)?;
+            }
+
+            let pool = Pool::Concentrated(concentrated);
+
+            record_observation(ctx.storage, ctx.block.timestamp, pool_id, &pool)?;
+
+            POOLS.save(ctx.storage, pool_id, &pool)?;
+
+            if refunds.is_empty() {
+                vec![]
+            } else {
+                vec![Message::transfer(ctx.sender, refunds)?]
+            }
         },
     };
 
-    // A minimum amount of liquidity tokens is to be withheld by the contract,
-    // in order to prevent share price manipulation attack:
-    // > https://docs.openzeppelin.com/contracts/4.x/erc4626#inflation-attack
-    // Error if the shares to mint is less than the minimum liquidity.
-    let shares_to_mint = shares_to_mint.checked_sub(MINIMUM_LIQUIDITY).map_err(|_| {
-        anyhow!(
-            "insufficient initial liquidity: {} < {}",
-            shares_to_mint,
-            MINIMUM_LIQUIDITY
-        )
-    })?;
-
-    POOLS.save(ctx.storage, pool_id, &pool)?;
-
-    let cfg = ctx.querier.query_config()?;
-    let denom = denom_of(pool_id)?;
-
-    // 1. Mint self the withheld liquidity tokens.
-    // 2. Mint the creator the remaining liquidity tokens.
-    // 3. Forward the pool creation fee to taxman.
+    // Forward the pool creation fee to taxman.
     Ok(Response::new()
-        .add_message(Message::execute(
-            cfg.bank,
-            &bank::ExecuteMsg::Mint {
-                to: ctx.contract,
-                denom: denom.clone(),
-                amount: MINIMUM_LIQUIDITY,
-            },
-            Coins::new(),
-        )?)
-        .add_message(Message::execute(
-            cfg.bank,
-            &bank::ExecuteMsg::Mint {
-                to: ctx.sender,
-                denom,
-                amount: shares_to_mint,
-            },
-            Coins::new(),
-        )?)
+        .add_messages(msgs)
         .add_message(Message::execute(
             cfg.taxman,
             &taxman::ExecuteMsg::Pay { payer: ctx.sender },
@@ -112,6 +171,23 @@ fn create_pool(ctx: MutableCtx, params: PoolParams) -> anyhow::Result<Response>
         )?))
 }
 
+/// Return the amount of liquidity to be given to a pool's creator, after
+/// withholding the minimum liquidity.
+///
+/// A minimum amount of liquidity is to be withheld by the contract, in order to
+/// prevent share price manipulation attack:
+/// > https://docs.openzeppelin.com/contracts/4.x/erc4626#inflation-attack
+/// Error if the initial liquidity is less than the minimum liquidity.
+fn withhold_minimum_liquidity(liquidity: Uint128) -> anyhow::Result<Uint128> {
+    liquidity.checked_sub(MINIMUM_LIQUIDITY).map_err(|_| {
+        anyhow!(
+            "insufficient initial liquidity: {} < {}",
+            liquidity,
+            MINIMUM_LIQUIDITY
+        )
+    })
+}
+
 fn swap(
     ctx: MutableCtx,
     route: UniqueVec<PoolId>,
@@ -122,11 +198,21 @@ fn swap(
     let mut pools = route
         .inner()
         .iter()
-        .map(|&pool_id| POOLS.load(ctx.storage, pool_id))
+        .map(|&pool_id| Ok((pool_id, POOLS.load(ctx.storage, pool_id)?)))
         .collect::<StdResult<Vec<_>>>()?;
 
+    // Record the pools' prices before they are moved by the swap.
+    for (pool_id, pool) in &pools {
+        record_observation(ctx.storage, ctx.block.timestamp, *pool_id, pool)?;
+    }
+
     // Perform the swap in each pool.
-    let outcome = perform_swap(&amm_cfg, input, pools.iter_mut())?;
+    let (outcome, tick_changes) = perform_swap(
+        ctx.storage,
+        &amm_cfg,
+        input,
+        pools.iter_mut().map(|(pool_id, pool)| (*pool_id, pool)),
+    )?;
 
     if let Some(minimum_output) = minimum_output {
         ensure!(
@@ -137,9 +223,13 @@ fn swap(
         );
     }
 
-    // Save the updated pool states.
-    for (pool_id, pool) in route.inner().iter().zip(pools) {
-        POOLS.save(ctx.storage, *pool_id, &pool)?;
+    // Save the updated pool states, including the ticks crossed.
+    for (pool_id, pool) in pools {
+        POOLS.save(ctx.storage, pool_id, &pool)?;
+    }
+
+    for changes in tick_changes {
+        changes.commit(ctx.storage)?;
     }
 
     // Transfer the post-fee output, if non-zero, to the trader.
@@ -178,6 +268,8 @@ fn provide_liquidity(
     // Sender must not send any other funds than what goes into the pool.
     ensure!(ctx.funds.is_empty(), "unexpected funds: {}", ctx.funds);
 
+    record_observation(ctx.storage, ctx.block.timestamp, pool_id, &pool)?;
+
     let shares_to_mint = match &mut pool {
         Pool::Xyk(xyk) => xyk.provide_liquidity(deposit)?,
         Pool::Concentrated(concentrated) => concentrated.provide_liquidity(deposit)?,
@@ -222,6 +314,8 @@ fn withdraw_liquidity(ctx: MutableCtx, pool_id: PoolId) -> anyhow::Result<Respon
     let mut pool = POOLS.load(ctx.storage, pool_id)?;
     let shares_to_burn = coin_to_burn.amount;
 
+    record_observation(ctx.storage, ctx.block.timestamp, pool_id, &pool)?;
+
     let refunds = match &mut pool {
         Pool::Xyk(xyk) => xyk.withdraw_liquidity(shares_to_burn)?,
         Pool::Concentrated(concentrated) => concentrated.withdraw_liquidity(shares_to_burn)?,
@@ -244,6 +338,146 @@ fn withdraw_liquidity(ctx: MutableCtx, pool_id: PoolId) -> anyhow::Result<Respon
         .add_message(Message::transfer(ctx.sender, refunds)?))
 }
 
+fn increase_position(
+    mut ctx: MutableCtx,
+    pool_id: PoolId,
+    lower_tick: Tick,
+    upper_tick: Tick,
+    minimum_liquidity: Option<Uint128>,
+) -> anyhow::Result<Response> {
+    let mut pool = POOLS.load(ctx.storage, pool_id)?;
+
+    let deposit = ctx.funds.take_pair(pool.denoms())?;
+
+    // Sender must not send any other funds than what goes into the pool.
+    ensure!(ctx.funds.is_empty(), "unexpected funds: {}", ctx.funds);
+
+    let concentrated = as_concentrated(&mut pool, pool_id)?;
+    let key = (pool_id, &ctx.sender, (lower_tick, upper_tick));
+    let mut position = POSITIONS
+        .may_load(ctx.storage, key)?
+        .unwrap_or_else(|| concentrated.empty_position());
+
+    let mut ticks = Ticks::new(ctx.storage, pool_id);
+    let (liquidity, refunds) = concentrated.increase_position(
+        &mut ticks,
+        &mut position,
+        lower_tick,
+        upper_tick,
+        deposit,
+    )?;
+    let tick_changes = ticks.into_changes();
+
+    if let Some(minimum_liquidity) = minimum_liquidity {
+        ensure!(
+            liquidity >= minimum_liquidity,
+            "insufficient liquidity provision output: {} < {}",
+            liquidity,
+            minimum_liquidity
+        );
+    }
+
+    POSITIONS.save(ctx.storage, key, &position)?;
+    POOLS.save(ctx.storage, pool_id, &pool)?;
+    tick_changes.commit(ctx.storage)?;
+
+    // Refund the part of the deposit that isn't needed, if any.
+    let refund_msg = if refunds.is_empty() {
+        None
+    } else {
+        Some(Message::transfer(ctx.sender, refunds)?)
+    };
+
+    Ok(Response::new().may_add_message(refund_msg))
+}
+
+fn decrease_position(
+    ctx: MutableCtx,
+    pool_id: PoolId,
+    lower_tick: Tick,
+    upper_tick: Tick,
+    liquidity: Uint128,
+) -> anyhow::Result<Response> {
+    let mut pool = POOLS.load(ctx.storage, pool_id)?;
+    let concentrated = as_concentrated(&mut pool, pool_id)?;
+    let key = (pool_id, &ctx.sender, (lower_tick, upper_tick));
+    let mut position = POSITIONS.load(ctx.storage, key)?;
+
+    let mut ticks = Ticks::new(ctx.storage, pool_id);
+    let refunds = concentrated.decrease_position(
+        &mut ticks,
+        &mut position,
+        lower_tick,
+        upper_tick,
+        liquidity,
+    )?;
+    let tick_changes = ticks.into_changes();
+
+    save_position(ctx.storage, key, &position)?;
+    POOLS.save(ctx.storage, pool_id, &pool)?;
+    tick_changes.commit(ctx.storage)?;
+
+    Ok(Response::new().add_message(Message::transfer(ctx.sender, refunds)?))
+}
+
+fn collect_fees(
+    ctx: MutableCtx,
+    pool_id: PoolId,
+    lower_tick: Tick,
+    upper_tick: Tick,
+) -> anyhow::Result<Response> {
+    let mut pool = POOLS.load(ctx.storage, pool_id)?;
+    let concentrated = as_concentrated(&mut pool, pool_id)?;
+    let key = (pool_id, &ctx.sender, (lower_tick, upper_tick));
+    let mut position = POSITIONS.load(ctx.storage, key)?;
+
+    let mut ticks = Ticks::new(ctx.storage, pool_id);
+    let fees: Coins = concentrated
+        .collect_fees(&mut ticks, &mut position, lower_tick, upper_tick)?
+        .into();
+    let tick_changes = ticks.into_changes();
+
+    save_position(ctx.storage, key, &position)?;
+    POOLS.save(ctx.storage, pool_id, &pool)?;
+    tick_changes.commit(ctx.storage)?;
+
+    // Transfer the fees, if non-zero, to the position owner.
+    let fee_msg = if fees.is_empty() {
+        None
+    } else {
+        Some(Message::transfer(ctx.sender, fees)?)
+    };
+
+    Ok(Response::new().may_add_message(fee_msg))
+}
+
+/// Returns the concentrated liquidity pool, or error if the pool is of a
+/// different type.
+#[inline]
+fn as_concentrated(pool: &mut Pool, pool_id: PoolId) -> anyhow::Result<&mut ConcentratedPool> {
+    match pool {
+        Pool::Concentrated(concentrated) => Ok(concentrated),
+        _ => bail!("pool {} is not a concentrated liquidity pool", pool_id),
+    }
+}
+
+/// Save the position, or delete it if it has neither liquidity nor fees owed.
+fn save_position(
+    storage: &mut dyn Storage,
+    key: (PoolId, &Addr, (Tick, Tick)),
+    position: &Position,
+) -> StdResult<()> {
+    if position.liquidity.is_zero()
+        && position.fees_owed.first().amount.is_zero()
+        && position.fees_owed.second().amount.is_zero()
+    {
+        POSITIONS.remove(storage, key);
+        Ok(())
+    } else {
+        POSITIONS.save(storage, key, position)
+    }
+}
+
 /// Returns the LP token denom of the given pool.
 #[inline]
 fn denom_of(pool_id: PoolId) -> StdResult<Denom> {
@@ -3,5 +3,7 @@ mod pools;
 mod query;
 mod state;
 mod swap;
+mod ticks;
+mod twap;
 
-pub use {execute::*, pools::*, query::*, state::*, swap::*};
+pub use {execute::*, pools::*, query::*, state::*, swap::*, ticks::*, twap::*};
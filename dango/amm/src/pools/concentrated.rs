@@ -1,27 +1,838 @@
 use {
-    super::{PoolExt, PoolInit},
-    dango_types::amm::{ConcentratedParams, ConcentratedPool},
-    grug::{Coin, CoinPair, Uint128},
+    crate::{ConcentratedPoolExt, PoolExt, PoolInit, Ticks},
+    anyhow::{bail, ensure},
+    dango_types::amm::{
+        ConcentratedParams, ConcentratedPool, FeeGrowth, Position, Tick, TickInfo, MAX_TICK,
+        MIN_TICK,
+    },
+    grug::{
+        Coin, CoinPair, Coins, Dec256, Decimal, Fraction, Inner, Int128, IsZero, MathResult,
+        NextNumber, Number, NumberConst, PrevNumber, Signed, Udec256, Uint128, Uint256, Unsigned,
+    },
 };
 
+/// Square root of 1.0001, i.e. the ratio between the square root prices of two
+/// adjacent ticks.
+const SQRT_TICK_BASE: Udec256 = Udec256::raw(Uint256::new_from_u128(1_000_049_998_750_062_496));
+
 impl PoolInit for ConcentratedPool {
     type Params = ConcentratedParams;
 
-    fn initialize(_liquidity: CoinPair, _params: ConcentratedParams) -> anyhow::Result<Self> {
-        todo!()
+    // The initial deposit determines the pool's starting price. The pool is
+    // returned without any liquidity; it's up to the caller to provide the
+    // deposit as liquidity over the full price range, and to assign it to
+    // positions.
+    fn initialize(liquidity: CoinPair, params: ConcentratedParams) -> anyhow::Result<Self> {
+        ensure!(
+            params.tick_spacing > 0 && params.tick_spacing <= MAX_TICK as u32,
+            "invalid tick spacing: {}",
+            params.tick_spacing
+        );
+
+        let (coin1, coin2) = liquidity.as_ref();
+
+        ensure!(
+            coin1.amount.is_non_zero() && coin2.amount.is_non_zero(),
+            "initial liquidity must be non-zero in both tokens: {} {}, {} {}",
+            coin1.amount,
+            coin1.denom,
+            coin2.amount,
+            coin2.denom
+        );
+
+        // Price is defined as the amount of the second token per unit of the
+        // first token.
+        let sqrt_price =
+            Udec256::checked_from_ratio(coin2.amount.into_next(), coin1.amount.into_next())?
+                .checked_sqrt()?;
+
+        let (lower_tick, upper_tick) = params.full_range();
+
+        ensure!(
+            sqrt_price > sqrt_price_at_tick(lower_tick)?
+                && sqrt_price < sqrt_price_at_tick(upper_tick)?,
+            "initial price is out of the supported range"
+        );
+
+        Ok(Self {
+            params,
+            liquidity: CoinPair::new_unchecked(
+                Coin {
+                    denom: coin1.denom.clone(),
+                    amount: Uint128::ZERO,
+                },
+                Coin {
+                    denom: coin2.denom.clone(),
+                    amount: Uint128::ZERO,
+                },
+            ),
+            sqrt_price,
+            tick: tick_at_sqrt_price(sqrt_price)?,
+            active_liquidity: Uint128::ZERO,
+            fee_growth_global: FeeGrowth::default(),
+        })
     }
 }
 
 impl PoolExt for ConcentratedPool {
     fn swap(&mut self, _input: Coin) -> anyhow::Result<(Coin, Coin)> {
-        todo!()
+        bail!("concentrated liquidity pools need access to their ticks to swap; use `swap_with_ticks` instead");
     }
 
     fn provide_liquidity(&mut self, _deposit: CoinPair) -> anyhow::Result<Uint128> {
-        todo!()
+        bail!("concentrated liquidity pools don't issue liquidity shares; use `increase_position` instead");
     }
 
     fn withdraw_liquidity(&mut self, _shares_to_burn: Uint128) -> anyhow::Result<CoinPair> {
-        todo!()
+        bail!("concentrated liquidity pools don't issue liquidity shares; use `decrease_position` instead");
+    }
+
+    fn spot_price(&self) -> anyhow::Result<Udec256> {
+        Ok(self.sqrt_price.checked_mul(self.sqrt_price)?)
+    }
+}
+
+impl ConcentratedPoolExt for ConcentratedPool {
+    fn swap_with_ticks(&mut self, ticks: &mut Ticks, input: Coin) -> anyhow::Result<(Coin, Coin)> {
+        // Selling the first token for the second pushes the price down;
+        // selling the second token for the first pushes the price up.
+        let zero_for_one = if input.denom == *self.liquidity.first().denom {
+            true
+        } else if input.denom == *self.liquidity.second().denom {
+            false
+        } else {
+            bail!(
+                "invalid input denom! must be {}|{}, got: {}",
+                self.liquidity.first().denom,
+                self.liquidity.second().denom,
+                input.denom
+            );
+        };
+
+        let fee_rate = self.params.fee_tier.inner().into_next();
+        let mut remaining = to_dec(input.amount)?;
+        let mut output = Udec256::ZERO;
+        let mut liquidity_fee = Udec256::ZERO;
+
+        // Swap within one price range at a time, until the input is used up.
+        // A price range is delimited by two adjacent initialized ticks, within
+        // which the active liquidity is constant.
+        while remaining.is_non_zero() {
+            // If there isn't any initialized tick in the direction of the swap,
+            // then there isn't any liquidity in that direction either.
+            let Some(next_tick) = ticks.next_initialized(self.tick, zero_for_one)? else {
+                bail!("insufficient liquidity to complete the swap");
+            };
+
+            let target_sqrt_price = sqrt_price_at_tick(next_tick)?;
+            let liquidity = to_dec(self.active_liquidity)?;
+
+            let (sqrt_price, step_input, step_output) = compute_swap_step(
+                self.sqrt_price,
+                target_sqrt_price,
+                liquidity,
+                remaining,
+                zero_for_one,
+            )?;
+
+            remaining.checked_sub_assign(step_input)?;
+
+            // Compute liquidity fee, and credit it to the liquidity that is
+            // active in this price range.
+            if step_output.is_non_zero() {
+                let step_fee = step_output.checked_mul(fee_rate)?;
+                let fee_growth = step_fee.checked_div(liquidity)?.checked_into_signed()?;
+
+                if zero_for_one {
+                    self.fee_growth_global
+                        .second
+                        .checked_add_assign(fee_growth)?;
+                } else {
+                    self.fee_growth_global
+                        .first
+                        .checked_add_assign(fee_growth)?;
+                }
+
+                output.checked_add_assign(step_output.checked_sub(step_fee)?)?;
+                liquidity_fee.checked_add_assign(step_fee)?;
+            }
+
+            self.sqrt_price = sqrt_price;
+
+            if sqrt_price == target_sqrt_price {
+                cross_tick(self, ticks, next_tick, zero_for_one)?;
+            } else {
+                self.tick = tick_at_sqrt_price(sqrt_price)?;
+            }
+        }
+
+        let output = floor_int(output)?;
+
+        // Update pool state.
+        let (offer, ask) = if zero_for_one {
+            self.liquidity.as_mut()
+        } else {
+            self.liquidity.as_mut_rev()
+        };
+
+        offer.amount.checked_add_assign(input.amount)?;
+        ask.amount.checked_sub_assign(output)?;
+
+        Ok((
+            Coin {
+                denom: ask.denom.clone(),
+                amount: output,
+            },
+            Coin {
+                denom: ask.denom.clone(),
+                amount: floor_int(liquidity_fee)?,
+            },
+        ))
+    }
+
+    fn increase_position(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        deposit: CoinPair,
+    ) -> anyhow::Result<(Uint128, Coins)> {
+        validate_range(&self.params, lower_tick, upper_tick)?;
+
+        let lower_sqrt_price = sqrt_price_at_tick(lower_tick)?;
+        let upper_sqrt_price = sqrt_price_at_tick(upper_tick)?;
+
+        // Find the biggest amount of liquidity the deposit can cover.
+        let liquidity = floor_int(liquidity_for_amounts(
+            self.sqrt_price,
+            lower_sqrt_price,
+            upper_sqrt_price,
+            to_dec(*deposit.first().amount)?,
+            to_dec(*deposit.second().amount)?,
+        )?)?;
+
+        ensure!(
+            liquidity.is_non_zero(),
+            "deposit is too small to provide any liquidity"
+        );
+
+        // Compute the amounts actually needed for this liquidity.
+        // Note: use ceil rounding, in favor of the pool.
+        let (amount1, amount2) = amounts_for_liquidity(
+            self.sqrt_price,
+            lower_sqrt_price,
+            upper_sqrt_price,
+            to_dec(liquidity)?,
+        )?;
+
+        let used = CoinPair::new_unchecked(
+            Coin {
+                denom: deposit.first().denom.clone(),
+                amount: ceil_int(amount1)?,
+            },
+            Coin {
+                denom: deposit.second().denom.clone(),
+                amount: ceil_int(amount2)?,
+            },
+        );
+
+        modify_position(
+            self,
+            ticks,
+            position,
+            lower_tick,
+            upper_tick,
+            liquidity.checked_into_signed()?,
+        )?;
+
+        self.liquidity.merge(used.clone())?;
+
+        let mut refunds = Coins::from(deposit);
+        refunds.deduct_many(used.into())?;
+
+        Ok((liquidity, refunds))
+    }
+
+    fn decrease_position(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        liquidity: Uint128,
+    ) -> anyhow::Result<CoinPair> {
+        ensure!(
+            liquidity.is_non_zero(),
+            "liquidity to withdraw must be non-zero"
+        );
+
+        ensure!(
+            liquidity <= position.liquidity,
+            "insufficient liquidity in position: {} < {}",
+            position.liquidity,
+            liquidity
+        );
+
+        // Compute the amounts to refund.
+        // Note: use floor rounding, in favor of the pool.
+        let (amount1, amount2) = amounts_for_liquidity(
+            self.sqrt_price,
+            sqrt_price_at_tick(lower_tick)?,
+            sqrt_price_at_tick(upper_tick)?,
+            to_dec(liquidity)?,
+        )?;
+
+        let refunds = CoinPair::new_unchecked(
+            Coin {
+                denom: self.liquidity.first().denom.clone(),
+                amount: floor_int(amount1)?,
+            },
+            Coin {
+                denom: self.liquidity.second().denom.clone(),
+                amount: floor_int(amount2)?,
+            },
+        );
+
+        modify_position(
+            self,
+            ticks,
+            position,
+            lower_tick,
+            upper_tick,
+            Int128::ZERO.checked_sub(liquidity.checked_into_signed()?)?,
+        )?;
+
+        deduct_reserves(self, &refunds)?;
+
+        Ok(refunds)
+    }
+
+    fn collect_fees(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    ) -> anyhow::Result<CoinPair> {
+        self.accrue_fees(ticks, position, lower_tick, upper_tick)?;
+
+        let fees = position.fees_owed.clone();
+
+        let (coin1, coin2) = position.fees_owed.as_mut();
+        *coin1.amount = Uint128::ZERO;
+        *coin2.amount = Uint128::ZERO;
+
+        deduct_reserves(self, &fees)?;
+
+        Ok(fees)
+    }
+
+    fn accrue_fees(
+        &self,
+        ticks: &Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    ) -> anyhow::Result<()> {
+        // A position without liquidity doesn't earn fees. Its ticks may also
+        // have been removed, so we can't compute the fee growth inside it.
+        if position.liquidity.is_zero() {
+            return Ok(());
+        }
+
+        let fee_growth_inside = fee_growth_inside(self, ticks, lower_tick, upper_tick)?;
+
+        accrue(position, fee_growth_inside)
+    }
+}
+
+// --------------------------------- positions ---------------------------------
+
+fn validate_range(
+    params: &ConcentratedParams,
+    lower_tick: Tick,
+    upper_tick: Tick,
+) -> anyhow::Result<()> {
+    ensure!(
+        lower_tick < upper_tick,
+        "lower tick must be less than upper tick: {} >= {}",
+        lower_tick,
+        upper_tick
+    );
+
+    ensure!(
+        lower_tick >= MIN_TICK && upper_tick <= MAX_TICK,
+        "ticks out of bounds: [{}, {}] is not within [{}, {}]",
+        lower_tick,
+        upper_tick,
+        MIN_TICK,
+        MAX_TICK
+    );
+
+    let spacing = params.tick_spacing as Tick;
+
+    ensure!(
+        lower_tick % spacing == 0 && upper_tick % spacing == 0,
+        "ticks must be multiples of the tick spacing {}: [{}, {}]",
+        spacing,
+        lower_tick,
+        upper_tick
+    );
+
+    Ok(())
+}
+
+/// Add or remove liquidity to a position, updating the ticks and the position's
+/// owed fees along the way.
+fn modify_position(
+    pool: &mut ConcentratedPool,
+    ticks: &mut Ticks,
+    position: &mut Position,
+    lower_tick: Tick,
+    upper_tick: Tick,
+    liquidity_delta: Int128,
+) -> anyhow::Result<()> {
+    update_tick(pool, ticks, lower_tick, liquidity_delta, false)?;
+    update_tick(pool, ticks, upper_tick, liquidity_delta, true)?;
+
+    // Settle the fees earned with the liquidity before the change.
+    accrue(
+        position,
+        fee_growth_inside(pool, ticks, lower_tick, upper_tick)?,
+    )?;
+
+    position.liquidity = apply_liquidity_delta(position.liquidity, liquidity_delta)?;
+
+    if lower_tick <= pool.tick && pool.tick < upper_tick {
+        pool.active_liquidity = apply_liquidity_delta(pool.active_liquidity, liquidity_delta)?;
+    }
+
+    // Remove ticks that are no longer the boundary of any position.
+    for tick in [lower_tick, upper_tick] {
+        if ticks.load(tick)?.liquidity_gross.is_zero() {
+            ticks.remove(tick);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the fees earned since the position was last updated to its owed fees.
+fn accrue(position: &mut Position, fee_growth_inside: FeeGrowth) -> anyhow::Result<()> {
+    if position.liquidity.is_non_zero() {
+        let liquidity = to_dec(position.liquidity)?;
+        let (coin1, coin2) = position.fees_owed.as_mut();
+
+        coin1.amount.checked_add_assign(fees_earned(
+            liquidity,
+            fee_growth_inside.first,
+            position.fee_growth_inside_last.first,
+        )?)?;
+        coin2.amount.checked_add_assign(fees_earned(
+            liquidity,
+            fee_growth_inside.second,
+            position.fee_growth_inside_last.second,
+        )?)?;
+    }
+
+    position.fee_growth_inside_last = fee_growth_inside;
+
+    Ok(())
+}
+
+fn fees_earned(
+    liquidity: Udec256,
+    fee_growth_inside: Dec256,
+    fee_growth_inside_last: Dec256,
+) -> anyhow::Result<Uint128> {
+    let fee_growth = fee_growth_inside
+        .checked_sub(fee_growth_inside_last)?
+        .checked_into_unsigned()?;
+
+    Ok(floor_int(liquidity.checked_mul(fee_growth)?)?)
+}
+
+fn deduct_reserves(pool: &mut ConcentratedPool, amounts: &CoinPair) -> anyhow::Result<()> {
+    let (coin1, coin2) = pool.liquidity.as_mut();
+
+    coin1.amount.checked_sub_assign(*amounts.first().amount)?;
+    coin2.amount.checked_sub_assign(*amounts.second().amount)?;
+
+    Ok(())
+}
+
+// ----------------------------------- ticks -----------------------------------
+
+fn update_tick(
+    pool: &ConcentratedPool,
+    ticks: &mut Ticks,
+    tick: Tick,
+    liquidity_delta: Int128,
+    upper: bool,
+) -> anyhow::Result<()> {
+    let mut info = ticks.may_load(tick)?.unwrap_or_else(|| TickInfo {
+        liquidity_gross: Uint128::ZERO,
+        liquidity_net: Int128::ZERO,
+        // By convention, assume all fees earned before the tick is initialized
+        // were earned below the tick.
+        fee_growth_outside: if tick <= pool.tick {
+            pool.fee_growth_global
+        } else {
+            FeeGrowth::default()
+        },
+    });
+
+    info.liquidity_gross = apply_liquidity_delta(info.liquidity_gross, liquidity_delta)?;
+
+    if upper {
+        info.liquidity_net.checked_sub_assign(liquidity_delta)?;
+    } else {
+        info.liquidity_net.checked_add_assign(liquidity_delta)?;
+    }
+
+    ticks.save(tick, info);
+
+    Ok(())
+}
+
+fn cross_tick(
+    pool: &mut ConcentratedPool,
+    ticks: &mut Ticks,
+    tick: Tick,
+    zero_for_one: bool,
+) -> anyhow::Result<()> {
+    let mut info = ticks.load(tick)?;
+
+    info.fee_growth_outside = fee_growth_sub(pool.fee_growth_global, info.fee_growth_outside)?;
+
+    let liquidity_net = if zero_for_one {
+        Int128::ZERO.checked_sub(info.liquidity_net)?
+    } else {
+        info.liquidity_net
+    };
+
+    pool.active_liquidity = apply_liquidity_delta(pool.active_liquidity, liquidity_net)?;
+    pool.tick = if zero_for_one {
+        tick - 1
+    } else {
+        tick
+    };
+
+    ticks.save(tick, info);
+
+    Ok(())
+}
+
+fn fee_growth_inside(
+    pool: &ConcentratedPool,
+    ticks: &Ticks,
+    lower_tick: Tick,
+    upper_tick: Tick,
+) -> anyhow::Result<FeeGrowth> {
+    let global = pool.fee_growth_global;
+    let lower_outside = ticks.load(lower_tick)?.fee_growth_outside;
+    let upper_outside = ticks.load(upper_tick)?.fee_growth_outside;
+
+    let below = if pool.tick >= lower_tick {
+        lower_outside
+    } else {
+        fee_growth_sub(global, lower_outside)?
+    };
+
+    let above = if pool.tick < upper_tick {
+        upper_outside
+    } else {
+        fee_growth_sub(global, upper_outside)?
+    };
+
+    Ok(fee_growth_sub(fee_growth_sub(global, below)?, above)?)
+}
+
+fn fee_growth_sub(a: FeeGrowth, b: FeeGrowth) -> MathResult<FeeGrowth> {
+    Ok(FeeGrowth {
+        first: a.first.checked_sub(b.first)?,
+        second: a.second.checked_sub(b.second)?,
+    })
+}
+
+fn apply_liquidity_delta(liquidity: Uint128, delta: Int128) -> MathResult<Uint128> {
+    liquidity
+        .checked_into_signed()?
+        .checked_add(delta)?
+        .checked_into_unsigned()
+}
+
+// ----------------------------------- math ------------------------------------
+
+/// Return the square root of the price at the given tick, i.e. `1.0001 ^ (tick / 2)`.
+fn sqrt_price_at_tick(tick: Tick) -> anyhow::Result<Udec256> {
+    ensure!(
+        (MIN_TICK..=MAX_TICK).contains(&tick),
+        "tick out of bounds: {} is not within [{}, {}]",
+        tick,
+        MIN_TICK,
+        MAX_TICK
+    );
+
+    let sqrt_price = SQRT_TICK_BASE.checked_pow(tick.unsigned_abs())?;
+
+    if tick < 0 {
+        Ok(sqrt_price.checked_inv()?)
+    } else {
+        Ok(sqrt_price)
+    }
+}
+
+/// Return the biggest tick whose square root price is no greater than the given
+/// value, using binary search.
+fn tick_at_sqrt_price(sqrt_price: Udec256) -> anyhow::Result<Tick> {
+    let mut low = MIN_TICK;
+    let mut high = MAX_TICK;
+
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+
+        if sqrt_price_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(low)
+}
+
+/// Compute a swap within a price range in which the liquidity is constant,
+/// until either the input is used up, or the target price is reached.
+///
+/// Returns:
+/// 1. the square root price after the swap;
+/// 2. the amount of input used;
+/// 3. the amount of output, before fees.
+fn compute_swap_step(
+    sqrt_price: Udec256,
+    target_sqrt_price: Udec256,
+    liquidity: Udec256,
+    remaining: Udec256,
+    zero_for_one: bool,
+) -> MathResult<(Udec256, Udec256, Udec256)> {
+    // There's no liquidity in this range, so the price moves to the target
+    // without consuming any input.
+    if liquidity.is_zero() {
+        return Ok((target_sqrt_price, Udec256::ZERO, Udec256::ZERO));
+    }
+
+    if zero_for_one {
+        let max_input = first_amount_delta(liquidity, target_sqrt_price, sqrt_price)?;
+
+        if remaining >= max_input {
+            let output = second_amount_delta(liquidity, target_sqrt_price, sqrt_price)?;
+            return Ok((target_sqrt_price, max_input, output));
+        }
+
+        // √P' = L * √P / (L + Δx * √P)
+        // Note: use ceil rounding, so that the price doesn't move further than
+        // it should, in favor of the pool.
+        let numerator = liquidity.checked_mul(sqrt_price)?;
+        let denominator = liquidity.checked_add(remaining.checked_mul(sqrt_price)?)?;
+        let next_sqrt_price =
+            Udec256::checked_from_ratio_ceil(numerator.numerator(), denominator.numerator())?
+                .max(target_sqrt_price);
+        let output = second_amount_delta(liquidity, next_sqrt_price, sqrt_price)?;
+
+        Ok((next_sqrt_price, remaining, output))
+    } else {
+        let max_input = second_amount_delta(liquidity, sqrt_price, target_sqrt_price)?;
+
+        if remaining >= max_input {
+            let output = first_amount_delta(liquidity, sqrt_price, target_sqrt_price)?;
+            return Ok((target_sqrt_price, max_input, output));
+        }
+
+        // √P' = √P + Δy / L
+        // Note: decimal division uses floor rounding, in favor of the pool.
+        let next_sqrt_price = sqrt_price
+            .checked_add(remaining.checked_div(liquidity)?)?
+            .min(target_sqrt_price);
+        let output = first_amount_delta(liquidity, sqrt_price, next_sqrt_price)?;
+
+        Ok((next_sqrt_price, remaining, output))
+    }
+}
+
+/// Return the maximum liquidity that can be provided to the price range
+/// `[lower_sqrt_price, upper_sqrt_price]` with the given token amounts.
+fn liquidity_for_amounts(
+    sqrt_price: Udec256,
+    lower_sqrt_price: Udec256,
+    upper_sqrt_price: Udec256,
+    amount1: Udec256,
+    amount2: Udec256,
+) -> MathResult<Udec256> {
+    let sqrt_price = sqrt_price.clamp(lower_sqrt_price, upper_sqrt_price);
+
+    // The first token covers the part of the range above the current price:
+    // L = Δx * √P * √P_upper / (√P_upper - √P)
+    let liquidity1 = if sqrt_price < upper_sqrt_price {
+        Some(
+            amount1
+                .checked_mul(sqrt_price)?
+                .checked_mul(upper_sqrt_price)?
+                .checked_div(upper_sqrt_price.checked_sub(sqrt_price)?)?,
+        )
+    } else {
+        None
+    };
+
+    // The second token covers the part of the range below the current price:
+    // L = Δy / (√P - √P_lower)
+    let liquidity2 = if sqrt_price > lower_sqrt_price {
+        Some(amount2.checked_div(sqrt_price.checked_sub(lower_sqrt_price)?)?)
+    } else {
+        None
+    };
+
+    match (liquidity1, liquidity2) {
+        (Some(liquidity1), Some(liquidity2)) => Ok(liquidity1.min(liquidity2)),
+        (Some(liquidity), None) | (None, Some(liquidity)) => Ok(liquidity),
+        // The lower price is strictly less than the upper price, so at least
+        // one of the two is defined.
+        (None, None) => unreachable!(),
+    }
+}
+
+/// Return the amounts of the two tokens that the given liquidity in the price
+/// range `[lower_sqrt_price, upper_sqrt_price]` is worth.
+fn amounts_for_liquidity(
+    sqrt_price: Udec256,
+    lower_sqrt_price: Udec256,
+    upper_sqrt_price: Udec256,
+    liquidity: Udec256,
+) -> MathResult<(Udec256, Udec256)> {
+    let sqrt_price = sqrt_price.clamp(lower_sqrt_price, upper_sqrt_price);
+
+    Ok((
+        first_amount_delta(liquidity, sqrt_price, upper_sqrt_price)?,
+        second_amount_delta(liquidity, lower_sqrt_price, sqrt_price)?,
+    ))
+}
+
+/// Δx = L * (√P_b - √P_a) / (√P_a * √P_b)
+fn first_amount_delta(
+    liquidity: Udec256,
+    sqrt_price_a: Udec256,
+    sqrt_price_b: Udec256,
+) -> MathResult<Udec256> {
+    liquidity
+        .checked_mul(sqrt_price_b.checked_sub(sqrt_price_a)?)?
+        .checked_div(sqrt_price_a)?
+        .checked_div(sqrt_price_b)
+}
+
+/// Δy = L * (√P_b - √P_a)
+fn second_amount_delta(
+    liquidity: Udec256,
+    sqrt_price_a: Udec256,
+    sqrt_price_b: Udec256,
+) -> MathResult<Udec256> {
+    liquidity.checked_mul(sqrt_price_b.checked_sub(sqrt_price_a)?)
+}
+
+fn to_dec(amount: Uint128) -> MathResult<Udec256> {
+    amount.into_next().checked_into_dec()
+}
+
+fn floor_int(dec: Udec256) -> MathResult<Uint128> {
+    dec.into_int().checked_into_prev()
+}
+
+fn ceil_int(dec: Udec256) -> MathResult<Uint128> {
+    dec.checked_ceil()?.into_int().checked_into_prev()
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, grug::Udec128};
+
+    #[test]
+    fn sqrt_price_at_tick_works() {
+        assert_eq!(sqrt_price_at_tick(0).unwrap(), Udec256::ONE);
+        assert_eq!(sqrt_price_at_tick(1).unwrap(), SQRT_TICK_BASE);
+
+        // 1.0001 ^ 10000 = 2.718145926825224864...
+        let price = sqrt_price_at_tick(10_000).unwrap().checked_pow(2).unwrap();
+        assert!(price > Udec256::new_permille(2_718) && price < Udec256::new_permille(2_719));
+
+        // Prices at the extremes are approximately 10^-18 and 10^18.
+        let price = sqrt_price_at_tick(MAX_TICK)
+            .unwrap()
+            .checked_pow(2)
+            .unwrap();
+        assert!(price > Udec256::new(999_000_000_000_000_000));
+        assert!(price < Udec256::new(1_000_000_000_000_000_000));
+
+        assert!(sqrt_price_at_tick(MIN_TICK).unwrap().is_non_zero());
+        assert!(sqrt_price_at_tick(MAX_TICK + 1).is_err());
+        assert!(sqrt_price_at_tick(MIN_TICK - 1).is_err());
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_works() {
+        for tick in [
+            MIN_TICK, -200_000, -12_345, -1, 0, 1, 12_345, 200_000, MAX_TICK,
+        ] {
+            let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+            assert_eq!(tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+
+            // A price slightly higher belongs to the same tick, unless it
+            // reaches the next tick.
+            if tick < MAX_TICK {
+                let higher = sqrt_price.checked_add(Udec256::raw(Uint256::ONE)).unwrap();
+                assert_eq!(tick_at_sqrt_price(higher).unwrap(), tick);
+            }
+
+            // A price slightly lower belongs to the previous tick.
+            if tick > MIN_TICK {
+                let lower = sqrt_price.checked_sub(Udec256::raw(Uint256::ONE)).unwrap();
+                assert_eq!(tick_at_sqrt_price(lower).unwrap(), tick - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn swap_step_round_trip() {
+        let liquidity = Udec256::new(1_000_000);
+        let sqrt_price = Udec256::ONE;
+        let lower = sqrt_price_at_tick(-1_000).unwrap();
+        let upper = sqrt_price_at_tick(1_000).unwrap();
+
+        // Swapping a small amount of the first token doesn't reach the target.
+        let (next, input, output) =
+            compute_swap_step(sqrt_price, lower, liquidity, Udec256::new(100), true).unwrap();
+        assert!(next > lower && next < sqrt_price);
+        assert_eq!(input, Udec256::new(100));
+        // At a price of ~1, the output is slightly less than the input.
+        assert!(output < Udec256::new(100) && output > Udec256::new(99));
+
+        // Swapping a big amount stops at the target.
+        let (next, input, _) =
+            compute_swap_step(sqrt_price, upper, liquidity, Udec256::new(1_000_000), false)
+                .unwrap();
+        assert_eq!(next, upper);
+        assert!(input < Udec256::new(1_000_000));
+
+        // Liquidity computed from amounts is worth no more than the amounts.
+        let amount1 = Udec256::new(1_000);
+        let amount2 = Udec256::new(2_000);
+        let liquidity = liquidity_for_amounts(sqrt_price, lower, upper, amount1, amount2).unwrap();
+        let (needed1, needed2) =
+            amounts_for_liquidity(sqrt_price, lower, upper, liquidity).unwrap();
+        assert!(needed1 <= amount1 && needed2 <= amount2);
+
+        // The first token is the limiting one here.
+        assert!(amount1.checked_sub(needed1).unwrap() < Udec128::new_bps(1).into_next());
     }
 }
@@ -1,4 +1,8 @@
-use grug::{Coin, CoinPair, Uint128};
+use {
+    crate::Ticks,
+    dango_types::amm::{Position, Tick},
+    grug::{Coin, CoinPair, Coins, Udec256, Uint128},
+};
 
 // Note: this trait is not object-safe, because of:
 // - it has an associated type;
@@ -28,4 +32,66 @@ pub trait PoolExt {
     /// Withdraw liquidity from the pool.
     /// Returns the amount of liquidity to be refunded to the user.
     fn withdraw_liquidity(&mut self, shares_to_burn: Uint128) -> anyhow::Result<CoinPair>;
+
+    /// Return the current price of the pool's first token, denominated in its
+    /// second token.
+    fn spot_price(&self) -> anyhow::Result<Udec256>;
+}
+
+/// Operations specific to concentrated liquidity pools, where liquidity is
+/// provided to price ranges in the form of positions, instead of in exchange
+/// for fungible liquidity shares.
+///
+/// The pool's initialized ticks are accessed through a [`Ticks`](crate::Ticks)
+/// buffer, since they aren't part of the pool's state.
+pub trait ConcentratedPoolExt {
+    /// Perform a swap operation, crossing initialized ticks as needed.
+    ///
+    /// Returns the same as [`PoolExt::swap`].
+    fn swap_with_ticks(&mut self, ticks: &mut Ticks, input: Coin) -> anyhow::Result<(Coin, Coin)>;
+
+    /// Provide liquidity to a position of the given price range.
+    ///
+    /// Returns:
+    /// 1. the amount of liquidity added to the position;
+    /// 2. the part of the deposit that isn't needed, to be refunded to the user.
+    fn increase_position(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        deposit: CoinPair,
+    ) -> anyhow::Result<(Uint128, Coins)>;
+
+    /// Withdraw liquidity from a position of the given price range.
+    /// Returns the amount of tokens to be refunded to the user.
+    fn decrease_position(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        liquidity: Uint128,
+    ) -> anyhow::Result<CoinPair>;
+
+    /// Collect the liquidity fees accrued to a position of the given price range.
+    /// Returns the amount of fees to be sent to the user.
+    fn collect_fees(
+        &mut self,
+        ticks: &mut Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    ) -> anyhow::Result<CoinPair>;
+
+    /// Update the position's owed fees to include those accrued since the
+    /// position was last updated.
+    fn accrue_fees(
+        &self,
+        ticks: &Ticks,
+        position: &mut Position,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    ) -> anyhow::Result<()>;
 }
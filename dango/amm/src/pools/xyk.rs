@@ -4,7 +4,7 @@ use {
     dango_types::amm::{XykParams, XykPool},
     grug::{
         Coin, CoinPair, Inner, MultiplyFraction, MultiplyRatio, NextNumber, Number, PrevNumber,
-        Uint128,
+        Udec256, Uint128,
     },
 };
 
@@ -107,4 +107,11 @@ impl PoolExt for XykPool {
 
         Ok(self.liquidity.split(shares_to_burn, shares_before)?)
     }
+
+    fn spot_price(&self) -> anyhow::Result<Udec256> {
+        Ok(Udec256::checked_from_ratio(
+            self.liquidity.second().amount.into_next(),
+            self.liquidity.first().amount.into_next(),
+        )?)
+    }
 }
@@ -1,12 +1,25 @@
 use {
-    crate::{perform_swap, CONFIG, POOLS},
-    dango_types::amm::{Config, Pool, PoolId, QueryMsg, SwapOutcome},
-    grug::{Bound, Coin, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage, UniqueVec},
+    crate::{
+        perform_swap, time_weighted_average_price, ConcentratedPoolExt, Ticks, CONFIG, POOLS,
+        POSITIONS, TICKS,
+    },
+    anyhow::bail,
+    dango_types::amm::{
+        Config, Pool, PoolId, Position, QueryMsg, SwapOutcome, Tick, TickInfo, TwapResponse,
+    },
+    grug::{
+        Addr, Bound, Coin, Duration, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage,
+        Timestamp, UniqueVec,
+    },
     std::collections::BTreeMap,
 };
 
 const DEFAULT_PAGE_LIMIT: u32 = 30;
 
+/// The maximum number of ticks returned by a single `Ticks` query, as a pool
+/// may have any number of initialized ticks.
+const MAX_PAGE_LIMIT: u32 = 100;
+
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
     match msg {
@@ -22,10 +35,31 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_pools(ctx.storage, start_after, limit)?;
             res.to_json_value()
         },
+        QueryMsg::Position {
+            pool_id,
+            owner,
+            lower_tick,
+            upper_tick,
+        } => {
+            let res = query_position(ctx.storage, pool_id, owner, lower_tick, upper_tick)?;
+            res.to_json_value()
+        },
+        QueryMsg::Ticks {
+            pool_id,
+            start_after,
+            limit,
+        } => {
+            let res = query_ticks(ctx.storage, pool_id, start_after, limit)?;
+            res.to_json_value()
+        },
         QueryMsg::Simulate { input, route } => {
             let res = query_simulte(ctx.storage, input, route)?;
             res.to_json_value()
         },
+        QueryMsg::Twap { pool_id, window } => {
+            let res = query_twap(ctx.storage, ctx.block.timestamp, pool_id, window)?;
+            res.to_json_value()
+        },
     }
     .map_err(Into::into)
 }
@@ -52,6 +86,46 @@ fn query_pools(
         .collect()
 }
 
+fn query_position(
+    storage: &dyn Storage,
+    pool_id: PoolId,
+    owner: Addr,
+    lower_tick: Tick,
+    upper_tick: Tick,
+) -> anyhow::Result<Position> {
+    let Pool::Concentrated(pool) = POOLS.load(storage, pool_id)? else {
+        bail!("pool {} is not a concentrated liquidity pool", pool_id);
+    };
+
+    let mut position = POSITIONS.load(storage, (pool_id, &owner, (lower_tick, upper_tick)))?;
+
+    // Include fees accrued since the position was last updated.
+    pool.accrue_fees(
+        &Ticks::new(storage, pool_id),
+        &mut position,
+        lower_tick,
+        upper_tick,
+    )?;
+
+    Ok(position)
+}
+
+fn query_ticks(
+    storage: &dyn Storage,
+    pool_id: PoolId,
+    start_after: Option<Tick>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Tick, TickInfo>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+
+    TICKS
+        .prefix(pool_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 fn query_simulte(
     storage: &dyn Storage,
     input: Coin,
@@ -60,8 +134,33 @@ fn query_simulte(
     let cfg = CONFIG.load(storage)?;
     let mut pools = route
         .into_iter()
-        .map(|pool_id| POOLS.load(storage, pool_id))
+        .map(|pool_id| Ok((pool_id, POOLS.load(storage, pool_id)?)))
         .collect::<StdResult<Vec<_>>>()?;
 
-    perform_swap(&cfg, input, pools.iter_mut())
+    // The pools' updated ticks are discarded, since this is only a simulation.
+    let (outcome, _) = perform_swap(
+        storage,
+        &cfg,
+        input,
+        pools.iter_mut().map(|(pool_id, pool)| (*pool_id, pool)),
+    )?;
+
+    Ok(outcome)
+}
+
+fn query_twap(
+    storage: &dyn Storage,
+    current_time: Timestamp,
+    pool_id: PoolId,
+    window: Duration,
+) -> anyhow::Result<TwapResponse> {
+    let pool = POOLS.load(storage, pool_id)?;
+    let (base_denom, quote_denom) = pool.denoms();
+    let price = time_weighted_average_price(storage, current_time, pool_id, &pool, window)?;
+
+    Ok(TwapResponse {
+        base_denom,
+        quote_denom,
+        price,
+    })
 }
@@ -1,6 +1,6 @@
 use {
-    dango_types::amm::{Config, Pool, PoolId},
-    grug::{Counter, Item, Map},
+    dango_types::amm::{Config, Pool, PoolId, Position, Tick, TickInfo},
+    grug::{Addr, Counter, Item, Map, Timestamp, Udec256},
 };
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -8,3 +8,19 @@ pub const CONFIG: Item<Config> = Item::new("config");
 pub const NEXT_POOL_ID: Counter<PoolId> = Counter::new("next_pool_id", 1, 1);
 
 pub const POOLS: Map<PoolId, Pool> = Map::new("pool");
+
+/// Liquidity positions in concentrated liquidity pools, keyed by pool ID, owner,
+/// and the lower and upper ticks of the price range.
+pub const POSITIONS: Map<(PoolId, &Addr, (Tick, Tick)), Position> = Map::new("position");
+
+/// Ticks of concentrated liquidity pools that are used as the boundary of at
+/// least one position, keyed by pool ID and tick.
+pub const TICKS: Map<(PoolId, Tick), TickInfo> = Map::new("tick");
+
+/// Cumulative prices of pools, keyed by pool ID and the time of observation.
+///
+/// The cumulative price is the integral of the pool's spot price (of the first
+/// token, denominated in the second) over time, in seconds. The average price
+/// over a period is then the difference in cumulative price divided by the
+/// period's length.
+pub const OBSERVATIONS: Map<(PoolId, Timestamp), Udec256> = Map::new("observation");
@@ -1,24 +1,39 @@
 use {
-    crate::PoolExt,
-    dango_types::amm::{Config, Pool, SwapOutcome},
-    grug::{Coin, Coins, Inner, MultiplyFraction, Number},
+    crate::{ConcentratedPoolExt, PoolExt, TickChanges, Ticks},
+    dango_types::amm::{Config, Pool, PoolId, SwapOutcome},
+    grug::{Coin, Coins, Inner, MultiplyFraction, Number, Storage},
 };
 
 // Note: this function assumes the swap route doesn't contain any loop, meaning
 // the same pool must not appear twice in the `pools` iterator.
 // The caller should make sure of this by using a `UniqueVec` when taking in
 // the swap route.
-pub fn perform_swap<'a, I>(cfg: &Config, mut input: Coin, pools: I) -> anyhow::Result<SwapOutcome>
+//
+// Changes to the ticks of concentrated liquidity pools aren't written to the
+// storage, but returned, to be committed by the caller if it wishes to.
+pub fn perform_swap<'a, I>(
+    storage: &dyn Storage,
+    cfg: &Config,
+    mut input: Coin,
+    pools: I,
+) -> anyhow::Result<(SwapOutcome, Vec<TickChanges>)>
 where
-    I: Iterator<Item = &'a mut Pool>,
+    I: Iterator<Item = (PoolId, &'a mut Pool)>,
 {
     let mut liquidity_fees = Coins::new();
 
+    let mut tick_changes = Vec::new();
+
     // Iterate through the pools and perform swaps.
-    for pool in pools {
+    for (pool_id, pool) in pools {
         let (output, liquidity_fee) = match pool {
             Pool::Xyk(xyk) => xyk.swap(input)?,
-            Pool::Concentrated(concentrated) => concentrated.swap(input)?,
+            Pool::Concentrated(concentrated) => {
+                let mut ticks = Ticks::new(storage, pool_id);
+                let res = concentrated.swap_with_ticks(&mut ticks, input)?;
+                tick_changes.push(ticks.into_changes());
+                res
+            },
         };
 
         // The output of this pool is the input for the next pool.
@@ -39,12 +54,14 @@ where
     // Deduct protocol fee from the output.
     output.amount = output.amount.checked_sub(protocol_fee)?;
 
-    Ok(SwapOutcome {
+    let outcome = SwapOutcome {
         protocol_fee: Coin {
             denom: output.denom.clone(),
             amount: protocol_fee,
         },
         output,
         liquidity_fees,
-    })
+    };
+
+    Ok((outcome, tick_changes))
 }
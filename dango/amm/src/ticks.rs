@@ -0,0 +1,193 @@
+use {
+    crate::TICKS,
+    dango_types::amm::{PoolId, Tick, TickInfo},
+    grug::{Bound, Order, StdResult, Storage},
+    std::collections::BTreeMap,
+};
+
+/// A view into the initialized ticks of a concentrated liquidity pool, which
+/// buffers changes in memory instead of writing them to the storage directly.
+///
+/// Ticks are loaded from the storage only when needed, so e.g. a swap only
+/// reads the ticks it actually crosses, instead of all ticks of the pool.
+///
+/// Buffering allows the same logics to be used in both execute and query
+/// (e.g. swap simulation) contexts, where the storage is read-only.
+pub struct Ticks<'a> {
+    storage: &'a dyn Storage,
+    pool_id: PoolId,
+    /// Ticks that have been updated (`Some`) or removed (`None`).
+    changes: BTreeMap<Tick, Option<TickInfo>>,
+}
+
+impl<'a> Ticks<'a> {
+    pub fn new(storage: &'a dyn Storage, pool_id: PoolId) -> Self {
+        Self {
+            storage,
+            pool_id,
+            changes: BTreeMap::new(),
+        }
+    }
+
+    pub fn may_load(&self, tick: Tick) -> StdResult<Option<TickInfo>> {
+        match self.changes.get(&tick) {
+            Some(info) => Ok(info.clone()),
+            None => TICKS.may_load(self.storage, (self.pool_id, tick)),
+        }
+    }
+
+    pub fn load(&self, tick: Tick) -> StdResult<TickInfo> {
+        match self.changes.get(&tick) {
+            Some(Some(info)) => Ok(info.clone()),
+            // Removed ticks don't exist in the storage either, so this errors.
+            _ => TICKS.load(self.storage, (self.pool_id, tick)),
+        }
+    }
+
+    pub fn save(&mut self, tick: Tick, info: TickInfo) {
+        self.changes.insert(tick, Some(info));
+    }
+
+    pub fn remove(&mut self, tick: Tick) {
+        self.changes.insert(tick, None);
+    }
+
+    /// Find the closest initialized tick in the direction of a swap.
+    ///
+    /// If `zero_for_one` (i.e. the price goes down), this is the biggest tick
+    /// that is no greater than the given tick; otherwise, the smallest tick
+    /// that is greater than the given tick.
+    pub fn next_initialized(&self, tick: Tick, zero_for_one: bool) -> StdResult<Option<Tick>> {
+        let (min, max, order) = if zero_for_one {
+            (None, Some(Bound::Inclusive(tick)), Order::Descending)
+        } else {
+            (Some(Bound::Exclusive(tick)), None, Order::Ascending)
+        };
+
+        // The closest tick in the storage that hasn't been removed.
+        let mut stored = None;
+
+        for res in TICKS
+            .prefix(self.pool_id)
+            .keys(self.storage, min, max, order)
+        {
+            let stored_tick = res?;
+
+            if !matches!(self.changes.get(&stored_tick), Some(None)) {
+                stored = Some(stored_tick);
+                break;
+            }
+        }
+
+        // The closest tick among those updated but not yet written to storage.
+        let pending = if zero_for_one {
+            self.changes
+                .range(..=tick)
+                .rev()
+                .find(|(_, info)| info.is_some())
+        } else {
+            self.changes
+                .range(tick + 1..)
+                .find(|(_, info)| info.is_some())
+        }
+        .map(|(tick, _)| *tick);
+
+        Ok(match (stored, pending) {
+            (Some(a), Some(b)) if zero_for_one => Some(a.max(b)),
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        })
+    }
+
+    /// Take the buffered changes, so that they can be written to the storage
+    /// once the storage is no longer borrowed.
+    pub fn into_changes(self) -> TickChanges {
+        TickChanges {
+            pool_id: self.pool_id,
+            changes: self.changes,
+        }
+    }
+}
+
+/// Changes to the initialized ticks of a pool, yet to be written to storage.
+pub struct TickChanges {
+    pool_id: PoolId,
+    changes: BTreeMap<Tick, Option<TickInfo>>,
+}
+
+impl TickChanges {
+    pub fn commit(self, storage: &mut dyn Storage) -> StdResult<()> {
+        for (tick, info) in self.changes {
+            match info {
+                Some(info) => TICKS.save(storage, (self.pool_id, tick), &info)?,
+                None => TICKS.remove(storage, (self.pool_id, tick)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_types::amm::FeeGrowth,
+        grug::{Int128, MockStorage, NumberConst, Uint128},
+    };
+
+    fn mock_tick_info() -> TickInfo {
+        TickInfo {
+            liquidity_gross: Uint128::ONE,
+            liquidity_net: Int128::ONE,
+            fee_growth_outside: FeeGrowth::default(),
+        }
+    }
+
+    #[test]
+    fn next_initialized_merges_changes() {
+        let mut storage = MockStorage::new();
+
+        for tick in [-10, 0, 20] {
+            TICKS
+                .save(&mut storage, (1, tick), &mock_tick_info())
+                .unwrap();
+        }
+
+        // A tick of another pool, which should be ignored.
+        TICKS.save(&mut storage, (2, 5), &mock_tick_info()).unwrap();
+
+        let mut ticks = Ticks::new(&storage, 1);
+        ticks.remove(0);
+        ticks.save(10, mock_tick_info());
+
+        for (tick, zero_for_one, expect) in [
+            (5, true, Some(-10)),
+            (5, false, Some(10)),
+            (10, true, Some(10)),
+            (10, false, Some(20)),
+            (-10, true, Some(-10)),
+            (-11, true, None),
+            (20, false, None),
+        ] {
+            assert_eq!(
+                ticks.next_initialized(tick, zero_for_one).unwrap(),
+                expect,
+                "tick: {tick}, zero_for_one: {zero_for_one}"
+            );
+        }
+
+        // Commit the changes. The storage should now reflect them.
+        ticks.into_changes().commit(&mut storage).unwrap();
+
+        let stored = TICKS
+            .prefix(1)
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(stored, [-10, 10, 20]);
+    }
+}
@@ -0,0 +1,234 @@
+use {
+    crate::{PoolExt, OBSERVATIONS},
+    anyhow::{bail, ensure},
+    dango_types::amm::{Pool, PoolId, MAX_TWAP_WINDOW},
+    grug::{
+        Bound, Duration, NextNumber, Number, NumberConst, Order, StdResult, Storage, Timestamp,
+        Udec128, Udec256,
+    },
+};
+
+/// Record the pool's cumulative price as of the current time.
+///
+/// Must be called before any change to the pool's price, so that the price
+/// accumulated is the one that has been in effect since the last observation.
+///
+/// Observations that are no longer needed to compute the TWAP over the maximum
+/// window are pruned.
+pub fn record_observation(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    pool_id: PoolId,
+    pool: &Pool,
+) -> anyhow::Result<()> {
+    let cumulative_price = match last_observation(storage, pool_id, current_time)? {
+        // The pool has already been observed in this block.
+        Some((time, _)) if time == current_time => return Ok(()),
+        Some((time, cumulative_price)) => {
+            let elapsed = seconds(current_time - time)?;
+            cumulative_price.checked_add(spot_price(pool)?.checked_mul(elapsed)?)?
+        },
+        // This is a new pool.
+        None => Udec256::ZERO,
+    };
+
+    OBSERVATIONS.save(storage, (pool_id, current_time), &cumulative_price)?;
+
+    prune_observations(storage, current_time, pool_id)?;
+
+    Ok(())
+}
+
+/// Delete observations older than the last one no later than the start of the
+/// maximum TWAP window. That one is kept, as it's needed to compute the
+/// cumulative price at the start of the window.
+fn prune_observations(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    pool_id: PoolId,
+) -> StdResult<()> {
+    if current_time <= MAX_TWAP_WINDOW {
+        return Ok(());
+    }
+
+    let Some((keep_time, _)) = last_observation(storage, pool_id, current_time - MAX_TWAP_WINDOW)?
+    else {
+        return Ok(());
+    };
+
+    let stale = OBSERVATIONS
+        .prefix(pool_id)
+        .keys(
+            storage,
+            None,
+            Some(Bound::Exclusive(keep_time)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for time in stale {
+        OBSERVATIONS.remove(storage, (pool_id, time));
+    }
+
+    Ok(())
+}
+
+/// Compute the time-weighted average price of the pool's first token,
+/// denominated in its second token, over the window ending at the current time.
+pub fn time_weighted_average_price(
+    storage: &dyn Storage,
+    current_time: Timestamp,
+    pool_id: PoolId,
+    pool: &Pool,
+    window: Duration,
+) -> anyhow::Result<Udec256> {
+    ensure!(
+        window.into_nanos() > 0 && window <= current_time && window <= MAX_TWAP_WINDOW,
+        "invalid TWAP window: {} seconds, must be non-zero and no longer than {} seconds",
+        window.into_seconds(),
+        MAX_TWAP_WINDOW.into_seconds()
+    );
+
+    let start_time = current_time - window;
+    let cumulative_price_start = cumulative_price_at(storage, pool_id, pool, start_time)?;
+    let cumulative_price_end = cumulative_price_at(storage, pool_id, pool, current_time)?;
+
+    Ok(cumulative_price_end
+        .checked_sub(cumulative_price_start)?
+        .checked_div(seconds(window)?)?)
+}
+
+/// Compute the pool's cumulative price as of the given time, by extrapolating
+/// from the last observation no later than that time.
+fn cumulative_price_at(
+    storage: &dyn Storage,
+    pool_id: PoolId,
+    pool: &Pool,
+    time: Timestamp,
+) -> anyhow::Result<Udec256> {
+    let Some((observed_time, cumulative_price)) = last_observation(storage, pool_id, time)? else {
+        bail!(
+            "pool {} has no price observation as old as {} seconds",
+            pool_id,
+            time.into_seconds()
+        );
+    };
+
+    // The price in effect since the observation is constant until the next
+    // one. If there isn't a next observation, it's the current spot price.
+    let price = match OBSERVATIONS
+        .prefix(pool_id)
+        .range(
+            storage,
+            Some(Bound::Exclusive(observed_time)),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?
+    {
+        Some((next_time, next_cumulative_price)) => next_cumulative_price
+            .checked_sub(cumulative_price)?
+            .checked_div(seconds(next_time - observed_time)?)?,
+        None => spot_price(pool)?,
+    };
+
+    Ok(cumulative_price.checked_add(price.checked_mul(seconds(time - observed_time)?)?)?)
+}
+
+/// Find the pool's last observation no later than the given time.
+fn last_observation(
+    storage: &dyn Storage,
+    pool_id: PoolId,
+    time: Timestamp,
+) -> StdResult<Option<(Timestamp, Udec256)>> {
+    OBSERVATIONS
+        .prefix(pool_id)
+        .range(
+            storage,
+            None,
+            Some(Bound::Inclusive(time)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()
+}
+
+fn spot_price(pool: &Pool) -> anyhow::Result<Udec256> {
+    match pool {
+        Pool::Xyk(xyk) => xyk.spot_price(),
+        Pool::Concentrated(concentrated) => concentrated.spot_price(),
+    }
+}
+
+/// Convert a duration to a fractional number of seconds.
+fn seconds(duration: Duration) -> StdResult<Udec256> {
+    Ok(Udec128::checked_from_ratio(duration.into_nanos(), 1_000_000_000)?.into_next())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_types::amm::{FeeRate, XykParams, XykPool},
+        grug::{Coin, CoinPair, MockStorage, Uint128},
+    };
+
+    #[test]
+    fn old_observations_are_pruned() {
+        let mut storage = MockStorage::new();
+        let pool = Pool::Xyk(XykPool {
+            params: XykParams {
+                liquidity_fee_rate: FeeRate::new_unchecked(Udec128::ZERO),
+            },
+            liquidity: CoinPair::new_unchecked(
+                Coin::new("uatom", 100).unwrap(),
+                Coin::new("uusdc", 1_000).unwrap(),
+            ),
+            shares: Uint128::new(100),
+        });
+
+        // Observe the pool every 10 hours, for 5 days.
+        let times = (1..=12)
+            .map(|i| Timestamp::from_hours(i * 10))
+            .collect::<Vec<_>>();
+
+        for time in &times {
+            record_observation(&mut storage, *time, 1, &pool).unwrap();
+        }
+
+        let observed = OBSERVATIONS
+            .prefix(1)
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+
+        // The maximum window starts at 96 hours. Observations before that are
+        // pruned, except for the last one at 90 hours.
+        assert_eq!(observed, times[8..]);
+
+        // The TWAP over the maximum window can still be computed.
+        let twap = time_weighted_average_price(
+            &storage,
+            *times.last().unwrap(),
+            1,
+            &pool,
+            MAX_TWAP_WINDOW,
+        )
+        .unwrap();
+
+        assert_eq!(twap, Udec256::new(10));
+
+        // Windows longer than the maximum are rejected.
+        assert!(time_weighted_average_price(
+            &storage,
+            *times.last().unwrap(),
+            1,
+            &pool,
+            MAX_TWAP_WINDOW + Duration::from_seconds(1),
+        )
+        .is_err());
+    }
+}
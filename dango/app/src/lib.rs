@@ -1,3 +1,4 @@
+mod price_feeder;
 mod proposal_preparer;
 
-pub use proposal_preparer::*;
+pub use {price_feeder::*, proposal_preparer::*};
@@ -0,0 +1,152 @@
+use {
+    dango_types::oracle::PythId,
+    grug::{Binary, JsonDeExt, StdError},
+    std::{fs, path::PathBuf, time::Duration},
+    thiserror::Error,
+};
+
+/// The default endpoint of the Pyth Hermes API.
+pub const DEFAULT_HERMES_URL: &str = "https://hermes.pyth.network";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Error)]
+pub enum PriceFeederError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A source of Pyth VAAs to be fed to the oracle contract during
+/// `PrepareProposal`.
+pub trait PriceFeeder: Send + 'static {
+    /// Fetch the latest VAAs of the given Pyth price feeds.
+    fn fetch_latest_vaas(&self, ids: &[PythId]) -> Result<Vec<Binary>, PriceFeederError>;
+}
+
+#[grug::derive(Serde)]
+pub(crate) struct LatestVaaResponse {
+    pub binary: LatestVaaBinaryResponse,
+}
+
+#[grug::derive(Serde)]
+pub(crate) struct LatestVaaBinaryResponse {
+    pub data: Vec<Binary>,
+}
+
+// ---------------------------------- hermes -----------------------------------
+
+/// Pulls VAAs from a Pyth Hermes API endpoint.
+pub struct HermesPriceFeeder {
+    url: String,
+}
+
+impl HermesPriceFeeder {
+    pub fn new<T>(url: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { url: url.into() }
+    }
+}
+
+impl Default for HermesPriceFeeder {
+    fn default() -> Self {
+        Self::new(DEFAULT_HERMES_URL)
+    }
+}
+
+impl PriceFeeder for HermesPriceFeeder {
+    fn fetch_latest_vaas(&self, ids: &[PythId]) -> Result<Vec<Binary>, PriceFeederError> {
+        let mut params = ids
+            .iter()
+            .map(|id| ("ids[]", id.to_string()))
+            .collect::<Vec<_>>();
+
+        // Set the encoding to base64 to match the oracle contract.
+        params.push(("encoding", "base64".to_string()));
+        // Set the parsed to false since we don't use parsed data.
+        params.push(("parsed", "false".to_string()));
+
+        Ok(reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?
+            .get(format!("{}/v2/updates/price/latest", self.url))
+            .query(&params)
+            .send()?
+            .error_for_status()?
+            .json::<LatestVaaResponse>()?
+            .binary
+            .data)
+    }
+}
+
+// ----------------------------------- file ------------------------------------
+
+/// Reads VAAs from a JSON file, containing an array of base64-encoded VAAs.
+///
+/// The file is read on every fetch, so it can be updated while the node is
+/// running. Useful for devnets that don't have access to a Hermes endpoint.
+pub struct FilePriceFeeder {
+    path: PathBuf,
+}
+
+impl FilePriceFeeder {
+    pub fn new<T>(path: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        Self { path: path.into() }
+    }
+}
+
+impl PriceFeeder for FilePriceFeeder {
+    fn fetch_latest_vaas(&self, _ids: &[PythId]) -> Result<Vec<Binary>, PriceFeederError> {
+        Ok(fs::read(&self.path)?.deserialize_json()?)
+    }
+}
+
+// ---------------------------------- fixture ----------------------------------
+
+/// Returns a fixed set of VAAs on every fetch. Useful for tests.
+pub struct FixturePriceFeeder {
+    vaas: Vec<Binary>,
+}
+
+impl FixturePriceFeeder {
+    pub fn new(vaas: Vec<Binary>) -> Self {
+        Self { vaas }
+    }
+}
+
+impl PriceFeeder for FixturePriceFeeder {
+    fn fetch_latest_vaas(&self, _ids: &[PythId]) -> Result<Vec<Binary>, PriceFeederError> {
+        Ok(self.vaas.clone())
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod test {
+    use {super::LatestVaaResponse, grug::JsonDeExt};
+
+    #[test]
+    fn deserializing_pyth_response() {
+        r#"{
+          "binary": {
+            "encoding": "base64",
+            "data": [
+              "UE5BVQEAAAADuAEAAAAEDQBkr0PLb+gk8uvpb1vCCnSzrkNBWuAKD+4/oHA1HhL2rywRRNl4NEsUMyNDHVVFJz7sb2TqUXbIVKSDR+cXZk12AQHIF5lSaXo4Br8HN9I8FxSKI+k39d0G/hfGGcg42L1lmhol2f3hJRw32z9e9ktuCvOIClAe0U0t8hQk2meeHWReAARHzS7dEnqYLo5cM0ct+0lmMftM+SER9GP/Kr/l1nnUaRNff+2443LwCqOay1A0DSn6sOa6FO16w5mbgsNiUuMlAAhiIiNh1QIxaoKUydS3R0MnKoBkdt7ixtVCvK/GPi0PeC3goY+ZgaheHaVYt6lfjD0nwITz2bdYFZNq2SqO510VAAoHTixQaLHPPgi72kww0j5hOlJn11W1Rz8LAGGl0gk7/GZwEhiBUCuUCfTFwpHqX4UHJIXftR0SV1mS6UB3XLV6AAtAVczuOpFCCPiH9Sg9I0l1xitkQotpP8h+di11JJ3CVkm6vRU7zy1KrhYFsoV082IZTi0XN0Xdv5fWZZdg3Hx5AAzpW38X3a88bFIoys/jeflLAN+A9VeABd5HN9D6snOgI3o5FXftoZjNP0c8Xd/J8rTlUgBIOgsyGFh2vRjJbe6JAQ2+2P9NCAAhBnOYoRjASsA/XlI2FDEGK58ati8kz4vJGyO9B8O6sdZiKE70ieoDirBl8puIkYb0sPU8vov/xF1kAA4cPqmQw6zUazq7F8VE0FSe9C2KD3bD2drA/5rkFExyABw+4XL/4KGbA02YEvp4rGpQvBHKjyS7HhdfuWaspCtjAQ8IoVHbmdLHcnc1dJLgduCtMOPVB3+kpJZBLRfu962mRhG6zLmIr5ioO8/HSsDSQWLWGfi7u2z+g9Nw0CVVhmwmARBAmbiFQ3nI4Idyu9XHuY9r9FaQa1rAcdCuDEGCZPdbphqX7kckA6r95J01ERAqLHwNvZDzuTTgY00Qcuh4v7bfABELTI+OKdU8zctRIb+HJxqQJKeCuBc2+zFo0Tfjs7A8Ix4mszdY/c+1iKZjQLP8MvDekc1rPgjCbhJeZrvoz7xHABJoPDAMGFjauAjWB3g8EJHV+V+oTH5pYLXfZrXdUzzHxTj+JdMaJNIl7EN9MQ7hPzwKkkvwEKuHx661sLdMwGt1AWc3bGMAAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFkcwYAUFVV1YAAAAAAAqelroAACcQy4n8eAYQTYIOJi7hMVMnT8+xofsBAFUA5i32yLSoX+GmfbRNwS3l2zMPesZrctxliv7fD0pBW0MAAAgFdR9DkgAAAAErfT7c////+AAAAABnN2xjAAAAAGc3bGMAAAgVwbgMwAAAAAErXavAC2svmTTo0dM2ChAXPyvygP0QyQvSR/BV5sMFociUhrsruHLNPmvHkOxzZMH4NQzepTk6Wc6cGiVU1RVKdqDYcPPsm+N2jsKYH5zp65jeHXG0h5pR53BajAgRmNmq7xzBP5e1vT6gv5CHEbv1QGAvbGXSReuOOj1LR29RgjJiM/A/PCysDvPuhI5r9QqT69mMvlraw2QdqlRGmTsq/1LlskABMC+bL64zMUlwLmo0N3kxXuS3Y906SY9J733py3EPMQMRrGD5C185kIK9iYlvyEOx5Tq0wvQeaB2/J7Q="
+            ]
+          }
+        }"#
+        .deserialize_json::<LatestVaaResponse>()
+        .unwrap();
+    }
+}
@@ -1,45 +1,62 @@
 use {
+    crate::{HermesPriceFeeder, PriceFeeder, PriceFeederError},
     dango_types::{
         config::AppConfig,
-        oracle::{ExecuteMsg, PriceSource, QueryPriceSourcesRequest},
+        oracle::{ExecuteMsg, PriceSource, PythId, PythVaa, QueryPriceSourcesRequest},
     },
-    grug::{Binary, Coins, Json, JsonSerExt, Message, NonEmpty, QuerierWrapper, StdError, Tx},
-    grug_app::{AppError, Shared},
+    grug::{
+        Addr, Binary, Coins, Inner, Json, JsonDeExt, JsonSerExt, Message, MsgExecute, NativeApi,
+        NonEmpty, QuerierWrapper, StdError, StdResult, Tx,
+    },
+    grug_app::{AppError, ExtendedVote, Shared},
     prost::bytes::Bytes,
     std::{
         cmp::min,
+        collections::{BTreeMap, BTreeSet},
         ops::Mul,
         thread::{self, JoinHandle},
         time::Duration,
     },
     thiserror::Error,
-    tracing::{error, info},
+    tracing::{error, info, warn},
 };
 
-const PYTH_URL: &str = "https://hermes.pyth.network";
-const REQUEST_TIMEOUT: Duration = Duration::from_millis(1000);
 const THREAD_SLEEP: Duration = Duration::from_millis(1000);
 const THREAD_SLEEP_ON_FIRST_429: Duration = Duration::from_millis(5000);
 const MAX_THREAD_SLEEP: Duration = Duration::from_secs(30);
 const GAS_LIMIT: u64 = 50_000_000;
 
-#[grug::derive(Serde)]
-struct LatestVaaResponse {
-    pub binary: LatestVaaBinaryResponse,
-}
-
-#[grug::derive(Serde)]
-struct LatestVaaBinaryResponse {
-    pub data: Vec<Binary>,
-}
-
 #[derive(Debug, Error)]
 pub enum ProposerError {
     #[error(transparent)]
     Std(#[from] StdError),
 
-    #[error(transparent)]
-    Reqwest(#[from] reqwest::Error),
+    #[error("transaction at index {index} is a duplicate")]
+    DuplicateTx { index: usize },
+
+    #[error("oracle transaction at index {index} isn't the first in the proposal")]
+    OracleTxNotFirst { index: usize },
+
+    #[error("oracle transaction gas limit too high! max: {max}, found: {found}")]
+    OracleTxGasLimitTooHigh { max: u64, found: u64 },
+
+    #[error("oracle transaction must contain exactly one message that feeds prices")]
+    MalformedOracleTx,
+
+    #[error("too many VAAs! max: {max}, found: {found}")]
+    TooManyVaas { max: usize, found: usize },
+
+    #[error("price feed {id} isn't registered in the oracle")]
+    UnknownPriceFeed { id: PythId },
+
+    #[error("aggregated prices don't match the vote extensions")]
+    AggregatedPricesMismatch,
+
+    #[error("proposer-fed prices aren't allowed when vote extensions are present")]
+    UnexpectedFeedPrices,
+
+    #[error("invalid VAA: {0}")]
+    InvalidVaa(String),
 }
 
 impl From<ProposerError> for AppError {
@@ -49,7 +66,7 @@ impl From<ProposerError> for AppError {
 }
 
 pub struct ProposalPreparer {
-    params: Shared<Vec<(&'static str, String)>>,
+    ids: Shared<Vec<PythId>>,
     latest_vaas: Shared<Vec<Binary>>,
     // Option since we don't want to clone the thread handle.
     // Store the thread to keep it alive.
@@ -59,7 +76,7 @@ pub struct ProposalPreparer {
 impl Clone for ProposalPreparer {
     fn clone(&self) -> Self {
         Self {
-            params: self.params.clone(),
+            ids: self.ids.clone(),
             latest_vaas: self.latest_vaas.clone(),
             _handle: None,
         }
@@ -73,36 +90,32 @@ impl Default for ProposalPreparer {
 }
 
 impl ProposalPreparer {
+    /// Create a proposal preparer that pulls prices from the default Hermes
+    /// endpoint.
     pub fn new() -> Self {
-        let params = Shared::new(Vec::new());
-        let thread_params = params.clone();
+        Self::new_with_feeder(HermesPriceFeeder::default())
+    }
+
+    /// Create a proposal preparer that pulls prices from the given feeder.
+    pub fn new_with_feeder<P>(feeder: P) -> Self
+    where
+        P: PriceFeeder,
+    {
+        let ids = Shared::new(Vec::new());
+        let thread_ids = ids.clone();
         let latest_vaas = Shared::new(Vec::new());
         let thread_latest_vaas = latest_vaas.clone();
 
         let _handle = thread::spawn(move || {
-            let update_func = || -> Result<(), reqwest::Error> {
-                // Copy the params to unlock the mutex.
-                let mut params = thread_params.read_access().clone();
-                if params.is_empty() {
+            let update_func = || -> Result<(), PriceFeederError> {
+                // Copy the IDs to unlock the mutex.
+                let ids = thread_ids.read_access().clone();
+                if ids.is_empty() {
                     return Ok(());
                 }
 
-                // Set the encoding to base64 to match the oracle contract.
-                params.push(("encoding", "base64".to_string()));
-                // Set the parsed to false since we don't use parsed data.
-                params.push(("parsed", "false".to_string()));
-
-                // Retrieve VAAs from pyth node.
-                let vaas = reqwest::blocking::Client::builder()
-                    .timeout(REQUEST_TIMEOUT)
-                    .build()?
-                    .get(format!("{PYTH_URL}/v2/updates/price/latest"))
-                    .query(&params)
-                    .send()?
-                    .error_for_status()?
-                    .json::<LatestVaaResponse>()?
-                    .binary
-                    .data;
+                // Retrieve VAAs from the feeder.
+                let vaas = feeder.fetch_latest_vaas(&ids)?;
 
                 info!(len = vaas.len(), "Prepare proposal: fetched latest VAAs");
 
@@ -132,7 +145,10 @@ impl ProposalPreparer {
                             THREAD_SLEEP.mul(2u32.pow(failed_requests)),
                         );
 
-                        if let Some(status_code) = err.status() {
+                        if let Some(status_code) = match &err {
+                            PriceFeederError::Reqwest(err) => err.status(),
+                            _ => None,
+                        } {
                             // The first time we get a 429, we increase
                             // the sleep time to avoid further rate limitation.
                             if status_code == 429 && failed_requests == 1 {
@@ -154,7 +170,7 @@ impl ProposalPreparer {
         });
 
         Self {
-            params,
+            ids,
             latest_vaas,
             _handle: Some(_handle),
         }
@@ -169,45 +185,50 @@ impl grug_app::ProposalPreparer for ProposalPreparer {
         querier: QuerierWrapper,
         mut txs: Vec<Bytes>,
         _max_tx_bytes: usize,
+        votes: &[ExtendedVote],
     ) -> Result<Vec<Bytes>, Self::Error> {
         let cfg: AppConfig = querier.query_app_config()?;
 
-        // Retrieve the price ids from the oracle and prepare the query params.
-        // TODO: optimize this by using the raw WasmScan query.
-        let params = querier
-            .query_wasm_smart(cfg.addresses.oracle, QueryPriceSourcesRequest {
-                start_after: None,
-                limit: Some(u32::MAX),
-            })?
-            .into_values()
-            .filter_map(|price_source| {
-                // For now there is only Pyth as PriceSource, but there could be more.
-                #[allow(irrefutable_let_patterns)]
-                if let PriceSource::Pyth { id, .. } = price_source {
-                    Some(("ids[]", id.to_string()))
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        // Retrieve the price ids from the oracle.
+        let ids = query_pyth_ids(&querier, cfg.addresses.oracle)?;
 
-        // Write the params to the shared memory.
-        self.params.write_with(|mut params_ref| {
-            *params_ref = params;
-        });
+        let msg = if has_price_votes(votes) {
+            // Aggregate the VAAs attached to the votes.
+            let (vaas, selections) = aggregate_price_votes(&ids, votes);
 
-        // Retreive the VAAs from the shared memory.
-        // Consuming the VAAs to avoid feeding the same prices multiple times.
-        let vaas = self.latest_vaas.write_with(|mut prices_lock| {
-            let prices = prices_lock.clone();
-            *prices_lock = vec![];
-            prices
-        });
+            // Return if there are no prices to feed.
+            if selections.is_empty() {
+                return Ok(txs);
+            }
 
-        // Return if there are no VAAs to feed.
-        if vaas.is_empty() {
-            return Ok(txs);
-        }
+            ExecuteMsg::FeedAggregatedPrices {
+                vaas: NonEmpty::new(vaas)?,
+                selections,
+            }
+        } else {
+            // Vote extensions aren't enabled, or no validator attached one.
+            // Feed the VAAs this node has fetched itself.
+
+            // Write the IDs to the shared memory.
+            self.ids.write_with(|mut ids_ref| {
+                *ids_ref = ids;
+            });
+
+            // Retreive the VAAs from the shared memory.
+            // Consuming the VAAs to avoid feeding the same prices multiple times.
+            let vaas = self.latest_vaas.write_with(|mut prices_lock| {
+                let prices = prices_lock.clone();
+                *prices_lock = vec![];
+                prices
+            });
+
+            // Return if there are no VAAs to feed.
+            if vaas.is_empty() {
+                return Ok(txs);
+            }
+
+            ExecuteMsg::FeedPrices(NonEmpty::new(vaas)?)
+        };
 
         // Build the tx.
         let tx = Tx {
@@ -215,7 +236,7 @@ impl grug_app::ProposalPreparer for ProposalPreparer {
             gas_limit: GAS_LIMIT,
             msgs: NonEmpty::new_unchecked(vec![Message::execute(
                 cfg.addresses.oracle,
-                &ExecuteMsg::FeedPrices(NonEmpty::new(vaas)?),
+                &msg,
                 Coins::new(),
             )?]),
             data: Json::null(),
@@ -226,25 +247,312 @@ impl grug_app::ProposalPreparer for ProposalPreparer {
 
         Ok(txs)
     }
+
+    /// The aggregated prices are recomputed from the votes carried in the
+    /// proposal, which the app has matched against the last commit, and whose
+    /// extensions it has verified to be signed by the validators. The prices
+    /// themselves are signed by Wormhole guardians, and are verified by the
+    /// oracle contract.
+    fn process_proposal(
+        &self,
+        querier: QuerierWrapper,
+        txs: &[Bytes],
+        votes: &[ExtendedVote],
+    ) -> Result<bool, Self::Error> {
+        match validate_proposal(&querier, txs, votes) {
+            Ok(()) => Ok(true),
+            // Failing to query the state is a fault on our side, rather than
+            // the proposer's.
+            Err(ProposerError::Std(err)) => Err(err.into()),
+            Err(err) => {
+                warn!(err = err.to_string(), "Invalid proposal");
+
+                Ok(false)
+            },
+        }
+    }
+
+    fn extend_vote(&self, querier: QuerierWrapper, _height: u64) -> Result<Vec<u8>, Self::Error> {
+        let cfg: AppConfig = querier.query_app_config()?;
+
+        // Retrieve the price ids from the oracle.
+        let ids = query_pyth_ids(&querier, cfg.addresses.oracle)?;
+
+        // Write the IDs to the shared memory.
+        self.ids.write_with(|mut ids_ref| {
+            *ids_ref = ids;
+        });
+
+        // Retreive the VAAs from the shared memory.
+        // Consuming the VAAs to avoid voting for the same prices multiple times.
+        let vaas = self.latest_vaas.write_with(|mut prices_lock| {
+            let prices = prices_lock.clone();
+            *prices_lock = vec![];
+            prices
+        });
+
+        // Don't attach an extension if there are no VAAs.
+        if vaas.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(PriceVote { vaas }.to_json_vec()?)
+    }
+
+    /// Only the structure of the extension is checked. Rejecting a vote is
+    /// harsh, as it may halt the chain if enough validators are affected, so
+    /// VAAs that contain unknown price feeds or fail verification are simply
+    /// ignored during aggregation.
+    fn verify_vote_extension(
+        &self,
+        querier: QuerierWrapper,
+        _height: u64,
+        extension: &[u8],
+    ) -> Result<(), Self::Error> {
+        if extension.is_empty() {
+            return Ok(());
+        }
+
+        let vote: PriceVote = extension.deserialize_json()?;
+
+        let cfg: AppConfig = querier.query_app_config()?;
+        let max = query_pyth_ids(&querier, cfg.addresses.oracle)?.len();
+
+        if vote.vaas.len() > max {
+            return Err(ProposerError::TooManyVaas {
+                max,
+                found: vote.vaas.len(),
+            });
+        }
+
+        for vaa in vote.vaas {
+            PythVaa::new(&NativeApi, vaa.into_inner())
+                .map_err(|err| ProposerError::InvalidVaa(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The vote extension that validators attach to their votes: the latest VAAs
+/// they have fetched from Pyth.
+#[grug::derive(Serde)]
+pub struct PriceVote {
+    pub vaas: Vec<Binary>,
+}
+
+/// Return whether any of the votes has a vote extension attached.
+fn has_price_votes(votes: &[ExtendedVote]) -> bool {
+    votes.iter().any(|vote| !vote.extension.is_empty())
+}
+
+/// Aggregate the VAAs attached to the votes, by selecting for each Pyth price
+/// source the VAA containing the stake-weighted median price.
+///
+/// Returns the VAAs to feed to the oracle, and the index of the VAA selected
+/// for each price source. VAAs that aren't selected for any price source are
+/// left out.
+fn aggregate_price_votes(
+    ids: &[PythId],
+    votes: &[ExtendedVote],
+) -> (Vec<Binary>, BTreeMap<PythId, u32>) {
+    // For each price source, the prices attested by validators, along with
+    // the validator's voting power and the VAA containing the price.
+    let mut attestations = BTreeMap::<PythId, Vec<(i64, u64, &Binary)>>::new();
+
+    // Deserialize the votes upfront, so that the attestations can borrow the
+    // VAAs.
+    let price_votes = votes
+        .iter()
+        .filter(|vote| !vote.extension.is_empty())
+        .filter_map(
+            |vote| match vote.extension.deserialize_json::<PriceVote>() {
+                Ok(price_vote) => Some((vote.power, price_vote)),
+                Err(err) => {
+                    warn!(err = err.to_string(), "Ignoring invalid vote extension");
+                    None
+                },
+            },
+        )
+        .collect::<Vec<_>>();
+
+    for (power, price_vote) in &price_votes {
+        // The newest price of each price source in this vote. A validator's
+        // voting power counts only once for each price source, even if
+        // multiple VAAs in its vote contain the price.
+        let mut newest = BTreeMap::<PythId, (i64, i64, &Binary)>::new();
+
+        for vaa in &price_vote.vaas {
+            let feeds = match PythVaa::new(&NativeApi, vaa.inner().clone()) {
+                Ok(pyth_vaa) => pyth_vaa.unverified(),
+                Err(err) => {
+                    warn!(err = err.to_string(), "Ignoring invalid VAA");
+                    continue;
+                },
+            };
+
+            for feed in feeds {
+                let id = PythId::from_inner(feed.id.to_bytes());
+                if !ids.contains(&id) {
+                    continue;
+                }
+
+                let price = feed.get_price_unchecked();
+                let is_newer = newest.get(&id).map_or(true, |(publish_time, ..)| {
+                    price.publish_time > *publish_time
+                });
+
+                if is_newer {
+                    newest.insert(id, (price.publish_time, price.price, vaa));
+                }
+            }
+        }
+
+        for (id, (_, price, vaa)) in newest {
+            attestations
+                .entry(id)
+                .or_default()
+                .push((price, *power, vaa));
+        }
+    }
+
+    let mut vaas = Vec::<Binary>::new();
+    let mut selections = BTreeMap::new();
+
+    for (id, mut prices) in attestations {
+        prices.sort_by_key(|(price, ..)| *price);
+
+        // Find the price at which the cumulative voting power reaches half of
+        // the total.
+        let total_power = prices
+            .iter()
+            .map(|(_, power, _)| *power as u128)
+            .sum::<u128>();
+        let mut cumulative_power = 0;
+
+        let Some((_, _, vaa)) = prices.into_iter().find(|(_, power, _)| {
+            cumulative_power += *power as u128;
+            cumulative_power * 2 >= total_power
+        }) else {
+            continue;
+        };
+
+        let index = vaas.iter().position(|v| v == vaa).unwrap_or_else(|| {
+            vaas.push(vaa.clone());
+            vaas.len() - 1
+        });
+
+        selections.insert(id, index as u32);
+    }
+
+    (vaas, selections)
 }
 
-// ----------------------------------- tests -----------------------------------
-
-#[cfg(test)]
-mod test {
-    use {super::LatestVaaResponse, grug::JsonDeExt};
-
-    #[test]
-    fn deserializing_pyth_response() {
-        r#"{
-          "binary": {
-            "encoding": "base64",
-            "data": [
-              "UE5BVQEAAAADuAEAAAAEDQBkr0PLb+gk8uvpb1vCCnSzrkNBWuAKD+4/oHA1HhL2rywRRNl4NEsUMyNDHVVFJz7sb2TqUXbIVKSDR+cXZk12AQHIF5lSaXo4Br8HN9I8FxSKI+k39d0G/hfGGcg42L1lmhol2f3hJRw32z9e9ktuCvOIClAe0U0t8hQk2meeHWReAARHzS7dEnqYLo5cM0ct+0lmMftM+SER9GP/Kr/l1nnUaRNff+2443LwCqOay1A0DSn6sOa6FO16w5mbgsNiUuMlAAhiIiNh1QIxaoKUydS3R0MnKoBkdt7ixtVCvK/GPi0PeC3goY+ZgaheHaVYt6lfjD0nwITz2bdYFZNq2SqO510VAAoHTixQaLHPPgi72kww0j5hOlJn11W1Rz8LAGGl0gk7/GZwEhiBUCuUCfTFwpHqX4UHJIXftR0SV1mS6UB3XLV6AAtAVczuOpFCCPiH9Sg9I0l1xitkQotpP8h+di11JJ3CVkm6vRU7zy1KrhYFsoV082IZTi0XN0Xdv5fWZZdg3Hx5AAzpW38X3a88bFIoys/jeflLAN+A9VeABd5HN9D6snOgI3o5FXftoZjNP0c8Xd/J8rTlUgBIOgsyGFh2vRjJbe6JAQ2+2P9NCAAhBnOYoRjASsA/XlI2FDEGK58ati8kz4vJGyO9B8O6sdZiKE70ieoDirBl8puIkYb0sPU8vov/xF1kAA4cPqmQw6zUazq7F8VE0FSe9C2KD3bD2drA/5rkFExyABw+4XL/4KGbA02YEvp4rGpQvBHKjyS7HhdfuWaspCtjAQ8IoVHbmdLHcnc1dJLgduCtMOPVB3+kpJZBLRfu962mRhG6zLmIr5ioO8/HSsDSQWLWGfi7u2z+g9Nw0CVVhmwmARBAmbiFQ3nI4Idyu9XHuY9r9FaQa1rAcdCuDEGCZPdbphqX7kckA6r95J01ERAqLHwNvZDzuTTgY00Qcuh4v7bfABELTI+OKdU8zctRIb+HJxqQJKeCuBc2+zFo0Tfjs7A8Ix4mszdY/c+1iKZjQLP8MvDekc1rPgjCbhJeZrvoz7xHABJoPDAMGFjauAjWB3g8EJHV+V+oTH5pYLXfZrXdUzzHxTj+JdMaJNIl7EN9MQ7hPzwKkkvwEKuHx661sLdMwGt1AWc3bGMAAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFkcwYAUFVV1YAAAAAAAqelroAACcQy4n8eAYQTYIOJi7hMVMnT8+xofsBAFUA5i32yLSoX+GmfbRNwS3l2zMPesZrctxliv7fD0pBW0MAAAgFdR9DkgAAAAErfT7c////+AAAAABnN2xjAAAAAGc3bGMAAAgVwbgMwAAAAAErXavAC2svmTTo0dM2ChAXPyvygP0QyQvSR/BV5sMFociUhrsruHLNPmvHkOxzZMH4NQzepTk6Wc6cGiVU1RVKdqDYcPPsm+N2jsKYH5zp65jeHXG0h5pR53BajAgRmNmq7xzBP5e1vT6gv5CHEbv1QGAvbGXSReuOOj1LR29RgjJiM/A/PCysDvPuhI5r9QqT69mMvlraw2QdqlRGmTsq/1LlskABMC+bL64zMUlwLmo0N3kxXuS3Y906SY9J733py3EPMQMRrGD5C185kIK9iYlvyEOx5Tq0wvQeaB2/J7Q="
-            ]
-          }
-        }"#
-        .deserialize_json::<LatestVaaResponse>()
-        .unwrap();
+/// Validate the transactions in a proposal, given the votes carried in it.
+fn validate_proposal(
+    querier: &QuerierWrapper,
+    txs: &[Bytes],
+    votes: &[ExtendedVote],
+) -> Result<(), ProposerError> {
+    let cfg: AppConfig = querier.query_app_config()?;
+    let mut seen = BTreeSet::new();
+
+    for (index, raw_tx) in txs.iter().enumerate() {
+        if !seen.insert(raw_tx) {
+            return Err(ProposerError::DuplicateTx { index });
+        }
+
+        // Transactions that can't be deserialized are rejected when the
+        // block is finalized, so they're no concern of ours here. We only
+        // need to check the ones sent by the oracle, which are exempt from
+        // the usual authentication.
+        let Ok(tx) = raw_tx.deserialize_json::<Tx>() else {
+            continue;
+        };
+
+        if tx.sender != cfg.addresses.oracle {
+            continue;
+        }
+
+        if index != 0 {
+            return Err(ProposerError::OracleTxNotFirst { index });
+        }
+
+        if tx.gas_limit > GAS_LIMIT {
+            return Err(ProposerError::OracleTxGasLimitTooHigh {
+                max: GAS_LIMIT,
+                found: tx.gas_limit,
+            });
+        }
+
+        // Assert the transaction contains exactly 1 message, which feeds
+        // prices to the oracle without sending any funds.
+        let mut msgs = tx.msgs.iter();
+
+        let (
+            Some(Message::Execute(MsgExecute {
+                contract,
+                msg,
+                funds,
+            })),
+            None,
+        ) = (msgs.next(), msgs.next())
+        else {
+            return Err(ProposerError::MalformedOracleTx);
+        };
+
+        if *contract != cfg.addresses.oracle || !funds.is_empty() {
+            return Err(ProposerError::MalformedOracleTx);
+        }
+
+        let ids = query_pyth_ids(querier, cfg.addresses.oracle)?;
+
+        match msg.clone().deserialize_json() {
+            // The proposer must have aggregated the votes exactly as we would.
+            Ok(ExecuteMsg::FeedAggregatedPrices { vaas, selections }) => {
+                if aggregate_price_votes(&ids, votes) != (vaas.into_inner(), selections) {
+                    return Err(ProposerError::AggregatedPricesMismatch);
+                }
+            },
+            // Without vote extensions, the proposer feeds the VAAs it has
+            // fetched itself. An honest proposer feeds at most one VAA per
+            // Pyth price source.
+            Ok(ExecuteMsg::FeedPrices(vaas)) => {
+                if has_price_votes(votes) {
+                    return Err(ProposerError::UnexpectedFeedPrices);
+                }
+
+                if vaas.len() > ids.len() {
+                    return Err(ProposerError::TooManyVaas {
+                        max: ids.len(),
+                        found: vaas.len(),
+                    });
+                }
+            },
+            _ => return Err(ProposerError::MalformedOracleTx),
+        }
     }
+
+    Ok(())
+}
+
+/// Query the IDs of the Pyth price sources registered in the oracle.
+fn query_pyth_ids(querier: &QuerierWrapper, oracle: Addr) -> StdResult<Vec<PythId>> {
+    // TODO: optimize this by using the raw WasmScan query.
+    Ok(querier
+        .query_wasm_smart(oracle, QueryPriceSourcesRequest {
+            start_after: None,
+            limit: Some(u32::MAX),
+        })?
+        .into_values()
+        .filter_map(|price_source| {
+            // Only Pyth prices need to be fed; the others are derived
+            // on-chain.
+            if let PriceSource::Pyth { id, .. } = price_source {
+                Some(id)
+            } else {
+                None
+            }
+        })
+        .collect())
 }
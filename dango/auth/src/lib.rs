@@ -12,16 +12,31 @@ use {
         DangoQuerier,
     },
     grug::{
-        json, Addr, Api, AuthCtx, AuthMode, BorshDeExt, Counter, Inner, JsonDeExt, JsonSerExt,
-        StdResult, Tx,
+        json, Addr, Api, AuthCtx, AuthMode, BorshDeExt, Inner, Item, JsonDeExt, JsonSerExt,
+        StdResult, Storage, Tx,
     },
+    std::collections::BTreeSet,
 };
 
-/// Expected nonce number of the next transaction this account sends.
+/// Max number of most recent nonces an account keeps track of.
+pub const MAX_SEEN_NONCES: usize = 20;
+
+/// The most recent nonces this account has seen, up to `MAX_SEEN_NONCES` of
+/// them.
 ///
 /// All three account types (spot, margin, Safe) stores their nonces in this
 /// same storage slot.
-pub const NEXT_NONCE: Counter<u32> = Counter::new("nonce", 0, 1);
+///
+/// See `book/notes/nonces.md` for how this allows transactions to be submitted
+/// out of order.
+pub const SEEN_NONCES: Item<BTreeSet<u32>> = Item::new("seen_nonces");
+
+/// Expected nonce number of the next transaction this account sends, as tracked
+/// by accounts created before they switched to `SEEN_NONCES`.
+///
+/// Ignored once `SEEN_NONCES` exists. See [`load_seen_nonces`] for how accounts
+/// are migrated.
+pub const LEGACY_NEXT_NONCE: Item<u32> = Item::new("nonce");
 
 /// Authenticate a transaction by ensuring:
 ///
@@ -92,22 +107,13 @@ pub fn verify_nonce_and_signature(
         tx.data.deserialize_json()?
     };
 
-    // Increment the nonce.
-    let (nonce, _) = NEXT_NONCE.increment(ctx.storage)?;
-
-    // Verify nonce.
     match ctx.mode {
-        // For `CheckTx`, we only make sure the tx's nonce is no smaller than
-        // the stored nonce. This allows the account to broadcast multiple
-        // txs for the same block.
-        AuthMode::Check => {
-            ensure!(
-                metadata.nonce >= nonce,
-                "nonce is too old: expecting at least {}, found {}",
-                nonce,
-                metadata.nonce
-            );
-
+        // For `CheckTx` and `FinalizeBlock`, we make sure the tx hasn't expired
+        // and its nonce is acceptable, then insert the nonce into the seen ones.
+        //
+        // Note that state changes made during `CheckTx` are discarded, so the
+        // account may broadcast multiple txs for the same block, in any order.
+        AuthMode::Check | AuthMode::Finalize => {
             if let Some(expiry) = metadata.expiry {
                 ensure!(
                     expiry > ctx.block.timestamp,
@@ -115,16 +121,18 @@ pub fn verify_nonce_and_signature(
                     expiry
                 );
             }
-        },
-        // For `FinalizeBlock`, we make sure the tx's nonce matches exactly
-        // the stored nonce.
-        AuthMode::Finalize => {
-            ensure!(
-                metadata.nonce == nonce,
-                "incorrect nonce: expecting {}, got {}",
-                nonce,
-                metadata.nonce
-            );
+
+            let mut nonces = load_seen_nonces(ctx.storage)?;
+
+            verify_nonce(&nonces, metadata.nonce)?;
+
+            nonces.insert(metadata.nonce);
+
+            if nonces.len() > MAX_SEEN_NONCES {
+                nonces.pop_first();
+            }
+
+            SEEN_NONCES.save(ctx.storage, &nonces)?;
         },
         // No need to verify nonce in simulation mode.
         AuthMode::Simulate => (),
@@ -180,7 +188,7 @@ pub fn verify_nonce_and_signature(
                     &VerifyData::Standard {
                         chain_id: ctx.chain_id,
                         sign_doc,
-                        nonce,
+                        nonce: metadata.nonce,
                     },
                 )?;
             } else {
@@ -188,7 +196,7 @@ pub fn verify_nonce_and_signature(
                 verify_signature(ctx.api, key, signature, &VerifyData::Standard {
                     chain_id: ctx.chain_id,
                     sign_doc,
-                    nonce,
+                    nonce: metadata.nonce,
                 })?;
             }
         },
@@ -198,6 +206,54 @@ pub fn verify_nonce_and_signature(
     Ok(())
 }
 
+/// Load the most recent nonces the account has seen.
+///
+/// Accounts created before the switch to `SEEN_NONCES` only have the legacy
+/// next nonce counter, meaning all nonces before it have been used. For them,
+/// the window is seeded with the most recent of these nonces; as a nonce must
+/// be newer than the oldest one in the window, none of them can be replayed.
+pub fn load_seen_nonces(storage: &dyn Storage) -> StdResult<BTreeSet<u32>> {
+    if let Some(nonces) = SEEN_NONCES.may_load(storage)? {
+        return Ok(nonces);
+    }
+
+    let next_nonce = LEGACY_NEXT_NONCE.may_load(storage)?.unwrap_or(0);
+
+    Ok((next_nonce.saturating_sub(MAX_SEEN_NONCES as u32)..next_nonce).collect())
+}
+
+/// Ensure the nonce is acceptable, given the most recent nonces the account
+/// has seen. That is, the nonce
+///
+/// - must not have been seen, and
+/// - must be newer than the oldest seen one, if any, regardless of how many
+///   nonces have been seen.
+///
+/// Nonces older than the oldest seen one have either been used, or been
+/// skipped, in which case the transactions signed with them are considered no
+/// longer relevant. This holds whether or not the window is full, such that
+/// whether a held-back transaction is accepted doesn't depend on how many
+/// transactions the account has sent since. Therefore, the first nonce an
+/// account sees is the lower bound of the nonces it accepts.
+fn verify_nonce(seen_nonces: &BTreeSet<u32>, nonce: u32) -> anyhow::Result<()> {
+    ensure!(
+        !seen_nonces.contains(&nonce),
+        "nonce is already seen: {}",
+        nonce
+    );
+
+    if let Some(oldest) = seen_nonces.first() {
+        ensure!(
+            nonce > *oldest,
+            "nonce is too old: expecting newer than {}, got {}",
+            oldest,
+            nonce
+        );
+    }
+
+    Ok(())
+}
+
 fn verify_signature(
     api: &dyn Api,
     key: Key,
@@ -291,6 +347,20 @@ fn verify_signature(
 
             api.secp256k1_verify(&sign_bytes, &sig, &pk)?;
         },
+        (Key::Ed25519(pk), Signature::Ed25519(sig)) => {
+            // Solana wallets sign messages with pure Ed25519, which hashes the
+            // message together with the signature's nonce, so the message
+            // can't be prehashed; `Api::ed25519_verify` verifies the Ed25519ph
+            // variant instead.
+            let sign_bytes = data.as_sign_bytes()?;
+
+            api.ed25519_pure_verify(&sign_bytes, &sig, &pk)?;
+        },
+        (Key::Schnorr(pk), Signature::Schnorr(sig)) => {
+            let sign_bytes = api.sha2_256(&data.as_sign_bytes()?);
+
+            api.secp256k1_schnorr_verify(&sign_bytes, &sig, &pk)?;
+        },
         _ => bail!("key and credential types don't match!"),
     }
     Ok(())
@@ -324,13 +394,73 @@ mod tests {
             account_factory::Username,
             config::{AppAddresses, AppConfig},
         },
-        grug::{btree_map, Addr, AuthMode, Hash256, MockContext, MockQuerier},
+        grug::{btree_map, Addr, AuthMode, Hash256, MockContext, MockQuerier, MockStorage},
         std::str::FromStr,
     };
 
     /// Address of the account factory for use in the following tests.
     const ACCOUNT_FACTORY: Addr = Addr::mock(254);
 
+    #[test]
+    fn verifying_nonce() {
+        // Before any nonce has been seen, any nonce is acceptable.
+        assert!(verify_nonce(&BTreeSet::new(), 0).is_ok());
+        assert!(verify_nonce(&BTreeSet::new(), 100).is_ok());
+
+        // The nonce must be unseen, and newer than the oldest seen one, even if
+        // fewer than `MAX_SEEN_NONCES` nonces have been seen.
+        let mut seen_nonces = BTreeSet::from([5, 6, 7, 9, 10]);
+        assert!(verify_nonce(&seen_nonces, 0).is_err());
+        assert!(verify_nonce(&seen_nonces, 5).is_err());
+        assert!(verify_nonce(&seen_nonces, 7).is_err());
+        assert!(verify_nonce(&seen_nonces, 8).is_ok());
+        assert!(verify_nonce(&seen_nonces, 12).is_ok());
+
+        // Same once `MAX_SEEN_NONCES` nonces have been seen.
+        seen_nonces.extend(11..(11 + MAX_SEEN_NONCES as u32 - 5));
+        assert_eq!(seen_nonces.len(), MAX_SEEN_NONCES);
+        assert!(verify_nonce(&seen_nonces, 4).is_err());
+        assert!(verify_nonce(&seen_nonces, 5).is_err());
+        assert!(verify_nonce(&seen_nonces, 8).is_ok());
+        assert!(verify_nonce(&seen_nonces, 100).is_ok());
+        assert!(verify_nonce(&seen_nonces, 11).is_err());
+    }
+
+    #[test]
+    fn migrating_legacy_nonce() {
+        // An account without any nonce.
+        let storage = MockStorage::new();
+        assert!(load_seen_nonces(&storage).unwrap().is_empty());
+
+        // An account that has sent fewer than `MAX_SEEN_NONCES` transactions.
+        // All nonces it has used are considered seen.
+        let mut storage = MockStorage::new();
+        LEGACY_NEXT_NONCE.save(&mut storage, &3).unwrap();
+        let seen_nonces = load_seen_nonces(&storage).unwrap();
+        assert_eq!(seen_nonces, BTreeSet::from([0, 1, 2]));
+        for nonce in 0..3 {
+            assert!(verify_nonce(&seen_nonces, nonce).is_err());
+        }
+        assert!(verify_nonce(&seen_nonces, 3).is_ok());
+
+        // An account that has sent more. The most recent nonces are considered
+        // seen, and older ones are too old.
+        let mut storage = MockStorage::new();
+        LEGACY_NEXT_NONCE.save(&mut storage, &100).unwrap();
+        let seen_nonces = load_seen_nonces(&storage).unwrap();
+        assert_eq!(seen_nonces.len(), MAX_SEEN_NONCES);
+        for nonce in 0..100 {
+            assert!(verify_nonce(&seen_nonces, nonce).is_err());
+        }
+        assert!(verify_nonce(&seen_nonces, 100).is_ok());
+
+        // Once the account has `SEEN_NONCES`, the legacy nonce is ignored.
+        SEEN_NONCES
+            .save(&mut storage, &BTreeSet::from([100]))
+            .unwrap();
+        assert_eq!(load_seen_nonces(&storage).unwrap(), BTreeSet::from([100]));
+    }
+
     #[test]
     fn passkey_authentication() {
         let user_address = Addr::from_str("0x4857ff85aa9d69c73bc86eb45949455b45cca580").unwrap();
@@ -383,6 +513,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -427,6 +558,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -530,6 +662,82 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
+                    ibc_transfer: Addr::mock(0),
+                    oracle: Addr::mock(1),
+                    lending: Addr::mock(0), // doesn't matter for this test
+                },
+                collateral_powers: btree_map! {},
+            })
+            .unwrap()
+            .with_raw_contract_storage(ACCOUNT_FACTORY, |storage| {
+                ACCOUNTS_BY_USER
+                    .insert(storage, (&user_username, user_address))
+                    .unwrap();
+                KEYS.save(storage, (&user_username, user_keyhash), &user_key)
+                    .unwrap();
+            });
+
+        let mut ctx = MockContext::new()
+            .with_querier(querier)
+            .with_contract(user_address)
+            .with_chain_id("dev-3")
+            .with_mode(AuthMode::Finalize);
+
+        authenticate_tx(ctx.as_auth(), tx.deserialize_json().unwrap(), None).unwrap();
+    }
+
+    /// The signature is over the sign doc, as signed by a Solana wallet's
+    /// `signMessage`, i.e. pure Ed25519 over the message's UTF-8 bytes.
+    #[test]
+    fn ed25519_authentication() {
+        let user_address = Addr::from_str("0x5c8cdb0bd55dc3ed6f2feab2a14e30fa1f5e2e3a").unwrap();
+        let user_username = Username::from_str("solana").unwrap();
+        let user_keyhash =
+            Hash256::from_str("234471FA353577EA8A22DD2C0FCAC48A4E20CFF34C4F70592D99C91EF2F1E6FB")
+                .unwrap();
+        let user_key = Key::Ed25519(
+            [
+                249, 98, 159, 42, 185, 79, 230, 199, 223, 25, 240, 180, 80, 142, 98, 120, 101, 37,
+                131, 249, 49, 12, 167, 255, 61, 233, 142, 197, 203, 142, 194, 207,
+            ]
+            .into(),
+        );
+
+        let tx = r#"{
+          "sender": "0x5c8cdb0bd55dc3ed6f2feab2a14e30fa1f5e2e3a",
+          "credential": {
+            "standard": {
+              "signature": {
+                "ed25519": "8hQcLgxk9F/JbOYn5zsuqIu4A4fs1aJT6Au/7+6GD3ftj8hP7gQtBvUmpG4v285mJv2MWJY+aEjHJ8RuNf8ZBA=="
+              },
+              "key_hash": "234471FA353577EA8A22DD2C0FCAC48A4E20CFF34C4F70592D99C91EF2F1E6FB"
+            }
+          },
+          "data": {
+            "username": "solana",
+            "nonce": 0,
+            "chain_id": "dev-3"
+          },
+          "msgs": [
+            {
+              "transfer": {
+                "to": "0x064c5e20b422b5d817fe800119dac0ab43b17a80",
+                "coins": {
+                  "uusdc": "1000000"
+                }
+              }
+            }
+          ],
+          "gas_limit": 2566278
+        }"#;
+
+        let querier = MockQuerier::new()
+            .with_app_config(AppConfig {
+                addresses: AppAddresses {
+                    account_factory: ACCOUNT_FACTORY,
+                    // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
@@ -574,6 +782,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory: ACCOUNT_FACTORY,
                     // Address below don't matter for this test.
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     oracle: Addr::mock(1),
                     lending: Addr::mock(0), // doesn't matter for this test
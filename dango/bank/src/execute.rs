@@ -3,8 +3,8 @@ use {
     anyhow::{bail, ensure},
     dango_types::bank::{ExecuteMsg, InstantiateMsg, Metadata},
     grug::{
-        Addr, BankMsg, Denom, IsZero, MutableCtx, Number, NumberConst, Part, Response, StdResult,
-        Storage, SudoCtx, Uint128,
+        Addr, BankMsg, Coins, Denom, IsZero, MutableCtx, Number, NumberConst, Order, Part,
+        Response, StdResult, Storage, SudoCtx, Uint128,
     },
     std::collections::HashMap,
 };
@@ -161,6 +161,30 @@ pub fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> StdResult<Response> {
     Ok(Response::new())
 }
 
+/// Set an account's balances to the given coins, adjusting the total supplies
+/// accordingly. Balances of denoms not among the coins are set to zero.
+///
+/// This is for overriding balances in simulations, by registering it with
+/// `grug_app::App::with_balance_overrides`.
+pub fn override_balances(storage: &mut dyn Storage, address: Addr, coins: &Coins) -> StdResult<()> {
+    let old_balances = BALANCES
+        .prefix(&address)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (denom, amount) in old_balances {
+        decrease_supply(storage, &denom, amount)?;
+        decrease_balance(storage, &address, &denom, amount)?;
+    }
+
+    for coin in coins {
+        increase_supply(storage, coin.denom, *coin.amount)?;
+        increase_balance(storage, &address, coin.denom, *coin.amount)?;
+    }
+
+    Ok(())
+}
+
 fn increase_supply(
     storage: &mut dyn Storage,
     denom: &Denom,
@@ -24,4 +24,14 @@ impl HomeDirectory {
     pub fn indexer_dir(&self) -> PathBuf {
         self.home.join("indexer")
     }
+
+    /// Used for state sync snapshots.
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.home.join("snapshots")
+    }
+
+    /// Used for compiled Wasm modules.
+    pub fn wasm_dir(&self) -> PathBuf {
+        self.home.join("wasm")
+    }
 }
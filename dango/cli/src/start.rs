@@ -1,15 +1,16 @@
 use {
     crate::home_directory::HomeDirectory,
     anyhow::anyhow,
-    clap::Parser,
-    dango_app::ProposalPreparer,
+    clap::{ArgAction, Parser},
+    dango_app::{FilePriceFeeder, HermesPriceFeeder, ProposalPreparer, DEFAULT_HERMES_URL},
     dango_genesis::build_rust_codes,
-    grug_app::{App, AppError, Db, Indexer, NullIndexer},
+    grug_app::{App, AppError, Db, Indexer, NullIndexer, SnapshotManager},
     grug_db_disk::DiskDb,
     grug_types::HashExt,
     grug_vm_hybrid::HybridVm,
+    grug_vm_wasm::DiskCache,
     indexer_sql::non_blocking_indexer,
-    std::{fmt::Debug, time},
+    std::{fmt::Debug, path::PathBuf, time},
     tower::ServiceBuilder,
     tower_abci::v038::{split, Server},
 };
@@ -24,6 +25,14 @@ pub struct StartCmd {
     #[arg(long, default_value = "1000")]
     wasm_cache_capacity: usize,
 
+    /// Persist compiled wasm modules on disk, so that they aren't re-compiled after a restart
+    #[arg(long, default_value = "true", action = ArgAction::Set)]
+    wasm_disk_cache: bool,
+
+    /// Execute the transactions in a block in parallel
+    #[arg(long, default_value = "false")]
+    parallel_execution: bool,
+
     /// Gas limit when serving query requests
     #[arg(long, default_value_t = u64::MAX)]
     query_gas_limit: u64,
@@ -39,6 +48,22 @@ pub struct StartCmd {
     /// The indexer database url
     #[arg(long, default_value = "postgres://localhost")]
     indexer_database_url: String,
+
+    /// Pyth Hermes API endpoint to pull oracle prices from
+    #[arg(long, default_value = DEFAULT_HERMES_URL)]
+    hermes_url: String,
+
+    /// Read oracle prices from a JSON file of base64-encoded VAAs instead of Hermes
+    #[arg(long)]
+    price_feed_file: Option<PathBuf>,
+
+    /// Create a state sync snapshot every this many blocks; zero means do not create snapshots
+    #[arg(long, default_value = "0")]
+    snapshot_interval: u64,
+
+    /// Number of most recent state sync snapshots to keep; zero means keep all
+    #[arg(long, default_value = "2")]
+    snapshot_keep_recent: usize,
 }
 
 impl StartCmd {
@@ -73,7 +98,7 @@ impl StartCmd {
             .expect("Can't start indexer");
 
         let codes = build_rust_codes();
-        let vm = HybridVm::new(self.wasm_cache_capacity, [
+        let mut vm = HybridVm::new(self.wasm_cache_capacity, [
             codes.account_factory.to_bytes().hash256(),
             codes.account_margin.to_bytes().hash256(),
             codes.account_safe.to_bytes().hash256(),
@@ -88,14 +113,31 @@ impl StartCmd {
             codes.vesting.to_bytes().hash256(),
         ]);
 
-        let app = App::new(
-            db,
-            vm,
-            ProposalPreparer::new(),
-            indexer,
-            self.query_gas_limit,
+        if self.wasm_disk_cache {
+            vm.wasm = vm
+                .wasm
+                .with_disk_cache(DiskCache::open(app_dir.wasm_dir())?);
+        }
+
+        let proposal_preparer = match self.price_feed_file {
+            Some(path) => ProposalPreparer::new_with_feeder(FilePriceFeeder::new(path)),
+            None => ProposalPreparer::new_with_feeder(HermesPriceFeeder::new(self.hermes_url)),
+        };
+
+        let snapshots = SnapshotManager::new(
+            app_dir.snapshots_dir(),
+            self.snapshot_interval,
+            self.snapshot_keep_recent,
         );
 
+        let mut app = App::new(db, vm, proposal_preparer, indexer, self.query_gas_limit)
+            .with_snapshots(snapshots)
+            .with_balance_overrides(dango_bank::override_balances);
+
+        if self.parallel_execution {
+            app = app.with_parallel_execution();
+        }
+
         let (consensus, mempool, snapshot, info) = split::service(app, 1);
 
         let mempool = ServiceBuilder::new()
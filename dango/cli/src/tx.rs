@@ -4,7 +4,6 @@ use {
     colored::Colorize,
     dango_client::{SigningKey, SingleSigner},
     dango_types::config::AppConfig,
-    grug_app::GAS_COSTS,
     grug_client::{GasOption, SigningClient},
     grug_types::{json, Addr, Binary, Coins, Hash256, Json, JsonDeExt, Message, NonEmpty, Signer},
     std::{fs::File, io::Read, path::PathBuf, str::FromStr},
@@ -191,12 +190,14 @@ impl TxCmd {
             let gas_opt = if let Some(gas_limit) = self.gas_limit {
                 GasOption::Predefined { gas_limit }
             } else {
+                let cfg = client.query_config(None).await?;
+
                 GasOption::Simulate {
                     scale: self.gas_adjustment,
                     // We always increase the simulated gas consumption by this
                     // amount, since signature verification is skipped during
                     // simulation.
-                    flat_increase: GAS_COSTS.secp256k1_verify,
+                    flat_increase: cfg.gas_costs.secp256k1_verify,
                 }
             };
 
@@ -1,4 +1,5 @@
 mod keystore;
+mod secret;
 mod signer;
 
-pub use {keystore::*, signer::*};
+pub use {keystore::*, secret::*, signer::*};
@@ -0,0 +1,156 @@
+use {
+    crate::SigningKey,
+    dango_types::auth::{Key, Signature},
+    ed25519_dalek::Signer,
+    grug::{ByteArray, Hash256, HashExt},
+    grug_crypto::sha2_256,
+    rand::{rngs::OsRng, Rng},
+};
+
+/// A private key that can sign transactions on behalf of a Dango user, in the
+/// format expected by `dango_auth::verify_signature`.
+pub trait Secret {
+    /// Return the public key, as it's associated with the username in the
+    /// account factory.
+    fn key(&self) -> Key;
+
+    /// Return the hash of the public key, which identifies the key among those
+    /// associated with the username.
+    fn key_hash(&self) -> Hash256;
+
+    /// Sign the given sign bytes, the way the key's signature scheme expects,
+    /// e.g. by hashing them first.
+    fn sign(&self, sign_bytes: &[u8]) -> Signature;
+}
+
+impl Secret for SigningKey {
+    fn key(&self) -> Key {
+        Key::Secp256k1(ByteArray::from_inner(self.public_key()))
+    }
+
+    fn key_hash(&self) -> Hash256 {
+        self.public_key().hash256()
+    }
+
+    fn sign(&self, sign_bytes: &[u8]) -> Signature {
+        Signature::Secp256k1(self.sign_digest(sha2_256(sign_bytes)).into())
+    }
+}
+
+// ---------------------------------- ed25519 ----------------------------------
+
+/// A wrapper over an Ed25519 [`SigningKey`](ed25519_dalek::SigningKey),
+/// providing a handy API to work with.
+#[derive(Debug, Clone)]
+pub struct Ed25519SigningKey {
+    inner: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519SigningKey {
+    /// Generate a random Ed25519 private key.
+    pub fn new_random() -> Self {
+        Self {
+            inner: ed25519_dalek::SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    /// Recover an Ed25519 private key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self {
+            inner: ed25519_dalek::SigningKey::from_bytes(&bytes),
+        }
+    }
+
+    /// Sign the given message with pure Ed25519, like a Solana wallet's
+    /// `signMessage` does. The message isn't prehashed.
+    pub fn sign_message(&self, msg: &[u8]) -> [u8; 64] {
+        self.inner.sign(msg).to_bytes()
+    }
+
+    /// Return the private key as a byte array.
+    pub fn private_key(&self) -> [u8; 32] {
+        self.inner.to_bytes()
+    }
+
+    /// Return the public key as a byte array.
+    pub fn public_key(&self) -> [u8; 32] {
+        self.inner.verifying_key().to_bytes()
+    }
+}
+
+impl Secret for Ed25519SigningKey {
+    fn key(&self) -> Key {
+        Key::Ed25519(ByteArray::from_inner(self.public_key()))
+    }
+
+    fn key_hash(&self) -> Hash256 {
+        self.public_key().hash256()
+    }
+
+    fn sign(&self, sign_bytes: &[u8]) -> Signature {
+        Signature::Ed25519(self.sign_message(sign_bytes).into())
+    }
+}
+
+// ---------------------------------- schnorr ----------------------------------
+
+/// A wrapper over a BIP-340 Schnorr [`SigningKey`](k256::schnorr::SigningKey),
+/// providing a handy API to work with.
+#[derive(Clone)]
+pub struct SchnorrSigningKey {
+    inner: k256::schnorr::SigningKey,
+}
+
+impl SchnorrSigningKey {
+    /// Generate a random Secp256k1 private key for Schnorr signatures.
+    pub fn new_random() -> Self {
+        Self {
+            inner: k256::schnorr::SigningKey::random(&mut OsRng),
+        }
+    }
+
+    /// Recover a Secp256k1 private key for Schnorr signatures from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> anyhow::Result<Self> {
+        Ok(Self {
+            inner: k256::schnorr::SigningKey::from_bytes(&bytes)?,
+        })
+    }
+
+    /// Sign the given SHA2-256 digest.
+    pub fn sign_digest(&self, digest: [u8; 32]) -> [u8; 64] {
+        // Signing only fails if the nonce derived from the auxiliary randomness
+        // is zero, which has a negligible probability. In that case, we simply
+        // try again with different randomness.
+        loop {
+            let aux_rand = OsRng.gen();
+
+            if let Ok(signature) = self.inner.sign_prehash_with_aux_rand(&digest, &aux_rand) {
+                return signature.to_bytes();
+            }
+        }
+    }
+
+    /// Return the private key as a byte array.
+    pub fn private_key(&self) -> [u8; 32] {
+        self.inner.to_bytes().into()
+    }
+
+    /// Return the x-only public key as a byte array.
+    pub fn public_key(&self) -> [u8; 32] {
+        self.inner.verifying_key().to_bytes().into()
+    }
+}
+
+impl Secret for SchnorrSigningKey {
+    fn key(&self) -> Key {
+        Key::Schnorr(ByteArray::from_inner(self.public_key()))
+    }
+
+    fn key_hash(&self) -> Hash256 {
+        self.public_key().hash256()
+    }
+
+    fn sign(&self, sign_bytes: &[u8]) -> Signature {
+        Signature::Schnorr(self.sign_digest(sha2_256(sign_bytes)).into())
+    }
+}
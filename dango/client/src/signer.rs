@@ -1,23 +1,28 @@
 use {
-    crate::SigningKey,
+    crate::{Secret, SigningKey},
+    anyhow::anyhow,
     bip32::{Language, Mnemonic},
     dango_types::{
         account::spot,
         account_factory::Username,
-        auth::{Credential, Key, Metadata, SignDoc, Signature, StandardCredential},
+        auth::{Credential, Key, Metadata, SignDoc, StandardCredential},
     },
     grug::{
-        Addr, Addressable, ByteArray, Client, Defined, Hash256, HashExt, Inner, JsonSerExt,
-        MaybeDefined, Message, NonEmpty, Signer, StdResult, Tx, Undefined, UnsignedTx,
+        Addr, Addressable, Client, Defined, Hash256, Inner, JsonSerExt, MaybeDefined, Message,
+        NonEmpty, Number, Signer, StdResult, Tx, Undefined, UnsignedTx,
     },
-    std::str::FromStr,
+    std::{collections::BTreeSet, str::FromStr},
 };
 
 pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
 
 /// Utility for signing transactions in the format by Dango's single-signature
 /// accounts, i.e. spot and margin accounts.
-pub struct SingleSigner<T>
+///
+/// The signer uses an Secp256k1 key by default, but can use any key that
+/// implements [`Secret`], e.g. [`Ed25519SigningKey`](crate::Ed25519SigningKey)
+/// or [`SchnorrSigningKey`](crate::SchnorrSigningKey).
+pub struct SingleSigner<T, S = SigningKey>
 where
     T: MaybeDefined<u32>,
 {
@@ -26,44 +31,27 @@ where
     pub key: Key,
     pub key_hash: Hash256,
     pub nonce: T,
-    sk: SigningKey,
+    sk: S,
 }
 
-impl SingleSigner<Undefined<u32>> {
-    pub fn new(username: &str, address: Addr, sk: SigningKey) -> anyhow::Result<Self> {
+impl<S> SingleSigner<Undefined<u32>, S>
+where
+    S: Secret,
+{
+    pub fn new(username: &str, address: Addr, sk: S) -> anyhow::Result<Self> {
         let username = Username::from_str(username)?;
 
         Ok(Self {
             username,
             address,
-            key: Key::Secp256k1(ByteArray::from_inner(sk.public_key())),
-            key_hash: sk.public_key().hash256(),
+            key: sk.key(),
+            key_hash: sk.key_hash(),
             nonce: Undefined::new(),
             sk,
         })
     }
 
-    pub fn new_random(username: &str, address: Addr) -> anyhow::Result<Self> {
-        Self::new(username, address, SigningKey::new_random())
-    }
-
-    pub fn from_private_key(username: &str, address: Addr, key: [u8; 32]) -> anyhow::Result<Self> {
-        Self::new(username, address, SigningKey::from_bytes(key)?)
-    }
-
-    pub fn from_mnemonic(
-        username: &str,
-        address: Addr,
-        mnemonic: &str,
-        coin_type: usize,
-    ) -> anyhow::Result<Self> {
-        let mnemonic = Mnemonic::new(mnemonic, Language::English)?;
-        let sk = SigningKey::from_mnemonic(&mnemonic, coin_type)?;
-
-        Self::new(username, address, sk)
-    }
-
-    pub fn with_nonce(self, nonce: u32) -> SingleSigner<Defined<u32>> {
+    pub fn with_nonce(self, nonce: u32) -> SingleSigner<Defined<u32>, S> {
         SingleSigner {
             username: self.username,
             address: self.address,
@@ -74,11 +62,27 @@ impl SingleSigner<Undefined<u32>> {
         }
     }
 
-    pub async fn query_nonce(self, client: &Client) -> anyhow::Result<SingleSigner<Defined<u32>>> {
-        let nonce = client
-            .query_wasm_smart(self.address, &spot::QueryMsg::Nonce {}, None)
+    /// Query the most recent nonces the account has seen, and use the one
+    /// after the newest as the nonce for the next transaction.
+    ///
+    /// The account accepts nonces out of order, so the signer simply signs each
+    /// subsequent transaction with an incremented nonce; these transactions
+    /// don't need to be included in blocks in the same order.
+    pub async fn query_nonce(
+        self,
+        client: &Client,
+    ) -> anyhow::Result<SingleSigner<Defined<u32>, S>> {
+        let seen_nonces: BTreeSet<u32> = client
+            .query_wasm_smart(self.address, &spot::QueryMsg::SeenNonces {}, None)
             .await?;
 
+        let nonce = match seen_nonces.last() {
+            Some(newest) => newest
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("nonce overflow: newest seen nonce is {newest}"))?,
+            None => 0,
+        };
+
         Ok(SingleSigner {
             username: self.username,
             address: self.address,
@@ -90,7 +94,29 @@ impl SingleSigner<Undefined<u32>> {
     }
 }
 
-impl<T> Addressable for SingleSigner<T>
+impl SingleSigner<Undefined<u32>> {
+    pub fn new_random(username: &str, address: Addr) -> anyhow::Result<Self> {
+        Self::new(username, address, SigningKey::new_random())
+    }
+
+    pub fn from_private_key(username: &str, address: Addr, key: [u8; 32]) -> anyhow::Result<Self> {
+        Self::new(username, address, SigningKey::from_bytes(key)?)
+    }
+
+    pub fn from_mnemonic(
+        username: &str,
+        address: Addr,
+        mnemonic: &str,
+        coin_type: usize,
+    ) -> anyhow::Result<Self> {
+        let mnemonic = Mnemonic::new(mnemonic, Language::English)?;
+        let sk = SigningKey::from_mnemonic(&mnemonic, coin_type)?;
+
+        Self::new(username, address, sk)
+    }
+}
+
+impl<T, S> Addressable for SingleSigner<T, S>
 where
     T: MaybeDefined<u32>,
 {
@@ -99,7 +125,10 @@ where
     }
 }
 
-impl Signer for SingleSigner<Defined<u32>> {
+impl<S> Signer for SingleSigner<Defined<u32>, S>
+where
+    S: Secret,
+{
     fn unsigned_transaction(
         &self,
         msgs: NonEmpty<Vec<Message>>,
@@ -125,7 +154,7 @@ impl Signer for SingleSigner<Defined<u32>> {
         gas_limit: u64,
     ) -> StdResult<Tx> {
         let nonce = self.nonce.into_inner();
-        *self.nonce.inner_mut() += 1;
+        self.nonce.inner_mut().checked_add_assign(1)?;
 
         let metadata = Metadata {
             username: self.username.clone(),
@@ -140,13 +169,11 @@ impl Signer for SingleSigner<Defined<u32>> {
             messages: msgs.clone(),
             data: metadata.clone(),
         }
-        .to_json_vec()?
-        .hash256()
-        .into_inner();
+        .to_json_vec()?;
 
         let credential = Credential::Standard(StandardCredential {
             key_hash: self.key_hash,
-            signature: Signature::Secp256k1(self.sk.sign_digest(sign_doc).into()),
+            signature: self.sk.sign(&sign_doc),
         });
 
         Ok(Tx {
@@ -165,20 +192,38 @@ impl Signer for SingleSigner<Defined<u32>> {
 mod tests {
     use {
         super::*,
+        crate::{Ed25519SigningKey, SchnorrSigningKey},
         dango_account_factory::{ACCOUNTS_BY_USER, KEYS},
-        dango_auth::{authenticate_tx, NEXT_NONCE},
+        dango_auth::{authenticate_tx, SEEN_NONCES},
         dango_types::config::{AppAddresses, AppConfig},
-        grug::{AuthMode, Coins, MockContext, MockQuerier, MockStorage, ResultExt, Storage},
+        grug::{AuthMode, Coins, MockContext, MockQuerier, MockStorage, ResultExt},
     };
 
     #[test]
     fn sign_transaction_works() {
+        sign_transaction_with(SigningKey::new_random());
+    }
+
+    #[test]
+    fn sign_transaction_with_ed25519_works() {
+        sign_transaction_with(Ed25519SigningKey::new_random());
+    }
+
+    #[test]
+    fn sign_transaction_with_schnorr_works() {
+        sign_transaction_with(SchnorrSigningKey::new_random());
+    }
+
+    fn sign_transaction_with<S>(sk: S)
+    where
+        S: Secret,
+    {
         let username = Username::from_str("alice").unwrap();
         let address = Addr::mock(0);
         let nonce = 456;
         let account_factory = Addr::mock(1);
 
-        let mut signer = SingleSigner::new_random(username.as_ref(), address)
+        let mut signer = SingleSigner::new(username.as_ref(), address, sk)
             .unwrap()
             .with_nonce(nonce);
 
@@ -205,6 +250,7 @@ mod tests {
                 addresses: AppAddresses {
                     account_factory,
                     // the other addresses don't matter
+                    amm: Addr::mock(0),
                     ibc_transfer: Addr::mock(0),
                     lending: Addr::mock(0),
                     oracle: Addr::mock(0),
@@ -216,7 +262,9 @@ mod tests {
         let mut mock_ctx = MockContext::default()
             .with_storage({
                 let mut storage = MockStorage::new();
-                storage.write(NEXT_NONCE.storage_key(), nonce.to_le_bytes().as_ref());
+                SEEN_NONCES
+                    .save(&mut storage, &BTreeSet::from_iter((nonce - 5)..nonce))
+                    .unwrap();
                 storage
             })
             .with_querier(mock_querier)
@@ -6,7 +6,7 @@ use {
         bank,
         config::{AppAddresses, AppConfig},
         ibc,
-        lending::{self, MarketUpdates},
+        lending::{self, CloseFactor, InterestRateModel, LiquidationBonus, MarketUpdates},
         oracle::{
             self, GuardianSet, PriceSource, ETH_USD_ID, GUARDIANS_ADDRESSES, GUARDIAN_SETS_INDEX,
             USDC_USD_ID, WBTC_USD_ID,
@@ -15,8 +15,8 @@ use {
     },
     grug::{
         btree_map, btree_set, Addr, Binary, Coin, Coins, Config, ContractBuilder, ContractWrapper,
-        Denom, Duration, GenesisState, Hash160, Hash256, HashExt, Inner, JsonSerExt, Message,
-        NonZero, Permission, Permissions, StdResult, Udec128, Uint128, GENESIS_SENDER,
+        Denom, Duration, GasCosts, GenesisState, Hash160, Hash256, HashExt, Inner, JsonSerExt,
+        Message, NonZero, Permission, Permissions, StdResult, Udec128, Uint128, GENESIS_SENDER,
     },
     serde::Serialize,
     std::{collections::BTreeMap, error::Error, fs, io, path::Path, str::FromStr},
@@ -26,6 +26,13 @@ pub type GenesisUsers = BTreeMap<Username, GenesisUser>;
 
 pub type Addresses = BTreeMap<Username, Addr>;
 
+/// The maximum age of Pyth prices accepted by the oracle at genesis.
+pub const PYTH_MAX_AGE: Duration = Duration::from_seconds(60);
+
+/// The maximum ratio between the confidence interval and the price, of Pyth
+/// prices accepted by the oracle at genesis.
+pub const PYTH_MAX_CONFIDENCE_RATIO: Udec128 = Udec128::new_percent(2);
+
 #[grug::derive(Serde)]
 pub struct Contracts {
     pub account_factory: Addr,
@@ -72,6 +79,7 @@ pub fn build_rust_codes() -> Codes<ContractWrapper> {
         .with_authenticate(Box::new(dango_account_margin::authenticate))
         .with_backrun(Box::new(dango_account_margin::backrun))
         .with_receive(Box::new(dango_account_margin::receive))
+        .with_execute(Box::new(dango_account_margin::execute))
         .with_query(Box::new(dango_account_margin::query))
         .build();
 
@@ -102,10 +110,15 @@ pub fn build_rust_codes() -> Codes<ContractWrapper> {
 
     let ibc_transfer = ContractBuilder::new(Box::new(dango_ibc_transfer::instantiate))
         .with_execute(Box::new(dango_ibc_transfer::execute))
+        .with_query(Box::new(dango_ibc_transfer::query))
+        .with_ibc_packet_receive(Box::new(dango_ibc_transfer::ibc_packet_receive))
+        .with_ibc_packet_ack(Box::new(dango_ibc_transfer::ibc_packet_ack))
+        .with_ibc_packet_timeout(Box::new(dango_ibc_transfer::ibc_packet_timeout))
         .build();
 
     let oracle = ContractBuilder::new(Box::new(dango_oracle::instantiate))
         .with_execute(Box::new(dango_oracle::execute))
+        .with_migrate(Box::new(dango_oracle::migrate))
         .with_authenticate(Box::new(dango_oracle::authenticate))
         .with_query(Box::new(dango_oracle::query))
         .build();
@@ -113,6 +126,7 @@ pub fn build_rust_codes() -> Codes<ContractWrapper> {
     let lending = ContractBuilder::new(Box::new(dango_lending::instantiate))
         .with_execute(Box::new(dango_lending::execute))
         .with_query(Box::new(dango_lending::query))
+        .with_reply(Box::new(dango_lending::reply))
         .build();
 
     let taxman = ContractBuilder::new(Box::new(dango_taxman::instantiate))
@@ -292,9 +306,13 @@ where
         &mut msgs,
         lending_code_hash,
         &lending::InstantiateMsg {
+            config: lending::Config {
+                close_factor: CloseFactor::new_unchecked(Udec128::new_percent(50)),
+                liquidation_bonus: LiquidationBonus::new_unchecked(Udec128::new_percent(5)),
+            },
             markets: btree_map! {
                 fee_denom.clone() => MarketUpdates {
-                    // TODO
+                    interest_rate_model: Some(InterestRateModel::default()),
                 },
             },
         },
@@ -376,9 +394,24 @@ where
                 },
             },
             price_sources: btree_map! {
-                Denom::from_str("usdc").unwrap() => PriceSource::Pyth { id: USDC_USD_ID, precision: 6 },
-                Denom::from_str("btc").unwrap()  => PriceSource::Pyth { id: WBTC_USD_ID, precision: 8 },
-                Denom::from_str("eth").unwrap()  => PriceSource::Pyth { id: ETH_USD_ID, precision: 18 },
+                Denom::from_str("usdc").unwrap() => PriceSource::Pyth {
+                    id: USDC_USD_ID,
+                    precision: 6,
+                    max_age: Some(PYTH_MAX_AGE),
+                    max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+                },
+                Denom::from_str("btc").unwrap() => PriceSource::Pyth {
+                    id: WBTC_USD_ID,
+                    precision: 8,
+                    max_age: Some(PYTH_MAX_AGE),
+                    max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+                },
+                Denom::from_str("eth").unwrap() => PriceSource::Pyth {
+                    id: ETH_USD_ID,
+                    precision: 18,
+                    max_age: Some(PYTH_MAX_AGE),
+                    max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+                },
             },
         },
         "dango/oracle",
@@ -417,14 +450,17 @@ where
         owner: addresses.get(owner).cloned().unwrap(),
         bank,
         taxman,
+        ibc_handler: None,
         cronjobs: BTreeMap::new(),
         permissions,
         max_orphan_age,
+        gas_costs: GasCosts::DEFAULT,
     };
 
     let app_config = AppConfig {
         addresses: AppAddresses {
             account_factory,
+            amm,
             ibc_transfer,
             lending,
             oracle,
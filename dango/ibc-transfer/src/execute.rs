@@ -0,0 +1,290 @@
+use {
+    crate::{DENOM_TRACES, ESCROWS},
+    anyhow::{anyhow, ensure},
+    dango_account_factory::ACCOUNTS,
+    dango_types::{
+        account_factory, bank,
+        ibc::{
+            host,
+            transfer::{
+                Acknowledgement, DenomTrace, ExecuteMsg, FungibleTokenPacketData, InstantiateMsg,
+                NAMESPACE, PORT_ID,
+            },
+        },
+        DangoQuerier,
+    },
+    grug::{
+        Addr, Coins, Denom, IbcEndpoint, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg,
+        IbcPacketTimeoutMsg, IsZero, JsonDeExt, JsonSerExt, Message, MutableCtx, Number,
+        NumberConst, QuerierWrapper, Response, StdResult, Storage, Timestamp, Uint128,
+    },
+    std::str::FromStr,
+};
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn instantiate(_ctx: MutableCtx, _msg: InstantiateMsg) -> anyhow::Result<Response> {
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::ReceiveTransfer { recipient } => receive_transfer(ctx, recipient),
+        ExecuteMsg::Transfer {
+            channel,
+            receiver,
+            timeout,
+        } => transfer(ctx, channel, receiver, timeout),
+    }
+}
+
+fn receive_transfer(ctx: MutableCtx, recipient: Addr) -> anyhow::Result<Response> {
+    let msg = deliver(&ctx.querier, recipient, ctx.funds)?;
+
+    Ok(Response::new().add_message(msg))
+}
+
+fn transfer(
+    ctx: MutableCtx,
+    channel: String,
+    receiver: String,
+    timeout: Timestamp,
+) -> anyhow::Result<Response> {
+    ensure!(
+        timeout > ctx.block.timestamp,
+        "timeout must be in the future"
+    );
+
+    let ibc_handler = ctx
+        .querier
+        .query_ibc_handler()?
+        .ok_or_else(|| anyhow!("IBC handler is not set"))?;
+
+    let coin = ctx.funds.into_one_coin()?;
+    let full_path = full_path(ctx.storage, &coin.denom)?;
+    let source = IbcEndpoint {
+        port_id: PORT_ID.to_string(),
+        channel_id: channel.clone(),
+    };
+
+    // If the token didn't come in over this channel, escrow it, so that it can
+    // be released when it comes back. Otherwise, it's a voucher that is going
+    // home, so burn it.
+    let burn_msg = if sender_chain_is_source(&source, &full_path) {
+        increase_escrow(ctx.storage, &channel, &coin.denom, coin.amount)?;
+
+        None
+    } else {
+        Some(Message::execute(
+            ctx.querier.query_bank()?,
+            &bank::ExecuteMsg::Burn {
+                from: ctx.contract,
+                denom: coin.denom,
+                amount: coin.amount,
+            },
+            Coins::new(),
+        )?)
+    };
+
+    let data = FungibleTokenPacketData {
+        denom: full_path,
+        amount: coin.amount,
+        sender: ctx.sender.to_string(),
+        receiver,
+    };
+
+    Ok(Response::new()
+        .may_add_message(burn_msg)
+        .add_message(Message::execute(
+            ibc_handler,
+            &host::ExecuteMsg::SendPacket {
+                channel_id: channel,
+                data: data.to_json_vec()?.into(),
+                timeout_height: None,
+                timeout_timestamp: Some(timeout),
+            },
+            Coins::new(),
+        )?))
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn ibc_packet_receive(ctx: MutableCtx, msg: IbcPacketReceiveMsg) -> anyhow::Result<Response> {
+    let packet = msg.packet;
+    let data: FungibleTokenPacketData = packet.data.deserialize_json()?;
+    let receiver = Addr::from_str(&data.receiver)?;
+
+    // If the token was sent from this chain over this channel in the first
+    // place, release it from escrow.
+    if let Some(unprefixed) = data.denom.strip_prefix(&channel_prefix(&packet.source)) {
+        let denom = local_denom(unprefixed)?;
+
+        decrease_escrow(
+            ctx.storage,
+            &packet.destination.channel_id,
+            &denom,
+            data.amount,
+        )?;
+
+        let msg = deliver(&ctx.querier, receiver, Coins::one(denom, data.amount)?)?;
+
+        return Ok(Response::new().add_message(msg));
+    }
+
+    // Otherwise, mint a voucher, with this end of the channel added to the
+    // token's trace.
+    let trace = DenomTrace::from_full_path(&format!(
+        "{}{}",
+        channel_prefix(&packet.destination),
+        data.denom
+    ));
+    let denom = trace.ibc_denom();
+
+    DENOM_TRACES.save(ctx.storage, &denom, &trace)?;
+
+    let mint_msg = Message::execute(
+        ctx.querier.query_bank()?,
+        &bank::ExecuteMsg::Mint {
+            to: ctx.contract,
+            denom: denom.clone(),
+            amount: data.amount,
+        },
+        Coins::new(),
+    )?;
+
+    let deliver_msg = deliver(&ctx.querier, receiver, Coins::one(denom, data.amount)?)?;
+
+    Ok(Response::new()
+        .add_message(mint_msg)
+        .add_message(deliver_msg))
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn ibc_packet_ack(ctx: MutableCtx, msg: IbcPacketAckMsg) -> anyhow::Result<Response> {
+    match msg.acknowledgement.deserialize_json()? {
+        Acknowledgement::Result(_) => Ok(Response::new()),
+        Acknowledgement::Error(_) => refund(ctx, msg.packet),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn ibc_packet_timeout(ctx: MutableCtx, msg: IbcPacketTimeoutMsg) -> anyhow::Result<Response> {
+    refund(ctx, msg.packet)
+}
+
+/// Return the tokens of a failed transfer to the sender, reverting what was
+/// done when the packet was sent.
+fn refund(ctx: MutableCtx, packet: IbcPacket) -> anyhow::Result<Response> {
+    let data: FungibleTokenPacketData = packet.data.deserialize_json()?;
+    let sender = Addr::from_str(&data.sender)?;
+
+    let msg = if sender_chain_is_source(&packet.source, &data.denom) {
+        let denom = local_denom(&data.denom)?;
+
+        decrease_escrow(ctx.storage, &packet.source.channel_id, &denom, data.amount)?;
+
+        Message::transfer(sender, Coins::one(denom, data.amount)?)?
+    } else {
+        Message::execute(
+            ctx.querier.query_bank()?,
+            &bank::ExecuteMsg::Mint {
+                to: sender,
+                denom: DenomTrace::from_full_path(&data.denom).ibc_denom(),
+                amount: data.amount,
+            },
+            Coins::new(),
+        )?
+    };
+
+    Ok(Response::new().add_message(msg))
+}
+
+/// Send coins to a recipient on this chain.
+///
+/// Query the factory to find whether the recipient exists:
+/// - if yes, simply send the tokens to the accounts;
+/// - if no, deposit the coins at the factory to be claimed later.
+fn deliver(querier: &QuerierWrapper, recipient: Addr, coins: Coins) -> StdResult<Message> {
+    let account_factory = querier.query_account_factory()?;
+
+    // Use a raw instead of smart query to save on gas.
+    if querier
+        .query_wasm_raw(account_factory, ACCOUNTS.path(recipient))?
+        .is_none()
+    {
+        Message::execute(
+            account_factory,
+            &account_factory::ExecuteMsg::Deposit { recipient },
+            coins,
+        )
+    } else {
+        Message::transfer(recipient, coins)
+    }
+}
+
+/// Return the denom of a token as it's known to other chains: the full trace
+/// for IBC vouchers, or the denom itself for tokens native to this chain.
+fn full_path(storage: &dyn Storage, denom: &Denom) -> StdResult<String> {
+    if denom.namespace() == Some(&*NAMESPACE) {
+        DENOM_TRACES
+            .load(storage, denom)
+            .map(|trace| trace.full_path())
+    } else {
+        Ok(denom.to_string())
+    }
+}
+
+/// The inverse of `full_path`.
+fn local_denom(full_path: &str) -> StdResult<Denom> {
+    let trace = DenomTrace::from_full_path(full_path);
+
+    if trace.path.is_empty() {
+        Denom::from_str(full_path)
+    } else {
+        Ok(trace.ibc_denom())
+    }
+}
+
+fn channel_prefix(endpoint: &IbcEndpoint) -> String {
+    format!("{}/{}/", endpoint.port_id, endpoint.channel_id)
+}
+
+/// Whether a token sent over the given channel end didn't come in over it,
+/// i.e. the sending chain is the token's source as far as the channel is
+/// concerned.
+fn sender_chain_is_source(source: &IbcEndpoint, full_path: &str) -> bool {
+    !full_path.starts_with(&channel_prefix(source))
+}
+
+fn increase_escrow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &Denom,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    ESCROWS.may_modify(storage, (channel, denom), |maybe_escrow| {
+        let escrow = maybe_escrow.unwrap_or(Uint128::ZERO).checked_add(amount)?;
+        // Only write to storage if the escrow is non-zero.
+        if escrow.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(escrow))
+        }
+    })
+}
+
+fn decrease_escrow(
+    storage: &mut dyn Storage,
+    channel: &str,
+    denom: &Denom,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    ESCROWS.may_modify(storage, (channel, denom), |maybe_escrow| {
+        let escrow = maybe_escrow.unwrap_or(Uint128::ZERO).checked_sub(amount)?;
+        // If escrow is reduced to zero, delete it, to save disk space.
+        if escrow.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(escrow))
+        }
+    })
+}
@@ -0,0 +1,77 @@
+use {
+    crate::{DENOM_TRACES, ESCROWS},
+    dango_types::ibc::transfer::{DenomTrace, QueryMsg},
+    grug::{
+        Bound, Denom, ImmutableCtx, Json, JsonSerExt, NumberConst, Order, StdResult, Storage,
+        Uint128,
+    },
+    std::collections::BTreeMap,
+};
+
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+    match msg {
+        QueryMsg::DenomTrace { denom } => {
+            let res = query_denom_trace(ctx.storage, denom)?;
+            res.to_json_value()
+        },
+        QueryMsg::DenomTraces { start_after, limit } => {
+            let res = query_denom_traces(ctx.storage, start_after, limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::Escrow { channel, denom } => {
+            let res = query_escrow(ctx.storage, channel, denom)?;
+            res.to_json_value()
+        },
+        QueryMsg::Escrows {
+            channel,
+            start_after,
+            limit,
+        } => {
+            let res = query_escrows(ctx.storage, channel, start_after, limit)?;
+            res.to_json_value()
+        },
+    }
+}
+
+fn query_denom_trace(storage: &dyn Storage, denom: Denom) -> StdResult<DenomTrace> {
+    DENOM_TRACES.load(storage, &denom)
+}
+
+fn query_denom_traces(
+    storage: &dyn Storage,
+    start_after: Option<Denom>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Denom, DenomTrace>> {
+    let start = start_after.as_ref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    DENOM_TRACES
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_escrow(storage: &dyn Storage, channel: String, denom: Denom) -> StdResult<Uint128> {
+    ESCROWS
+        .may_load(storage, (&channel, &denom))
+        .map(|maybe_escrow| maybe_escrow.unwrap_or(Uint128::ZERO))
+}
+
+fn query_escrows(
+    storage: &dyn Storage,
+    channel: String,
+    start_after: Option<Denom>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Denom, Uint128>> {
+    let start = start_after.as_ref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    ESCROWS
+        .prefix(&channel)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,11 @@
+use {
+    dango_types::ibc::transfer::DenomTrace,
+    grug::{Denom, Map, Uint128},
+};
+
+/// Denom traces of IBC vouchers, indexed by the vouchers' denoms.
+pub const DENOM_TRACES: Map<&Denom, DenomTrace> = Map::new("denom_trace");
+
+/// Amounts of tokens escrowed, indexed by the channel they were sent over and
+/// their denoms.
+pub const ESCROWS: Map<(&str, &Denom), Uint128> = Map::new("escrow");
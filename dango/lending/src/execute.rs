@@ -1,21 +1,33 @@
 use {
-    crate::{DEBTS, MARKETS},
+    crate::{CONFIG, DEBTS, MARKETS},
     anyhow::{anyhow, bail, ensure, Ok},
     dango_account_factory::ACCOUNTS,
+    dango_oracle::OracleQuerier,
     dango_types::{
+        account::margin::{self, QueryHealthRequest},
         account_factory::Account,
         bank,
-        lending::{ExecuteMsg, InstantiateMsg, Market, MarketUpdates, NAMESPACE, SUBNAMESPACE},
+        config::AppConfig,
+        lending::{
+            Config, ExecuteMsg, InstantiateMsg, Market, MarketUpdates, ReplyMsg, NAMESPACE,
+            SUBNAMESPACE,
+        },
         DangoQuerier,
     },
-    grug::{BorshDeExt, Coin, Coins, Denom, Message, MutableCtx, Response},
+    grug::{
+        Addr, BorshDeExt, Coin, Coins, Denom, IsZero, Message, MultiplyFraction, MutableCtx,
+        Number, NumberConst, Response, Storage, SubMessage, SubMsgResult, SudoCtx, Timestamp,
+        Udec128, Uint128,
+    },
     std::collections::BTreeMap,
 };
 
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Response> {
-    for (denom, _updates) in msg.markets {
-        MARKETS.save(ctx.storage, &denom, &Market {})?;
+    CONFIG.save(ctx.storage, &msg.config)?;
+
+    for (denom, updates) in msg.markets {
+        apply_market_updates(ctx.storage, ctx.block.timestamp, &denom, updates)?;
     }
 
     Ok(Response::new())
@@ -24,14 +36,41 @@ pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Respo
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
     match msg {
+        ExecuteMsg::Configure { new_cfg } => configure(ctx, new_cfg),
         ExecuteMsg::UpdateMarkets(updates) => update_markets(ctx, updates),
         ExecuteMsg::Deposit {} => deposit(ctx),
         ExecuteMsg::Withdraw {} => withdraw(ctx),
         ExecuteMsg::Borrow(coins) => borrow(ctx, coins),
         ExecuteMsg::Repay {} => repay(ctx),
+        ExecuteMsg::Liquidate {
+            account,
+            debt_to_repay,
+        } => liquidate(ctx, account, debt_to_repay),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn reply(ctx: SudoCtx, msg: ReplyMsg, _res: SubMsgResult) -> anyhow::Result<Response> {
+    match msg {
+        ReplyMsg::AfterLiquidation {
+            account,
+            utilization_rate,
+        } => after_liquidation(ctx, account, utilization_rate),
     }
 }
 
+fn configure(ctx: MutableCtx, new_cfg: Config) -> anyhow::Result<Response> {
+    // Ensure only chain owner can update the config.
+    ensure!(
+        ctx.sender == ctx.querier.query_owner()?,
+        "Only the owner can update the config"
+    );
+
+    CONFIG.save(ctx.storage, &new_cfg)?;
+
+    Ok(Response::new())
+}
+
 fn update_markets(
     ctx: MutableCtx,
     updates: BTreeMap<Denom, MarketUpdates>,
@@ -42,26 +81,72 @@ fn update_markets(
         "Only the owner can whitelist denoms"
     );
 
-    for (denom, _updates) in updates {
-        MARKETS.save(ctx.storage, &denom, &Market {})?;
+    for (denom, updates) in updates {
+        apply_market_updates(ctx.storage, ctx.block.timestamp, &denom, updates)?;
     }
 
     Ok(Response::new())
 }
 
+fn apply_market_updates(
+    storage: &mut dyn Storage,
+    current_time: Timestamp,
+    denom: &Denom,
+    updates: MarketUpdates,
+) -> anyhow::Result<()> {
+    let market = match MARKETS.may_load(storage, denom)? {
+        // Accrue interest under the old parameters before applying the update.
+        Some(market) => {
+            let mut market = market.accrue_interest(current_time)?;
+
+            if let Some(interest_rate_model) = updates.interest_rate_model {
+                market.interest_rate_model = interest_rate_model;
+            }
+
+            market
+        },
+        None => {
+            let Some(interest_rate_model) = updates.interest_rate_model else {
+                bail!("interest rate model must be provided for new market `{denom}`");
+            };
+
+            Market::new(interest_rate_model, current_time)
+        },
+    };
+
+    MARKETS.save(storage, denom, &market)?;
+
+    Ok(())
+}
+
 fn deposit(ctx: MutableCtx) -> anyhow::Result<Response> {
     let bank = ctx.querier.query_bank()?;
+    let taxman = ctx.querier.query_taxman()?;
     let mut msgs = vec![];
 
     for coin in ctx.funds {
-        ensure!(MARKETS.has(ctx.storage, &coin.denom), "Invalid denom");
+        let Some(market) = MARKETS.may_load(ctx.storage, &coin.denom)? else {
+            bail!("Invalid denom");
+        };
 
+        let mut market = market.accrue_interest(ctx.block.timestamp)?;
         let denom = coin.denom.prepend(&[&NAMESPACE, &SUBNAMESPACE])?;
 
-        // TODO:
-        // 1. compute LP token mint amount
-        // 2. update `Market`
-        let amount = coin.amount;
+        msgs.extend(mint_protocol_fee(&mut market, bank, taxman, &denom)?);
+
+        // Compute the amount of LP tokens to mint.
+        let amount = market.supplied_to_scaled(coin.amount)?;
+
+        ensure!(
+            amount.is_non_zero(),
+            "deposit amount too small: {} {}",
+            coin.amount,
+            coin.denom
+        );
+
+        market.total_supplied_scaled.checked_add_assign(amount)?;
+
+        MARKETS.save(ctx.storage, &coin.denom, &market)?;
 
         msgs.push(Message::execute(
             bank,
@@ -79,6 +164,7 @@ fn deposit(ctx: MutableCtx) -> anyhow::Result<Response> {
 
 fn withdraw(ctx: MutableCtx) -> anyhow::Result<Response> {
     let bank = ctx.querier.query_bank()?;
+    let taxman = ctx.querier.query_taxman()?;
     let mut msgs = vec![];
     let mut withdrawn = Coins::new();
 
@@ -87,10 +173,30 @@ fn withdraw(ctx: MutableCtx) -> anyhow::Result<Response> {
             bail!("not a lending pool token: {}", coin.denom)
         };
 
-        // TODO:
-        // 1. compute withdraw amount
-        // 2. update `Market`
-        let underlying_amount = coin.amount;
+        let mut market = MARKETS
+            .load(ctx.storage, &underlying_denom)?
+            .accrue_interest(ctx.block.timestamp)?;
+
+        msgs.extend(mint_protocol_fee(&mut market, bank, taxman, &coin.denom)?);
+
+        // Compute the amount of the underlying asset to withdraw.
+        let underlying_amount = market.scaled_to_supplied(coin.amount)?;
+
+        // Ensure the amount isn't currently lent out.
+        let available = market.available_liquidity()?;
+        ensure!(
+            underlying_amount <= available,
+            "not enough liquidity in market `{}`: {} available, {} requested",
+            underlying_denom,
+            available,
+            underlying_amount
+        );
+
+        market
+            .total_supplied_scaled
+            .checked_sub_assign(coin.amount)?;
+
+        MARKETS.save(ctx.storage, &underlying_denom, &market)?;
 
         // Burn the LP tokens
         msgs.push(Message::execute(
@@ -129,50 +235,291 @@ fn borrow(ctx: MutableCtx, coins: Coins) -> anyhow::Result<Response> {
         "Only margin accounts can borrow and repay"
     );
 
-    // Ensure the coins are whitelisted
+    let bank = ctx.querier.query_bank()?;
+    let taxman = ctx.querier.query_taxman()?;
+    let mut msgs = vec![];
+    let mut scaled_debts = DEBTS.may_load(ctx.storage, ctx.sender)?.unwrap_or_default();
+
     for coin in &coins {
+        // Ensure the coins are whitelisted
+        let Some(market) = MARKETS.may_load(ctx.storage, coin.denom)? else {
+            bail!("Invalid denom. Only whitelisted denoms can be borrowed.");
+        };
+
+        let mut market = market.accrue_interest(ctx.block.timestamp)?;
+        let lp_denom = coin.denom.prepend(&[&NAMESPACE, &SUBNAMESPACE])?;
+
+        msgs.extend(mint_protocol_fee(&mut market, bank, taxman, &lp_denom)?);
+
+        // Ensure the market has enough liquidity.
+        let available = market.available_liquidity()?;
         ensure!(
-            MARKETS.has(ctx.storage, coin.denom),
-            "Invalid denom. Only whitelisted denoms can be borrowed."
+            *coin.amount <= available,
+            "not enough liquidity in market `{}`: {} available, {} requested",
+            coin.denom,
+            available,
+            coin.amount
         );
-    }
 
-    // Update the sender's liabilities
-    DEBTS.may_update(ctx.storage, ctx.sender, |maybe_debts| {
-        let mut debts = maybe_debts.unwrap_or_default();
+        // Update the sender's liabilities. The debt is rounded up, in favor
+        // of the lenders.
+        let scaled_amount = market.borrowed_to_scaled_ceil(*coin.amount)?;
+
+        scaled_debts.insert(Coin::new(coin.denom.clone(), scaled_amount)?)?;
+        market
+            .total_borrowed_scaled
+            .checked_add_assign(scaled_amount)?;
 
-        debts.insert_many(coins.clone())?;
+        MARKETS.save(ctx.storage, coin.denom, &market)?;
+    }
 
-        Ok(debts)
-    })?;
+    DEBTS.save(ctx.storage, ctx.sender, &scaled_debts)?;
 
     // Transfer the coins to the caller
-    Ok(Response::new().add_message(Message::transfer(ctx.sender, coins)?))
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_message(Message::transfer(ctx.sender, coins)?))
 }
 
 fn repay(ctx: MutableCtx) -> anyhow::Result<Response> {
-    // Ensure all sent coins are whitelisted
-    for coin in &ctx.funds {
-        ensure!(
-            MARKETS.has(ctx.storage, coin.denom),
-            "Invalid denom. Only whitelisted denoms can be repaid."
-        );
+    let bank = ctx.querier.query_bank()?;
+    let taxman = ctx.querier.query_taxman()?;
+    let mut msgs = vec![];
+    let mut scaled_debts = DEBTS.may_load(ctx.storage, ctx.sender)?.unwrap_or_default();
+    let mut remainders = Coins::new();
+
+    for coin in ctx.funds {
+        // Ensure all sent coins are whitelisted
+        let Some(market) = MARKETS.may_load(ctx.storage, &coin.denom)? else {
+            bail!("Invalid denom. Only whitelisted denoms can be repaid.");
+        };
+
+        let mut market = market.accrue_interest(ctx.block.timestamp)?;
+        let lp_denom = coin.denom.prepend(&[&NAMESPACE, &SUBNAMESPACE])?;
+
+        msgs.extend(mint_protocol_fee(&mut market, bank, taxman, &lp_denom)?);
+
+        let remainder = repay_debt(&mut market, &mut scaled_debts, &coin)?;
+
+        MARKETS.save(ctx.storage, &coin.denom, &market)?;
+
+        remainders.insert(Coin::new(coin.denom, remainder)?)?;
+    }
+
+    save_debts(ctx.storage, ctx.sender, &scaled_debts)?;
+
+    // Refund the remainders to the sender, if any.
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_message(Message::transfer(ctx.sender, remainders)?))
+}
+
+fn liquidate(ctx: MutableCtx, account: Addr, debt_to_repay: Coin) -> anyhow::Result<Response> {
+    let cfg = CONFIG.load(ctx.storage)?;
+    let app_cfg: AppConfig = ctx.querier.query_app_config()?;
+
+    ensure!(
+        ctx.funds.into_one_coin()? == debt_to_repay,
+        "must attach exactly the debt to repay: {}",
+        debt_to_repay
+    );
+
+    // Only margin accounts can borrow, so if the account has a debt, it's
+    // necessarily a margin account.
+    let mut scaled_debts = DEBTS.may_load(ctx.storage, account)?.unwrap_or_default();
+    let scaled_debt = scaled_debts.amount_of(&debt_to_repay.denom);
+
+    ensure!(
+        scaled_debt.is_non_zero(),
+        "account {} has no debt of denom `{}`",
+        account,
+        debt_to_repay.denom
+    );
+
+    // Ensure the account is undercollateralized.
+    let health = ctx
+        .querier
+        .query_wasm_smart(account, QueryHealthRequest {})?;
+
+    ensure!(
+        health.utilization_rate > Udec128::ONE,
+        "account {} is not liquidatable! utilization rate: {}",
+        account,
+        health.utilization_rate
+    );
+
+    let bank = ctx.querier.query_bank()?;
+    let taxman = ctx.querier.query_taxman()?;
+    let mut msgs = vec![];
+
+    let mut market = MARKETS
+        .load(ctx.storage, &debt_to_repay.denom)?
+        .accrue_interest(ctx.block.timestamp)?;
+    let lp_denom = debt_to_repay.denom.prepend(&[&NAMESPACE, &SUBNAMESPACE])?;
+
+    msgs.extend(mint_protocol_fee(&mut market, bank, taxman, &lp_denom)?);
+
+    // Repay no more than the close factor of the debt. Rounded up, so that
+    // dust debts can be liquidated in full. The excess is refunded to the
+    // liquidator.
+    let max_repay = market
+        .scaled_to_borrowed(scaled_debt)?
+        .checked_mul_dec_ceil(*cfg.close_factor)?;
+
+    let mut repaid = debt_to_repay.clone();
+    repaid.amount = repaid.amount.min(max_repay);
+
+    let remainder = repay_debt(&mut market, &mut scaled_debts, &repaid)?;
+    repaid.amount.checked_sub_assign(remainder)?;
+
+    let refund = Coin::new(
+        repaid.denom.clone(),
+        debt_to_repay.amount.checked_sub(repaid.amount)?,
+    )?;
+
+    MARKETS.save(ctx.storage, &repaid.denom, &market)?;
+
+    save_debts(ctx.storage, account, &scaled_debts)?;
+
+    // Compute the value of collateral to be seized, including the bonus.
+    let seize_value = ctx
+        .querier
+        .query_price(app_cfg.addresses.oracle, &repaid.denom, ctx.block.timestamp)?
+        .value_of_unit_amount(repaid.amount)?
+        .checked_mul(Udec128::ONE.checked_add(*cfg.liquidation_bonus)?)?;
+
+    // Collateral is seized pro-rata across all of the account's collateral
+    // tokens. If the account doesn't have enough collateral, all of it is
+    // seized.
+    let seize_ratio = if seize_value >= health.total_collateral_value {
+        Udec128::ONE
+    } else {
+        seize_value.checked_div(health.total_collateral_value)?
+    };
+
+    let mut seized = Coins::new();
+    for coin in health.collaterals {
+        let amount = coin.amount.checked_mul_dec_floor(seize_ratio)?;
+        seized.insert(Coin::new(coin.denom, amount)?)?;
+    }
+
+    let seize_msg = Message::execute(
+        account,
+        &margin::ExecuteMsg::SeizeCollateral {
+            liquidator: ctx.sender,
+            coins: seized,
+        },
+        Coins::new(),
+    )?;
+
+    // Once the collateral is seized, ensure the liquidation has made the
+    // account healthier. The exception is if all of the collateral is seized,
+    // in which case the account is insolvent and can't be made healthier.
+    let seize_msg = if seize_ratio < Udec128::ONE {
+        SubMessage::reply_on_success(seize_msg, &ReplyMsg::AfterLiquidation {
+            account,
+            utilization_rate: health.utilization_rate,
+        })?
+    } else {
+        SubMessage::reply_never(seize_msg)
+    };
+
+    let refund_msg = if refund.is_non_zero() {
+        Some(Message::transfer(ctx.sender, refund)?)
+    } else {
+        None
+    };
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(seize_msg)
+        .may_add_message(refund_msg))
+}
+
+fn after_liquidation(
+    ctx: SudoCtx,
+    account: Addr,
+    utilization_rate_before: Udec128,
+) -> anyhow::Result<Response> {
+    let health = ctx
+        .querier
+        .query_wasm_smart(account, QueryHealthRequest {})?;
+
+    ensure!(
+        health.utilization_rate < utilization_rate_before,
+        "liquidation must make account {} healthier! utilization rate before: {}, after: {}",
+        account,
+        utilization_rate_before,
+        health.utilization_rate
+    );
+
+    Ok(Response::new())
+}
+
+/// Deduct a repayment from an account's scaled debts, saturating at zero.
+/// Returns the portion of the repayment in excess of the debt.
+fn repay_debt(
+    market: &mut Market,
+    scaled_debts: &mut Coins,
+    coin: &Coin,
+) -> anyhow::Result<Uint128> {
+    let scaled_debt = scaled_debts.amount_of(&coin.denom);
+    let debt = market.scaled_to_borrowed(scaled_debt)?;
+
+    // If the debt is only partially repaid, the scaled amount to deduct is
+    // rounded down, in favor of the lenders.
+    let (scaled_repaid, remainder) = if coin.amount >= debt {
+        (scaled_debt, coin.amount - debt)
+    } else {
+        (market.borrowed_to_scaled_floor(coin.amount)?, Uint128::ZERO)
+    };
+
+    if scaled_repaid.is_non_zero() {
+        scaled_debts.deduct(Coin::new(coin.denom.clone(), scaled_repaid)?)?;
+        market
+            .total_borrowed_scaled
+            .checked_sub_assign(scaled_repaid)?;
     }
 
-    let mut maybe_msg = None;
+    Ok(remainder)
+}
 
-    // Update the sender's liabilities
-    DEBTS.may_update(ctx.storage, ctx.sender, |maybe_debts| {
-        let mut debts = maybe_debts.unwrap_or_default();
+/// Save an account's scaled debts, or delete them if empty.
+fn save_debts(
+    storage: &mut dyn Storage,
+    account: Addr,
+    scaled_debts: &Coins,
+) -> anyhow::Result<()> {
+    if scaled_debts.is_empty() {
+        DEBTS.remove(storage, account);
+    } else {
+        DEBTS.save(storage, account, scaled_debts)?;
+    }
 
-        // Deduct the sent coins from the account's debts, saturating at zero.
-        let remainders = debts.saturating_deduct_many(ctx.funds)?;
+    Ok(())
+}
 
-        // Refund the remainders to the sender, if any.
-        maybe_msg = Some(Message::transfer(ctx.sender, remainders)?);
+/// Mint the protocol fee accrued in a market, if any, as LP tokens to the
+/// taxman.
+fn mint_protocol_fee(
+    market: &mut Market,
+    bank: Addr,
+    taxman: Addr,
+    lp_denom: &Denom,
+) -> anyhow::Result<Option<Message>> {
+    if market.pending_protocol_fee_scaled.is_zero() {
+        return Ok(None);
+    }
 
-        Ok(debts)
-    })?;
+    let amount = std::mem::take(&mut market.pending_protocol_fee_scaled);
 
-    Ok(Response::new().may_add_message(maybe_msg))
+    Ok(Some(Message::execute(
+        bank,
+        &bank::ExecuteMsg::Mint {
+            to: taxman,
+            denom: lp_denom.clone(),
+            amount,
+        },
+        Coins::new(),
+    )?))
 }
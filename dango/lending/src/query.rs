@@ -1,7 +1,13 @@
 use {
-    crate::{DEBTS, MARKETS},
-    dango_types::lending::{Market, QueryMsg},
-    grug::{Addr, Bound, Coins, Denom, ImmutableCtx, Json, JsonSerExt, Order, StdResult, Storage},
+    crate::{CONFIG, DEBTS, MARKETS},
+    dango_types::{
+        account::margin::{HealthResponse, QueryHealthRequest},
+        lending::{Config, Market, QueryMsg},
+    },
+    grug::{
+        Addr, Bound, Coin, Coins, Denom, ImmutableCtx, Json, JsonSerExt, NumberConst, Order,
+        QuerierWrapper, StdResult, Storage, Timestamp, Udec128,
+    },
     std::collections::BTreeMap,
 };
 
@@ -10,31 +16,44 @@ const DEFAULT_PAGE_LIMIT: u32 = 30;
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
+        QueryMsg::Config {} => {
+            let res = query_config(ctx.storage)?;
+            res.to_json_value()
+        },
         QueryMsg::Market { denom } => {
-            let res = query_market(ctx.storage, denom)?;
+            let res = query_market(ctx.storage, ctx.block.timestamp, denom)?;
             res.to_json_value()
         },
         QueryMsg::Markets { start_after, limit } => {
-            let res = query_markets(ctx.storage, start_after, limit)?;
+            let res = query_markets(ctx.storage, ctx.block.timestamp, start_after, limit)?;
             res.to_json_value()
         },
         QueryMsg::Debt { account } => {
-            let res = query_debt(ctx.storage, account)?;
+            let res = query_debt(ctx.storage, ctx.block.timestamp, account)?;
             res.to_json_value()
         },
         QueryMsg::Debts { start_after, limit } => {
-            let res = query_debts(ctx.storage, start_after, limit)?;
+            let res = query_debts(ctx.storage, ctx.block.timestamp, start_after, limit)?;
+            res.to_json_value()
+        },
+        QueryMsg::Liquidatable { start_after, limit } => {
+            let res = query_liquidatable(ctx.storage, ctx.querier, start_after, limit)?;
             res.to_json_value()
         },
     }
 }
 
-fn query_market(storage: &dyn Storage, denom: Denom) -> StdResult<Market> {
-    MARKETS.load(storage, &denom)
+fn query_config(storage: &dyn Storage) -> StdResult<Config> {
+    CONFIG.load(storage)
+}
+
+fn query_market(storage: &dyn Storage, current_time: Timestamp, denom: Denom) -> StdResult<Market> {
+    MARKETS.load(storage, &denom)?.accrue_interest(current_time)
 }
 
 fn query_markets(
     storage: &dyn Storage,
+    current_time: Timestamp,
     start_after: Option<Denom>,
     limit: Option<u32>,
 ) -> StdResult<BTreeMap<Denom, Market>> {
@@ -44,23 +63,94 @@ fn query_markets(
     MARKETS
         .range(storage, start, None, Order::Ascending)
         .take(limit as usize)
+        .map(|res| {
+            let (denom, market) = res?;
+            Ok((denom, market.accrue_interest(current_time)?))
+        })
         .collect()
 }
 
-fn query_debt(storage: &dyn Storage, account: Addr) -> StdResult<Coins> {
-    DEBTS.load(storage, account)
+fn query_debt(storage: &dyn Storage, current_time: Timestamp, account: Addr) -> StdResult<Coins> {
+    let scaled_debts = DEBTS.may_load(storage, account)?.unwrap_or_default();
+
+    unscale_debts(storage, current_time, &mut BTreeMap::new(), scaled_debts)
 }
 
 fn query_debts(
     storage: &dyn Storage,
+    current_time: Timestamp,
     start_after: Option<Addr>,
     limit: Option<u32>,
 ) -> StdResult<BTreeMap<Addr, Coins>> {
     let start = start_after.map(Bound::Exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    let mut markets = BTreeMap::new();
 
     DEBTS
         .range(storage, start, None, Order::Ascending)
         .take(limit as usize)
+        .map(|res| {
+            let (account, scaled_debts) = res?;
+            let debts = unscale_debts(storage, current_time, &mut markets, scaled_debts)?;
+            Ok((account, debts))
+        })
         .collect()
 }
+
+fn query_liquidatable(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Addr, HealthResponse>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+
+    let mut liquidatable = BTreeMap::new();
+
+    for account in DEBTS
+        .keys(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+    {
+        let account = account?;
+
+        // Skip accounts whose health can't be computed, e.g. because a price
+        // is stale, such that they don't keep the rest of the page from
+        // liquidators.
+        let Ok(health) = querier.query_wasm_smart(account, QueryHealthRequest {}) else {
+            continue;
+        };
+
+        if health.utilization_rate > Udec128::ONE {
+            liquidatable.insert(account, health);
+        }
+    }
+
+    Ok(liquidatable)
+}
+
+/// Convert scaled debts to actual debts, with interest accrued up to the
+/// current time. Markets are loaded into the given cache as needed.
+fn unscale_debts(
+    storage: &dyn Storage,
+    current_time: Timestamp,
+    markets: &mut BTreeMap<Denom, Market>,
+    scaled_debts: Coins,
+) -> StdResult<Coins> {
+    let mut debts = Coins::new();
+
+    for coin in scaled_debts {
+        if !markets.contains_key(&coin.denom) {
+            let market = MARKETS
+                .load(storage, &coin.denom)?
+                .accrue_interest(current_time)?;
+            markets.insert(coin.denom.clone(), market);
+        }
+
+        let amount = markets[&coin.denom].scaled_to_borrowed(coin.amount)?;
+
+        debts.insert(Coin::new(coin.denom, amount)?)?;
+    }
+
+    Ok(debts)
+}
@@ -1,12 +1,17 @@
 use {
-    dango_types::lending::Market,
-    grug::{Addr, Coins, Denom, Map},
+    dango_types::lending::{Config, Market},
+    grug::{Addr, Coins, Denom, Item, Map},
 };
 
+pub const CONFIG: Item<Config> = Item::new("config");
+
 /// The markets that are available to borrow from. The key is the denom of the
 /// borrowable asset.
 pub const MARKETS: Map<&Denom, Market> = Map::new("market");
 
 /// The debts of all margin accounts. The key is the address of the margin
 /// account.
+///
+/// Debts are stored in scaled form. To get the actual debt, including interest,
+/// multiply it by the respective market's borrow index.
 pub const DEBTS: Map<Addr, Coins> = Map::new("debt");
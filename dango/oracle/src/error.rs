@@ -0,0 +1,42 @@
+use {
+    dango_types::{amm::PoolId, oracle::PythId},
+    grug::{Denom, MathError, StdError, Udec128},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum OracleError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Math(#[from] MathError),
+
+    #[error("price source not found for denom `{denom}`")]
+    PriceSourceNotFound { denom: Denom },
+
+    #[error("price not found for pyth id: {id}")]
+    PriceNotFound { id: PythId },
+
+    #[error("price of `{denom}` is stale! published at: {timestamp}, max age: {max_age} seconds")]
+    StalePrice {
+        denom: Denom,
+        timestamp: u64,
+        max_age: u128,
+    },
+
+    #[error("price of `{denom}` is too uncertain! confidence ratio: {confidence_ratio}, max: {max_confidence_ratio}")]
+    UncertainPrice {
+        denom: Denom,
+        confidence_ratio: Udec128,
+        max_confidence_ratio: Udec128,
+    },
+
+    #[error("pool {pool_id} doesn't contain denom `{denom}`")]
+    DenomNotInPool { pool_id: PoolId, denom: Denom },
+
+    #[error("price of `{denom}` is an AMM TWAP, which can't be used to derive another AMM TWAP")]
+    NestedAmmTwap { denom: Denom },
+}
+
+pub type OracleResult<T> = core::result::Result<T, OracleError>;
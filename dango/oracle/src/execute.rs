@@ -1,11 +1,18 @@
 use {
-    crate::{state::GUARDIAN_SETS, PRICE_SOURCES},
+    crate::{
+        state::GUARDIAN_SETS, LegacyPrice, LegacyPriceSource, LEGACY_PRICES, LEGACY_PRICE_SOURCES,
+        PRICE_SOURCES,
+    },
     anyhow::{bail, ensure},
-    dango_types::oracle::{ExecuteMsg, InstantiateMsg, PriceSource, PythId, PythVaa, PRICES},
+    dango_types::oracle::{
+        ExecuteMsg, InstantiateMsg, MigrateMsg, PrecisionlessPrice, PriceSource, PythId, PythVaa,
+        PRICES,
+    },
     grug::{
-        AuthCtx, AuthMode, AuthResponse, Binary, Denom, Inner, JsonDeExt, Message, MsgExecute,
-        MutableCtx, Response, Tx,
+        AuthCtx, AuthMode, AuthResponse, Binary, BorshDeExt, Denom, Inner, JsonDeExt, Message,
+        MsgExecute, MutableCtx, NumberConst, Order, Response, Storage, SudoCtx, Tx, Udec128,
     },
+    pyth_sdk::PriceFeed,
     std::collections::BTreeMap,
 };
 
@@ -22,6 +29,63 @@ pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Respo
     Ok(Response::new())
 }
 
+#[cfg_attr(not(feature = "library"), grug::export)]
+pub fn migrate(ctx: SudoCtx, _msg: MigrateMsg) -> anyhow::Result<Response> {
+    // Prices already in the current layout fail to deserialize as the legacy
+    // layout, so they're skipped. This makes the migration idempotent.
+    let legacy_prices = LEGACY_PRICES
+        .range_raw(ctx.storage, None, None, Order::Ascending)
+        .filter_map(|(id_raw, price_raw)| {
+            let price = price_raw.deserialize_borsh::<LegacyPrice>().ok()?;
+            Some((id_raw, price))
+        })
+        .collect::<Vec<_>>();
+
+    for (id_raw, price) in legacy_prices {
+        let id = PythId::try_from(id_raw.as_slice())?;
+
+        // The confidence interval of a legacy price is unknown. It's assumed
+        // zero, until the price is replaced by the next feed; meanwhile, the
+        // maximum age of the price source still applies.
+        let price = PrecisionlessPrice::new(
+            price.humanized_price,
+            price.humanized_ema,
+            Udec128::ZERO,
+            price.timestamp,
+        );
+
+        PRICES.save(ctx.storage, id, &price)?;
+    }
+
+    // Likewise for price sources. Legacy Pyth price sources had no limits, so
+    // they're migrated without any, which preserves how they behave. The owner
+    // can set limits by registering the price sources again.
+    let legacy_price_sources = LEGACY_PRICE_SOURCES
+        .range_raw(ctx.storage, None, None, Order::Ascending)
+        .filter_map(|(denom_raw, price_source_raw)| {
+            let price_source = price_source_raw
+                .deserialize_borsh::<LegacyPriceSource>()
+                .ok()?;
+            Some((denom_raw, price_source))
+        })
+        .collect::<Vec<_>>();
+
+    for (denom_raw, LegacyPriceSource::Pyth { id, precision }) in legacy_price_sources {
+        let price_source = PriceSource::Pyth {
+            id,
+            precision,
+            max_age: None,
+            max_confidence_ratio: None,
+        };
+
+        PRICE_SOURCES
+            .path_raw(&denom_raw)
+            .save(ctx.storage, &price_source)?;
+    }
+
+    Ok(Response::new())
+}
+
 /// The oracle can be used as sender when:
 ///
 /// - Auth mode must be `Finalize`. This ensures such transactions are only
@@ -30,7 +94,7 @@ pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> anyhow::Result<Respo
 /// - The tranaction contains exactly one message.
 /// - This one message is an `Execute`.
 /// - The contract being executed must be the oracle itself.
-/// - the execute message must be `FeedPrices`.
+/// - the execute message must be `FeedPrices` or `FeedAggregatedPrices`.
 #[cfg_attr(not(feature = "library"), grug::export)]
 pub fn authenticate(ctx: AuthCtx, tx: Tx) -> anyhow::Result<AuthResponse> {
     // Authenticate can only be called during finalize.
@@ -51,8 +115,11 @@ pub fn authenticate(ctx: AuthCtx, tx: Tx) -> anyhow::Result<AuthResponse> {
     // Assert the contract is the oracle.
     ensure!(contract == ctx.contract, "contract must be the oracle");
 
-    // Assert the message is `ExecuteMsg::FeedPrices`.
-    let Ok(ExecuteMsg::FeedPrices(..)) = msg.clone().deserialize_json() else {
+    // Assert the message is `ExecuteMsg::FeedPrices` or
+    // `ExecuteMsg::FeedAggregatedPrices`.
+    let Ok(ExecuteMsg::FeedPrices(..) | ExecuteMsg::FeedAggregatedPrices { .. }) =
+        msg.clone().deserialize_json()
+    else {
         bail!("the execute message must be feed prices");
     };
 
@@ -66,6 +133,9 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
             register_price_sources(ctx, price_sources)
         },
         ExecuteMsg::FeedPrices(vaas) => feed_prices(ctx, vaas.into_inner()),
+        ExecuteMsg::FeedAggregatedPrices { vaas, selections } => {
+            feed_aggregated_prices(ctx, vaas.into_inner(), selections)
+        },
     }
 }
 
@@ -93,23 +163,135 @@ fn feed_prices(ctx: MutableCtx, vaas: Vec<Binary>) -> anyhow::Result<Response> {
 
         // Verify the VAA, and store the prices.
         for feed in vaa.verify(ctx.storage, ctx.api, ctx.block, GUARDIAN_SETS)? {
-            let hash = PythId::from_inner(feed.id.to_bytes());
-
-            // Save the price if there isn't already a price saved, or if there
-            // is but it's older.
-            PRICES.may_update(ctx.storage, hash, |maybe_price| -> anyhow::Result<_> {
-                if let Some(price) = maybe_price {
-                    if price.timestamp < feed.get_price_unchecked().publish_time as u64 {
-                        feed.try_into()
-                    } else {
-                        Ok(price)
-                    }
-                } else {
-                    feed.try_into()
-                }
-            })?;
+            save_price(ctx.storage, feed)?;
         }
     }
 
     Ok(Response::new())
 }
+
+fn feed_aggregated_prices(
+    ctx: MutableCtx,
+    vaas: Vec<Binary>,
+    selections: BTreeMap<PythId, u32>,
+) -> anyhow::Result<Response> {
+    // Deserialize and verify all VAAs first, so that each one is only verified
+    // once, even if selected for multiple price feeds.
+    let feeds = vaas
+        .into_iter()
+        .map(|vaa| {
+            PythVaa::new(ctx.api, vaa.into_inner())?.verify(
+                ctx.storage,
+                ctx.api,
+                ctx.block,
+                GUARDIAN_SETS,
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    for (id, index) in selections {
+        let Some(feed) = feeds
+            .get(index as usize)
+            .and_then(|feeds| feeds.iter().find(|feed| feed.id.to_bytes() == *id.inner()))
+        else {
+            bail!("VAA at index {index} doesn't contain price feed {id}");
+        };
+
+        save_price(ctx.storage, feed.clone())?;
+    }
+
+    Ok(Response::new())
+}
+
+/// Save the price if there isn't already a price saved, or if there is but
+/// it's older.
+fn save_price(storage: &mut dyn Storage, feed: PriceFeed) -> anyhow::Result<()> {
+    let hash = PythId::from_inner(feed.id.to_bytes());
+
+    PRICES.may_update(storage, hash, |maybe_price| -> anyhow::Result<_> {
+        if let Some(price) = maybe_price {
+            if price.timestamp < feed.get_price_unchecked().publish_time as u64 {
+                feed.try_into()
+            } else {
+                Ok(price)
+            }
+        } else {
+            feed.try_into()
+        }
+    })?;
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        dango_types::oracle::{ETH_USD_ID, USDC_USD_ID},
+        grug::{Duration, MockContext},
+        std::str::FromStr,
+    };
+
+    #[test]
+    fn migrating_legacy_state() {
+        let mut ctx = MockContext::new();
+        let usdc = Denom::from_str("usdc").unwrap();
+        let eth = Denom::from_str("eth").unwrap();
+
+        // A price and a price source in the legacy layouts.
+        LEGACY_PRICES
+            .save(&mut ctx.storage, USDC_USD_ID, &LegacyPrice {
+                humanized_price: Udec128::new_percent(99),
+                humanized_ema: Udec128::ONE,
+                timestamp: 1730802926,
+            })
+            .unwrap();
+        LEGACY_PRICE_SOURCES
+            .save(&mut ctx.storage, &usdc, &LegacyPriceSource::Pyth {
+                id: USDC_USD_ID,
+                precision: 6,
+            })
+            .unwrap();
+
+        // A price source already in the current layout.
+        let eth_price_source = PriceSource::Pyth {
+            id: ETH_USD_ID,
+            precision: 18,
+            max_age: Some(Duration::from_seconds(60)),
+            max_confidence_ratio: Some(Udec128::new_percent(2)),
+        };
+        PRICE_SOURCES
+            .save(&mut ctx.storage, &eth, &eth_price_source)
+            .unwrap();
+
+        // Migrating a second time changes nothing.
+        for _ in 0..2 {
+            migrate(ctx.as_sudo(), MigrateMsg {}).unwrap();
+
+            assert_eq!(
+                PRICES.load(&ctx.storage, USDC_USD_ID).unwrap(),
+                PrecisionlessPrice::new(
+                    Udec128::new_percent(99),
+                    Udec128::ONE,
+                    Udec128::ZERO,
+                    1730802926
+                )
+            );
+            assert_eq!(
+                PRICE_SOURCES.load(&ctx.storage, &usdc).unwrap(),
+                PriceSource::Pyth {
+                    id: USDC_USD_ID,
+                    precision: 6,
+                    max_age: None,
+                    max_confidence_ratio: None,
+                }
+            );
+            assert_eq!(
+                PRICE_SOURCES.load(&ctx.storage, &eth).unwrap(),
+                eth_price_source
+            );
+        }
+    }
+}
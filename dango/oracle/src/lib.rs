@@ -1,6 +1,7 @@
+mod error;
 mod execute;
 mod oracle_querier;
 mod query;
 mod state;
 
-pub use {execute::*, oracle_querier::*, query::*, state::*};
+pub use {error::*, execute::*, oracle_querier::*, query::*, state::*};
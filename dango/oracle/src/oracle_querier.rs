@@ -1,34 +1,165 @@
 use {
-    crate::PRICE_SOURCES,
-    anyhow::anyhow,
-    dango_types::oracle::{PrecisionedPrice, PrecisionlessPrice, PriceSource, PRICES},
-    grug::{Addr, BorshDeExt, Denom, QuerierWrapper},
+    crate::{OracleError, OracleResult, PRICE_SOURCES},
+    dango_types::{
+        amm::QueryTwapRequest,
+        config::AppConfig,
+        oracle::{PrecisionedPrice, PrecisionlessPrice, PriceSource, PRICES},
+    },
+    grug::{
+        Addr, BorshDeExt, Denom, Number, NumberConst, PrevNumber, QuerierWrapper, Timestamp,
+        Udec128, Uint128,
+    },
 };
 
 /// A trait for querying prices from the oracle.
 pub trait OracleQuerier {
     /// Queries the price for a given denom from the oracle.
-    fn query_price(&self, oracle: Addr, denom: &Denom) -> anyhow::Result<PrecisionedPrice>;
+    ///
+    /// Errors if the price is older than, or more uncertain than, what the
+    /// denom's price source allows as of the given time.
+    fn query_price(
+        &self,
+        oracle: Addr,
+        denom: &Denom,
+        current_time: Timestamp,
+    ) -> OracleResult<PrecisionedPrice>;
 }
 
 impl OracleQuerier for QuerierWrapper<'_> {
-    fn query_price(&self, oracle: Addr, denom: &Denom) -> anyhow::Result<PrecisionedPrice> {
-        let price_source = self
-            .query_wasm_raw(oracle, PRICE_SOURCES.path(denom))?
-            .ok_or(anyhow::anyhow!(
-                "price source not found for denom `{denom}`"
-            ))?
-            .deserialize_borsh::<PriceSource>()?;
+    fn query_price(
+        &self,
+        oracle: Addr,
+        denom: &Denom,
+        current_time: Timestamp,
+    ) -> OracleResult<PrecisionedPrice> {
+        let price_source = query_price_source(self, oracle, denom)?;
 
         match price_source {
-            PriceSource::Pyth { id, precision } => {
-                let price = self
-                    .query_wasm_raw(oracle, PRICES.path(id))?
-                    .ok_or(anyhow!("price not found for pyth id: {id}"))?
-                    .deserialize_borsh::<PrecisionlessPrice>()?
-                    .with_precision(precision);
-                Ok(price)
+            PriceSource::AmmTwap {
+                pool_id,
+                window,
+                precision,
+            } => {
+                let app_cfg: AppConfig = self.query_app_config()?;
+                let twap = self.query_wasm_smart(app_cfg.addresses.amm, QueryTwapRequest {
+                    pool_id,
+                    window,
+                })?;
+
+                // Find the pool's other token, and the amount of it that one
+                // unit of this token is worth.
+                let (other_denom, unit_price) = if *denom == twap.base_denom {
+                    (twap.quote_denom, twap.price.checked_into_prev()?)
+                } else if *denom == twap.quote_denom {
+                    (
+                        twap.base_denom,
+                        Udec128::ONE.checked_div(twap.price.checked_into_prev()?)?,
+                    )
+                } else {
+                    return Err(OracleError::DenomNotInPool {
+                        pool_id,
+                        denom: denom.clone(),
+                    });
+                };
+
+                let other_price_source = query_price_source(self, oracle, &other_denom)?;
+                let other_price =
+                    get_price(self, oracle, &other_denom, other_price_source, current_time)?;
+
+                // Convert the price from per unit of the other token to per
+                // humanized unit of this token.
+                let scale = Udec128::checked_from_ratio(
+                    Uint128::TEN.checked_pow(precision as u32)?,
+                    Uint128::TEN.checked_pow(other_price.precision() as u32)?,
+                )?;
+                let humanized_price = other_price
+                    .humanized_price
+                    .checked_mul(unit_price)?
+                    .checked_mul(scale)?;
+                let humanized_confidence = other_price
+                    .humanized_confidence
+                    .checked_mul(unit_price)?
+                    .checked_mul(scale)?;
+
+                Ok(PrecisionedPrice::new(
+                    humanized_price,
+                    humanized_price,
+                    humanized_confidence,
+                    other_price.timestamp,
+                    precision,
+                ))
             },
+            price_source => get_price(self, oracle, denom, price_source, current_time),
         }
     }
 }
+
+fn query_price_source(
+    querier: &QuerierWrapper,
+    oracle: Addr,
+    denom: &Denom,
+) -> OracleResult<PriceSource> {
+    Ok(querier
+        .query_wasm_raw(oracle, PRICE_SOURCES.path(denom))?
+        .ok_or_else(|| OracleError::PriceSourceNotFound {
+            denom: denom.clone(),
+        })?
+        .deserialize_borsh()?)
+}
+
+/// Get the price from a price source that doesn't depend on other prices.
+fn get_price(
+    querier: &QuerierWrapper,
+    oracle: Addr,
+    denom: &Denom,
+    price_source: PriceSource,
+    current_time: Timestamp,
+) -> OracleResult<PrecisionedPrice> {
+    match price_source {
+        PriceSource::Pyth {
+            id,
+            precision,
+            max_age,
+            max_confidence_ratio,
+        } => {
+            let price = querier
+                .query_wasm_raw(oracle, PRICES.path(id))?
+                .ok_or(OracleError::PriceNotFound { id })?
+                .deserialize_borsh::<PrecisionlessPrice>()?
+                .with_precision(precision);
+
+            if let Some(max_age) = max_age {
+                if price.is_stale(current_time, max_age) {
+                    return Err(OracleError::StalePrice {
+                        denom: denom.clone(),
+                        timestamp: price.timestamp,
+                        max_age: max_age.into_seconds(),
+                    });
+                }
+            }
+
+            if let Some(max_confidence_ratio) = max_confidence_ratio {
+                let confidence_ratio = price.confidence_ratio()?;
+                if confidence_ratio > max_confidence_ratio {
+                    return Err(OracleError::UncertainPrice {
+                        denom: denom.clone(),
+                        confidence_ratio,
+                        max_confidence_ratio,
+                    });
+                }
+            }
+
+            Ok(price)
+        },
+        PriceSource::Fixed { price, precision } => Ok(PrecisionedPrice::new(
+            price,
+            price,
+            Udec128::ZERO,
+            0,
+            precision,
+        )),
+        PriceSource::AmmTwap { .. } => Err(OracleError::NestedAmmTwap {
+            denom: denom.clone(),
+        }),
+    }
+}
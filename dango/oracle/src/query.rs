@@ -1,6 +1,6 @@
 use {
-    crate::{GUARDIAN_SETS, PRICE_SOURCES},
-    dango_types::oracle::{GuardianSet, PrecisionedPrice, PriceSource, QueryMsg},
+    crate::{OracleQuerier, GUARDIAN_SETS, PRICE_SOURCES},
+    dango_types::oracle::{GuardianSet, PrecisionedPrice, PriceSource, QueryMsg, PRICES},
     grug::{Bound, Denom, ImmutableCtx, Json, JsonSerExt, Order, StdResult},
     std::collections::BTreeMap,
 };
@@ -26,6 +26,10 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
             let res = query_price_sources(ctx, start_after, limit)?;
             Ok(res.to_json_value()?)
         },
+        QueryMsg::StalePrices { start_after, limit } => {
+            let res = query_stale_prices(ctx, start_after, limit)?;
+            Ok(res.to_json_value()?)
+        },
         QueryMsg::GuardianSet { index } => {
             let res = query_guardian_set(ctx, index)?;
             Ok(res.to_json_value()?)
@@ -38,9 +42,9 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> anyhow::Result<Json> {
 }
 
 fn query_price(ctx: ImmutableCtx, denom: Denom) -> anyhow::Result<PrecisionedPrice> {
-    PRICE_SOURCES
-        .load(ctx.storage, &denom)?
-        .get_price(ctx.storage)
+    Ok(ctx
+        .querier
+        .query_price(ctx.contract, &denom, ctx.block.timestamp)?)
 }
 
 fn query_prices(
@@ -52,11 +56,13 @@ fn query_prices(
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
 
     PRICE_SOURCES
-        .range(ctx.storage, start, None, Order::Ascending)
+        .keys(ctx.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|res| {
-            let (denom, price_source) = res?;
-            let price = price_source.get_price(ctx.storage)?;
+            let denom = res?;
+            let price = ctx
+                .querier
+                .query_price(ctx.contract, &denom, ctx.block.timestamp)?;
             Ok((denom, price))
         })
         .collect()
@@ -80,6 +86,46 @@ fn query_price_sources(
         .collect()
 }
 
+fn query_stale_prices(
+    ctx: ImmutableCtx,
+    start_after: Option<Denom>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<Denom, Option<u64>>> {
+    let start = start_after.as_ref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+
+    let mut stale_prices = BTreeMap::new();
+
+    for res in PRICE_SOURCES
+        .range(ctx.storage, start, None, Order::Ascending)
+        .take(limit)
+    {
+        let (denom, price_source) = res?;
+
+        // Only Pyth feeds with a maximum age can be stale.
+        let PriceSource::Pyth {
+            id,
+            max_age: Some(max_age),
+            ..
+        } = price_source
+        else {
+            continue;
+        };
+
+        match PRICES.may_load(ctx.storage, id)? {
+            Some(price) if price.is_stale(ctx.block.timestamp, max_age) => {
+                stale_prices.insert(denom, Some(price.timestamp));
+            },
+            Some(_) => {},
+            None => {
+                stale_prices.insert(denom, None);
+            },
+        }
+    }
+
+    Ok(stale_prices)
+}
+
 fn query_guardian_set(ctx: ImmutableCtx, index: u32) -> StdResult<GuardianSet> {
     GUARDIAN_SETS.load(ctx.storage, index)
 }
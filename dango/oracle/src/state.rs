@@ -1,8 +1,28 @@
 use {
-    dango_types::oracle::{GuardianSet, PriceSource},
-    grug::{Denom, Map},
+    dango_types::oracle::{GuardianSet, PriceSource, PythId},
+    grug::{Denom, Map, Udec128},
 };
 
 pub const GUARDIAN_SETS: Map<u32, GuardianSet> = Map::new("guardian_set");
 
 pub const PRICE_SOURCES: Map<&Denom, PriceSource> = Map::new("price_source");
+
+/// Price sources as stored by previous versions of the contract, under the
+/// same namespace as `PRICE_SOURCES`, before the price limits were added.
+pub const LEGACY_PRICE_SOURCES: Map<&Denom, LegacyPriceSource> = Map::new("price_source");
+
+/// Prices as stored by previous versions of the contract, under the same
+/// namespace as `PRICES`, before the confidence interval was added.
+pub const LEGACY_PRICES: Map<PythId, LegacyPrice> = Map::new("price");
+
+#[grug::derive(Borsh)]
+pub struct LegacyPrice {
+    pub humanized_price: Udec128,
+    pub humanized_ema: Udec128,
+    pub timestamp: u64,
+}
+
+#[grug::derive(Borsh)]
+pub enum LegacyPriceSource {
+    Pyth { id: PythId, precision: u8 },
+}
@@ -1,6 +1,6 @@
 use {
     crate::{TestAccount, TestAccounts},
-    dango_app::ProposalPreparer,
+    dango_app::{PriceFeeder, ProposalPreparer},
     dango_genesis::{
         build_genesis, build_rust_codes, read_wasm_files, Codes, Contracts, GenesisUser,
     },
@@ -64,6 +64,29 @@ pub fn setup_test() -> (TestSuite, TestAccounts, Codes<ContractWrapper>, Contrac
     )
 }
 
+/// Set up a `TestSuite` with `MemDb`, `RustVm`, `ProposalPreparer`, and
+/// `ContractWrapper` codes, with the proposal preparer pulling prices from the
+/// given feeder.
+///
+/// Used for running tests that require an oracle feed, without depending on
+/// the Pyth API.
+pub fn setup_test_with_price_feeder<P>(
+    feeder: P,
+) -> (TestSuite, TestAccounts, Codes<ContractWrapper>, Contracts)
+where
+    P: PriceFeeder,
+{
+    let codes = build_rust_codes();
+
+    setup_suite_with_db_and_vm(
+        MemDb::new(),
+        RustVm::new(),
+        codes,
+        ProposalPreparer::new_with_feeder(feeder),
+        NullIndexer,
+    )
+}
+
 /// Set up a `TestSuite` with `MemDb`, `RustVm`, `NaiveProposalPreparer`, and
 /// `ContractWrapper` codes.
 ///
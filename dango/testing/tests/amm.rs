@@ -2,14 +2,15 @@ use {
     dango_testing::setup_test,
     dango_types::{
         amm::{
-            self, ExecuteMsg, FeeRate, Pool, PoolParams, QueryPoolRequest, QueryPoolsRequest,
-            XykParams, XykPool, MINIMUM_LIQUIDITY,
+            self, ConcentratedParams, ExecuteMsg, FeeGrowth, FeeRate, Pool, PoolParams, Position,
+            QueryPoolRequest, QueryPoolsRequest, QueryPositionRequest, QuerySimulateRequest,
+            QueryTicksRequest, SwapOutcome, XykParams, XykPool, MINIMUM_LIQUIDITY,
         },
         config::DANGO_DENOM,
     },
     grug::{
-        btree_map, coins, Coin, CoinPair, Coins, Denom, Message, NonEmpty, ResultExt, Udec128,
-        Uint128, UniqueVec,
+        btree_map, coins, Addressable, Coin, CoinPair, Coins, Denom, IsZero, Message, NonEmpty,
+        ResultExt, Udec128, Uint128, UniqueVec,
     },
     std::{str::FromStr, sync::LazyLock},
 };
@@ -437,3 +438,310 @@ fn amm() {
             shares: Uint128::new(1_311_793_604_756),
         }));
 }
+
+#[test]
+fn concentrated_liquidity() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    let params = ConcentratedParams {
+        tick_spacing: 10,
+        fee_tier: FeeRate::new_unchecked(Udec128::new_bps(30)),
+    };
+    let full_range = params.full_range();
+
+    // ----------------------------- Pool creation -----------------------------
+
+    // Create an ATOM-USDC pool at a price of 4 USDC per ATOM.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::CreatePool(PoolParams::Concentracted(params.clone())),
+            coins! {
+                ATOM.clone() => Uint128::new(1_000_000_000),
+                // liquidity + pool creation fee
+                USDC.clone() => Uint128::new(4_010_000_000),
+            },
+        )
+        .should_succeed();
+
+    // The initial liquidity is provided over the full price range.
+    //
+    // Since the range is finite, the liquidity is slightly bigger than that
+    // of an xyk pool: sqrt(1,000,000,000 * 4,000,000,000) = 2,000,000,000.
+    // One unit of ATOM isn't needed due to rounding, and is refunded.
+    let pool = suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed();
+    let Pool::Concentrated(pool) = pool else {
+        panic!("expecting a concentrated liquidity pool");
+    };
+
+    assert_eq!(pool.params, params);
+    assert_eq!(pool.tick, 13_863);
+    assert_eq!(pool.active_liquidity, Uint128::new(2_000_000_001));
+    assert_eq!(
+        pool.liquidity,
+        CoinPair::new_unchecked(
+            Coin::new(ATOM.clone(), Uint128::new(999_999_999)).unwrap(),
+            Coin::new(USDC.clone(), Uint128::new(4_000_000_000)).unwrap(),
+        )
+    );
+
+    // The minimum liquidity is withheld by the contract. The rest goes to the
+    // pool creator.
+    for (owner, liquidity) in [
+        (contracts.amm, MINIMUM_LIQUIDITY),
+        (accounts.user1.address(), Uint128::new(1_999_999_001)),
+    ] {
+        suite
+            .query_wasm_smart(contracts.amm, QueryPositionRequest {
+                pool_id: 1,
+                owner,
+                lower_tick: full_range.0,
+                upper_tick: full_range.1,
+            })
+            .should_succeed_and_equal(Position {
+                liquidity,
+                ..pool.empty_position()
+            });
+    }
+
+    // No liquidity tokens are minted.
+    suite
+        .query_balances(&accounts.user1)
+        .should_succeed_and_equal(coins! {
+            DANGO_DENOM.clone() => Uint128::new(100_000_000_000_000),
+            // 100,000,000,000,000 - 999,999,999
+            ATOM.clone() => Uint128::new(99_999_000_000_001),
+            OSMO.clone() => Uint128::new(100_000_000_000_000),
+            // 100,000,000,000,000 - 4,000,000,000 - 10,000,000
+            USDC.clone() => Uint128::new(99_995_990_000_000),
+        });
+
+    // -------------------------- Liquidity provision --------------------------
+
+    // Shares-based liquidity provision doesn't apply to this pool type.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::ProvideLiquidity {
+                pool_id: 1,
+                minimum_output: None,
+            },
+            coins! {
+                ATOM.clone() => Uint128::new(100),
+                USDC.clone() => Uint128::new(400),
+            },
+        )
+        .should_fail_with_error("concentrated liquidity pools don't issue liquidity shares");
+
+    // Ticks must be multiples of the tick spacing.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::IncreasePosition {
+                pool_id: 1,
+                lower_tick: 13_005,
+                upper_tick: 14_500,
+                minimum_liquidity: None,
+            },
+            coins! {
+                ATOM.clone() => Uint128::new(500_000_000),
+                USDC.clone() => Uint128::new(500_000_000),
+            },
+        )
+        .should_fail_with_error("ticks must be multiples of the tick spacing");
+
+    // Provide liquidity to the price range [1.0001^13,000, 1.0001^14,500],
+    // or approximately [3.67, 4.26], which contains the current price.
+    //
+    // USDC is the limiting token. Only 92,626,668 ATOM is needed; the rest is
+    // refunded.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::IncreasePosition {
+                pool_id: 1,
+                lower_tick: 13_000,
+                upper_tick: 14_500,
+                minimum_liquidity: Some(Uint128::new(5_900_000_000)),
+            },
+            coins! {
+                ATOM.clone() => Uint128::new(500_000_000),
+                USDC.clone() => Uint128::new(500_000_000),
+            },
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.amm, QueryPositionRequest {
+            pool_id: 1,
+            owner: accounts.user1.address(),
+            lower_tick: 13_000,
+            upper_tick: 14_500,
+        })
+        .should_succeed_and_equal(Position {
+            liquidity: Uint128::new(5_915_660_132),
+            ..pool.empty_position()
+        });
+
+    suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed_and(|pool: &Pool| match pool {
+            // 2,000,000,001 + 5,915,660,132
+            Pool::Concentrated(pool) => pool.active_liquidity == Uint128::new(7_915_660_133),
+            _ => false,
+        });
+
+    // --------------------------------- Swap ----------------------------------
+
+    // Swap USDC for ATOM. This pushes the price above the ranged position's
+    // upper tick, after which only the full range liquidity is active.
+    let outcome = suite
+        .query_wasm_smart(contracts.amm, QuerySimulateRequest {
+            input: Coin::new(USDC.clone(), Uint128::new(3_000_000_000)).unwrap(),
+            route: UniqueVec::new_unchecked(vec![1]),
+        })
+        .should_succeed();
+
+    // output_before_protocol_fee = 486,705,699
+    // protocol_fee = 486,705,699 * 10 / 10,000 = 486,706 (Note: ceil)
+    // output = 486,705,699 - 486,706 = 486,218,993
+    assert_eq!(outcome, SwapOutcome {
+        output: Coin::new(ATOM.clone(), Uint128::new(486_218_993)).unwrap(),
+        protocol_fee: Coin::new(ATOM.clone(), Uint128::new(486_706)).unwrap(),
+        liquidity_fees: coins! { ATOM.clone() => Uint128::new(1_464_510) },
+    });
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.amm,
+            &ExecuteMsg::Swap {
+                route: UniqueVec::new_unchecked(vec![1]),
+                minimum_output: Some(outcome.output.amount),
+            },
+            Coin::new(USDC.clone(), Uint128::new(3_000_000_000)).unwrap(),
+        )
+        .should_succeed();
+
+    // The actual swap should match the simulation.
+    suite
+        .query_balance(&accounts.owner, ATOM.clone())
+        .should_succeed_and_equal(outcome.output.amount);
+
+    suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed_and(|pool: &Pool| match pool {
+            Pool::Concentrated(pool) => {
+                pool.tick == 23_932 && pool.active_liquidity == Uint128::new(2_000_000_001)
+            },
+            _ => false,
+        });
+
+    // ------------------------------ Fee accrual ------------------------------
+
+    // The ranged position earns fees only from the part of the swap within its
+    // range, pro rata to its share of the active liquidity.
+    let position = suite
+        .query_wasm_smart(contracts.amm, QueryPositionRequest {
+            pool_id: 1,
+            owner: accounts.user1.address(),
+            lower_tick: 13_000,
+            upper_tick: 14_500,
+        })
+        .should_succeed();
+
+    assert_eq!(
+        position.fees_owed,
+        CoinPair::new_unchecked(
+            Coin::new(ATOM.clone(), Uint128::new(277_880)).unwrap(),
+            Coin::new(USDC.clone(), Uint128::ZERO).unwrap(),
+        )
+    );
+
+    let atom_before = suite
+        .query_balance(&accounts.user1, ATOM.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::CollectFees {
+                pool_id: 1,
+                lower_tick: 13_000,
+                upper_tick: 14_500,
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.user1, ATOM.clone())
+        .should_succeed_and_equal(atom_before + Uint128::new(277_880));
+
+    // ------------------------- Liquidity withdrawal --------------------------
+
+    // Withdraw all liquidity from the ranged position. The price is now above
+    // the range, so the position consists entirely of USDC.
+    let usdc_before = suite
+        .query_balance(&accounts.user1, USDC.clone())
+        .should_succeed();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &ExecuteMsg::DecreasePosition {
+                pool_id: 1,
+                lower_tick: 13_000,
+                upper_tick: 14_500,
+                liquidity: Uint128::new(5_915_660_132),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.user1, USDC.clone())
+        .should_succeed_and_equal(usdc_before + Uint128::new(882_484_458));
+
+    // The position has neither liquidity nor fees left, so it's deleted.
+    suite
+        .query_wasm_smart(contracts.amm, QueryPositionRequest {
+            pool_id: 1,
+            owner: accounts.user1.address(),
+            lower_tick: 13_000,
+            upper_tick: 14_500,
+        })
+        .should_fail();
+
+    // The range's ticks are no longer used, so they're removed as well.
+    suite
+        .query_wasm_smart(contracts.amm, QueryTicksRequest {
+            pool_id: 1,
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and(|ticks| {
+            ticks.keys().copied().collect::<Vec<_>>() == vec![full_range.0, full_range.1]
+        });
+
+    // Fees earned by the full range liquidity aren't affected.
+    suite
+        .query_wasm_smart(contracts.amm, QueryPositionRequest {
+            pool_id: 1,
+            owner: accounts.user1.address(),
+            lower_tick: full_range.0,
+            upper_tick: full_range.1,
+        })
+        .should_succeed_and(|position: &Position| {
+            position.fees_owed.first().amount.is_non_zero()
+                && position.fee_growth_inside_last != FeeGrowth::default()
+        });
+}
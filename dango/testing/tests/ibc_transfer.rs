@@ -0,0 +1,322 @@
+use {
+    dango_testing::setup_test,
+    dango_types::ibc::transfer::{
+        self, Acknowledgement, DenomTrace, FungibleTokenPacketData, QueryDenomTraceRequest,
+        QueryEscrowRequest,
+    },
+    grug::{
+        Addressable, Binary, Coins, Empty, IbcEndpoint, IbcPacket, JsonSerExt, Message,
+        MsgIbcPacketAck, MsgIbcPacketReceive, MsgIbcPacketTimeout, NumberConst, ResultExt,
+        Timestamp, Uint128,
+    },
+    grug_vm_rust::ContractBuilder,
+};
+
+/// An IBC handler that accepts any packet sent to it, and delivers whatever
+/// message a relayer gives it without verifying any proof.
+mod mock_ibc_handler {
+    use grug::{Binary, Empty, Message, MutableCtx, Response, StdResult, Timestamp};
+
+    #[grug::derive(Serde)]
+    pub enum ExecuteMsg {
+        SendPacket {
+            channel_id: String,
+            data: Binary,
+            timeout_height: Option<u64>,
+            timeout_timestamp: Option<Timestamp>,
+        },
+        Relay {
+            msg: Message,
+        },
+    }
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(_ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::SendPacket { .. } => Ok(Response::new()),
+            ExecuteMsg::Relay { msg } => Ok(Response::new().add_message(msg)),
+        }
+    }
+}
+
+/// A packet sent from the counterparty chain's `channel-5` to this chain's
+/// `channel-0`, or the other way around.
+fn mock_packet(outgoing: bool, data: &FungibleTokenPacketData) -> IbcPacket {
+    let here = IbcEndpoint {
+        port_id: transfer::PORT_ID.to_string(),
+        channel_id: "channel-0".to_string(),
+    };
+    let there = IbcEndpoint {
+        port_id: transfer::PORT_ID.to_string(),
+        channel_id: "channel-5".to_string(),
+    };
+
+    let (source, destination) = if outgoing {
+        (here, there)
+    } else {
+        (there, here)
+    };
+
+    IbcPacket {
+        source,
+        destination,
+        sequence: 1,
+        data: data.to_json_vec().unwrap().into(),
+        timeout_height: None,
+        timeout_timestamp: None,
+    }
+}
+
+#[test]
+fn ibc_transfer() {
+    let (mut suite, mut accounts, _, contracts) = setup_test();
+
+    // Deploy the mock IBC handler, and set it as the chain's IBC handler.
+    let handler = suite
+        .upload_and_instantiate(
+            &mut accounts.owner,
+            ContractBuilder::new(Box::new(mock_ibc_handler::instantiate))
+                .with_execute(Box::new(mock_ibc_handler::execute))
+                .build(),
+            &Empty {},
+            "mock_ibc_handler",
+            None::<String>,
+            None,
+            Coins::new(),
+        )
+        .should_succeed()
+        .address;
+
+    let mut cfg = suite.query_config().should_succeed();
+    cfg.ibc_handler = Some(handler);
+
+    suite
+        .configure(&mut accounts.owner, Some(cfg), None::<Empty>)
+        .should_succeed();
+
+    let timeout = suite.block.timestamp + Timestamp::from_seconds(600);
+    let usdc_balance = suite.query_balance(&accounts.user1, "uusdc").unwrap();
+
+    // ------------------------- send a native token out -------------------------
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.ibc_transfer,
+            &transfer::ExecuteMsg::Transfer {
+                channel: "channel-0".to_string(),
+                receiver: "cosmos1receiver".to_string(),
+                timeout,
+            },
+            Coins::one("uusdc", 100).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.ibc_transfer, QueryEscrowRequest {
+            channel: "channel-0".to_string(),
+            denom: "uusdc".parse().unwrap(),
+        })
+        .should_succeed_and_equal(Uint128::new(100));
+
+    // The packet times out, so the tokens are released from escrow back to
+    // the sender.
+    let outgoing_usdc = FungibleTokenPacketData {
+        denom: "uusdc".to_string(),
+        amount: Uint128::new(100),
+        sender: accounts.user1.address().to_string(),
+        receiver: "cosmos1receiver".to_string(),
+    };
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            handler,
+            &mock_ibc_handler::ExecuteMsg::Relay {
+                msg: Message::IbcPacketTimeout(MsgIbcPacketTimeout {
+                    contract: contracts.ibc_transfer,
+                    packet: mock_packet(true, &outgoing_usdc),
+                    relayer: accounts.user2.address(),
+                }),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.ibc_transfer, QueryEscrowRequest {
+            channel: "channel-0".to_string(),
+            denom: "uusdc".parse().unwrap(),
+        })
+        .should_succeed_and_equal(Uint128::ZERO);
+
+    suite
+        .query_balance(&accounts.user1, "uusdc")
+        .should_succeed_and_equal(usdc_balance);
+
+    // ---------------------- receive a token from elsewhere ----------------------
+
+    let incoming_atom = FungibleTokenPacketData {
+        denom: "uatom".to_string(),
+        amount: Uint128::new(200),
+        sender: "cosmos1sender".to_string(),
+        receiver: accounts.user1.address().to_string(),
+    };
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            handler,
+            &mock_ibc_handler::ExecuteMsg::Relay {
+                msg: Message::IbcPacketReceive(MsgIbcPacketReceive {
+                    contract: contracts.ibc_transfer,
+                    packet: mock_packet(false, &incoming_atom),
+                    relayer: accounts.user2.address(),
+                }),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    let atom_trace = DenomTrace {
+        path: "transfer/channel-0".to_string(),
+        base_denom: "uatom".to_string(),
+    };
+    let atom = atom_trace.ibc_denom();
+
+    suite
+        .query_wasm_smart(contracts.ibc_transfer, QueryDenomTraceRequest {
+            denom: atom.clone(),
+        })
+        .should_succeed_and_equal(atom_trace);
+
+    suite
+        .query_balance(&accounts.user1, atom.clone())
+        .should_succeed_and_equal(Uint128::new(200));
+
+    // Send the voucher back. It's burned instead of escrowed.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.ibc_transfer,
+            &transfer::ExecuteMsg::Transfer {
+                channel: "channel-0".to_string(),
+                receiver: "cosmos1sender".to_string(),
+                timeout,
+            },
+            Coins::one(atom.clone(), 200).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.user1, atom.clone())
+        .should_succeed_and_equal(Uint128::ZERO);
+
+    suite
+        .query_wasm_smart(contracts.ibc_transfer, QueryEscrowRequest {
+            channel: "channel-0".to_string(),
+            denom: atom.clone(),
+        })
+        .should_succeed_and_equal(Uint128::ZERO);
+
+    // The counterparty fails to process the packet, so the voucher is minted
+    // back to the sender.
+    let outgoing_atom = FungibleTokenPacketData {
+        denom: "transfer/channel-0/uatom".to_string(),
+        amount: Uint128::new(200),
+        sender: accounts.user1.address().to_string(),
+        receiver: "cosmos1sender".to_string(),
+    };
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            handler,
+            &mock_ibc_handler::ExecuteMsg::Relay {
+                msg: Message::IbcPacketAck(MsgIbcPacketAck {
+                    contract: contracts.ibc_transfer,
+                    packet: mock_packet(true, &outgoing_atom),
+                    acknowledgement: Acknowledgement::Error("oops".to_string())
+                        .to_json_vec()
+                        .map(Binary::from)
+                        .unwrap(),
+                    relayer: accounts.user2.address(),
+                }),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.user1, atom)
+        .should_succeed_and_equal(Uint128::new(200));
+
+    // --------------------- receive a native token back home ---------------------
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.ibc_transfer,
+            &transfer::ExecuteMsg::Transfer {
+                channel: "channel-0".to_string(),
+                receiver: "cosmos1receiver".to_string(),
+                timeout,
+            },
+            Coins::one("uusdc", 100).unwrap(),
+        )
+        .should_succeed();
+
+    // The token comes back over the same channel, with the counterparty's end
+    // of the channel in its trace. The tokens are released from escrow.
+    let incoming_usdc = FungibleTokenPacketData {
+        denom: "transfer/channel-5/uusdc".to_string(),
+        amount: Uint128::new(100),
+        sender: "cosmos1receiver".to_string(),
+        receiver: accounts.user1.address().to_string(),
+    };
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            handler,
+            &mock_ibc_handler::ExecuteMsg::Relay {
+                msg: Message::IbcPacketReceive(MsgIbcPacketReceive {
+                    contract: contracts.ibc_transfer,
+                    packet: mock_packet(false, &incoming_usdc),
+                    relayer: accounts.user2.address(),
+                }),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.ibc_transfer, QueryEscrowRequest {
+            channel: "channel-0".to_string(),
+            denom: "uusdc".parse().unwrap(),
+        })
+        .should_succeed_and_equal(Uint128::ZERO);
+
+    suite
+        .query_balance(&accounts.user1, "uusdc")
+        .should_succeed_and_equal(usdc_balance);
+
+    // More tokens can't be released than escrowed.
+    suite
+        .execute(
+            &mut accounts.user2,
+            handler,
+            &mock_ibc_handler::ExecuteMsg::Relay {
+                msg: Message::IbcPacketReceive(MsgIbcPacketReceive {
+                    contract: contracts.ibc_transfer,
+                    packet: mock_packet(false, &incoming_usdc),
+                    relayer: accounts.user2.address(),
+                }),
+            },
+            Coins::new(),
+        )
+        .should_fail_with_error("subtraction overflow");
+}
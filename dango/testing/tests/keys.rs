@@ -0,0 +1,123 @@
+use {
+    dango_client::{Ed25519SigningKey, SchnorrSigningKey, Secret, SigningKey, SingleSigner},
+    dango_testing::setup_test_naive,
+    dango_types::{
+        account::spot::QuerySeenNoncesRequest,
+        account_factory::{self, QueryKeyRequest},
+    },
+    grug::{Addressable, Coins, Op, ResultExt},
+};
+
+/// Associate a new key with an existing user, then send a transaction signed
+/// by the new key.
+fn configure_key_then_sign_with<S>(sk: S)
+where
+    S: Secret,
+{
+    let (mut suite, mut accounts, _codes, contracts) = setup_test_naive();
+
+    let key = sk.key();
+    let key_hash = sk.key_hash();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::ConfigureKey {
+                key_hash,
+                key: Op::Insert(key),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.account_factory, QueryKeyRequest {
+            hash: key_hash,
+            username: accounts.user1.username.clone(),
+        })
+        .should_succeed_and_equal(key);
+
+    // The account accepts nonces out of order, so any nonce newer than the
+    // ones already seen works.
+    let nonce = suite
+        .query_wasm_smart(accounts.user1.address(), QuerySeenNoncesRequest {})
+        .should_succeed()
+        .last()
+        .map_or(0, |newest| newest.checked_add(1).unwrap());
+
+    let mut signer = SingleSigner::new(
+        accounts.user1.username.as_ref(),
+        accounts.user1.address(),
+        sk,
+    )
+    .unwrap()
+    .with_nonce(nonce);
+
+    suite
+        .transfer(
+            &mut signer,
+            accounts.user2.address(),
+            Coins::one("uusdc", 100).unwrap(),
+        )
+        .should_succeed();
+}
+
+#[test]
+fn configuring_secp256k1_key_works() {
+    configure_key_then_sign_with(SigningKey::new_random());
+}
+
+#[test]
+fn configuring_ed25519_key_works() {
+    configure_key_then_sign_with(Ed25519SigningKey::new_random());
+}
+
+#[test]
+fn configuring_schnorr_key_works() {
+    configure_key_then_sign_with(SchnorrSigningKey::new_random());
+}
+
+#[test]
+fn mismatched_key_and_signature_fails() {
+    let (mut suite, mut accounts, _codes, contracts) = setup_test_naive();
+
+    // Register an Ed25519 public key, but sign with a Schnorr key under the
+    // same key hash.
+    let ed25519_sk = Ed25519SigningKey::new_random();
+    let schnorr_sk = SchnorrSigningKey::new_random();
+
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.account_factory,
+            &account_factory::ExecuteMsg::ConfigureKey {
+                key_hash: schnorr_sk.key_hash(),
+                key: Op::Insert(ed25519_sk.key()),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    let nonce = suite
+        .query_wasm_smart(accounts.user1.address(), QuerySeenNoncesRequest {})
+        .should_succeed()
+        .last()
+        .map_or(0, |newest| newest.checked_add(1).unwrap());
+
+    let mut signer = SingleSigner::new(
+        accounts.user1.username.as_ref(),
+        accounts.user1.address(),
+        schnorr_sk,
+    )
+    .unwrap()
+    .with_nonce(nonce);
+
+    suite
+        .transfer(
+            &mut signer,
+            accounts.user2.address(),
+            Coins::one("uusdc", 100).unwrap(),
+        )
+        .should_fail_with_error("key and credential types don't match!");
+}
@@ -6,15 +6,16 @@ use {
         account_factory::AccountParams,
         config::AppConfig,
         lending::{
-            self, MarketUpdates, QueryDebtRequest, QueryDebtsRequest, QueryMarketsRequest,
-            NAMESPACE, SUBNAMESPACE,
+            self, InterestRateModel, MarketUpdates, QueryDebtRequest, QueryDebtsRequest,
+            QueryLiquidatableRequest, QueryMarketRequest, QueryMarketsRequest, NAMESPACE,
+            SUBNAMESPACE,
         },
         oracle::{self, PythId},
         token_factory,
     },
     grug::{
-        btree_map, Addressable, Binary, Coin, Coins, Denom, JsonSerExt, Message, MsgConfigure,
-        MsgTransfer, NonEmpty, NumberConst, ResultExt, Udec128, Uint128,
+        btree_map, Addressable, Binary, Coin, Coins, Denom, Duration, Inner, JsonSerExt, Message,
+        MsgConfigure, MsgTransfer, NonEmpty, NumberConst, ResultExt, Udec128, Uint128,
     },
     grug_app::NaiveProposalPreparer,
     grug_vm_rust::VmError,
@@ -52,7 +53,12 @@ fn feed_oracle_usdc_price(
             &mut accounts.owner,
             contracts.oracle,
             &dango_types::oracle::ExecuteMsg::RegisterPriceSources(btree_map! {
-                usdc_denom.clone() => dango_types::oracle::PriceSource::Pyth { id, precision }
+                usdc_denom.clone() => dango_types::oracle::PriceSource::Pyth {
+                    id,
+                    precision,
+                    max_age: None,
+                    max_confidence_ratio: None,
+                }
             }),
             Coins::default(),
         )
@@ -131,13 +137,27 @@ fn update_markets_works() {
         )
         .should_fail_with_error("Only the owner can whitelist denoms");
 
+    // Try to whitelist ATOM without an interest rate model, should fail.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.lending,
+            &lending::ExecuteMsg::UpdateMarkets(btree_map! {
+                ATOM.clone() => MarketUpdates::default(),
+            }),
+            Coins::new(),
+        )
+        .should_fail_with_error("interest rate model must be provided for new market `uatom`");
+
     // Whitelist ATOM from owner, should succeed.
     suite
         .execute(
             &mut accounts.owner,
             contracts.lending,
             &lending::ExecuteMsg::UpdateMarkets(btree_map! {
-                ATOM.clone() => MarketUpdates {},
+                ATOM.clone() => MarketUpdates {
+                    interest_rate_model: Some(InterestRateModel::default()),
+                },
             }),
             Coins::new(),
         )
@@ -171,6 +191,7 @@ fn set_collateral_power(
             Message::Configure(MsgConfigure {
                 new_app_cfg: Some(config.to_json_value().unwrap()),
                 new_cfg: None,
+                upgrade: None,
             }),
         )
         .should_succeed();
@@ -386,7 +407,9 @@ fn borrowing_works() {
             &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 100).unwrap()),
             Coins::new(),
         )
-        .should_fail_with_error("subtraction overflow: 0 - 100");
+        .should_fail_with_error(
+            "not enough liquidity in market `uusdc`: 0 available, 100 requested",
+        );
 
     // Deposit some USDC
     suite
@@ -469,7 +492,9 @@ fn composite_denom() {
             &mut accounts.owner,
             contracts.lending,
             &lending::ExecuteMsg::UpdateMarkets(btree_map! {
-                denom.clone() => MarketUpdates {},
+                denom.clone() => MarketUpdates {
+                    interest_rate_model: Some(InterestRateModel::default()),
+                },
             }),
             Coins::new(),
         )
@@ -641,10 +666,11 @@ fn excess_refunded_when_repaying_more_than_debts() {
         )
         .should_succeed();
 
-    // Check that the excess is refunded
+    // Check that the excess is refunded. The debt accrued interest in the block
+    // between borrowing and repaying, which is rounded up to one unit.
     suite
         .query_balance(&margin_account.address(), USDC.clone())
-        .should_succeed_and_equal(Uint128::new(100));
+        .should_succeed_and_equal(Uint128::new(99));
 }
 
 #[test]
@@ -711,3 +737,472 @@ fn repay_works() {
         )
         .should_succeed();
 }
+
+#[test]
+fn repay_works_with_stale_price() {
+    let (mut suite, mut accounts, _, contracts) = setup_test_naive();
+
+    feed_oracle_usdc_price(&mut suite, &mut accounts, &contracts);
+
+    // Limit the age of the USDC price.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.oracle,
+            &oracle::ExecuteMsg::RegisterPriceSources(btree_map! {
+                USDC.clone() => oracle::PriceSource::Pyth {
+                    id: PythId::from_str(USDC_USD_ID).unwrap(),
+                    precision: 6,
+                    max_age: Some(Duration::from_seconds(60)),
+                    max_confidence_ratio: None,
+                }
+            }),
+            Coins::default(),
+        )
+        .should_succeed();
+
+    // Create a margin account.
+    let mut margin_account = accounts
+        .user1
+        .register_new_account(
+            &mut suite,
+            contracts.account_factory,
+            AccountParams::Margin(single::Params::new(accounts.user1.username.clone())),
+            Coins::new(),
+        )
+        .unwrap();
+
+    // Send some USDC to the margin account.
+    suite
+        .transfer(
+            &mut accounts.user1,
+            margin_account.address(),
+            Coins::one(USDC.clone(), 100).unwrap(),
+        )
+        .should_succeed();
+
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(100)).unwrap(),
+    );
+
+    // Deposit some USDC.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.lending,
+            &lending::ExecuteMsg::Deposit {},
+            Coins::one(USDC.clone(), 100).unwrap(),
+        )
+        .should_succeed();
+
+    // Borrow some USDC, while the price is still fresh.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 50).unwrap()),
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // Fast forward until long after the price was published.
+    suite.block_time = Duration::from_days(365 * 60);
+    suite.make_empty_block();
+    suite.block_time = Duration::from_seconds(0);
+
+    // Borrowing more requires a health check, which fails with a stale price.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 1).unwrap()),
+            Coins::new(),
+        )
+        .should_fail_with_error("is stale");
+
+    // Repaying only makes the account healthier, so it isn't blocked.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Repay {},
+            Coins::one(USDC.clone(), 50).unwrap(),
+        )
+        .should_succeed();
+}
+
+#[test]
+fn interest_accrues() {
+    let (mut suite, mut accounts, _, contracts) = setup_test_naive();
+
+    feed_oracle_usdc_price(&mut suite, &mut accounts, &contracts);
+
+    let lp_denom = USDC.prepend(&[&NAMESPACE, &SUBNAMESPACE]).unwrap();
+
+    // Create a margin account.
+    let mut margin_account = accounts
+        .user1
+        .register_new_account(
+            &mut suite,
+            contracts.account_factory,
+            AccountParams::Margin(single::Params::new(accounts.user1.username.clone())),
+            Coins::new(),
+        )
+        .unwrap();
+
+    // Send some USDC to the margin account as collateral.
+    suite
+        .transfer(
+            &mut accounts.user1,
+            margin_account.address(),
+            Coins::one(USDC.clone(), 1_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(100)).unwrap(),
+    );
+
+    // Deposit some USDC.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.lending,
+            &lending::ExecuteMsg::Deposit {},
+            Coins::one(USDC.clone(), 1_000_000).unwrap(),
+        )
+        .should_succeed();
+
+    // Borrow half of it, bringing the utilization to 50%.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 500_000).unwrap()),
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // Fast forward one year.
+    suite.block_time = Duration::from_days(365);
+    suite.make_empty_block();
+    suite.block_time = Duration::from_seconds(0);
+
+    // With the default interest rate model, at 50% utilization:
+    // - borrow rate = 4% * 50% / 80% = 2.5%
+    // - supply rate = 2.5% * 50% * (1 - 10%) = 1.125%
+    // The remaining 1,250 uusdc of interest go to the protocol, equivalent to
+    // 1,250 / 1.01125 = 1,236 LP tokens.
+    suite
+        .query_wasm_smart(contracts.lending, QueryMarketRequest {
+            denom: USDC.clone(),
+        })
+        .should_succeed_and(|market| {
+            market.borrow_index == Udec128::new_permille(1025)
+                && market.supply_index == Udec128::from_str("1.01125").unwrap()
+                && market.total_borrowed_scaled == Uint128::new(500_000)
+                && market.total_supplied_scaled == Uint128::new(1_001_236)
+                && market.pending_protocol_fee_scaled == Uint128::new(1_236)
+        });
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtRequest {
+            account: margin_account.address(),
+        })
+        .should_succeed_and_equal(Coins::one(USDC.clone(), 512_500).unwrap());
+
+    let balance_before = suite.query_balance(&accounts.user1, USDC.clone()).unwrap();
+
+    // Withdraw some of the deposit, which now includes interest.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.lending,
+            &lending::ExecuteMsg::Withdraw {},
+            Coins::one(lp_denom.clone(), 100_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&accounts.user1, USDC.clone())
+        .should_succeed_and_equal(balance_before + Uint128::new(101_125));
+
+    // The protocol fee is minted to the taxman as LP tokens.
+    suite
+        .query_balance(&contracts.taxman, lp_denom)
+        .should_succeed_and_equal(Uint128::new(1_236));
+
+    // Repay the debt in full. The excess is refunded.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Repay {},
+            Coins::one(USDC.clone(), 600_000).unwrap(),
+        )
+        .should_succeed();
+
+    suite
+        .query_balance(&margin_account.address(), USDC.clone())
+        .should_succeed_and_equal(Uint128::new(1_000_000 + 500_000 - 512_500));
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtsRequest {
+            limit: None,
+            start_after: None,
+        })
+        .should_succeed_and_equal(btree_map! {});
+}
+
+#[test]
+fn liquidation_works() {
+    let (mut suite, mut accounts, _, contracts) = setup_test_naive();
+
+    feed_oracle_usdc_price(&mut suite, &mut accounts, &contracts);
+
+    // Create a margin account.
+    let mut margin_account = accounts
+        .user1
+        .register_new_account(
+            &mut suite,
+            contracts.account_factory,
+            AccountParams::Margin(single::Params::new(accounts.user1.username.clone())),
+            Coins::new(),
+        )
+        .unwrap();
+
+    // Send some USDC to the margin account as collateral.
+    suite
+        .transfer(
+            &mut accounts.user1,
+            margin_account.address(),
+            Coins::one(USDC.clone(), 100).unwrap(),
+        )
+        .should_succeed();
+
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(100)).unwrap(),
+    );
+
+    // Deposit some USDC.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.lending,
+            &lending::ExecuteMsg::Deposit {},
+            Coins::one(USDC.clone(), 100).unwrap(),
+        )
+        .should_succeed();
+
+    // Borrow all of it. The account now has 200 USDC as collateral, and 100
+    // USDC of debt.
+    suite
+        .execute(
+            &mut margin_account,
+            contracts.lending,
+            &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), 100).unwrap()),
+            Coins::new(),
+        )
+        .should_succeed();
+
+    // The account is healthy, so it can't be liquidated.
+    suite
+        .query_wasm_smart(contracts.lending, QueryLiquidatableRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and(|accounts| accounts.is_empty());
+
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.lending,
+            &lending::ExecuteMsg::Liquidate {
+                account: margin_account.address(),
+                debt_to_repay: Coin::new(USDC.clone(), 50).unwrap(),
+            },
+            Coins::one(USDC.clone(), 50).unwrap(),
+        )
+        .should_fail_with_error("is not liquidatable");
+
+    // Lower the collateral power, such that the adjusted collateral value (80)
+    // is now less than the debt.
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(40)).unwrap(),
+    );
+
+    suite
+        .query_wasm_smart(contracts.lending, QueryLiquidatableRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and(|accounts| accounts.contains_key(&margin_account.address()));
+
+    let liquidator_balance_before = suite.query_balance(&accounts.user2, USDC.clone()).unwrap();
+
+    // The debt has accrued interest, rounded up to 101. With a close factor of
+    // 50%, no more than 51 can be repaid, so the rest is refunded.
+    suite
+        .execute(
+            &mut accounts.user2,
+            contracts.lending,
+            &lending::ExecuteMsg::Liquidate {
+                account: margin_account.address(),
+                debt_to_repay: Coin::new(USDC.clone(), 60).unwrap(),
+            },
+            Coins::one(USDC.clone(), 60).unwrap(),
+        )
+        .should_succeed();
+
+    // With a bonus of 5%, the liquidator seizes 51 * 1.05 = 53.55 worth of
+    // collateral, rounded down.
+    suite
+        .query_balance(&accounts.user2, USDC.clone())
+        .should_succeed_and_equal(liquidator_balance_before - Uint128::new(51) + Uint128::new(53));
+
+    suite
+        .query_balance(&margin_account.address(), USDC.clone())
+        .should_succeed_and_equal(Uint128::new(147));
+
+    // About half of the debt is left, depending on rounding.
+    suite
+        .query_wasm_smart(contracts.lending, QueryDebtRequest {
+            account: margin_account.address(),
+        })
+        .should_succeed_and(|debt: &Coins| (50..=52).contains(&debt.amount_of(&USDC).into_inner()));
+
+    // The account is healthy again.
+    suite
+        .query_wasm_smart(contracts.lending, QueryLiquidatableRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and(|accounts| accounts.is_empty());
+}
+
+#[test]
+fn liquidatable_skips_accounts_without_health() {
+    let (mut suite, mut accounts, _, contracts) = setup_test_naive();
+
+    feed_oracle_usdc_price(&mut suite, &mut accounts, &contracts);
+
+    // USDC has a fixed price, so it's never stale. ATOM uses the USDC price
+    // feed, which becomes stale a minute after it's published.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.oracle,
+            &oracle::ExecuteMsg::RegisterPriceSources(btree_map! {
+                USDC.clone() => oracle::PriceSource::Fixed {
+                    price: Udec128::ONE,
+                    precision: 6,
+                },
+                ATOM.clone() => oracle::PriceSource::Pyth {
+                    id: PythId::from_str(USDC_USD_ID).unwrap(),
+                    precision: 6,
+                    max_age: Some(Duration::from_seconds(60)),
+                    max_confidence_ratio: None,
+                },
+            }),
+            Coins::default(),
+        )
+        .should_succeed();
+
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(100)).unwrap(),
+    );
+
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        ATOM.clone(),
+        CollateralPower::new(Udec128::new_percent(100)).unwrap(),
+    );
+
+    // Deposit some USDC.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.lending,
+            &lending::ExecuteMsg::Deposit {},
+            Coins::one(USDC.clone(), 200).unwrap(),
+        )
+        .should_succeed();
+
+    // Create two margin accounts: one with USDC as collateral, the other with
+    // ATOM. Both borrow some USDC.
+    let mut accounts_with_debts = Vec::new();
+
+    for (collateral, borrowed) in [(USDC.clone(), 100), (ATOM.clone(), 10)] {
+        let mut margin_account = accounts
+            .user1
+            .register_new_account(
+                &mut suite,
+                contracts.account_factory,
+                AccountParams::Margin(single::Params::new(accounts.user1.username.clone())),
+                Coins::new(),
+            )
+            .unwrap();
+
+        suite
+            .transfer(
+                &mut accounts.user1,
+                margin_account.address(),
+                Coins::one(collateral, 100).unwrap(),
+            )
+            .should_succeed();
+
+        suite
+            .execute(
+                &mut margin_account,
+                contracts.lending,
+                &lending::ExecuteMsg::Borrow(Coins::one(USDC.clone(), borrowed).unwrap()),
+                Coins::new(),
+            )
+            .should_succeed();
+
+        accounts_with_debts.push(margin_account);
+    }
+
+    // Fast forward until long after the ATOM price was published, making it
+    // stale, so the health of the account with ATOM collateral can't be
+    // computed.
+    suite.block_time = Duration::from_days(365 * 60);
+    suite.make_empty_block();
+    suite.block_time = Duration::from_seconds(0);
+
+    // Lower the collateral power of USDC, such that the account with USDC
+    // collateral becomes liquidatable.
+    set_collateral_power(
+        &mut suite,
+        &mut accounts,
+        USDC.clone(),
+        CollateralPower::new(Udec128::new_percent(40)).unwrap(),
+    );
+
+    // The account with ATOM collateral is skipped, instead of failing the
+    // query.
+    suite
+        .query_wasm_smart(contracts.lending, QueryLiquidatableRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and(|liquidatable| {
+            liquidatable.len() == 1 && liquidatable.contains_key(&accounts_with_debts[0].address())
+        });
+}
@@ -0,0 +1,164 @@
+use {
+    dango_testing::{setup_test_naive, TestAccount},
+    dango_types::{
+        account::spot::QuerySeenNoncesRequest,
+        auth::{Credential, SignDoc},
+    },
+    grug::{Addressable, Coins, Duration, JsonSerExt, Message, NonEmpty, ResultExt, Timestamp, Tx},
+    std::collections::BTreeSet,
+};
+
+fn transfer_tx(
+    sender: &TestAccount,
+    recipient: &TestAccount,
+    chain_id: &str,
+    nonce: u32,
+    expiry: Option<Timestamp>,
+) -> Tx {
+    let msgs = NonEmpty::new_unchecked(vec![Message::transfer(
+        recipient.address(),
+        Coins::one("uusdc", 100).unwrap(),
+    )
+    .unwrap()]);
+
+    let mut data = sender.metadata(chain_id, nonce);
+    data.expiry = expiry;
+
+    let sign_doc = SignDoc {
+        sender: sender.address(),
+        gas_limit: 2_000_000,
+        messages: msgs.clone(),
+        data: data.clone(),
+    };
+
+    let credential =
+        Credential::Standard(sender.create_standard_credential(&sign_doc.to_json_vec().unwrap()));
+
+    Tx {
+        sender: sender.address(),
+        gas_limit: 2_000_000,
+        msgs,
+        data: data.to_json_value().unwrap(),
+        credential: credential.to_json_value().unwrap(),
+    }
+}
+
+#[test]
+fn unordered_nonces() {
+    let (mut suite, accounts, ..) = setup_test_naive();
+
+    let chain_id = suite.chain_id.clone();
+    let tx = |nonce| transfer_tx(&accounts.user1, &accounts.user2, &chain_id, nonce, None);
+
+    // Txs with nonces 2, 0, 1 in this order. A nonce must be newer than the
+    // oldest seen one, even before the window is full, so the first nonce an
+    // account sees is the lower bound of the nonces it accepts. Hence, 2 is
+    // accepted, but 0 and 1 are rejected.
+    let mut outcomes = suite
+        .make_block(vec![tx(2), tx(0), tx(1)])
+        .tx_outcomes
+        .into_iter();
+
+    outcomes.next().unwrap().should_succeed();
+    outcomes
+        .next()
+        .unwrap()
+        .should_fail_with_error("nonce is too old: expecting newer than 2, got 0");
+    outcomes
+        .next()
+        .unwrap()
+        .should_fail_with_error("nonce is too old: expecting newer than 2, got 1");
+
+    // Txs with nonces newer than the oldest seen one are accepted in any
+    // order: 4, then 3.
+    let outcome = suite.make_block(vec![tx(4), tx(3)]);
+    for tx_outcome in outcome.tx_outcomes {
+        tx_outcome.should_succeed();
+    }
+
+    // Skip nonce 5, send 6.
+    suite.make_block(vec![tx(6)]).tx_outcomes[0]
+        .clone()
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(accounts.user1.address(), QuerySeenNoncesRequest {})
+        .should_succeed_and_equal(BTreeSet::from([2, 3, 4, 6]));
+
+    // Replaying a seen nonce should fail.
+    suite.make_block(vec![tx(3)]).tx_outcomes[0]
+        .clone()
+        .should_fail_with_error("nonce is already seen: 3");
+
+    // The skipped nonce can still be used.
+    suite.make_block(vec![tx(5)]).tx_outcomes[0]
+        .clone()
+        .should_succeed();
+}
+
+#[test]
+fn seen_nonces_window() {
+    let (mut suite, accounts, ..) = setup_test_naive();
+
+    let chain_id = suite.chain_id.clone();
+    let tx = |nonce| transfer_tx(&accounts.user1, &accounts.user2, &chain_id, nonce, None);
+
+    // Skip nonce 0, and start from 1.
+    suite.make_block(vec![tx(1)]).tx_outcomes[0]
+        .clone()
+        .should_succeed();
+
+    // Nonce 0 is older than all seen nonces, so it's not acceptable, even
+    // though the window isn't full yet.
+    suite.make_block(vec![tx(0)]).tx_outcomes[0]
+        .clone()
+        .should_fail_with_error("nonce is too old");
+
+    // Fill up the window.
+    let txs = (2..=dango_auth::MAX_SEEN_NONCES as u32).map(tx).collect();
+    for tx_outcome in suite.make_block(txs).tx_outcomes {
+        tx_outcome.should_succeed();
+    }
+
+    // A new nonce pushes the oldest one out of the window.
+    let next = dango_auth::MAX_SEEN_NONCES as u32 + 1;
+    suite.make_block(vec![tx(next)]).tx_outcomes[0]
+        .clone()
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(accounts.user1.address(), QuerySeenNoncesRequest {})
+        .should_succeed_and_equal(BTreeSet::from_iter(2..=next));
+}
+
+#[test]
+fn expired_transactions_are_rejected() {
+    let (mut suite, accounts, ..) = setup_test_naive();
+
+    suite.block_time = Duration::from_seconds(10);
+
+    let expiry = suite.block.timestamp + Duration::from_seconds(5);
+    let tx = transfer_tx(
+        &accounts.user1,
+        &accounts.user2,
+        &suite.chain_id,
+        0,
+        Some(expiry),
+    );
+
+    // The next block's time is later than the expiry.
+    suite
+        .send_transaction(tx)
+        .should_fail_with_error("transaction expired");
+
+    let expiry = suite.block.timestamp + Duration::from_seconds(15);
+    let tx = transfer_tx(
+        &accounts.user1,
+        &accounts.user2,
+        &suite.chain_id,
+        0,
+        Some(expiry),
+    );
+
+    suite.send_transaction(tx).should_succeed();
+}
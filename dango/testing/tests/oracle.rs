@@ -1,11 +1,17 @@
 use {
     dango_testing::{setup_test_naive, TestAccounts, TestSuite},
-    dango_types::oracle::{
-        ExecuteMsg, PrecisionlessPrice, PriceSource, PythId, PythVaa, QueryPriceRequest,
-        ATOM_USD_ID, BNB_USD_ID, DOGE_USD_ID, ETH_USD_ID, SHIBA_USD_ID, SOL_USD_ID, TON_USD_ID,
-        USDC_USD_ID, WBTC_USD_ID, XRP_USD_ID,
+    dango_types::{
+        amm::{self, FeeRate, PoolParams, QueryPoolRequest, XykParams},
+        oracle::{
+            ExecuteMsg, PrecisionlessPrice, PriceSource, PythId, PythVaa, QueryPriceRequest,
+            QueryStalePricesRequest, ATOM_USD_ID, BNB_USD_ID, DOGE_USD_ID, ETH_USD_ID,
+            SHIBA_USD_ID, SOL_USD_ID, TON_USD_ID, USDC_USD_ID, WBTC_USD_ID, XRP_USD_ID,
+        },
+    },
+    grug::{
+        btree_map, coins, Addr, Binary, Coin, Coins, Denom, Inner, MockApi, NonEmpty, NumberConst,
+        ResultExt, Udec128, Uint128, UniqueVec,
     },
-    grug::{btree_map, Addr, Binary, Coins, Denom, Inner, MockApi, NonEmpty, ResultExt, Udec128},
     grug_app::NaiveProposalPreparer,
     pyth_sdk::PriceFeed,
     std::{collections::BTreeMap, str::FromStr, thread, time::Duration},
@@ -46,7 +52,12 @@ fn oracle() {
     let btc_denom = Denom::from_str("bridge/btc").unwrap();
 
     let (mut suite, mut accounts, oracle) = setup_oracle_test(btree_map! {
-        btc_denom.clone() => PriceSource::Pyth { id: WBTC_USD_ID, precision }
+        btc_denom.clone() => PriceSource::Pyth {
+            id: WBTC_USD_ID,
+            precision,
+            max_age: None,
+            max_confidence_ratio: None,
+        }
     });
 
     // Push price
@@ -155,8 +166,18 @@ fn double_vaas() {
     let eth_denom = Denom::from_str("bridge/eth").unwrap();
 
     let (mut suite, mut accounts, oracle) = setup_oracle_test(btree_map! {
-        btc_denom.clone() => PriceSource::Pyth { id: WBTC_USD_ID, precision: 8 },
-        eth_denom.clone() => PriceSource::Pyth { id: ETH_USD_ID, precision: 8 },
+        btc_denom.clone() => PriceSource::Pyth {
+            id: WBTC_USD_ID,
+            precision: 8,
+            max_age: None,
+            max_confidence_ratio: None,
+        },
+        eth_denom.clone() => PriceSource::Pyth {
+            id: ETH_USD_ID,
+            precision: 8,
+            max_age: None,
+            max_confidence_ratio: None,
+        },
     });
 
     for _ in 0..5 {
@@ -297,6 +318,8 @@ fn multiple_vaas() {
             (denom.clone(), PriceSource::Pyth {
                 id: *id,
                 precision: 8,
+                max_age: None,
+                max_confidence_ratio: None,
             })
         })
         .collect();
@@ -388,6 +411,222 @@ fn multiple_vaas() {
     }
 }
 
+#[test]
+fn fixed_price() {
+    let usdc_denom = Denom::from_str("uusdc").unwrap();
+
+    let (suite, _, oracle) = setup_oracle_test(btree_map! {
+        usdc_denom.clone() => PriceSource::Fixed { price: Udec128::ONE, precision: 6 },
+    });
+
+    let current_price = suite
+        .query_wasm_smart(oracle, QueryPriceRequest { denom: usdc_denom })
+        .should_succeed();
+
+    assert_eq!(current_price.humanized_price, Udec128::ONE);
+    assert_eq!(current_price.humanized_ema, Udec128::ONE);
+    assert_eq!(current_price.precision(), 6);
+    assert_eq!(current_price.timestamp, 0);
+}
+
+#[test]
+fn stale_price() {
+    let btc_denom = Denom::from_str("bridge/btc").unwrap();
+
+    let (mut suite, mut accounts, oracle) = setup_oracle_test(btree_map! {
+        btc_denom.clone() => PriceSource::Pyth {
+            id: WBTC_USD_ID,
+            precision: 8,
+            max_age: Some(grug::Duration::from_seconds(60)),
+            max_confidence_ratio: None,
+        }
+    });
+
+    // No price has been fed yet.
+    suite
+        .query_wasm_smart(oracle, QueryStalePricesRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and_equal(btree_map! { btc_denom.clone() => None });
+
+    // Feed a price. The chain's clock is behind the publish time, so the price
+    // is fresh.
+    suite
+        .execute(
+            &mut accounts.owner,
+            oracle,
+            &ExecuteMsg::FeedPrices(NonEmpty::new_unchecked(vec![
+                Binary::from_str(VAA_1).unwrap()
+            ])),
+            Coins::default(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(oracle, QueryPriceRequest {
+            denom: btc_denom.clone(),
+        })
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(oracle, QueryStalePricesRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and_equal(BTreeMap::new());
+
+    // Move the clock far past the publish time. The price is now stale.
+    suite.block_time = grug::Duration::from_seconds(1_800_000_000);
+    suite.make_empty_block();
+
+    suite
+        .query_wasm_smart(oracle, QueryPriceRequest {
+            denom: btc_denom.clone(),
+        })
+        .should_fail_with_error("is stale");
+
+    suite
+        .query_wasm_smart(oracle, QueryStalePricesRequest {
+            start_after: None,
+            limit: None,
+        })
+        .should_succeed_and_equal(btree_map! { btc_denom => Some(1730804420) });
+}
+
+#[test]
+fn uncertain_price() {
+    let btc_denom = Denom::from_str("bridge/btc").unwrap();
+
+    let (mut suite, mut accounts, oracle) = setup_oracle_test(btree_map! {
+        btc_denom.clone() => PriceSource::Pyth {
+            id: WBTC_USD_ID,
+            precision: 8,
+            max_age: None,
+            max_confidence_ratio: Some(Udec128::ZERO),
+        }
+    });
+
+    suite
+        .execute(
+            &mut accounts.owner,
+            oracle,
+            &ExecuteMsg::FeedPrices(NonEmpty::new_unchecked(vec![
+                Binary::from_str(VAA_1).unwrap()
+            ])),
+            Coins::default(),
+        )
+        .should_succeed();
+
+    // Any real price has a non-zero confidence interval.
+    suite
+        .query_wasm_smart(oracle, QueryPriceRequest { denom: btc_denom })
+        .should_fail_with_error("too uncertain");
+}
+
+#[test]
+fn amm_twap_price() {
+    let (mut suite, mut accounts, _, contracts) = setup_test_naive();
+    let atom_denom = Denom::from_str("uatom").unwrap();
+    let usdc_denom = Denom::from_str("uusdc").unwrap();
+
+    suite.block_time = grug::Duration::from_seconds(60);
+
+    // Create an ATOM-USDC pool, where 1 uatom is worth 10 uusdc.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &amm::ExecuteMsg::CreatePool(PoolParams::Xyk(XykParams {
+                liquidity_fee_rate: FeeRate::new_unchecked(Udec128::new_bps(30)),
+            })),
+            coins! {
+                atom_denom.clone() => Uint128::new(100_000_000),
+                // liquidity + pool creation fee
+                usdc_denom.clone() => Uint128::new(1_010_000_000),
+            },
+        )
+        .should_succeed();
+
+    // Price USDC at a fixed $1, and ATOM by its 5-minute TWAP in the pool.
+    suite
+        .execute(
+            &mut accounts.owner,
+            contracts.oracle,
+            &ExecuteMsg::RegisterPriceSources(btree_map! {
+                usdc_denom.clone() => PriceSource::Fixed { price: Udec128::ONE, precision: 6 },
+                atom_denom.clone() => PriceSource::AmmTwap {
+                    pool_id: 1,
+                    window: grug::Duration::from_minutes(5),
+                    precision: 6,
+                },
+            }),
+            Coins::default(),
+        )
+        .should_succeed();
+
+    // The pool is only a minute old, which doesn't cover the window.
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: atom_denom.clone(),
+        })
+        .should_fail_with_error("pool 1 has no price observation");
+
+    // Once the pool is 5 minutes old, the TWAP is simply the spot price.
+    for _ in 0..4 {
+        suite.make_empty_block();
+    }
+
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: atom_denom.clone(),
+        })
+        .should_succeed_and(|price| {
+            price.humanized_price == Udec128::new(10) && price.precision() == 6
+        });
+
+    // Sell ATOM into the pool, 6 minutes after its creation.
+    suite
+        .execute(
+            &mut accounts.user1,
+            contracts.amm,
+            &amm::ExecuteMsg::Swap {
+                route: UniqueVec::new_unchecked(vec![1]),
+                minimum_output: None,
+            },
+            Coin::new(atom_denom.clone(), Uint128::new(100_000_000)).unwrap(),
+        )
+        .should_succeed();
+
+    let pool = suite
+        .query_wasm_smart(contracts.amm, QueryPoolRequest { pool_id: 1 })
+        .should_succeed();
+
+    let amm::Pool::Xyk(xyk) = pool else {
+        panic!("pool 1 is not an xyk pool");
+    };
+
+    let spot_price = Udec128::checked_from_ratio(
+        *xyk.liquidity.second().amount,
+        *xyk.liquidity.first().amount,
+    )
+    .unwrap();
+
+    // Two minutes later, the window covers 3 minutes at the old price of 10,
+    // and 2 minutes at the new spot price.
+    for _ in 0..2 {
+        suite.make_empty_block();
+    }
+
+    let expected_price = (Udec128::new(1800) + Udec128::new(120) * spot_price) / Udec128::new(300);
+
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest { denom: atom_denom })
+        .should_succeed_and(|price| {
+            price.humanized_price == expected_price && price.humanized_price < Udec128::new(10)
+        });
+}
+
 /// Return JSON string of the latest VAA from Pyth network.
 fn get_latest_vaas<I>(ids: I) -> reqwest::Result<Vec<Binary>>
 where
@@ -1,18 +1,55 @@
 use {
     core::time,
-    dango_testing::setup_test,
+    dango_app::{FixturePriceFeeder, HermesPriceFeeder, PriceVote},
+    dango_genesis::{PYTH_MAX_AGE, PYTH_MAX_CONFIDENCE_RATIO},
+    dango_testing::{setup_test, setup_test_with_price_feeder, TestSuite},
     dango_types::oracle::{
-        PriceSource, QueryPriceSourcesRequest, QueryPricesRequest, ETH_USD_ID, USDC_USD_ID,
-        WBTC_USD_ID,
+        ExecuteMsg, PriceSource, QueryPriceRequest, QueryPriceSourcesRequest, QueryPricesRequest,
+        ETH_USD_ID, USDC_USD_ID, WBTC_USD_ID,
     },
-    grug::{btree_map, setup_tracing_subscriber, Denom, ResultExt},
+    grug::{
+        btree_map, serve_http, setup_tracing_subscriber, Addr, Binary, Coins, Denom, Json,
+        JsonSerExt, Message, NonEmpty, ResultExt, Tx, Udec128,
+    },
+    grug_app::{ExtendedCommit, ExtendedVote},
     std::{
+        collections::BTreeMap,
         str::FromStr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
         thread::{self, sleep},
         time::Duration,
     },
 };
 
+/// A VAA containing the price of USDC, signed by the guardian set registered
+/// at genesis:
+/// - price: **100000966**
+/// - expo: **-8**
+/// - publish_time: **1730802926**
+const USDC_VAA: &str = "UE5BVQEAAAADuAEAAAAEDQOoMTxJ5BWLUCMy94ZlQ6qBjQEzA/+ZpDKw9AGFXXSyQF2eIKCGN6cNh1f/jzNSYOf15Yk2CRvOtMc7LqzdG7NpAQSNSaXe+ZOZU4+kxAgG74ZwDUuFmTPlElG90sIMNXfFmS6WJrbTBBQNWFL2gUKpdpEp5z/wUwJo/TzB9lHDnq2vAAbYj1fi3S3mzyOvZAPbe5Qy2/L/oQdLW4FPXTVcNxjMl1m0VLYRonpvIO4/S21ovvsefil9l8R3tYNG879aE2LMAQicgal5v2vVqicVvzE2J1vhg61mEvUKKhiZhzzWo8naRgQfuvKVk3257QhmGaDaAYWxU4MJ7goFUBPbBww9gk53AQpxhRMcpv+qmFMHZCdvoWwF4I/x230bO9VOQXie1tLSf25E62lWTAdYiyrh+h/ny7GA1aDLDZYwEzT6fXUPPlg/AAuQHWuf7TcUkOuIeVisiiI5XINdK8NFu36IacZjf0okOT9dApIx4sLAReROml2hs75v4a1K8SlLB3JdQkQLMoUDAQxZDK7Rh3UBSbjTrBKe+c+5lvT6ZgP26SOqF0F26xJIqwn29C8ZzCKkDgBNzx7GbA4bwL1tNNbv6NSxyx+72AlQAQ2+4nnWuPFUrn5dJJRD5VO6CYNu42Mx4XialbPJ6Lbp3gewVGOIIiU69PyeCxX6/Q/qO99Qtc+QlDGcyjmCwQP1AA7IcDlMiDVc4wEhkfCVRxCr//C3pGZsnxZguQr0MYaSnwGQ/FzJhBsU4knRtTZgUUm3rlcwNWDAJlp5MnNcPuYpAQ91tfYjBU0lRYDoYV/00L+RgJ66vx4P4T3R3x1MuDMAalgVHg43JfcUBGytMHWSbJr/24jMWMsEPMqwBuzPvba5ABDyPKTil7cKBdhyJhTJPYNS0V9JLbS6QLPCThaTyapMMW5BQfB07Q21fXnDNZE/FSoS4JxRiKcViiwRQ4lcLE/UARHcd8PSiHsEilgDjWOH/hvpaQ+Iza+rrBithaw+nJIIdClnizW0DqO2lVx0DlERwF8C9hL3hatj888kVWzwtj6RARJoRZtdYKzWJX8KzJvlOcOBxjjiCSyfo3qLfoLHIw2rJwT7HRxqg1wXswDjq2NVjms9jz24dRIEKM0dxfEP6OckAGcp9O4AAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFb/IiAUFVV1YAAAAAAAp8vHgAACcQO03kFK+kZ552XKseu11fj2cpvpUBAFUA6qAgxhzEeXEoE0Yc4VOJSpamwAsh7Qz8J5jR+anpyUoAAAAABfXkxgAAAAAAAPrQ////+AAAAABnKfTuAAAAAGcp9O4AAAAABfXgkQAAAAAAAQTcCsjx5ZH7wLv7N+2Vzze0aT71EUmuA4n/zf/zQdrI6za/FR4xTLzViierrotGyMoKwkcBs++77xpXHT1p3YXWRMQCLxEONHC/rFMy+rS7i7XohTAftvazeHYjyF6a2rZNmf+KdZS2umZMH9qPKRD3USxGDnXfQMg9mgD6HwJnHiPgaublP56r5AqPcI1tyXKMfF10MWvyxkvJbXFUuYkzW0Pi03Asu75UoUT4XeKBXfvF+EL0NmKGNrmXDYH9NpT5H6pKDeS0JDCZ";
+
+/// A stand-in for Pyth's Hermes API, which serves the given VAAs on every
+/// request for the latest price updates.
+///
+/// Returns the endpoint URL, and a counter of how many requests were served.
+fn serve_latest_vaas(vaas: &'static [&'static str]) -> (String, Arc<AtomicUsize>) {
+    serve_http(move |req| {
+        assert_eq!(req.method, "GET");
+        assert!(req.target.starts_with("/v2/updates/price/latest?"));
+
+        let data = vaas
+            .iter()
+            .map(|vaa| format!("\"{vaa}\""))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(r#"{{"binary":{{"encoding":"base64","data":[{data}]}}}}"#)
+    })
+}
+
 #[test]
 fn proposal_pyth() {
     let (mut suite, _, _, contracts) = setup_test();
@@ -20,9 +57,24 @@ fn proposal_pyth() {
     setup_tracing_subscriber(tracing::Level::INFO);
 
     let price_ids = btree_map! {
-        Denom::from_str("usdc").unwrap() => PriceSource::Pyth { id: USDC_USD_ID, precision: 6 },
-        Denom::from_str("btc").unwrap()  => PriceSource::Pyth { id: WBTC_USD_ID, precision: 8 },
-        Denom::from_str("eth").unwrap()  => PriceSource::Pyth { id: ETH_USD_ID, precision: 18 },
+        Denom::from_str("usdc").unwrap() => PriceSource::Pyth {
+            id: USDC_USD_ID,
+            precision: 6,
+            max_age: Some(PYTH_MAX_AGE),
+            max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+        },
+        Denom::from_str("btc").unwrap() => PriceSource::Pyth {
+            id: WBTC_USD_ID,
+            precision: 8,
+            max_age: Some(PYTH_MAX_AGE),
+            max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+        },
+        Denom::from_str("eth").unwrap() => PriceSource::Pyth {
+            id: ETH_USD_ID,
+            precision: 18,
+            max_age: Some(PYTH_MAX_AGE),
+            max_confidence_ratio: Some(PYTH_MAX_CONFIDENCE_RATIO),
+        },
     };
 
     // Check if they are registered.
@@ -78,3 +130,368 @@ fn proposal_pyth() {
         thread::sleep(Duration::from_secs(2));
     }
 }
+
+#[test]
+fn proposal_local_hermes() {
+    let (url, counter) = serve_latest_vaas(&[USDC_VAA]);
+
+    let (mut suite, _, _, contracts) = setup_test_with_price_feeder(HermesPriceFeeder::new(url));
+
+    // Trigger the prepare proposal to write the price ids into the shared
+    // memory. There are no VAAs yet, so no tx is inserted.
+    assert!(suite.make_empty_block().tx_outcomes.is_empty());
+
+    // Give time to the thread to fetch the VAAs from the stand-in.
+    thread::sleep(Duration::from_secs(2));
+
+    assert!(counter.load(Ordering::SeqCst) > 0);
+
+    // Trigger the prepare proposal to feed the prices to the oracle.
+    let mut outcome = suite.make_empty_block();
+
+    assert_eq!(outcome.tx_outcomes.len(), 1);
+    outcome.tx_outcomes.pop().unwrap().should_succeed();
+
+    let price = suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: Denom::from_str("usdc").unwrap(),
+        })
+        .should_succeed();
+
+    assert_eq!(
+        price.humanized_price,
+        Udec128::from_str("1.00000966").unwrap()
+    );
+    assert_eq!(price.timestamp, 1730802926);
+}
+
+#[test]
+fn proposal_fixture() {
+    let (mut suite, _, _, contracts) = setup_test_with_price_feeder(FixturePriceFeeder::new(vec![
+        Binary::from_str(USDC_VAA).unwrap(),
+    ]));
+
+    suite.make_empty_block();
+
+    thread::sleep(Duration::from_secs(2));
+
+    let mut outcome = suite.make_empty_block();
+
+    assert_eq!(outcome.tx_outcomes.len(), 1);
+    outcome.tx_outcomes.pop().unwrap().should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: Denom::from_str("usdc").unwrap(),
+        })
+        .should_succeed_and(|price| price.timestamp == 1730802926);
+}
+
+/// Compose an oracle transaction feeding the given number of VAAs, as a
+/// proposer would without vote extensions.
+fn oracle_tx(oracle: Addr, num_vaas: usize, funds: Coins) -> Vec<u8> {
+    let vaas = vec![Binary::from_str(USDC_VAA).unwrap(); num_vaas];
+
+    compose_oracle_tx(
+        oracle,
+        &ExecuteMsg::FeedPrices(NonEmpty::new(vaas).unwrap()),
+        funds,
+    )
+}
+
+/// Compose an oracle transaction feeding an aggregated USDC price.
+fn aggregated_oracle_tx(oracle: Addr) -> Vec<u8> {
+    compose_oracle_tx(
+        oracle,
+        &ExecuteMsg::FeedAggregatedPrices {
+            vaas: NonEmpty::new(vec![Binary::from_str(USDC_VAA).unwrap()]).unwrap(),
+            selections: btree_map! { USDC_USD_ID => 0 },
+        },
+        Coins::new(),
+    )
+}
+
+fn compose_oracle_tx(oracle: Addr, msg: &ExecuteMsg, funds: Coins) -> Vec<u8> {
+    Tx {
+        sender: oracle,
+        gas_limit: 50_000_000,
+        msgs: NonEmpty::new_unchecked(vec![Message::execute(oracle, msg, funds).unwrap()]),
+        data: Json::null(),
+        credential: Json::null(),
+    }
+    .to_json_vec()
+    .unwrap()
+}
+
+#[test]
+fn process_proposal() {
+    let (suite, _, _, contracts) = setup_test();
+
+    let user_tx = b"not a valid tx, but not our concern".to_vec();
+
+    // Vote extensions aren't enabled, so the proposal carries no votes.
+    let no_votes = BTreeMap::new();
+
+    // A proposal with a well-formed oracle tx at the beginning is accepted.
+    assert!(suite.app.do_process_proposal(
+        vec![
+            oracle_tx(contracts.oracle, 1, Coins::new()).into(),
+            user_tx.clone().into(),
+        ],
+        0,
+        &no_votes
+    ));
+
+    // Duplicate txs are rejected.
+    assert!(!suite.app.do_process_proposal(
+        vec![user_tx.clone().into(), user_tx.clone().into()],
+        0,
+        &no_votes
+    ));
+
+    // The oracle tx must come first.
+    assert!(!suite.app.do_process_proposal(
+        vec![
+            user_tx.clone().into(),
+            oracle_tx(contracts.oracle, 1, Coins::new()).into(),
+        ],
+        0,
+        &no_votes
+    ));
+
+    // The oracle tx can't send funds.
+    assert!(!suite.app.do_process_proposal(
+        vec![oracle_tx(contracts.oracle, 1, Coins::one("uusdc", 1).unwrap()).into()],
+        0,
+        &no_votes
+    ));
+
+    // Three Pyth price sources are registered at genesis, so the oracle tx
+    // can't contain more than three VAAs.
+    assert!(suite.app.do_process_proposal(
+        vec![oracle_tx(contracts.oracle, 3, Coins::new()).into()],
+        0,
+        &no_votes
+    ));
+    assert!(!suite.app.do_process_proposal(
+        vec![oracle_tx(contracts.oracle, 4, Coins::new()).into()],
+        0,
+        &no_votes
+    ));
+
+    // Aggregated prices can't be fed without the votes they're aggregated
+    // from.
+    assert!(!suite.app.do_process_proposal(
+        vec![aggregated_oracle_tx(contracts.oracle).into()],
+        0,
+        &no_votes
+    ));
+}
+
+/// A VAA containing the price of BTC, signed by the guardian set registered at
+/// genesis:
+/// - price: **6864578657006**
+/// - publish_time: **1730804420**
+const BTC_VAA_LOW: &str = "UE5BVQEAAAADuAEAAAAEDQBnC+7yOL2qsxrpxHzhTnaruVWTSfjBRIF7sk1bJUZzj3s7wZyytPTHtoxXFQaFFSCgVpCXeLdeHuN3ZM2LOvQMAAMOXaxpZYUuwjEhbN8yP3wfgSDdaFgiS0Abr1Hyf29BX1sYEEH82xUVspIdEv7DBves+XjKJWWnZ51De4KMmDqgAQR9ExeR/D3QbvfFarB73jLQ+QKGS0tb50229RyjKCHv2VbRJL5go04kePmSqLjqjhBn/IBx2Rr1W16DF9fKV2h+AAaPsmegjpPIfPIDZwqMcgvNfXqG77+8RYSH95azsCTMEFOaQVtJGJbjQUWdSrlqXukLgxIxf6yKdzp7sOBNFFVdAAhXAe1EFhONyQgWDnViECw7DbvmwNtjJ2xM/DslvZ2RJVA46pZ5St6IKyK2Ucqq/0Hu2nC1CEB39Rtcvu0Sm6DCAQpyh2KzwK+i9CtzyZNYfRFn+esWmnSHpoZrBYLgxayqtRIiTPetE3hudyHUxm4xk7CfcBrRD8uThsny1YHeiQpiAQtcR4XqjxUWHNLXsMaqaF3B/pskIjxVjWEiDkJCIpqoJFn8tktkDh00XREbZ68SUhUQQ1/S6icJLUIQt2Rf4cy5AAwJVyMi0NmjVs0X5NYzwO1Uk6Yfx96HQtibi9gPiCR4gXTW0udFzqvQ2u2xiiXonGjmaRMW86hm/6kx08d341PTAQ2ypzyZJiPhPZAo4I2IJtdjkq72uyR4lL1kqaIGupLxtCq36i1tD61Yjt3HRruBuVvHqjC60xDvWIVQL6UAHAu9AQ7wH5SeZ1ra473yrfVGIEtuGSh0iITJ3Tnzh+4IJMdnvjFARCrxHLmne50gjYcG+CQYSHl/TJ+fElFtiDx43ouGABCT8qRJAJYpusR2A1mGXDX/oBSq0NoaKKr7u4c8zLDsLWUudBRRVkDS4281f+GuQupa5eRPKdDHXt40lFY5V+FWABEcD+ka2buu8h4ZAK9gWcOhe9Ms0COktqchnwS3oZV7lXXcZM1K+LKc+gKshOln7r3JC1UrkcjJa6gy9v5Ka9YTARIYLaAd0TkttKtK9hoALKRTkEqpqgtvBLqJA9qW1UDYoAZksJo2X0th7lFdIZJQsCIkDxqedbuS1H7EQ7Im6XUHAGcp+sQAAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFcADhAUFVV1YAAAAAAAp8yzcAACcQXNrQIBXy4Cs6ul3jv4wlMishtwkBAFUAydiwdaXGkwM2WuI2M9TghRmb9cUgo7kP7RMioDQv/DMAAAY+SMW67gAAAAI/1aJY////+AAAAABnKfrEAAAAAGcp+sQAAAY+IAqHoAAAAAIKJkDECnJa8p4N3HJckOG0/XBHQ4HSCFfFvzHVwHvYJ9V5NPKGlOHwUp0GbOXWbNIMhSmoX+hk8FUMlP6NlHHbf8S2YxVixm+nMOOrhtH9+3bMQQh26XE6/E5UIoNgScjtRRQ32qtHxrU1ezhAhHmTAAD07E8S/ACc8F8xjDAZgLgjSFLHptczUSe1wR5IrrbZQRQhERagNdCcBUp8S5wl7VAQBPqprw5ZZ6dvI0y/P8UaldqRoa8eN47BbGvH/12oNzfcUiLjHCFciAwc";
+
+/// A VAA containing the price of BTC, signed by the guardian set registered at
+/// genesis:
+/// - price: **6984382159562**
+/// - publish_time: **1730157441**
+const BTC_VAA_MID: &str = "UE5BVQEAAAADuAEAAAAEDQBkMyJzGWOwAlhd3NDvcYJvct5KACRi6oi9InIE/PYqXh1z92MOXFyFPGP5y9uOpubgMIvUh/pa5aXsM/z+aaCdAALKQlwSVB5YIQ/C0NuqXqam0fAAQYUJeBe+G7rjnv7UXhHRIqNiqCvTE1ygz3zUztg07pqoYahCI7SlqI23hHizAAPG7cQdoENAUMDgYC1znnRkG8NUDS/Yzlxb3Krl/fKDUjpgKM2ZEB5HD11bCTzIhPHTI8KQxIDbyKxF6o4cwf5QAAQxrIWXQX0Bx9/lDEDfFOOqRU6LwZhFMmiDwUedUxsIvR73V/yfZKNtObHA0O9McjdTo1JibRqnbNqw6H8hw4/JAAax4DOJ/M8yxbIk88rV0n8sttzelXPuMnnJCXV2CFpwlSqYu0cQ+gmWvfjK/zJSFKHhNF0N7wzOX9J/bghUeQ8nAQgJ7BPYtJo/qowTuQfDCa4ZHIhLjC9frRQh3/UWLrxosG5xWODfYWtpDLKwfmi2gjMV4PIMUdhwZLyMDfZIqR6MAQrB/IQ438iz+1cgU+i8ij7eB5+MeUxcV0ukQhJW/0nwVCm234OqZ+ES3fNPIpWHRo4nq5ZVCdX4ZE3MF+SjZIW2AAu4DFxPpw3tokuOP6z2jNk9AFzjC/WUqlZaIx+6Se5ZeGr4chhEh2IiwChhSUJnGsKtkXHSqTuLZpXf8QZ+ZiRFAAz9XiWxbiOvw6E4+I/0JRutYrALssiRNYBah4I1QzYSU1gIAeMEHz2jvMX9lGGZMfS/uJrv1VtW9UCJMxMCUqgOAA2Hkv95hjyj6toIigG6PyEpzzoJE3ZVqI92F2kWoGSE0l/7aV/sz6jhRl8udbq/Mqu+i9wpbUZqa/ZUCFFi0NLSAQ5s3Le7hPfK1QnMOU8eWkJqiy/XL+remqBwR92Omm8FFANUVzHwOKBsj0Zlrp9o7UW05BJUrUgVXbvJ61r2F+zoAREVSnZt5Tt3JOQs/JRFUway6AvKiQQJihLAOo6AkKiUCTR2G4kbFGiILq4hwgASZGshfdgKRCy+jbHlfDGpNF+vABIwoeTGgkil6kOH/Dg+hNKmqS8N41Y1tQn7i7RkfjMw7gMOQoZcNTKDCNGfgR0gu62ZIkDBIXmea25leCk6VnH2AGcgG4EAAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFVzmdAUFVV1YAAAAAAApj+2QAACcQuyA5y12P+HQ9xkG4YvVJJeqDZf4BAFUAydiwdaXGkwM2WuI2M9TghRmb9cUgo7kP7RMioDQv/DMAAAZaLZ4aygAAAAIyAxQV////+AAAAABnIBuBAAAAAGcgG4AAAAZXwuHPYAAAAAJwWNtUCsIlij3mTR7FLM4Pu9qzDhJrUtUxIctFWnmj84Af485oCfcURBzjS8v9xlCaHMjofeED+Ml66aUMg3GKE8PDVhr5SAP4MJU436Fr6IFOxCWwq4hIuPuRgtLh6xy3t1dAZmA1SLzhr+OAOS1cKUapaSIeOdv/Mclu2fbSsnRU72f3eNeVU1v13bHKNJ70zxX/fMj109FD2kNQf4+VnjXn0jbxUKWfH5PZBT9oXoD9C59CFRYhLKAuMLSgi1sRBH0T1SmF59vcZjsn";
+
+/// A VAA containing the price of BTC, signed by the guardian set registered at
+/// genesis:
+/// - price: **7131950295749**
+/// - publish_time: **1730209108**
+const BTC_VAA_HIGH: &str = "UE5BVQEAAAADuAEAAAAEDQBLJRnF435tmWmnpCautCMOcWFhH0neObVk2iw/qtQ/jX44qUBV+Du+woo5lWLrE1ttnAPfwv9aftKy/r0pz0OdAQP25Bjy5Hx3MaOEF49sx+OrA6fxSNtBIxEkZ/wqznQAvlNE86loIz2osKoAWYeCg9FjU/8A2CmZZhcyXb4Cf+beAQSN829+7wKOw6tdMnKwtiYKdXL1yo1uP10iZ3EhU2M4cxrD0xYKA0pkb9hmhRo+zHrOY9pyTGXAsz7FjlI+gvgCAQa5MiGBgMRLFGW0fTd+bqc+isCQDbhgm/99yNkVaDt40ASST8CfH5zp4Xim5l5Yhs+/HMpeFSuTNULeDXsTO2FaAAjaPzeC8Bie6n154BaKA+45xn0lDa0epmVZs16zVCkKczSUNVG5e5VZe6N8edT+dVicoZYT9tgHJn2WDIjcpRv7AAsc0fdXE42zolp1Dhg1XVL5oe6NeTZi2Beu2ecv5FkvtCwm9dytTv6C359wJqUZLbZVaqOU9CEVbBvTzbKAm/tQAAx12qSCdkLtlJZAmhhrCvW56375q1Dy74L417r+GhDgYRqPCNWyaY7azRFfOwahxc9ECZgHj1aJg0bk395+JhTnAQ2K/IC6aRcSpPd+SfbWnfPtdJTdJFw5QCS50FbBfxxmqBTcG8E8fyYyCz5SGC8rtXgrBi+cQZe8FgW4CoLXXxC+AQ7TotPy0p9aHpwlIrXvu9B2nThByrwd4icwnOfQsUDHcG65PXWvu9nc1o5EK6SImnv+AmIu+RID2MnyTavsGEMpAA/XdQHG8mkgdWlZ1w7fg2MBs3fa0VxIlKc1DuaBdZVZEjrnB4gE15oqMZ21Bt8ji6r6J+ar/9K46EUeYC2t6CuBARDpRTI9ZZlh0MvxIbxRkuAgtRTv8oNrSz4sQJMNbhWdswTmqQQMZjtdJwGWepaAGhnEiuF/JgIr20AnDxCWbolgABGwVILVFDCHnLV54/bIdXUEiigPZvsKcDxLpOoJ722xZT1cXwXoBmwQ2lXQxGOjyj8VvgAt2kZJNbGc77+pmsqdABIFwK9Dc5BLxz+dXztA5bPMcEKkfZ18t7HPZ9BVQN7f1Cw4XcBZDSRR0MM6tqeBYvLJZhDMbt2Ax0m0+RlzQTZyAWcg5VQAAAAAABrhAfrtrFhR4yubI7X5QRqMK6xKrj7U3XuBHdGnLqSqcQAAAAAFWSo1AUFVV1YAAAAAAApl86sAACcQTdtYrFsURmdX9JeZM/nLGOdGy18BAFUAydiwdaXGkwM2WuI2M9TghRmb9cUgo7kP7RMioDQv/DMAAAZ8iV0qxQAAAAIvYnVX////+AAAAABnIOVUAAAAAGcg5VQAAAZ3rChYAAAAAAIykC3MCknCJZOvI3H3Ijt5NftDL77S253kTxg9ywpWvf3kzbZeQqXixw7K/fcAEWCww773jqhfS4CdRyUc38SMv+DhHywJbnUSyzFEWOTBVmVuvEtt6xWOTDMifAi8cAX0cBtZOyeIeLytWSqkMVYhtbm0gKCLnjtBEKLg/zEHSL48Ndm9VTihIpe8REto4Pf2MjlxRY6Smgw2TMZCJTCEj2869KzQsQhVSH4VmOJNJpevlYaqeFmJ7WDOC1tFWrVulGSZ/nIt63NKB+JP";
+
+/// A vote extension attaching the given VAAs.
+fn price_extension(vaas: &[&str]) -> Vec<u8> {
+    let vaas = vaas
+        .iter()
+        .map(|vaa| Binary::from_str(vaa).unwrap())
+        .collect();
+
+    PriceVote { vaas }.to_json_vec().unwrap()
+}
+
+/// The vote of the given simulated validator, with the given VAAs attached as
+/// its vote extension.
+fn price_vote(suite: &TestSuite, validator: usize, power: u64, vaas: &[&str]) -> ExtendedVote {
+    suite.extended_vote(validator, power, price_extension(vaas))
+}
+
+#[test]
+fn vote_extensions() {
+    // The test suite's own node doesn't fetch any VAA, so that only the votes
+    // of the simulated validators below are aggregated.
+    let (mut suite, _, _, contracts) =
+        setup_test_with_price_feeder(FixturePriceFeeder::new(vec![]));
+
+    // Validators attach too many VAAs, or VAAs that fail to parse.
+    for extension in [
+        price_extension(&[USDC_VAA, BTC_VAA_LOW, BTC_VAA_MID, BTC_VAA_HIGH]),
+        br#"{"vaas":["aGVsbG8="]}"#.to_vec(),
+    ] {
+        assert!(!suite
+            .app
+            .do_verify_vote_extension(suite.block.height, &extension));
+    }
+
+    // Three validators with equal voting power each attest a different BTC
+    // price. The median is selected.
+    let votes = [
+        price_vote(&suite, 1, 1, &[BTC_VAA_LOW]),
+        price_vote(&suite, 2, 1, &[BTC_VAA_MID]),
+        price_vote(&suite, 3, 1, &[USDC_VAA, BTC_VAA_HIGH]),
+    ];
+
+    for vote in &votes {
+        assert!(suite
+            .app
+            .do_verify_vote_extension(suite.block.height, &vote.extension));
+    }
+
+    let last_commit = votes
+        .iter()
+        .map(|vote| (vote.validator, vote.power))
+        .collect::<BTreeMap<_, _>>();
+
+    // The proposal carries the votes, followed by the oracle tx.
+    let txs = suite.app.do_prepare_proposal(vec![], usize::MAX, 0, &votes);
+
+    assert_eq!(txs.len(), 2);
+    assert_eq!(
+        ExtendedCommit::decode(&txs[0]).unwrap().unwrap().votes,
+        votes
+    );
+    assert!(suite.app.do_process_proposal(txs.clone(), 0, &last_commit));
+
+    // The votes must match the last commit.
+    let mut partial_commit = last_commit.clone();
+    partial_commit.remove(&votes[2].validator);
+
+    assert!(!suite
+        .app
+        .do_process_proposal(txs.clone(), 0, &partial_commit));
+
+    // The proposer can't drop the votes.
+    assert!(!suite
+        .app
+        .do_process_proposal(txs[1..].to_vec(), 0, &last_commit));
+
+    // The aggregated prices must match the votes.
+    let mut tampered = txs.clone();
+    tampered[0] = ExtendedCommit {
+        votes: vec![
+            price_vote(&suite, 1, 1, &[BTC_VAA_HIGH]),
+            votes[1].clone(),
+            votes[2].clone(),
+        ],
+    }
+    .encode()
+    .unwrap();
+
+    assert!(!suite.app.do_process_proposal(tampered, 0, &last_commit));
+
+    let mut outcome = suite.make_block_with_votes(vec![], votes.to_vec());
+
+    assert_eq!(outcome.tx_outcomes.len(), 1);
+    outcome.tx_outcomes.pop().unwrap().should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: Denom::from_str("btc").unwrap(),
+        })
+        .should_succeed_and(|price| price.timestamp == 1730157441);
+
+    // USDC's price is attested by only one validator, which makes it the
+    // median.
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: Denom::from_str("usdc").unwrap(),
+        })
+        .should_succeed_and(|price| price.timestamp == 1730802926);
+
+    // A validator with the majority of voting power determines the median.
+    let votes = [
+        price_vote(&suite, 1, 5, &[BTC_VAA_LOW]),
+        price_vote(&suite, 2, 1, &[BTC_VAA_MID]),
+        price_vote(&suite, 3, 1, &[BTC_VAA_HIGH]),
+    ];
+
+    suite
+        .make_block_with_votes(vec![], votes.to_vec())
+        .tx_outcomes
+        .pop()
+        .unwrap()
+        .should_succeed();
+
+    suite
+        .query_wasm_smart(contracts.oracle, QueryPriceRequest {
+            denom: Denom::from_str("btc").unwrap(),
+        })
+        .should_succeed_and(|price| price.timestamp == 1730804420);
+}
+
+#[test]
+fn tampered_vote_extensions() {
+    // The test suite's own node doesn't fetch any VAA.
+    let (suite, ..) = setup_test_with_price_feeder(FixturePriceFeeder::new(vec![]));
+
+    // Three validators attest BTC prices.
+    let votes = [
+        price_vote(&suite, 1, 1, &[BTC_VAA_LOW]),
+        price_vote(&suite, 2, 1, &[BTC_VAA_MID]),
+        price_vote(&suite, 3, 1, &[BTC_VAA_MID]),
+    ];
+
+    let last_commit = votes
+        .iter()
+        .map(|vote| (vote.validator, vote.power))
+        .collect::<BTreeMap<_, _>>();
+
+    // The proposer replaces the first validator's extension with one that
+    // attests a higher price, keeping the validator's signature. The oracle tx
+    // is consistent with the tampered votes, so only the signature gives the
+    // proposer away.
+    let mut tampered_votes = votes.clone();
+    tampered_votes[0].extension = price_extension(&[BTC_VAA_HIGH]);
+
+    // What an honest proposer would propose, had the first validator attested
+    // the higher price itself.
+    let honest_votes = [
+        price_vote(&suite, 1, 1, &[BTC_VAA_HIGH]),
+        votes[1].clone(),
+        votes[2].clone(),
+    ];
+
+    let mut txs = suite
+        .app
+        .do_prepare_proposal(vec![], usize::MAX, 0, &honest_votes);
+
+    assert_eq!(txs.len(), 2);
+    assert!(suite.app.do_process_proposal(txs.clone(), 0, &last_commit));
+
+    txs[0] = ExtendedCommit {
+        votes: tampered_votes.to_vec(),
+    }
+    .encode()
+    .unwrap();
+
+    assert!(!suite.app.do_process_proposal(txs, 0, &last_commit));
+
+    // The signatures must be for the round in which the votes were cast.
+    let txs = suite.app.do_prepare_proposal(vec![], usize::MAX, 0, &votes);
+
+    assert!(suite.app.do_process_proposal(txs.clone(), 0, &last_commit));
+    assert!(!suite.app.do_process_proposal(txs, 1, &last_commit));
+
+    // An honest proposer doesn't carry votes whose extensions it can't verify.
+    // As this node has no VAAs of its own to feed, the proposal is empty.
+    assert!(suite
+        .app
+        .do_prepare_proposal(vec![], usize::MAX, 0, &tampered_votes)
+        .is_empty());
+}
@@ -1,4 +1,7 @@
-use grug::{Bound, Bounded, Bounds, NumberConst, Udec128};
+use {
+    grug::{Addr, Bound, Bounded, Bounds, Coins, NumberConst, Udec128},
+    std::collections::BTreeSet,
+};
 
 /// Defines the bounds for a collateral power: 0 < CollateralPower <= 1.
 #[grug::derive(Serde)]
@@ -22,14 +25,26 @@ pub struct HealthResponse {
     /// The total value of the margin account's collateral, adjusted for
     /// the collateral power of each denom.
     pub total_adjusted_collateral_value: Udec128,
+    /// The total value of the margin account's collateral, not adjusted.
+    pub total_collateral_value: Udec128,
+    /// The margin account's balances of collateral tokens.
+    pub collaterals: Coins,
+}
+
+/// Execute messages for the margin account
+#[grug::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Transfer collateral to a liquidator.
+    /// Can only be called by the lending contract, during a liquidation.
+    SeizeCollateral { liquidator: Addr, coins: Coins },
 }
 
 /// Query messages for the margin account
 #[grug::derive(Serde, QueryRequest)]
 pub enum QueryMsg {
-    /// Query the account's current nonce number.
-    #[returns(u32)]
-    Nonce {},
+    /// Query the most recent transaction nonces that have been recorded.
+    #[returns(BTreeSet<u32>)]
+    SeenNonces {},
     /// Queries the health of the margin account.
     #[returns(HealthResponse)]
     Health {},
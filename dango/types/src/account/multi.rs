@@ -2,7 +2,7 @@ use {
     crate::account_factory::Username,
     anyhow::anyhow,
     grug::{ChangeSet, Duration, Inner, Message, NonZero, Timestamp},
-    std::collections::BTreeMap,
+    std::collections::{BTreeMap, BTreeSet},
 };
 
 /// Identifier of a proposal.
@@ -155,9 +155,9 @@ pub enum ExecuteMsg {
 // account factory for this instead.
 #[grug::derive(Serde, QueryRequest)]
 pub enum QueryMsg {
-    /// Query the account's current nonce number.
-    #[returns(u32)]
-    Nonce {},
+    /// Query the most recent transaction nonces that have been recorded.
+    #[returns(BTreeSet<u32>)]
+    SeenNonces {},
     /// Query a proposal by ID.
     #[returns(Proposal)]
     Proposal { proposal_id: ProposalId },
@@ -1,7 +1,9 @@
+use std::collections::BTreeSet;
+
 /// Query messages for the spot account
 #[grug::derive(Serde, QueryRequest)]
 pub enum QueryMsg {
-    /// Query the account's current nonce number.
-    #[returns(u32)]
-    Nonce {},
+    /// Query the most recent transaction nonces that have been recorded.
+    #[returns(BTreeSet<u32>)]
+    SeenNonces {},
 }
@@ -42,6 +42,7 @@ impl NewUserSalt<'_> {
     /// - `0` for Secp256r1
     /// - `1` for Secp256k1
     /// - `2` for Ed25519
+    /// - `3` for Schnorr
     pub fn into_bytes(self) -> Vec<u8> {
         // Maximum possible length for the bytes:
         // - len(username): 1
@@ -63,6 +64,14 @@ impl NewUserSalt<'_> {
                 bytes.push(1);
                 bytes.extend_from_slice(&pk);
             },
+            Key::Ed25519(pk) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&pk);
+            },
+            Key::Schnorr(pk) => {
+                bytes.push(3);
+                bytes.extend_from_slice(&pk);
+            },
         }
         bytes
     }
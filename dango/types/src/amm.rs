@@ -5,7 +5,7 @@ mod pool;
 
 pub use {config::*, msg::*, namespace::*, pool::*};
 
-use grug::Uint128;
+use grug::{Duration, Uint128};
 
 /// The amount of liquidity shares that will be withheld by the AMM contract
 /// during a pool's creation.
@@ -14,3 +14,9 @@ use grug::Uint128;
 /// liquidity token's value. See:
 /// <https://ethereum.stackexchange.com/questions/132491/why-minimum-liquidity-is-used-in-dex-like-uniswap>
 pub const MINIMUM_LIQUIDITY: Uint128 = Uint128::new(1000);
+
+/// The longest window over which the time-weighted average price of a pool can
+/// be queried.
+///
+/// Price observations older than necessary to cover this window are pruned.
+pub const MAX_TWAP_WINDOW: Duration = Duration::from_days(1);
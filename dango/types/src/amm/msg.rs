@@ -1,6 +1,6 @@
 use {
-    crate::amm::{Config, Pool, PoolId, PoolParams},
-    grug::{Coin, Coins, Uint128, UniqueVec},
+    crate::amm::{Config, Pool, PoolId, PoolParams, Position, Tick, TickInfo},
+    grug::{Addr, Coin, Coins, Denom, Duration, Udec256, Uint128, UniqueVec},
     std::collections::BTreeMap,
 };
 
@@ -27,6 +27,32 @@ pub enum ExecuteMsg {
     },
     /// Withdraw liquidity from a trading pool.
     WithdrawLiquidity { pool_id: PoolId },
+    /// Provide liquidity to a concentrated liquidity pool within a price range.
+    ///
+    /// The liquidity is added to the sender's position of the given range.
+    /// The part of the deposit that isn't needed is refunded.
+    IncreasePosition {
+        pool_id: PoolId,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        minimum_liquidity: Option<Uint128>,
+    },
+    /// Withdraw liquidity from a position in a concentrated liquidity pool.
+    ///
+    /// Accrued liquidity fees are not withdrawn; use `CollectFees` for that.
+    DecreasePosition {
+        pool_id: PoolId,
+        lower_tick: Tick,
+        upper_tick: Tick,
+        liquidity: Uint128,
+    },
+    /// Collect the liquidity fees accrued to a position in a concentrated
+    /// liquidity pool.
+    CollectFees {
+        pool_id: PoolId,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    },
 }
 
 #[grug::derive(Serde, QueryRequest)]
@@ -43,12 +69,36 @@ pub enum QueryMsg {
         start_after: Option<PoolId>,
         limit: Option<u32>,
     },
+    /// Query a liquidity position in a concentrated liquidity pool.
+    ///
+    /// The position's owed fees include those accrued since the position was
+    /// last updated.
+    #[returns(Position)]
+    Position {
+        pool_id: PoolId,
+        owner: Addr,
+        lower_tick: Tick,
+        upper_tick: Tick,
+    },
+    /// Enumerate the initialized ticks of a concentrated liquidity pool.
+    ///
+    /// At most 100 ticks are returned, regardless of `limit`.
+    #[returns(BTreeMap<Tick, TickInfo>)]
+    Ticks {
+        pool_id: PoolId,
+        start_after: Option<Tick>,
+        limit: Option<u32>,
+    },
     /// Simulate the output of a swap.
     #[returns(SwapOutcome)]
     Simulate {
         input: Coin,
         route: UniqueVec<PoolId>,
     },
+    /// Query the time-weighted average price of a pool over the given window,
+    /// ending at the current block time.
+    #[returns(TwapResponse)]
+    Twap { pool_id: PoolId, window: Duration },
 }
 
 /// The outcome of performing a swap.
@@ -61,3 +111,14 @@ pub struct SwapOutcome {
     /// The amount of fee paid to liquidity providers.
     pub liquidity_fees: Coins,
 }
+
+/// The time-weighted average price of a pool.
+#[grug::derive(Serde)]
+pub struct TwapResponse {
+    /// The pool's first token, whose price is being quoted.
+    pub base_denom: Denom,
+    /// The pool's second token, in which the price is denominated.
+    pub quote_denom: Denom,
+    /// The average amount of the quote token per unit of the base token.
+    pub price: Udec256,
+}
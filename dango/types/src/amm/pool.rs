@@ -1,11 +1,28 @@
 use {
     crate::amm::FeeRate,
-    grug::{CoinPair, Denom, Uint128},
+    grug::{Coin, CoinPair, Dec256, Denom, Int128, NumberConst, Udec256, Uint128},
 };
 
 /// Identifier of an AMM pool.
 pub type PoolId = u32;
 
+/// Index of a price point in a concentrated liquidity pool.
+///
+/// Tick `i` corresponds to the price `1.0001 ^ i`, where price is defined as
+/// the amount of the pool's second token per unit of the first token.
+pub type Tick = i32;
+
+/// The smallest tick a concentrated liquidity pool supports.
+///
+/// Corresponds to a price of approximately 10^-18. Prices below this can't be
+/// represented with sufficient precision by an 18-decimal fixed-point number.
+pub const MIN_TICK: Tick = -MAX_TICK;
+
+/// The biggest tick a concentrated liquidity pool supports.
+///
+/// Corresponds to a price of approximately 10^18.
+pub const MAX_TICK: Tick = 414_486;
+
 // -------------------------------- pool params --------------------------------
 
 /// Parameters of an AMM pool.
@@ -23,10 +40,30 @@ pub struct XykParams {
     pub liquidity_fee_rate: FeeRate,
 }
 
-/// Parameter of a concentracted liquidity AMM pool (a.k.a. Curve V2 pool).
+/// Parameter of a concentracted liquidity AMM pool (a.k.a. Uniswap V3 pool).
 #[grug::derive(Serde, Borsh)]
 pub struct ConcentratedParams {
-    // TODO
+    /// Only ticks that are multiples of this number can be used as the
+    /// boundaries of liquidity positions.
+    ///
+    /// A smaller spacing allows more granular price ranges, at the cost of
+    /// swaps having to cross more ticks.
+    pub tick_spacing: u32,
+    /// Percentage of swap output that is charged as liquidity fee, paid to
+    /// liquidity providers whose positions are active during the swap.
+    pub fee_tier: FeeRate,
+}
+
+impl ConcentratedParams {
+    /// Return the lower and upper ticks of a position that covers the entire
+    /// price range supported by the pool.
+    pub fn full_range(&self) -> (Tick, Tick) {
+        let spacing = self.tick_spacing as Tick;
+
+        // Note: integer division rounds towards zero, so the results are within
+        // the range of `MIN_TICK..=MAX_TICK`.
+        (MIN_TICK / spacing * spacing, MAX_TICK / spacing * spacing)
+    }
 }
 
 // -------------------------------- pool state ---------------------------------
@@ -60,14 +97,90 @@ pub struct XykPool {
     pub shares: Uint128,
 }
 
-/// State of a concentrated liquidity AMM pool (a.k.a. Curve V2 pool).
+/// State of a concentrated liquidity AMM pool (a.k.a. Uniswap V3 pool).
+///
+/// The pool's initialized ticks are stored separately by the AMM contract, so
+/// that a swap only needs to load the ticks it crosses.
 #[grug::derive(Serde, Borsh)]
 pub struct ConcentratedPool {
     /// The pool's parameters.
     pub params: ConcentratedParams,
-    /// The amount of liquidity provided to this pool.
+    /// The amount of tokens held by this pool, including liquidity fees that
+    /// have been accrued to positions but not yet collected.
     pub liquidity: CoinPair,
-    /// The total amount of liquidity shares outstanding.
-    pub shares: Uint128,
-    // TODO: other params...
+    /// Square root of the current price.
+    pub sqrt_price: Udec256,
+    /// The current tick, i.e. the biggest tick whose price is no greater than
+    /// the current price.
+    pub tick: Tick,
+    /// Total liquidity of positions whose price ranges contain the current price.
+    pub active_liquidity: Uint128,
+    /// Total liquidity fee earned per unit of liquidity since the pool's creation.
+    pub fee_growth_global: FeeGrowth,
+}
+
+impl ConcentratedPool {
+    /// Return a position with zero liquidity and no fees owed in this pool.
+    pub fn empty_position(&self) -> Position {
+        let (coin1, coin2) = self.liquidity.as_ref();
+
+        Position {
+            liquidity: Uint128::ZERO,
+            fee_growth_inside_last: FeeGrowth::default(),
+            fees_owed: CoinPair::new_unchecked(
+                Coin {
+                    denom: coin1.denom.clone(),
+                    amount: Uint128::ZERO,
+                },
+                Coin {
+                    denom: coin2.denom.clone(),
+                    amount: Uint128::ZERO,
+                },
+            ),
+        }
+    }
+}
+
+/// State of a tick in a concentrated liquidity pool.
+#[grug::derive(Serde, Borsh)]
+pub struct TickInfo {
+    /// Total liquidity of positions that use this tick as either boundary.
+    pub liquidity_gross: Uint128,
+    /// The amount of liquidity that becomes active when the price crosses this
+    /// tick going up, or inactive when crossing going down.
+    pub liquidity_net: Int128,
+    /// Liquidity fee earned per unit of liquidity on the other side of this
+    /// tick, relative to the current tick.
+    ///
+    /// This is only meaningful relative to the value of other ticks. See
+    /// Uniswap V3 whitepaper section 6.3.
+    pub fee_growth_outside: FeeGrowth,
+}
+
+/// Liquidity fee earned per unit of liquidity, in each of the pool's two tokens.
+///
+/// Signed, because the fee growth inside a price range, computed from values
+/// of the ticks, can be negative. Only the difference between two such values
+/// is meaningful.
+#[grug::derive(Serde, Borsh)]
+#[derive(Copy, Default)]
+pub struct FeeGrowth {
+    pub first: Dec256,
+    pub second: Dec256,
+}
+
+/// A liquidity position in a concentrated liquidity pool.
+///
+/// Positions are keyed by the pool ID, the owner's address, and the lower and
+/// upper ticks of the price range.
+#[grug::derive(Serde, Borsh)]
+pub struct Position {
+    /// The amount of liquidity in this position.
+    pub liquidity: Uint128,
+    /// The fee growth inside the position's price range as of the last time
+    /// the position was updated.
+    pub fee_growth_inside_last: FeeGrowth,
+    /// Liquidity fees that have been accrued to this position but not yet
+    /// collected.
+    pub fees_owed: CoinPair,
 }
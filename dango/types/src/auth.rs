@@ -11,6 +11,11 @@ pub enum Key {
     Secp256r1(ByteArray<33>),
     /// An Secp256k1 public key in compressed form.
     Secp256k1(ByteArray<33>),
+    /// An Ed25519 public key, such as that of a Solana wallet.
+    Ed25519(ByteArray<32>),
+    /// An Secp256k1 public key in x-only form, for BIP-340 Schnorr signatures
+    /// such as those of Bitcoin Taproot wallets.
+    Schnorr(ByteArray<32>),
 }
 
 /// Data that the account expects for the transaction's [`credential`](grug::Tx::credential)
@@ -23,6 +28,10 @@ pub enum Signature {
     Secp256k1(ByteArray<64>),
     /// An EIP712 signature signed by a compatible eth wallet.
     Eip712(Eip712Signature),
+    /// An Ed25519 signature.
+    Ed25519(ByteArray<64>),
+    /// A BIP-340 Schnorr signature.
+    Schnorr(ByteArray<64>),
 }
 
 #[grug::derive(Serde)]
@@ -23,6 +23,7 @@ pub struct AppConfig {
 #[grug::derive(Serde)]
 pub struct AppAddresses {
     pub account_factory: Addr,
+    pub amm: Addr,
     pub ibc_transfer: Addr,
     pub lending: Addr,
     pub oracle: Addr,
@@ -1 +1,15 @@
+use grug::{Binary, Timestamp};
 
+/// Message that IBC applications send to the chain's IBC handler contract.
+#[grug::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Send a packet over a channel.
+    ///
+    /// The source port is the one bound to the sender contract.
+    SendPacket {
+        channel_id: String,
+        data: Binary,
+        timeout_height: Option<u64>,
+        timeout_timestamp: Option<Timestamp>,
+    },
+}
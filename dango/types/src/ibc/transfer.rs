@@ -1,10 +1,18 @@
 use {
-    grug::{Addr, Empty, Part},
-    std::sync::LazyLock,
+    grug::{Addr, Binary, Denom, Empty, HashExt, Part, Timestamp, Uint128},
+    std::{collections::BTreeMap, sync::LazyLock},
 };
 
+/// The namespace that IBC vouchers are minted under.
 pub static NAMESPACE: LazyLock<Part> = LazyLock::new(|| Part::new_unchecked("ibc"));
 
+/// The ID of the port that the IBC transfer contract is bound to.
+pub const PORT_ID: &str = "transfer";
+
+/// The prefix of channel IDs, used to tell channels apart from ports when
+/// parsing a denom trace.
+const CHANNEL_PREFIX: &str = "channel-";
+
 pub type InstantiateMsg = Empty;
 
 #[grug::derive(Serde)]
@@ -16,4 +24,99 @@ pub enum ExecuteMsg {
     /// Otherwise, the funds are held in the contract, waiting for the recipient
     /// to claim it during account creation.
     ReceiveTransfer { recipient: Addr },
+    /// Send the attached coin to a receiver on the counterparty chain.
+    ///
+    /// Tokens native to this chain are escrowed in the contract; vouchers of
+    /// tokens that came in over the same channel are burned.
+    Transfer {
+        channel: String,
+        receiver: String,
+        timeout: Timestamp,
+    },
+}
+
+#[grug::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the denom trace of an IBC voucher.
+    #[returns(DenomTrace)]
+    DenomTrace { denom: Denom },
+    /// Enumerate denom traces of all IBC vouchers.
+    #[returns(BTreeMap<Denom, DenomTrace>)]
+    DenomTraces {
+        start_after: Option<Denom>,
+        limit: Option<u32>,
+    },
+    /// Query the amount of a token escrowed for a channel.
+    #[returns(Uint128)]
+    Escrow { channel: String, denom: Denom },
+    /// Enumerate the amounts of all tokens escrowed for a channel.
+    #[returns(BTreeMap<Denom, Uint128>)]
+    Escrows {
+        channel: String,
+        start_after: Option<Denom>,
+        limit: Option<u32>,
+    },
+}
+
+/// The data of an ICS-20 fungible token transfer packet.
+#[grug::derive(Serde)]
+pub struct FungibleTokenPacketData {
+    /// The denom as known on the sending chain, prefixed with the trace of the
+    /// channels the token has travelled through to get there.
+    pub denom: String,
+    pub amount: Uint128,
+    pub sender: String,
+    pub receiver: String,
+}
+
+/// The acknowledgement of an ICS-20 fungible token transfer packet.
+#[grug::derive(Serde)]
+pub enum Acknowledgement {
+    Result(Binary),
+    Error(String),
+}
+
+/// The path of channels an IBC voucher has travelled through, and the denom it
+/// has on its origin chain.
+#[grug::derive(Serde, Borsh)]
+pub struct DenomTrace {
+    /// Port and channel IDs joined by `/`, e.g. `transfer/channel-0`; empty if
+    /// the token is native to this chain.
+    pub path: String,
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// Parse a full denom path, e.g. `transfer/channel-0/uatom`.
+    ///
+    /// The trace consists of the leading port and channel ID pairs; the rest is
+    /// the base denom.
+    pub fn from_full_path(full_path: &str) -> Self {
+        let segments = full_path.split('/').collect::<Vec<_>>();
+
+        let mut len = 0;
+        while len + 2 < segments.len() && segments[len + 1].starts_with(CHANNEL_PREFIX) {
+            len += 2;
+        }
+
+        Self {
+            path: segments[..len].join("/"),
+            base_denom: segments[len..].join("/"),
+        }
+    }
+
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// The denom of the voucher representing this token on this chain, in the
+    /// format `ibc/{hash}`, where `hash` is the SHA-256 hash of the full path.
+    pub fn ibc_denom(&self) -> Denom {
+        let hash = self.full_path().as_bytes().hash256();
+        Denom::new_unchecked([NAMESPACE.to_string(), hash.to_string()])
+    }
 }
@@ -0,0 +1,39 @@
+use grug::{Bound, Bounded, Bounds, NumberConst, Udec128};
+
+/// Defines the bounds for a close factor: 0 < CloseFactor <= 1.
+#[grug::derive(Serde)]
+pub struct CloseFactorBounds;
+
+impl Bounds<Udec128> for CloseFactorBounds {
+    const MAX: Option<Bound<Udec128>> = Some(Bound::Inclusive(Udec128::ONE));
+    const MIN: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ZERO));
+}
+
+/// A decimal bounded by the close factor bounds.
+pub type CloseFactor = Bounded<Udec128, CloseFactorBounds>;
+
+/// Defines the bounds for a liquidation bonus: 0 <= LiquidationBonus < 1.
+#[grug::derive(Serde)]
+pub struct LiquidationBonusBounds;
+
+impl Bounds<Udec128> for LiquidationBonusBounds {
+    const MAX: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ONE));
+    const MIN: Option<Bound<Udec128>> = None;
+}
+
+/// A decimal bounded by the liquidation bonus bounds.
+pub type LiquidationBonus = Bounded<Udec128, LiquidationBonusBounds>;
+
+/// Global configuration of the lending contract.
+#[grug::derive(Serde, Borsh)]
+pub struct Config {
+    /// The maximum portion of a margin account's debt of a single denom that
+    /// can be repaid in one liquidation.
+    pub close_factor: CloseFactor,
+    /// The extra value, relative to the value of the debt repaid, that a
+    /// liquidator receives in collateral.
+    ///
+    /// E.g. with a bonus of 5%, a liquidator repaying $100 of debt receives
+    /// $105 worth of collateral.
+    pub liquidation_bonus: LiquidationBonus,
+}
@@ -0,0 +1,108 @@
+use grug::{Bound, Bounded, Bounds, IsZero, Number, NumberConst, StdResult, Udec128};
+
+/// Defines the bounds for the optimal utilization: 0 < OptimalUtilization < 1.
+#[grug::derive(Serde)]
+pub struct OptimalUtilizationBounds;
+
+impl Bounds<Udec128> for OptimalUtilizationBounds {
+    const MAX: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ONE));
+    const MIN: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ZERO));
+}
+
+/// A decimal bounded by the optimal utilization bounds.
+pub type OptimalUtilization = Bounded<Udec128, OptimalUtilizationBounds>;
+
+/// Defines the bounds for a reserve factor: 0 <= ReserveFactor < 1.
+#[grug::derive(Serde)]
+pub struct ReserveFactorBounds;
+
+impl Bounds<Udec128> for ReserveFactorBounds {
+    const MAX: Option<Bound<Udec128>> = Some(Bound::Exclusive(Udec128::ONE));
+    const MIN: Option<Bound<Udec128>> = None;
+}
+
+/// A decimal bounded by the reserve factor bounds.
+pub type ReserveFactor = Bounded<Udec128, ReserveFactorBounds>;
+
+/// A utilization-based, kinked interest rate curve.
+///
+/// Below the optimal utilization, the borrow rate grows linearly from the base
+/// rate with the first slope. Above it, the rate grows with the (typically much
+/// steeper) second slope, incentivizing borrowers to repay and lenders to
+/// deposit:
+///
+/// ```plain
+/// rate
+///  ^                        /
+///  |                       / slope 2
+///  |                      /
+///  |          slope 1 ___/
+///  |          _____---   |
+///  |   ___---            |
+///  +---------------------+----------> utilization
+///  0                  optimal       1
+/// ```
+///
+/// All rates are annualized.
+#[grug::derive(Serde, Borsh)]
+pub struct InterestRateModel {
+    /// The borrow rate when utilization is zero.
+    pub base_rate: Udec128,
+    /// The utilization at which the curve switches from the first slope to
+    /// the second.
+    pub optimal_utilization: OptimalUtilization,
+    /// The increase in borrow rate from zero to optimal utilization.
+    pub first_slope: Udec128,
+    /// The increase in borrow rate from optimal to full utilization.
+    pub second_slope: Udec128,
+    /// Portion of the interest paid by borrowers that goes to the protocol,
+    /// instead of to lenders.
+    pub reserve_factor: ReserveFactor,
+}
+
+impl Default for InterestRateModel {
+    fn default() -> Self {
+        Self {
+            base_rate: Udec128::ZERO,
+            optimal_utilization: OptimalUtilization::new_unchecked(Udec128::new_percent(80)),
+            first_slope: Udec128::new_percent(4),
+            second_slope: Udec128::new_percent(75),
+            reserve_factor: ReserveFactor::new_unchecked(Udec128::new_percent(10)),
+        }
+    }
+}
+
+impl InterestRateModel {
+    /// Compute the annualized borrow and supply rates, in this order, at the
+    /// given utilization.
+    pub fn calculate_rates(&self, utilization: Udec128) -> StdResult<(Udec128, Udec128)> {
+        if utilization.is_zero() {
+            return Ok((self.base_rate, Udec128::ZERO));
+        }
+
+        // Borrowed amount may slightly exceed supplied amount due to rounding.
+        let utilization = utilization.min(Udec128::ONE);
+        let optimal_utilization = *self.optimal_utilization;
+
+        let borrow_rate = if utilization <= optimal_utilization {
+            self.first_slope
+                .checked_mul(utilization)?
+                .checked_div(optimal_utilization)?
+                .checked_add(self.base_rate)?
+        } else {
+            self.second_slope
+                .checked_mul(utilization - optimal_utilization)?
+                .checked_div(Udec128::ONE - optimal_utilization)?
+                .checked_add(self.first_slope)?
+                .checked_add(self.base_rate)?
+        };
+
+        // Lenders receive the interest paid by borrowers, spread over the
+        // entire supply, minus the protocol's cut.
+        let supply_rate = borrow_rate
+            .checked_mul(utilization)?
+            .checked_mul(Udec128::ONE - *self.reserve_factor)?;
+
+        Ok((borrow_rate, supply_rate))
+    }
+}
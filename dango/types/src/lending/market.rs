@@ -0,0 +1,144 @@
+use {
+    crate::lending::{InterestRateModel, SECONDS_PER_YEAR},
+    grug::{IsZero, MultiplyFraction, Number, NumberConst, StdResult, Timestamp, Udec128, Uint128},
+};
+
+/// Configurations and state of a market.
+///
+/// Supplied and borrowed amounts are tracked in scaled form: the actual amount
+/// is the scaled amount multiplied by the respective index. Indices start at
+/// one and grow as interest accrues, so that every deposit and debt grows with
+/// them without having to be updated individually.
+#[grug::derive(Serde, Borsh)]
+pub struct Market {
+    /// The curve that determines the borrow and supply rates.
+    pub interest_rate_model: InterestRateModel,
+    /// Total scaled amount supplied, which equals the total supply of the
+    /// market's LP token, including protocol fee that has yet to be minted.
+    pub total_supplied_scaled: Uint128,
+    /// Total scaled amount borrowed.
+    pub total_borrowed_scaled: Uint128,
+    /// The exchange rate between the LP token and the underlying asset.
+    pub supply_index: Udec128,
+    /// The ratio between the actual and the scaled debt.
+    pub borrow_index: Udec128,
+    /// Scaled amount of protocol fee accrued but not yet minted as LP tokens.
+    pub pending_protocol_fee_scaled: Uint128,
+    /// The last time interest was accrued.
+    pub last_update_time: Timestamp,
+}
+
+impl Market {
+    /// Create a new market with no deposits or debts.
+    pub fn new(interest_rate_model: InterestRateModel, current_time: Timestamp) -> Self {
+        Self {
+            interest_rate_model,
+            total_supplied_scaled: Uint128::ZERO,
+            total_borrowed_scaled: Uint128::ZERO,
+            supply_index: Udec128::ONE,
+            borrow_index: Udec128::ONE,
+            pending_protocol_fee_scaled: Uint128::ZERO,
+            last_update_time: current_time,
+        }
+    }
+
+    /// Total amount of the underlying asset supplied, including interest.
+    pub fn total_supplied(&self) -> StdResult<Uint128> {
+        self.scaled_to_supplied(self.total_supplied_scaled)
+    }
+
+    /// Total amount of the underlying asset borrowed, including interest.
+    pub fn total_borrowed(&self) -> StdResult<Uint128> {
+        self.scaled_to_borrowed(self.total_borrowed_scaled)
+    }
+
+    /// Amount of the underlying asset available to be borrowed or withdrawn.
+    pub fn available_liquidity(&self) -> StdResult<Uint128> {
+        Ok(self
+            .total_supplied()?
+            .saturating_sub(self.total_borrowed()?))
+    }
+
+    /// Ratio between the total borrowed and total supplied amounts.
+    pub fn utilization_rate(&self) -> StdResult<Udec128> {
+        let total_supplied = self.total_supplied()?;
+
+        if total_supplied.is_zero() {
+            return Ok(Udec128::ZERO);
+        }
+
+        Ok(Udec128::checked_from_ratio(
+            self.total_borrowed()?,
+            total_supplied,
+        )?)
+    }
+
+    /// Convert an amount of LP tokens to the underlying asset, rounding down.
+    pub fn scaled_to_supplied(&self, scaled: Uint128) -> StdResult<Uint128> {
+        Ok(scaled.checked_mul_dec_floor(self.supply_index)?)
+    }
+
+    /// Convert an amount of the underlying asset to LP tokens, rounding down.
+    pub fn supplied_to_scaled(&self, amount: Uint128) -> StdResult<Uint128> {
+        Ok(amount.checked_div_dec_floor(self.supply_index)?)
+    }
+
+    /// Convert a scaled debt to the actual debt, rounding up.
+    pub fn scaled_to_borrowed(&self, scaled: Uint128) -> StdResult<Uint128> {
+        Ok(scaled.checked_mul_dec_ceil(self.borrow_index)?)
+    }
+
+    /// Convert an amount to be borrowed to scaled debt, rounding up.
+    pub fn borrowed_to_scaled_ceil(&self, amount: Uint128) -> StdResult<Uint128> {
+        Ok(amount.checked_div_dec_ceil(self.borrow_index)?)
+    }
+
+    /// Convert an amount to be repaid to scaled debt, rounding down.
+    pub fn borrowed_to_scaled_floor(&self, amount: Uint128) -> StdResult<Uint128> {
+        Ok(amount.checked_div_dec_floor(self.borrow_index)?)
+    }
+
+    /// Accrue interest up to the given time, updating the indices.
+    ///
+    /// The difference between the interest paid by borrowers and that earned
+    /// by lenders is credited to the protocol, as pending protocol fee.
+    pub fn accrue_interest(mut self, current_time: Timestamp) -> StdResult<Self> {
+        if current_time <= self.last_update_time {
+            return Ok(self);
+        }
+
+        let time_elapsed = current_time - self.last_update_time;
+        let year_fraction = Udec128::checked_from_ratio(
+            time_elapsed.into_nanos(),
+            SECONDS_PER_YEAR * 1_000_000_000,
+        )?;
+
+        let (borrow_rate, supply_rate) = self
+            .interest_rate_model
+            .calculate_rates(self.utilization_rate()?)?;
+
+        let total_borrowed_before = self.total_borrowed()?;
+        let total_supplied_before = self.total_supplied()?;
+
+        self.borrow_index = self
+            .borrow_index
+            .checked_mul(Udec128::ONE.checked_add(borrow_rate.checked_mul(year_fraction)?)?)?;
+        self.supply_index = self
+            .supply_index
+            .checked_mul(Udec128::ONE.checked_add(supply_rate.checked_mul(year_fraction)?)?)?;
+
+        let borrow_interest = self.total_borrowed()?.checked_sub(total_borrowed_before)?;
+        let supply_interest = self.total_supplied()?.checked_sub(total_supplied_before)?;
+
+        let protocol_fee_scaled =
+            self.supplied_to_scaled(borrow_interest.saturating_sub(supply_interest))?;
+
+        self.total_supplied_scaled
+            .checked_add_assign(protocol_fee_scaled)?;
+        self.pending_protocol_fee_scaled
+            .checked_add_assign(protocol_fee_scaled)?;
+        self.last_update_time = current_time;
+
+        Ok(self)
+    }
+}
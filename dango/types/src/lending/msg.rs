@@ -0,0 +1,103 @@
+use {
+    crate::{
+        account::margin::HealthResponse,
+        lending::{Config, InterestRateModel, Market},
+    },
+    grug::{Addr, Coin, Coins, Denom, Udec128},
+    std::collections::BTreeMap,
+};
+
+/// A set of updates to be applied to a market.
+///
+/// When creating a new market, the interest rate model must be provided.
+#[grug::derive(Serde)]
+#[derive(Default)]
+pub struct MarketUpdates {
+    /// The new interest rate model of the market.
+    pub interest_rate_model: Option<InterestRateModel>,
+}
+
+#[grug::derive(Serde)]
+pub struct InstantiateMsg {
+    pub config: Config,
+    pub markets: BTreeMap<Denom, MarketUpdates>,
+}
+
+#[grug::derive(Serde)]
+pub enum ExecuteMsg {
+    /// Update the configurations.
+    /// Can only be called by the chain's owner.
+    Configure { new_cfg: Config },
+    /// Apply updates to markets.
+    UpdateMarkets(BTreeMap<Denom, MarketUpdates>),
+    /// Deposit tokens into the lending pool.
+    /// Sender must attach one or more supported tokens and nothing else.
+    Deposit {},
+    /// Withdraw tokens from the lending pool by redeeming LP tokens.
+    /// Sender must attach one or more LP tokens and nothing else.
+    Withdraw {},
+    /// Borrow coins from the lending pool.
+    /// Sender must be a margin account.
+    Borrow(Coins),
+    /// Repay debt.
+    /// Sender must be a margin account.
+    Repay {},
+    /// Repay part of an undercollateralized margin account's debt, and seize
+    /// its collateral at a bonus.
+    /// Sender must attach exactly the debt to repay and nothing else. At most
+    /// the close factor of the debt is repaid; the excess is refunded.
+    Liquidate { account: Addr, debt_to_repay: Coin },
+}
+
+/// Callbacks the lending contract receives once its submessages are executed.
+#[grug::derive(Serde)]
+pub enum ReplyMsg {
+    /// The collateral of a margin account has been seized in a liquidation.
+    /// Ensure the account's utilization rate is now lower than before.
+    AfterLiquidation {
+        account: Addr,
+        utilization_rate: Udec128,
+    },
+}
+
+#[grug::derive(Serde, QueryRequest)]
+pub enum QueryMsg {
+    /// Query the configurations.
+    #[returns(Config)]
+    Config {},
+    /// Query the lending market of a single token, with interest accrued up to
+    /// the current block time.
+    #[returns(Market)]
+    Market { denom: Denom },
+    /// Enumerate all lending markets, with interest accrued up to the current
+    /// block time.
+    #[returns(BTreeMap<Denom, Market>)]
+    Markets {
+        start_after: Option<Denom>,
+        limit: Option<u32>,
+    },
+    /// Query the debt of a single margin account, including interest accrued
+    /// up to the current block time.
+    #[returns(Coins)]
+    Debt { account: Addr },
+    /// Enumerate debts of all margin accounts, including interest accrued up
+    /// to the current block time.
+    #[returns(BTreeMap<Addr, Coins>)]
+    Debts {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+    /// Enumerate margin accounts that are undercollateralized and can be
+    /// liquidated, along with their health.
+    ///
+    /// Pagination is over all accounts with debts, so a page may contain fewer
+    /// than `limit` entries, even if more liquidatable accounts exist.
+    ///
+    /// Accounts whose health can't be computed, e.g. because the price of one
+    /// of their tokens is stale, are skipped.
+    #[returns(BTreeMap<Addr, HealthResponse>)]
+    Liquidatable {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+}
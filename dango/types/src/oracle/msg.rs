@@ -1,5 +1,5 @@
 use {
-    super::{GuardianSet, PrecisionedPrice, PriceSource},
+    super::{GuardianSet, PrecisionedPrice, PriceSource, PythId},
     grug::{Binary, Denom, NonEmpty},
     std::collections::BTreeMap,
 };
@@ -10,12 +10,26 @@ pub struct InstantiateMsg {
     pub price_sources: BTreeMap<Denom, PriceSource>,
 }
 
+/// Migrate prices stored by a previous version of the contract, which don't
+/// include the confidence interval, to the current layout.
+#[grug::derive(Serde)]
+pub struct MigrateMsg {}
+
 #[grug::derive(Serde)]
 pub enum ExecuteMsg {
     /// Set the price sources for the given denoms.
     RegisterPriceSources(BTreeMap<Denom, PriceSource>),
     /// Submit price data from Pyth Network.
     FeedPrices(NonEmpty<Vec<Binary>>),
+    /// Submit price data from Pyth Network, aggregated from the vote extensions
+    /// of validators.
+    ///
+    /// For each price feed, only the price in the VAA at the selected index is
+    /// used, even if other VAAs also contain the feed.
+    FeedAggregatedPrices {
+        vaas: NonEmpty<Vec<Binary>>,
+        selections: BTreeMap<PythId, u32>,
+    },
 }
 
 #[grug::derive(Serde, QueryRequest)]
@@ -38,6 +52,17 @@ pub enum QueryMsg {
         start_after: Option<Denom>,
         limit: Option<u32>,
     },
+    /// Enumerate Pyth price feeds that are older than their maximum age, along
+    /// with the publish time of their latest price, or `None` if no price has
+    /// been fed yet.
+    ///
+    /// Pagination is over all price sources, so a page may contain fewer than
+    /// `limit` entries, even if more stale feeds exist.
+    #[returns(BTreeMap<Denom, Option<u64>>)]
+    StalePrices {
+        start_after: Option<Denom>,
+        limit: Option<u32>,
+    },
     /// Query the guardian set of the given index.
     #[returns(GuardianSet)]
     GuardianSet { index: u32 },
@@ -1,5 +1,8 @@
 use {
-    grug::{Defined, StdResult, Udec128, Uint128, Undefined},
+    grug::{
+        Defined, Duration, IsZero, Number, NumberConst, StdResult, Timestamp, Udec128, Uint128,
+        Undefined,
+    },
     pyth_sdk::PriceFeed,
 };
 
@@ -14,6 +17,10 @@ pub struct Price<P = Defined<u8>> {
     /// The exponential moving average of the price of the token in its
     /// humanized form.
     pub humanized_ema: Udec128,
+    /// The confidence interval of the price, in the same unit as the price.
+    /// Pyth publishers are fairly certain the actual price lies within
+    /// `humanized_price ± humanized_confidence`.
+    pub humanized_confidence: Udec128,
     /// The UNIX timestamp of the price (seconds since UNIX epoch).
     pub timestamp: u64,
     /// The number of decimal places of the token that is used to convert
@@ -22,11 +29,50 @@ pub struct Price<P = Defined<u8>> {
     precision: P,
 }
 
+impl<P> Price<P> {
+    /// Returns whether the price is older than the given maximum age.
+    ///
+    /// A price published after the current time, which can happen due to
+    /// clock drift between Pyth and the chain, is considered fresh.
+    pub fn is_stale(&self, current_time: Timestamp, max_age: Duration) -> bool {
+        let publish_time = Timestamp::from_seconds(self.timestamp as u128);
+
+        current_time > publish_time && current_time - publish_time > max_age
+    }
+
+    /// Returns the ratio between the confidence interval and the price.
+    pub fn confidence_ratio(&self) -> StdResult<Udec128> {
+        if self.humanized_price.is_zero() {
+            return Ok(Udec128::MAX);
+        }
+
+        Ok(self
+            .humanized_confidence
+            .checked_div(self.humanized_price)?)
+    }
+}
+
 impl PrecisionlessPrice {
+    pub fn new(
+        humanized_price: Udec128,
+        humanized_ema: Udec128,
+        humanized_confidence: Udec128,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            humanized_price,
+            humanized_ema,
+            humanized_confidence,
+            timestamp,
+            precision: Undefined::new(),
+        }
+    }
+
     pub fn with_precision(self, precision: u8) -> Price<Defined<u8>> {
         Price {
             humanized_price: self.humanized_price,
             humanized_ema: self.humanized_ema,
+            humanized_confidence: self.humanized_confidence,
             timestamp: self.timestamp,
             precision: Defined::new(precision),
         }
@@ -34,6 +80,22 @@ impl PrecisionlessPrice {
 }
 
 impl PrecisionedPrice {
+    pub fn new(
+        humanized_price: Udec128,
+        humanized_ema: Udec128,
+        humanized_confidence: Udec128,
+        timestamp: u64,
+        precision: u8,
+    ) -> Self {
+        Self {
+            humanized_price,
+            humanized_ema,
+            humanized_confidence,
+            timestamp,
+            precision: Defined::new(precision),
+        }
+    }
+
     /// Returns the number of decimal places of the token that is used to
     /// convert the price from its smallest unit to a humanized form. E.g.
     /// 1 ATOM is 10^6 uatom, so the precision is 6.
@@ -53,7 +115,7 @@ impl PrecisionedPrice {
         Ok(self.humanized_price
             * Udec128::checked_from_ratio(
                 unit_amount,
-                10u128.pow(self.precision.into_inner() as u32),
+                Uint128::TEN.checked_pow(self.precision.into_inner() as u32)?,
             )?)
     }
 }
@@ -68,6 +130,11 @@ impl TryFrom<PriceFeed> for PrecisionlessPrice {
             price_unchecked.expo.unsigned_abs(),
         )?;
 
+        let confidence = Udec128::checked_from_atomics(
+            price_unchecked.conf as u128,
+            price_unchecked.expo.unsigned_abs(),
+        )?;
+
         let ema_unchecked = value.get_ema_price_unchecked();
         let ema = Udec128::checked_from_atomics(
             ema_unchecked.price.unsigned_abs() as u128,
@@ -77,6 +144,7 @@ impl TryFrom<PriceFeed> for PrecisionlessPrice {
         Ok(Price {
             humanized_price: price,
             humanized_ema: ema,
+            humanized_confidence: confidence,
             timestamp: price_unchecked.publish_time.unsigned_abs(),
             precision: Undefined::new(),
         })
@@ -87,7 +155,7 @@ impl TryFrom<PriceFeed> for PrecisionlessPrice {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, grug::NumberConst};
+    use super::*;
 
     #[test]
     fn value_of_unit_amount_does_not_overflow_with_large_precision() {
@@ -95,6 +163,7 @@ mod tests {
         let price = PrecisionedPrice {
             humanized_price: Udec128::new(100_000_000u128),
             humanized_ema: Udec128::ONE,
+            humanized_confidence: Udec128::ZERO,
             timestamp: 0,
             precision: Defined::new(18),
         };
@@ -105,4 +174,11 @@ mod tests {
             .unwrap();
         assert_eq!(value, Udec128::new(10_000_000_000_000_000u128));
     }
+
+    #[test]
+    fn value_of_unit_amount_errors_with_too_large_precision() {
+        let price = PrecisionedPrice::new(Udec128::ONE, Udec128::ONE, Udec128::ZERO, 0, 39);
+
+        assert!(price.value_of_unit_amount(Uint128::ONE).is_err());
+    }
 }
@@ -1,6 +1,9 @@
 use {
-    crate::oracle::{PrecisionedPrice, PrecisionlessPrice, PythId},
-    grug::{Map, Storage},
+    crate::{
+        amm::PoolId,
+        oracle::{PrecisionlessPrice, PythId},
+    },
+    grug::{Duration, Map, Udec128},
 };
 
 pub const PRICES: Map<PythId, PrecisionlessPrice> = Map::new("price");
@@ -14,17 +17,34 @@ pub enum PriceSource {
         /// the price from its smallest unit to a humanized form. E.g. 1 ATOM
         /// is 10^6 uatom, so the precision is 6.
         precision: u8,
+        /// The maximum age of the price. Older prices are rejected as stale.
+        /// If `None`, prices are accepted regardless of age.
+        max_age: Option<Duration>,
+        /// The maximum ratio between the price's confidence interval and the
+        /// price itself. Prices more uncertain than this are rejected. If
+        /// `None`, prices are accepted regardless of confidence.
+        max_confidence_ratio: Option<Udec128>,
+    },
+    /// A price that never changes. Intended for stablecoins and testnets.
+    ///
+    /// Fixed prices don't have a publish time, so their timestamp is zero.
+    Fixed {
+        /// The price of the token in its humanized form.
+        price: Udec128,
+        /// The number of decimal places of the token.
+        precision: u8,
+    },
+    /// The time-weighted average price of the token in a Dango AMM pool,
+    /// multiplied by the price of the pool's other token.
+    ///
+    /// The other token must have a price source of a different kind. The
+    /// timestamp is that of the other token's price.
+    AmmTwap {
+        /// ID of the pool containing the token.
+        pool_id: PoolId,
+        /// The period of time to average the price over.
+        window: Duration,
+        /// The number of decimal places of the token.
+        precision: u8,
     },
-}
-
-impl PriceSource {
-    /// Directly loads the price for the price source from the storage.
-    pub fn get_price(&self, storage: &dyn Storage) -> anyhow::Result<PrecisionedPrice> {
-        match self {
-            Self::Pyth { id, precision } => {
-                let price = PRICES.load(storage, *id)?;
-                Ok(price.with_precision(*precision))
-            },
-        }
-    }
 }
@@ -51,7 +51,7 @@ impl PythVaa {
     {
         let bytes = bytes.into();
 
-        let (vaa, feeds) = if bytes[0..4] == *PYTHNET_ACCUMULATOR_UPDATE_MAGIC {
+        let (vaa, feeds) = if bytes.starts_with(PYTHNET_ACCUMULATOR_UPDATE_MAGIC) {
             let res = AccumulatorUpdateData::try_from_slice(&bytes)?;
 
             let (vaa, updates) = uncast_enum!(res.proof, Proof::WormholeMerkle, vaa, updates);
@@ -1,20 +1,27 @@
 use {
-    crate::{App, AppError, AppResult, Db, Indexer, ProposalPreparer, Vm},
+    crate::{
+        App, AppError, AppResult, BlockLimits, ExtendedCommit, ExtendedVote, Indexer,
+        ProposalPreparer, Snapshot, SnapshotDb, ValidatorUpdate, Vm,
+    },
     grug_math::Inner,
     grug_types::{
-        BlockInfo, CheckTxOutcome, Duration, GenericResult, Hash256, JsonSerExt, TxOutcome,
-        GENESIS_BLOCK_HASH,
+        BlockInfo, BorshDeExt, BorshSerExt, CheckTxOutcome, Duration, GenericResult, Hash256,
+        JsonSerExt, TxOutcome, GENESIS_BLOCK_HASH,
     },
-    prost::bytes::Bytes,
     std::{
         any::type_name,
+        collections::BTreeMap,
         future::Future,
         num::NonZeroU32,
         pin::Pin,
         task::{Context, Poll},
     },
     tendermint::{
-        abci::{request, response, types::ExecTxResult, Code},
+        abci::{
+            request, response,
+            types::{ExecTxResult, Snapshot as TmSnapshot},
+            Code,
+        },
         block::Height,
         merkle::proof::{ProofOp, ProofOps},
         v0_38::abci::{Request, Response},
@@ -26,7 +33,7 @@ use {
 
 impl<DB, VM, PP, ID> Service<Request> for App<DB, VM, PP, ID>
 where
-    DB: Db,
+    DB: SnapshotDb,
     VM: Vm + Clone + 'static,
     ID: Indexer,
     PP: ProposalPreparer,
@@ -49,7 +56,7 @@ where
 
 impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
 where
-    DB: Db,
+    DB: SnapshotDb,
     VM: Vm + Clone + 'static,
     ID: Indexer,
     PP: ProposalPreparer,
@@ -66,21 +73,16 @@ where
                 let res = self.tower_prepare_proposal(req)?;
                 Ok(Response::PrepareProposal(res))
             },
-            Request::ProcessProposal(_) => {
-                // Always accept.
-                let res = response::ProcessProposal::Accept;
+            Request::ProcessProposal(req) => {
+                let res = self.tower_process_proposal(req);
                 Ok(Response::ProcessProposal(res))
             },
-            Request::ExtendVote(_) => {
-                // Vote extension isn't supported yet. Do nothing.
-                let res = response::ExtendVote {
-                    vote_extension: Bytes::default(),
-                };
+            Request::ExtendVote(req) => {
+                let res = self.tower_extend_vote(req);
                 Ok(Response::ExtendVote(res))
             },
-            Request::VerifyVoteExtension(_) => {
-                // Always accept.
-                let res = response::VerifyVoteExtension::Accept;
+            Request::VerifyVoteExtension(req) => {
+                let res = self.tower_verify_vote_extension(req);
                 Ok(Response::VerifyVoteExtension(res))
             },
             Request::FinalizeBlock(req) => {
@@ -109,10 +111,22 @@ where
             },
 
             // ---------------------- state sync methods -----------------------
-            Request::ListSnapshots => Ok(Response::ListSnapshots(Default::default())),
-            Request::OfferSnapshot(_) => Ok(Response::OfferSnapshot(Default::default())),
-            Request::LoadSnapshotChunk(_) => Ok(Response::LoadSnapshotChunk(Default::default())),
-            Request::ApplySnapshotChunk(_) => Ok(Response::ApplySnapshotChunk(Default::default())),
+            Request::ListSnapshots => {
+                let res = self.tower_list_snapshots()?;
+                Ok(Response::ListSnapshots(res))
+            },
+            Request::OfferSnapshot(req) => {
+                let res = self.tower_offer_snapshot(req);
+                Ok(Response::OfferSnapshot(res))
+            },
+            Request::LoadSnapshotChunk(req) => {
+                let res = self.tower_load_snapshot_chunk(req)?;
+                Ok(Response::LoadSnapshotChunk(res))
+            },
+            Request::ApplySnapshotChunk(req) => {
+                let res = self.tower_apply_snapshot_chunk(req);
+                Ok(Response::ApplySnapshotChunk(res))
+            },
 
             // ------------------------- other methods -------------------------
             Request::Echo(req) => {
@@ -187,11 +201,28 @@ where
         let block = from_tm_block(req.height.value(), req.time, Some(req.hash));
 
         match self.do_finalize_block_raw(block, &req.txs) {
-            Ok(outcome) => {
-                let tx_results = outcome
-                    .tx_outcomes
-                    .into_iter()
-                    .map(into_tm_tx_result)
+            Ok((outcome, executed)) => {
+                // CometBFT expects one result per transaction in the block,
+                // including those that weren't executed.
+                let mut tx_outcomes = outcome.tx_outcomes.into_iter();
+                let tx_results = req
+                    .txs
+                    .iter()
+                    .zip(executed)
+                    .map(|(raw_tx, executed)| {
+                        if executed {
+                            into_tm_tx_result(tx_outcomes.next().unwrap())
+                        } else if ExtendedCommit::decode(raw_tx).is_some() {
+                            ExecTxResult::default()
+                        } else {
+                            ExecTxResult {
+                                code: into_tm_code_error(1),
+                                codespace: "tx".to_string(),
+                                log: "failed to deserialize transaction".to_string(),
+                                ..Default::default()
+                            }
+                        }
+                    })
                     .collect();
 
                 Ok(response::FinalizeBlock {
@@ -205,7 +236,9 @@ where
                     events: vec![],
                     tx_results,
                     // We haven't implemented any mechanism to alter the
-                    // validator set or consensus params yet.
+                    // validator set or consensus params yet. Validator updates
+                    // returned here must also be recorded in the state, so
+                    // that the validators' vote extensions can be verified.
                     validator_updates: vec![],
                     consensus_param_updates: None,
                 })
@@ -231,7 +264,32 @@ where
     fn tower_init_chain(&self, req: request::InitChain) -> AppResult<response::InitChain> {
         let block = from_tm_block(0, req.time, None);
 
-        match self.do_init_chain_raw(req.chain_id, block, &req.app_state_bytes) {
+        let block_limits = BlockLimits {
+            max_tx_bytes: Some(req.consensus_params.block.max_bytes),
+            // CometBFT uses -1 to indicate unlimited gas.
+            max_gas: req.consensus_params.block.max_gas.try_into().ok(),
+        };
+
+        // Only Ed25519 consensus keys are supported. Vote extensions of
+        // validators with other keys can't be verified.
+        let validators = req
+            .validators
+            .iter()
+            .filter_map(|update| {
+                Some(ValidatorUpdate {
+                    pubkey: update.pub_key.ed25519()?.as_bytes().try_into().ok()?,
+                    power: update.power.value(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        match self.do_init_chain_raw(
+            req.chain_id,
+            block,
+            block_limits,
+            &validators,
+            &req.app_state_bytes,
+        ) {
             Ok(app_hash) => Ok(response::InitChain {
                 consensus_params: Some(req.consensus_params),
                 validators: req.validators,
@@ -246,11 +304,159 @@ where
         req: request::PrepareProposal,
     ) -> AppResult<response::PrepareProposal> {
         let max_tx_bytes = req.max_tx_bytes.try_into().unwrap_or(0);
-        let txs = self.do_prepare_proposal(req.txs.clone(), max_tx_bytes);
+
+        // Only votes for the block carry vote extensions. Votes for nil and
+        // absent votes are skipped. Votes without extensions are kept, so that
+        // the votes carried in the proposal match the last commit.
+        let (round, votes) = req
+            .local_last_commit
+            .map(|commit| {
+                let votes = commit
+                    .votes
+                    .into_iter()
+                    .filter(|vote| vote.sig_info.is_signed())
+                    .map(|vote| ExtendedVote {
+                        validator: vote.validator.address,
+                        power: vote.validator.power.value(),
+                        extension: vote.vote_extension.to_vec(),
+                        extension_signature: vote
+                            .extension_signature
+                            .map(|sig| sig.as_bytes().to_vec())
+                            .unwrap_or_default(),
+                    })
+                    .collect::<Vec<_>>();
+
+                (commit.round.value(), votes)
+            })
+            .unwrap_or_default();
+
+        let txs = self.do_prepare_proposal(req.txs, max_tx_bytes, round, &votes);
 
         Ok(response::PrepareProposal { txs })
     }
 
+    fn tower_extend_vote(&self, req: request::ExtendVote) -> response::ExtendVote {
+        let vote_extension = self.do_extend_vote(req.height.value());
+
+        response::ExtendVote {
+            vote_extension: vote_extension.into(),
+        }
+    }
+
+    fn tower_verify_vote_extension(
+        &self,
+        req: request::VerifyVoteExtension,
+    ) -> response::VerifyVoteExtension {
+        if self.do_verify_vote_extension(req.height.value(), &req.vote_extension) {
+            response::VerifyVoteExtension::Accept
+        } else {
+            response::VerifyVoteExtension::Reject
+        }
+    }
+
+    fn tower_process_proposal(&self, req: request::ProcessProposal) -> response::ProcessProposal {
+        let (round, last_commit) = req
+            .proposed_last_commit
+            .map(|commit| {
+                let last_commit = commit
+                    .votes
+                    .into_iter()
+                    .filter(|vote| vote.sig_info.is_signed())
+                    .map(|vote| (vote.validator.address, vote.validator.power.value()))
+                    .collect::<BTreeMap<_, _>>();
+
+                (commit.round.value(), last_commit)
+            })
+            .unwrap_or_default();
+
+        if self.do_process_proposal(req.txs, round, &last_commit) {
+            response::ProcessProposal::Accept
+        } else {
+            response::ProcessProposal::Reject
+        }
+    }
+
+    fn tower_list_snapshots(&self) -> AppResult<response::ListSnapshots> {
+        let snapshots = match self.do_list_snapshots() {
+            Ok(snapshots) => snapshots
+                .into_iter()
+                .map(into_tm_snapshot)
+                .collect::<AppResult<_>>()?,
+            // If state sync isn't enabled, simply report no snapshot.
+            Err(AppError::SnapshotsNotEnabled) => vec![],
+            Err(err) => return Err(err),
+        };
+
+        Ok(response::ListSnapshots { snapshots })
+    }
+
+    fn tower_load_snapshot_chunk(
+        &self,
+        req: request::LoadSnapshotChunk,
+    ) -> AppResult<response::LoadSnapshotChunk> {
+        let chunk = match self.do_load_snapshot_chunk(req.height.value(), req.format, req.chunk) {
+            Ok(chunk) => chunk.unwrap_or_default(),
+            Err(AppError::SnapshotsNotEnabled) => vec![],
+            Err(err) => return Err(err),
+        };
+
+        Ok(response::LoadSnapshotChunk {
+            chunk: chunk.into(),
+        })
+    }
+
+    fn tower_offer_snapshot(&self, req: request::OfferSnapshot) -> response::OfferSnapshot {
+        // Reject the snapshot if its metadata is malformed, or doesn't match
+        // the snapshot hash.
+        let Some(snapshot) = from_tm_snapshot(req.snapshot) else {
+            return response::OfferSnapshot::Reject;
+        };
+
+        let Some(app_hash) = from_tm_app_hash(req.app_hash) else {
+            return response::OfferSnapshot::Reject;
+        };
+
+        match self.do_offer_snapshot(snapshot, app_hash) {
+            Ok(()) => response::OfferSnapshot::Accept,
+            Err(AppError::UnsupportedSnapshotFormat { .. }) => {
+                response::OfferSnapshot::RejectFormat
+            },
+            Err(_) => response::OfferSnapshot::Reject,
+        }
+    }
+
+    fn tower_apply_snapshot_chunk(
+        &self,
+        req: request::ApplySnapshotChunk,
+    ) -> response::ApplySnapshotChunk {
+        let (result, refetch_chunks, reject_senders) =
+            match self.do_apply_snapshot_chunk(req.index, req.chunk.to_vec()) {
+                Ok(_) => (response::ApplySnapshotChunkResult::Accept, vec![], vec![]),
+                // The chunk is corrupted. Fetch it again from another peer.
+                Err(AppError::SnapshotChunkHashMismatch { index, .. }) => (
+                    response::ApplySnapshotChunkResult::Retry,
+                    vec![index],
+                    vec![req.sender],
+                ),
+                Err(AppError::NoSnapshotBeingRestored) => {
+                    (response::ApplySnapshotChunkResult::Abort, vec![], vec![])
+                },
+                // Restoring failed, e.g. the state doesn't match the app hash.
+                // Try another snapshot.
+                Err(_) => (
+                    response::ApplySnapshotChunkResult::RejectSnapshot,
+                    vec![],
+                    vec![],
+                ),
+            };
+
+        response::ApplySnapshotChunk {
+            result,
+            refetch_chunks,
+            reject_senders,
+        }
+    }
+
     fn tower_query(&self, req: request::Query) -> AppResult<response::Query> {
         let res = match req.path.as_str() {
             "/app" => match self.do_query_app_raw(&req.data, req.height.value(), req.prove) {
@@ -303,10 +509,50 @@ where
                     ..Default::default()
                 },
             },
+            "/store_many" => match self.do_query_store_many_raw(&req.data, req.height.value(), req.prove) {
+                Ok((values, proof)) => {
+                    let proof = proof.map(|proof| ProofOps {
+                        ops: vec![ProofOp {
+                            field_type: type_name::<DB::MultiProof>().into(),
+                            key: vec![],
+                            data: proof,
+                        }],
+                    });
+                    response::Query {
+                        code: Code::Ok,
+                        value: values.into(),
+                        height: req.height,
+                        proof,
+                        ..Default::default()
+                    }
+                },
+                Err(err) => response::Query {
+                    code: into_tm_code_error(1),
+                    codespace: "store_many".into(),
+                    log: err.to_string(),
+                    ..Default::default()
+                },
+            },
+            "/store_scan" => match self.do_query_store_scan_raw(&req.data, req.height.value()) {
+                Ok(res) => response::Query {
+                    code: Code::Ok,
+                    value: res.into(),
+                    height: req.height,
+                    ..Default::default()
+                },
+                Err(err) => response::Query {
+                    code: into_tm_code_error(1),
+                    codespace: "store_scan".into(),
+                    log: err.to_string(),
+                    ..Default::default()
+                },
+            },
             unknown => response::Query {
                 code: into_tm_code_error(1),
                 codespace: "app".into(),
-                log: format!("unknown path `{unknown}`; must be `/app`, `/simulate`, or `/store`"),
+                log: format!(
+                    "unknown path `{unknown}`; must be `/app`, `/simulate`, `/store`, `/store_many`, or `/store_scan`"
+                ),
                 ..Default::default()
             },
         };
@@ -330,6 +576,47 @@ fn from_tm_hash(bytes: Hash) -> Hash256 {
     }
 }
 
+/// The snapshot's chunk hashes are encoded in its metadata.
+fn into_tm_snapshot(snapshot: Snapshot) -> AppResult<TmSnapshot> {
+    Ok(TmSnapshot {
+        height: snapshot
+            .height
+            .try_into()
+            .expect("block height exceeds i64"),
+        format: snapshot.format,
+        chunks: snapshot.chunk_hashes.len() as u32,
+        hash: snapshot.hash().into_inner().to_vec().into(),
+        metadata: snapshot.chunk_hashes.to_borsh_vec()?.into(),
+    })
+}
+
+/// Return `None` if the metadata can't be decoded into chunk hashes, or they
+/// don't match the snapshot's chunk count or hash.
+fn from_tm_snapshot(snapshot: TmSnapshot) -> Option<Snapshot> {
+    let chunk_hashes = snapshot.metadata.deserialize_borsh::<Vec<Hash256>>().ok()?;
+
+    if chunk_hashes.len() != snapshot.chunks as usize {
+        return None;
+    }
+
+    let res = Snapshot {
+        height: snapshot.height.value(),
+        format: snapshot.format,
+        chunk_hashes,
+    };
+
+    if res.hash().as_ref() != snapshot.hash.as_ref() {
+        return None;
+    }
+
+    Some(res)
+}
+
+/// Return `None` if the hash is of incorrect length.
+fn from_tm_app_hash(hash: AppHash) -> Option<Hash256> {
+    hash.as_bytes().try_into().ok().map(Hash256::from_inner)
+}
+
 fn into_tm_tx_result(outcome: TxOutcome) -> ExecTxResult {
     match outcome.result {
         GenericResult::Ok(_) => ExecTxResult {
@@ -1,26 +1,49 @@
+#[cfg(feature = "parallel")]
+use crate::process_txs_in_parallel;
+#[cfg(feature = "abci")]
+use data_encoding::BASE64;
 use {
     crate::{
         catch_and_append_event, catch_and_update_event, do_authenticate, do_backrun, do_configure,
-        do_cron_execute, do_execute, do_finalize_fee, do_instantiate, do_migrate, do_transfer,
-        do_upload, do_withhold_fee, query_app_config, query_balance, query_balances, query_code,
-        query_codes, query_config, query_contract, query_contracts, query_supplies, query_supply,
-        query_wasm_raw, query_wasm_scan, query_wasm_smart, AppError, AppResult, Buffer, Db,
-        EventResult, GasTracker, Indexer, NaiveProposalPreparer, NaiveQuerier, NullIndexer,
-        ProposalPreparer, QuerierProviderImpl, Shared, Vm, APP_CONFIG, CHAIN_ID, CODES, CONFIG,
-        LAST_FINALIZED_BLOCK, NEXT_CRONJOBS,
+        do_cron_execute, do_execute, do_finalize_fee, do_ibc_packet_ack, do_ibc_packet_receive,
+        do_ibc_packet_timeout, do_instantiate, do_migrate, do_transfer, do_upgrade, do_upload,
+        do_withhold_fee, query_app_config, query_balance, query_balances, query_code, query_codes,
+        query_config, query_contract, query_contracts, query_ibc_client, query_supplies,
+        query_supply, query_wasm_raw, query_wasm_scan, query_wasm_smart, update_validators,
+        verify_vote_extensions, AppError, AppResult, BlockLimits, Buffer, Db, EventResult,
+        ExtendedCommit, ExtendedVote, GasTracker, Indexer, NaiveProposalPreparer, NaiveQuerier,
+        NullIndexer, ProposalPreparer, QuerierProviderImpl, Shared, Snapshot, SnapshotDb,
+        SnapshotManager, StorageProvider, UpgradeHandler, ValidatorUpdate, Vm, APP_CONFIG,
+        BLOCK_LIMITS, CHAIN_ID, CODES, CONFIG, CONTRACTS, CONTRACT_NAMESPACE, LAST_FINALIZED_BLOCK,
+        MAX_STORE_MANY_KEYS, MAX_STORE_SCAN_LIMIT, NEXT_CRONJOBS,
     },
+    grug_math::Inner,
     grug_storage::PrefixBound,
     grug_types::{
-        Addr, AuthMode, Block, BlockInfo, BlockOutcome, BorshSerExt, CheckTxOutcome, CodeStatus,
-        CommitmentStatus, CronOutcome, Duration, Event, GenericResult, GenericResultExt,
-        GenesisState, Hash256, Json, JsonSerExt, Message, MsgsAndBackrunEvents, Order, Permission,
-        QuerierWrapper, Query, QueryResponse, StdResult, Storage, Timestamp, Tx, TxEvents,
-        TxOutcome, UnsignedTx, GENESIS_SENDER,
+        Addr, AuthMode, Binary, Block, BlockInfo, BlockOutcome, BorshSerExt, CheckTxOutcome, Code,
+        CodeStatus, Coins, CommitmentStatus, Config, CronOutcome, Duration, Event, GasProfile,
+        GenericResult, GenericResultExt, GenesisState, Hash256, HashExt, Json, JsonDeExt,
+        JsonSerExt, Message, MsgsAndBackrunEvents, Order, Permission, QuerierWrapper, Query,
+        QueryResponse, QueryStoreManyRequest, QueryStoreScanRequest, SimulateRequest,
+        StateOverrides, StdResult, Storage, Timestamp, Tx, TxEvents, TxOutcome, UnsignedTx,
+        GENESIS_SENDER,
     },
     prost::bytes::Bytes,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        sync::Arc,
+    },
 };
-#[cfg(feature = "abci")]
-use {data_encoding::BASE64, grug_types::JsonDeExt};
+
+/// A function that sets an account's token balances to the given coins, by
+/// writing to the bank contract's storage, which is passed as the first
+/// argument. Denoms not among the coins are set to zero, and the total supplies
+/// are adjusted accordingly.
+///
+/// The host doesn't know how the bank contract stores balances, so balances can
+/// only be overridden in simulations if such a function is provided.
+pub type BalanceOverrider =
+    Arc<dyn Fn(&mut dyn Storage, Addr, &Coins) -> StdResult<()> + Send + Sync>;
 
 /// The ABCI application.
 ///
@@ -45,6 +68,21 @@ pub struct App<DB, VM, PP = NaiveProposalPreparer, ID = NullIndexer> {
     /// Related config in CosmWasm:
     /// <https://github.com/CosmWasm/wasmd/blob/v0.51.0/x/wasm/types/types.go#L322-L323>
     query_gas_limit: u64,
+    /// Creates and restores state sync snapshots. `None` if state sync is not
+    /// enabled.
+    snapshots: Option<SnapshotManager>,
+    /// Handlers of the chain upgrades this software version supports, indexed
+    /// by upgrade names.
+    upgrade_handlers: BTreeMap<String, UpgradeHandler>,
+    /// The maximum size of the codes that contracts' codes can be overridden
+    /// with in simulations. `None` if code overrides are not enabled.
+    max_override_code_size: Option<usize>,
+    /// Sets accounts' balances in the bank contract's storage, for overriding
+    /// balances in simulations. `None` if balance overrides are not enabled.
+    balance_overrider: Option<BalanceOverrider>,
+    /// Whether to execute the transactions in a block in parallel.
+    #[cfg(feature = "parallel")]
+    parallel_execution: bool,
 }
 
 impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
@@ -55,8 +93,66 @@ impl<DB, VM, PP, ID> App<DB, VM, PP, ID> {
             pp,
             indexer,
             query_gas_limit,
+            snapshots: None,
+            upgrade_handlers: BTreeMap::new(),
+            max_override_code_size: None,
+            balance_overrider: None,
+            #[cfg(feature = "parallel")]
+            parallel_execution: false,
         }
     }
+
+    /// Enable state sync, using the given snapshot manager.
+    pub fn with_snapshots(mut self, snapshots: SnapshotManager) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Register the handler of the chain upgrade of the given name, which is
+    /// run at the upgrade height to migrate the state.
+    ///
+    /// If an upgrade is scheduled but its handler isn't registered, the node
+    /// halts at the upgrade height.
+    pub fn with_upgrade_handler<N, F>(mut self, name: N, handler: F) -> Self
+    where
+        N: Into<String>,
+        F: Fn(&mut dyn Storage, BlockInfo) -> AppResult<()> + Send + Sync + 'static,
+    {
+        self.upgrade_handlers.insert(name.into(), Arc::new(handler));
+        self
+    }
+
+    /// Allow simulations to override contracts' codes with codes no larger
+    /// than the given size, in bytes.
+    ///
+    /// Override codes are compiled without being metered, and aren't cached,
+    /// so they're disabled by default.
+    pub fn with_code_overrides(mut self, max_code_size: usize) -> Self {
+        self.max_override_code_size = Some(max_code_size);
+        self
+    }
+
+    /// Allow simulations to override accounts' balances, using the given
+    /// function, which must match the storage layout of the chain's bank
+    /// contract.
+    pub fn with_balance_overrides<F>(mut self, overrider: F) -> Self
+    where
+        F: Fn(&mut dyn Storage, Addr, &Coins) -> StdResult<()> + Send + Sync + 'static,
+    {
+        self.balance_overrider = Some(Arc::new(overrider));
+        self
+    }
+
+    /// Execute the transactions in a block optimistically in parallel. See
+    /// [`process_txs_in_parallel`](crate::process_txs_in_parallel) for details.
+    ///
+    /// The outcomes and state changes are identical to those of sequential
+    /// execution, which remains the default.
+    #[cfg(feature = "parallel")]
+    pub fn with_parallel_execution(mut self) -> Self {
+        self.parallel_execution = true;
+        self
+    }
 }
 
 impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
@@ -71,6 +167,8 @@ where
         &self,
         chain_id: String,
         block: BlockInfo,
+        block_limits: BlockLimits,
+        validators: &[ValidatorUpdate],
         genesis_state: GenesisState,
     ) -> AppResult<Hash256> {
         let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
@@ -86,15 +184,21 @@ where
 
         // Create gas tracker for genesis.
         // During genesis, there is no gas limit.
-        let gas_tracker = GasTracker::new_limitless();
+        let gas_tracker =
+            GasTracker::new_limitless().with_costs(genesis_state.config.gas_costs.clone());
 
         // Save the config and genesis block, so that they can be queried when
         // executing genesis messages.
         CHAIN_ID.save(&mut buffer, &chain_id)?;
         LAST_FINALIZED_BLOCK.save(&mut buffer, &block)?;
+        BLOCK_LIMITS.save(&mut buffer, &block_limits)?;
         CONFIG.save(&mut buffer, &genesis_state.config)?;
         APP_CONFIG.save(&mut buffer, &genesis_state.app_config)?;
 
+        // Record the validators' consensus keys, so that their vote extensions
+        // can be verified.
+        update_validators(&mut buffer, validators)?;
+
         // Schedule cronjobs.
         for (contract, interval) in genesis_state.config.cronjobs {
             schedule_cronjob(&mut buffer, contract, block.timestamp + interval)?;
@@ -155,9 +259,41 @@ where
         Ok(root_hash.unwrap())
     }
 
-    pub fn do_prepare_proposal(&self, txs: Vec<Bytes>, max_tx_bytes: usize) -> Vec<Bytes> {
+    /// Prepare a proposal, given the votes for the last finalized block, cast
+    /// in `round`.
+    pub fn do_prepare_proposal(
+        &self,
+        txs: Vec<Bytes>,
+        max_tx_bytes: usize,
+        round: u32,
+        votes: &[ExtendedVote],
+    ) -> Vec<Bytes> {
+        // If any validator attached a vote extension, carry the votes in the
+        // proposal, so that other validators can verify what the preparer did
+        // with them. If they don't fit, or their extensions can't be verified
+        // (e.g. the validators' consensus keys aren't known), which would get
+        // the proposal rejected, prepare the proposal without them.
+        let extended_commit = votes
+            .iter()
+            .any(|vote| !vote.extension.is_empty())
+            .then(|| {
+                ExtendedCommit {
+                    votes: votes.to_vec(),
+                }
+                .encode()
+                .ok()
+            })
+            .flatten()
+            .filter(|raw_tx| raw_tx.len() <= max_tx_bytes)
+            .filter(|_| self.verify_vote_extensions(round, votes));
+
+        let (votes, max_tx_bytes) = match &extended_commit {
+            Some(raw_tx) => (votes, max_tx_bytes - raw_tx.len()),
+            None => (&[][..], max_tx_bytes),
+        };
+
         let txs = self
-            ._do_prepare_proposal(txs.clone(), max_tx_bytes)
+            ._do_prepare_proposal(txs.clone(), max_tx_bytes, votes)
             .unwrap_or_else(|_err| {
                 #[cfg(feature = "tracing")]
                 tracing::error!(
@@ -169,34 +305,238 @@ where
             });
 
         // Call naive proposal preparer to check the `max_tx_bytes`.
-        NaiveProposalPreparer
-            .prepare_proposal(QuerierWrapper::new(&NaiveQuerier), txs, max_tx_bytes)
-            .unwrap()
+        let mut txs = NaiveProposalPreparer
+            .prepare_proposal(QuerierWrapper::new(&NaiveQuerier), txs, max_tx_bytes, &[])
+            .unwrap();
+
+        if let Some(raw_tx) = extended_commit {
+            txs.insert(0, raw_tx);
+        }
+
+        txs
     }
 
     #[inline]
-    fn _do_prepare_proposal(&self, txs: Vec<Bytes>, max_tx_bytes: usize) -> AppResult<Vec<Bytes>> {
+    fn _do_prepare_proposal(
+        &self,
+        txs: Vec<Bytes>,
+        max_tx_bytes: usize,
+        votes: &[ExtendedVote],
+    ) -> AppResult<Vec<Bytes>> {
         let storage = self.db.state_storage(None)?;
+        let cfg = CONFIG.load(&storage)?;
         let block = LAST_FINALIZED_BLOCK.load(&storage)?;
         let querier = QuerierProviderImpl::new_boxed(
             self.vm.clone(),
             Box::new(storage),
-            GasTracker::new_limitless(),
+            GasTracker::new_limitless().with_costs(cfg.gas_costs),
             block,
         );
 
         Ok(self
             .pp
-            .prepare_proposal(QuerierWrapper::new(&querier), txs, max_tx_bytes)?)
+            .prepare_proposal(QuerierWrapper::new(&querier), txs, max_tx_bytes, votes)?)
+    }
+
+    /// Return whether the extensions of the votes for the last finalized block,
+    /// cast in `round`, are signed by the validators that cast them.
+    fn verify_vote_extensions(&self, round: u32, votes: &[ExtendedVote]) -> bool {
+        self._verify_vote_extensions(round, votes)
+            .unwrap_or_else(|_err| {
+                #[cfg(feature = "tracing")]
+                tracing::error!(err = _err.to_string(), "Failed to verify vote extensions!");
+
+                false
+            })
+    }
+
+    #[inline]
+    fn _verify_vote_extensions(&self, round: u32, votes: &[ExtendedVote]) -> AppResult<bool> {
+        let storage = self.db.state_storage(None)?;
+        let chain_id = CHAIN_ID.load(&storage)?;
+        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+
+        Ok(verify_vote_extensions(
+            &storage,
+            &chain_id,
+            block.height,
+            round,
+            votes,
+        )?)
+    }
+
+    /// Return whether the proposal should be accepted.
+    ///
+    /// `last_commit` maps the validators that voted for the previous block in
+    /// `round`, as reported by CometBFT, to their voting power. The votes
+    /// carried in the proposal must be exactly these, with their extensions
+    /// signed by the validators.
+    pub fn do_process_proposal(
+        &self,
+        txs: Vec<Bytes>,
+        round: u32,
+        last_commit: &BTreeMap<[u8; 20], u64>,
+    ) -> bool {
+        match self._do_process_proposal(&txs, round, last_commit) {
+            Ok(true) => {
+                #[cfg(feature = "tracing")]
+                tracing::info!(num_txs = txs.len(), "Accepted proposal");
+
+                true
+            },
+            Ok(false) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(num_txs = txs.len(), "Rejected proposal");
+
+                false
+            },
+            // An error means this node failed to process the proposal, e.g.
+            // it failed to read the state, which isn't the proposer's fault.
+            // Rejecting the proposal would hinder consensus, so we accept it.
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!(
+                    err = _err.to_string(),
+                    "Failed to process proposal! Accepting it."
+                );
+
+                true
+            },
+        }
+    }
+
+    #[inline]
+    fn _do_process_proposal(
+        &self,
+        txs: &[Bytes],
+        round: u32,
+        last_commit: &BTreeMap<[u8; 20], u64>,
+    ) -> AppResult<bool> {
+        let storage = self.db.state_storage(None)?;
+        let limits = BLOCK_LIMITS.may_load(&storage)?.unwrap_or_default();
+
+        if !within_block_limits(txs, limits) {
+            return Ok(false);
+        }
+
+        // Take out the votes carried in the proposal, if any.
+        let (votes, txs) = match txs.split_first() {
+            Some((first, rest)) => match ExtendedCommit::decode(first) {
+                Some(Ok(extended_commit)) => (extended_commit.votes, rest),
+                Some(Err(_err)) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(err = _err.to_string(), "Malformed extended commit");
+
+                    return Ok(false);
+                },
+                None => (Vec::new(), txs),
+            },
+            None => (Vec::new(), txs),
+        };
+
+        if txs.iter().any(|tx| ExtendedCommit::decode(tx).is_some()) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Extended commit isn't the first transaction");
+
+            return Ok(false);
+        }
+
+        if !votes.is_empty() && !votes_match_commit(&votes, last_commit) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Extended commit doesn't match the last commit");
+
+            return Ok(false);
+        }
+
+        let chain_id = CHAIN_ID.load(&storage)?;
+        let cfg = CONFIG.load(&storage)?;
+        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+
+        if !verify_vote_extensions(&storage, &chain_id, block.height, round, &votes)? {
+            #[cfg(feature = "tracing")]
+            tracing::warn!("Extended commit contains a vote extension with an invalid signature");
+
+            return Ok(false);
+        }
+
+        let querier = QuerierProviderImpl::new_boxed(
+            self.vm.clone(),
+            Box::new(storage),
+            GasTracker::new_limitless().with_costs(cfg.gas_costs),
+            block,
+        );
+
+        self.pp
+            .process_proposal(QuerierWrapper::new(&querier), txs, &votes)
+            .map_err(|err| AppError::ProcessProposal(err.to_string()))
+    }
+
+    /// Return the vote extension to attach to this node's vote for the block at
+    /// the given height.
+    pub fn do_extend_vote(&self, height: u64) -> Vec<u8> {
+        self._do_extend_vote(height).unwrap_or_else(|_err| {
+            #[cfg(feature = "tracing")]
+            tracing::error!(
+                err = _err.to_string(),
+                "Failed to extend vote! Falling back to empty extension."
+            );
+
+            Vec::new()
+        })
+    }
+
+    #[inline]
+    fn _do_extend_vote(&self, height: u64) -> AppResult<Vec<u8>> {
+        let storage = self.db.state_storage(None)?;
+        let cfg = CONFIG.load(&storage)?;
+        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let querier = QuerierProviderImpl::new_boxed(
+            self.vm.clone(),
+            Box::new(storage),
+            GasTracker::new_limitless().with_costs(cfg.gas_costs),
+            block,
+        );
+
+        self.pp
+            .extend_vote(QuerierWrapper::new(&querier), height)
+            .map_err(|err| AppError::ExtendVote(err.to_string()))
+    }
+
+    /// Return whether the vote extension should be accepted.
+    pub fn do_verify_vote_extension(&self, height: u64, extension: &[u8]) -> bool {
+        match self._do_verify_vote_extension(height, extension) {
+            Ok(()) => true,
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(err = _err.to_string(), height, "Rejected vote extension");
+
+                false
+            },
+        }
+    }
+
+    #[inline]
+    fn _do_verify_vote_extension(&self, height: u64, extension: &[u8]) -> AppResult<()> {
+        let storage = self.db.state_storage(None)?;
+        let cfg = CONFIG.load(&storage)?;
+        let block = LAST_FINALIZED_BLOCK.load(&storage)?;
+        let querier = QuerierProviderImpl::new_boxed(
+            self.vm.clone(),
+            Box::new(storage),
+            GasTracker::new_limitless().with_costs(cfg.gas_costs),
+            block,
+        );
+
+        self.pp
+            .verify_vote_extension(QuerierWrapper::new(&querier), height, extension)
+            .map_err(|err| AppError::VerifyVoteExtension(err.to_string()))
     }
 
     pub fn do_finalize_block(&self, block: Block) -> AppResult<BlockOutcome> {
         let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
-        let cfg = CONFIG.load(&buffer)?;
         let last_finalized_block = LAST_FINALIZED_BLOCK.load(&buffer)?;
 
         let mut cron_outcomes = vec![];
-        let mut tx_outcomes = vec![];
 
         self.indexer.pre_indexing(block.info.height)?;
 
@@ -209,6 +549,14 @@ where
             });
         }
 
+        // Perform the chain upgrade scheduled at this height, if any. This must
+        // be done before anything else, so that the rest of the block is run
+        // against the migrated state.
+        do_upgrade(&mut buffer, block.info, &self.upgrade_handlers)?;
+
+        // Load the config after the upgrade, which may have migrated it.
+        let cfg = CONFIG.load(&buffer)?;
+
         // Remove orphaned codes (those that are not used by any contract) that
         // have been orphaned longer than the maximum age.
         if let Some(since) = block
@@ -264,7 +612,7 @@ where
             );
 
             let cron_buffer = Shared::new(Buffer::new(buffer.clone(), None));
-            let cron_gas_tracker = GasTracker::new_limitless();
+            let cron_gas_tracker = GasTracker::new_limitless().with_costs(cfg.gas_costs.clone());
             let next_time = block.info.timestamp + cfg.cronjobs[&contract];
 
             let cron_event = do_cron_execute(
@@ -293,20 +641,41 @@ where
             ));
         }
 
-        // Process transactions one-by-one.
-        for (_idx, tx) in block.txs.clone().into_iter().enumerate() {
-            #[cfg(feature = "tracing")]
-            tracing::debug!(idx = _idx, "Processing transaction");
+        // Process transactions.
+        let tx_outcomes = self.process_txs(buffer.clone(), &cfg, &block);
 
-            let tx_outcome = process_tx(
-                self.vm.clone(),
-                buffer.clone(),
-                block.info,
-                tx.clone(),
-                AuthMode::Finalize,
-            );
+        // Prepare the codes uploaded in this block ahead of their first use.
+        // This is only done for finalized uploads, as preparing a code isn't
+        // metered, and only an optimization. A code that has already been used
+        // in this block is no longer orphaned, and has been prepared already.
+        let uploaded = CodeStatus::Orphaned {
+            since: block.info.timestamp,
+        };
+
+        for hash in CODES
+            .idx
+            .status
+            .prefix_keys(
+                &buffer,
+                Some(PrefixBound::Inclusive(uploaded)),
+                Some(PrefixBound::Inclusive(uploaded)),
+                Order::Ascending,
+            )
+            .map(|res| res.map(|(_status, hash)| hash))
+            .collect::<StdResult<Vec<_>>>()?
+        {
+            let code = CODES.load(&buffer, hash)?;
 
-            tx_outcomes.push(tx_outcome);
+            // If the code is indeed invalid, the error will be raised again
+            // when it's used.
+            if let Err(_err) = self.vm.clone().warm_up(&code.code, hash, &cfg.gas_costs) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    code_hash = hash.to_string(),
+                    err = _err.to_string(),
+                    "Failed to warm up code"
+                );
+            }
         }
 
         // Save the last committed block.
@@ -346,6 +715,47 @@ where
         Ok(block_outcome)
     }
 
+    fn process_txs<S>(
+        &self,
+        buffer: Shared<Buffer<S>>,
+        cfg: &Config,
+        block: &Block,
+    ) -> Vec<TxOutcome>
+    where
+        S: Storage + Clone + 'static,
+    {
+        #[cfg(feature = "parallel")]
+        if self.parallel_execution {
+            return process_txs_in_parallel(
+                self.vm.clone(),
+                buffer,
+                &cfg.gas_costs,
+                block.info,
+                block.txs.clone(),
+            );
+        }
+
+        // Process transactions one-by-one.
+        block
+            .txs
+            .iter()
+            .enumerate()
+            .map(|(_idx, tx)| {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(idx = _idx, "Processing transaction");
+
+                process_tx(
+                    self.vm.clone(),
+                    buffer.clone(),
+                    GasTracker::new_limited(tx.gas_limit).with_costs(cfg.gas_costs.clone()),
+                    block.info,
+                    tx.clone(),
+                    AuthMode::Finalize,
+                )
+            })
+            .collect()
+    }
+
     pub fn do_commit(&self) -> AppResult<()> {
         self.db.commit()?;
 
@@ -354,6 +764,31 @@ where
 
         if let Some(block_height) = self.db.latest_version() {
             self.indexer.post_indexing(block_height)?;
+
+            if let Some(snapshots) = &self.snapshots {
+                if snapshots.should_create(block_height) {
+                    // Failing to create a snapshot shouldn't halt the chain.
+                    match self.db.state_storage(Some(block_height)) {
+                        Ok(storage) => {
+                            if !snapshots.spawn_create(storage, block_height) {
+                                #[cfg(feature = "tracing")]
+                                tracing::warn!(
+                                    height = block_height,
+                                    "Skipped snapshot, as the previous one is still being created"
+                                );
+                            }
+                        },
+                        Err(_err) => {
+                            #[cfg(feature = "tracing")]
+                            tracing::error!(
+                                height = block_height,
+                                err = _err.to_string(),
+                                "Failed to create snapshot"
+                            );
+                        },
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -366,13 +801,14 @@ where
     // 2. `authenticate`, where the sender account authenticates the transaction.
     pub fn do_check_tx(&self, tx: Tx) -> AppResult<CheckTxOutcome> {
         let buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
+        let cfg = CONFIG.load(&buffer)?;
         let block = LAST_FINALIZED_BLOCK.load(&buffer)?;
-        let gas_tracker = GasTracker::new_limited(tx.gas_limit);
+        let gas_tracker = GasTracker::new_limited(tx.gas_limit).with_costs(cfg.gas_costs.clone());
 
         if let Err((_, err)) = do_withhold_fee(
             self.vm.clone(),
             Box::new(buffer.clone()),
-            GasTracker::new_limitless(),
+            GasTracker::new_limitless().with_costs(cfg.gas_costs),
             block,
             &tx,
             AuthMode::Check,
@@ -436,12 +872,13 @@ where
 
         // Use the state storage at the given version to perform the query.
         let storage = self.db.state_storage(version)?;
+        let cfg = CONFIG.load(&storage)?;
         let block = LAST_FINALIZED_BLOCK.load(&storage)?;
 
         process_query(
             self.vm.clone(),
             Box::new(storage),
-            GasTracker::new_limited(self.query_gas_limit),
+            GasTracker::new_limited(self.query_gas_limit).with_costs(cfg.gas_costs),
             block,
             0,
             req,
@@ -478,14 +915,106 @@ where
         Ok((value, proof))
     }
 
+    /// Performs raw queries of multiple keys of the app's underlying key-value
+    /// store at once.
+    ///
+    /// At most [`MAX_STORE_MANY_KEYS`] keys can be queried at once.
+    ///
+    /// Returns:
+    /// - the values corresponding to the given keys, in the same order as the
+    ///   keys; `None` for keys that don't exist;
+    /// - the Merkle proof of all the keys; `None` if a proof is not requested
+    ///   (`prove` is false).
+    pub fn do_query_store_many(
+        &self,
+        req: QueryStoreManyRequest,
+        height: u64,
+        prove: bool,
+    ) -> AppResult<(Vec<Option<Binary>>, Option<Vec<u8>>)> {
+        if req.keys.len() > MAX_STORE_MANY_KEYS {
+            return Err(AppError::TooManyStoreKeys {
+                max: MAX_STORE_MANY_KEYS,
+                actual: req.keys.len(),
+            });
+        }
+
+        // Determine the version upfront, so that the values and the proof are
+        // of the same version, even if a new one is committed in the meantime.
+        let version = if height == 0 {
+            self.db.latest_version()
+        } else {
+            Some(height)
+        };
+
+        let keys = req
+            .keys
+            .into_iter()
+            .map(Binary::into_inner)
+            .collect::<Vec<_>>();
+
+        let storage = self.db.state_storage(version)?;
+        let values = keys
+            .iter()
+            .map(|key| storage.read(key).map(Binary::from))
+            .collect();
+
+        let proof = if prove {
+            Some(self.db.prove_many(keys, version)?.to_borsh_vec()?)
+        } else {
+            None
+        };
+
+        Ok((values, proof))
+    }
+
+    /// Enumerate raw key-value pairs in the app's underlying key-value store,
+    /// in ascending order.
+    ///
+    /// At most [`MAX_STORE_SCAN_LIMIT`] records are returned; to enumerate more,
+    /// make another request starting after the last key returned.
+    pub fn do_query_store_scan(
+        &self,
+        req: QueryStoreScanRequest,
+        height: u64,
+    ) -> AppResult<BTreeMap<Binary, Binary>> {
+        let version = if height == 0 {
+            None
+        } else {
+            Some(height)
+        };
+
+        let limit = req
+            .limit
+            .unwrap_or(MAX_STORE_SCAN_LIMIT)
+            .min(MAX_STORE_SCAN_LIMIT);
+
+        let records = self
+            .db
+            .state_storage(version)?
+            .scan(req.min.as_deref(), req.max.as_deref(), Order::Ascending)
+            .take(limit as usize)
+            .map(|(k, v)| (Binary::from(k), Binary::from(v)))
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Simulate a transaction, optionally with the given overrides applied to
+    /// the state. The overrides only affect this simulation.
+    ///
+    /// If `profile` is true, also return a profile of the gas consumed by each
+    /// call made during the transaction; otherwise, the profile is `None`.
     pub fn do_simulate(
         &self,
         unsigned_tx: UnsignedTx,
+        overrides: StateOverrides,
         height: u64,
         prove: bool,
-    ) -> AppResult<TxOutcome> {
-        let buffer = Buffer::new(self.db.state_storage(None)?, None);
-        let block = LAST_FINALIZED_BLOCK.load(&buffer)?;
+        profile: bool,
+    ) -> AppResult<(TxOutcome, Option<GasProfile>)> {
+        let buffer = Shared::new(Buffer::new(self.db.state_storage(None)?, None));
+        let cfg = CONFIG.load(&buffer)?;
+        let mut block = LAST_FINALIZED_BLOCK.load(&buffer)?;
 
         // We can't "prove" a gas simulation
         if prove {
@@ -497,6 +1026,20 @@ where
             return Err(AppError::PastHeightNotSupported);
         }
 
+        // Apply the state overrides. They're written to the buffer, which is
+        // discarded after the simulation, so never persisted.
+        //
+        // Codes that only exist because of the overrides aren't to be cached,
+        // since they're only used in this simulation.
+        let uncached_codes = apply_state_overrides(
+            buffer.clone(),
+            &mut block,
+            overrides,
+            cfg.bank,
+            self.balance_overrider.as_ref(),
+            self.max_override_code_size,
+        )?;
+
         // Create a `Tx` from the unsigned transaction.
         // Use using the node's query gas limit as the transaction gas limit,
         // and empty bytes as credential.
@@ -508,15 +1051,74 @@ where
             credential: Json::null(),
         };
 
+        let mut gas_tracker = GasTracker::new_limited(tx.gas_limit).with_costs(cfg.gas_costs);
+
+        if profile {
+            gas_tracker = gas_tracker.with_profiler();
+        }
+
         // Run the transaction with `simulate` as `true`. Track how much gas was
         // consumed, and, if it was successful, what events were emitted.
-        Ok(process_tx(
-            self.vm.clone(),
+        let outcome = process_tx(
+            self.vm.clone().without_caching(uncached_codes),
             buffer,
+            gas_tracker.clone(),
             block,
             tx,
             AuthMode::Simulate,
-        ))
+        );
+
+        Ok((outcome, gas_tracker.profile()))
+    }
+}
+
+// State sync methods. These require the DB to be able to restore snapshots.
+impl<DB, VM, PP, ID> App<DB, VM, PP, ID>
+where
+    DB: SnapshotDb,
+    AppError: From<DB::Error>,
+{
+    pub fn do_list_snapshots(&self) -> AppResult<Vec<Snapshot>> {
+        self.snapshots()?.list()
+    }
+
+    pub fn do_load_snapshot_chunk(
+        &self,
+        height: u64,
+        format: u32,
+        index: u32,
+    ) -> AppResult<Option<Vec<u8>>> {
+        self.snapshots()?.load_chunk(height, format, index)
+    }
+
+    pub fn do_offer_snapshot(&self, snapshot: Snapshot, app_hash: Hash256) -> AppResult<()> {
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            height = snapshot.height,
+            chunks = snapshot.chunk_hashes.len(),
+            "Offered snapshot"
+        );
+
+        self.snapshots()?.offer(snapshot, app_hash)
+    }
+
+    /// Apply a chunk of the snapshot being restored. Once all chunks have been
+    /// applied, restore the state from them, and return `true`.
+    pub fn do_apply_snapshot_chunk(&self, index: u32, chunk: Vec<u8>) -> AppResult<bool> {
+        let Some((height, app_hash, batch)) = self.snapshots()?.apply_chunk(index, chunk)? else {
+            return Ok(false);
+        };
+
+        self.db.restore(height, batch, app_hash)?;
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(height, "Restored state from snapshot");
+
+        Ok(true)
+    }
+
+    fn snapshots(&self) -> AppResult<&SnapshotManager> {
+        self.snapshots.as_ref().ok_or(AppError::SnapshotsNotEnabled)
     }
 }
 
@@ -536,25 +1138,34 @@ where
         &self,
         chain_id: String,
         block: BlockInfo,
+        block_limits: BlockLimits,
+        validators: &[ValidatorUpdate],
         raw_genesis_state: &[u8],
     ) -> AppResult<Hash256> {
         let genesis_state = raw_genesis_state.deserialize_json()?;
 
-        self.do_init_chain(chain_id, block, genesis_state)
+        self.do_init_chain(chain_id, block, block_limits, validators, genesis_state)
     }
 
+    /// Finalize a block of raw transactions.
+    ///
+    /// Besides the block outcome, return whether each transaction was executed.
+    /// The extended commit pseudo-transaction, and transactions that fail to
+    /// deserialize, aren't executed.
     pub fn do_finalize_block_raw<T>(
         &self,
         block_info: BlockInfo,
         raw_txs: &[T],
-    ) -> AppResult<BlockOutcome>
+    ) -> AppResult<(BlockOutcome, Vec<bool>)>
     where
         T: AsRef<[u8]>,
     {
         let txs = raw_txs
             .iter()
-            .filter_map(|raw_tx| {
-                if let Ok(tx) = raw_tx.deserialize_json() {
+            .map(|raw_tx| {
+                if ExtendedCommit::decode(raw_tx.as_ref()).is_some() {
+                    None
+                } else if let Ok(tx) = raw_tx.deserialize_json() {
                     Some(tx)
                 } else {
                     // The transaction failed to deserialize.
@@ -577,14 +1188,16 @@ where
                     None
                 }
             })
-            .collect();
+            .collect::<Vec<Option<Tx>>>();
+
+        let executed = txs.iter().map(Option::is_some).collect();
 
         let block = Block {
             info: block_info,
-            txs,
+            txs: txs.into_iter().flatten().collect(),
         };
 
-        self.do_finalize_block(block)
+        Ok((self.do_finalize_block(block)?, executed))
     }
 
     pub fn do_check_tx_raw(&self, raw_tx: &[u8]) -> AppResult<CheckTxOutcome> {
@@ -599,8 +1212,8 @@ where
         height: u64,
         prove: bool,
     ) -> AppResult<Vec<u8>> {
-        let tx = raw_unsigned_tx.deserialize_json()?;
-        let res = self.do_simulate(tx, height, prove)?;
+        let req: SimulateRequest = raw_unsigned_tx.deserialize_json()?;
+        let (res, _) = self.do_simulate(req.tx, req.overrides, height, prove, false)?;
 
         Ok(res.to_json_vec()?)
     }
@@ -611,26 +1224,178 @@ where
 
         Ok(res.to_json_vec()?)
     }
+
+    pub fn do_query_store_many_raw(
+        &self,
+        raw_req: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> AppResult<(Vec<u8>, Option<Vec<u8>>)> {
+        let req = raw_req.deserialize_json()?;
+        let (values, proof) = self.do_query_store_many(req, height, prove)?;
+
+        Ok((values.to_json_vec()?, proof))
+    }
+
+    pub fn do_query_store_scan_raw(&self, raw_req: &[u8], height: u64) -> AppResult<Vec<u8>> {
+        let req = raw_req.deserialize_json()?;
+        let res = self.do_query_store_scan(req, height)?;
+
+        Ok(res.to_json_vec()?)
+    }
+}
+
+/// Apply the overrides of a simulation to the block info and the storage.
+///
+/// Balances are overridden after contracts' storages, through the bank
+/// contract's storage layout, so they take precedence. They can only be
+/// overridden if enabled, i.e. `balance_overrider` is `Some`.
+///
+/// Overriding a contract's code stores the code if it doesn't exist yet. Code
+/// usage counts aren't adjusted, as the storage is discarded afterwards anyway.
+/// Codes can only be overridden if enabled, i.e. `max_code_size` is `Some`.
+///
+/// Returns the hashes of the codes stored this way.
+fn apply_state_overrides<S>(
+    mut storage: S,
+    block: &mut BlockInfo,
+    overrides: StateOverrides,
+    bank: Addr,
+    balance_overrider: Option<&BalanceOverrider>,
+    max_code_size: Option<usize>,
+) -> AppResult<BTreeSet<Hash256>>
+where
+    S: Storage + Clone + 'static,
+{
+    if let Some(height) = overrides.block_height {
+        block.height = height;
+    }
+
+    if let Some(timestamp) = overrides.block_timestamp {
+        block.timestamp = timestamp;
+    }
+
+    for (contract, kvs) in overrides.contract_storage {
+        let mut substore =
+            StorageProvider::new(Box::new(storage.clone()), &[CONTRACT_NAMESPACE, &contract]);
+
+        for (key, value) in kvs {
+            match value {
+                Some(value) => substore.write(&key, &value),
+                None => substore.remove(&key),
+            }
+        }
+    }
+
+    if !overrides.balances.is_empty() {
+        let overrider = balance_overrider.ok_or(AppError::BalanceOverridesNotEnabled)?;
+        let mut substore =
+            StorageProvider::new(Box::new(storage.clone()), &[CONTRACT_NAMESPACE, &bank]);
+
+        for (address, coins) in &overrides.balances {
+            overrider(&mut substore, *address, coins)?;
+        }
+    }
+
+    let mut new_codes = BTreeSet::new();
+
+    if !overrides.contract_code.is_empty() {
+        let max = max_code_size.ok_or(AppError::CodeOverridesNotEnabled)?;
+
+        if let Some(code) = overrides
+            .contract_code
+            .values()
+            .find(|code| code.len() > max)
+        {
+            return Err(AppError::OverrideCodeTooLarge {
+                max,
+                actual: code.len(),
+            });
+        }
+    }
+
+    for (contract, code) in overrides.contract_code {
+        let code_hash = code.hash256();
+        let mut contract_info = CONTRACTS.load(&storage, contract)?;
+
+        if CODES.may_load(&storage, code_hash)?.is_none() {
+            new_codes.insert(code_hash);
+
+            CODES.save(&mut storage, code_hash, &Code {
+                code,
+                status: CodeStatus::InUse { usage: 1 },
+            })?;
+        }
+
+        contract_info.code_hash = code_hash;
+
+        CONTRACTS.save(&mut storage, contract, &contract_info)?;
+    }
+
+    Ok(new_codes)
 }
 
-fn process_tx<S, VM>(vm: VM, storage: S, block: BlockInfo, tx: Tx, mode: AuthMode) -> TxOutcome
+pub(crate) fn process_tx<S, VM>(
+    vm: VM,
+    storage: S,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: Tx,
+    mode: AuthMode,
+) -> TxOutcome
 where
     S: Storage + Clone + 'static,
     VM: Vm + Clone + 'static,
     AppError: From<VM::Error>,
 {
-    // Create the gas tracker, with the limit being the gas limit requested by
-    // the transaction.
-    let gas_tracker = GasTracker::new_limited(tx.gas_limit);
-
-    // Create two layers of buffers.
-    //
-    // The 1st layer is for fee handling; the 2nd is for tx authentication and
-    // processing of the messages.
-    let fee_buffer = Shared::new(Buffer::new(storage.clone(), None));
-    let msg_buffer = Shared::new(Buffer::new(fee_buffer.clone(), None));
+    process_tx_with(
+        vm.clone(),
+        storage,
+        gas_tracker.clone(),
+        block,
+        tx.clone(),
+        mode,
+        |fee_buffer, events| {
+            Some(process_authenticate_then_msgs(
+                vm,
+                fee_buffer,
+                gas_tracker,
+                block,
+                &tx,
+                mode,
+                events,
+            ))
+        },
+    )
+    .unwrap_or_else(|| unreachable!("authentication and messages are always processed"))
+}
 
-    // Record the events emitted during the processing of this transaction.
+/// Process a transaction, using the given closure for authenticating it and
+/// processing its messages, in between withholding and finalizing the fee.
+///
+/// The closure is given the buffer holding the fee withholding's state changes,
+/// into which it should flush its own state changes if they're to be kept, and
+/// the events so far, which it should update with its own events. It returns
+/// the result of the transaction, or `None` to abort processing altogether, in
+/// which case no state change is made, and `None` is returned.
+pub(crate) fn process_tx_with<S, VM, F>(
+    vm: VM,
+    storage: S,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: Tx,
+    mode: AuthMode,
+    authenticate_then_msgs: F,
+) -> Option<TxOutcome>
+where
+    S: Storage + Clone + 'static,
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+    F: FnOnce(Shared<Buffer<S>>, &mut TxEvents) -> Option<GenericResult<()>>,
+{
+    // Create a buffer for fee handling. Authentication and processing of the
+    // messages happen in another buffer on top of it.
+    let fee_buffer = Shared::new(Buffer::new(storage, None));
 
     // Call the taxman's `withhold_fee` function.
     //
@@ -645,7 +1410,7 @@ where
         do_withhold_fee(
             vm.clone(),
             Box::new(fee_buffer.clone()),
-            GasTracker::new_limitless(),
+            GasTracker::new_limitless().with_costs(gas_tracker.costs().clone()),
             block,
             &tx,
             mode,
@@ -655,47 +1420,79 @@ where
 
     if let Some(err) = events.withhold.maybe_error() {
         let err = err.to_string();
-        return new_tx_outcome(gas_tracker, events, Err(err));
+        return Some(new_tx_outcome(gas_tracker, events, Err(err)));
     }
 
+    let result = authenticate_then_msgs(fee_buffer.clone(), &mut events)?;
+
+    // Finally, call the taxman's `finalize_fee` function.
+    //
+    // If the transaction didn't use up all the gas it has requested, it can get
+    // a refund here (if taxman is programmed to do so).
+    //
+    // Taxman should be designed such that this call always succeeds. This
+    // failing can be considered an "undefined behavior". In such a case, we
+    // discard all previous state changes and events, as if the tx never happened.
+    // Also, print a tracing message at the ERROR level to the CLI, to raise
+    // developer's awareness.
+    Some(process_finalize_fee(
+        vm,
+        fee_buffer,
+        gas_tracker,
+        block,
+        tx,
+        mode,
+        events,
+        result,
+    ))
+}
+
+/// Authenticate the transaction, then process its messages and backrun, on top
+/// of the given storage.
+///
+/// State changes are flushed into the given storage, except those of the steps
+/// that failed. Events are recorded in `events`.
+pub(crate) fn process_authenticate_then_msgs<S, VM>(
+    vm: VM,
+    storage: S,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: &Tx,
+    mode: AuthMode,
+    events: &mut TxEvents,
+) -> GenericResult<()>
+where
+    S: Storage + Clone + 'static,
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let msg_buffer = Shared::new(Buffer::new(storage, None));
+
     // Call the sender account's `authenticate` function.
     //
     // The sender account is supposed to perform authentication here, such as
     // verifying a cryptographic signature, to ensure the tx comes from the
     // sender account's rightful owner.
     //
-    // Note that we use `msg_buffer` for this.
-    //
-    // If succeeds, commit state changes in `msg_buffer` into `fee_buffer`, and record
-    // the events emitted.
+    // If succeeds, commit state changes in `msg_buffer` into the underlying
+    // storage, and record the events emitted.
     //
-    // If fails, discard state changes in `msg_buffer` (but keeping those in
-    // `fee_buffer`), discard the events, and jump to `finalize_fee`.
+    // If fails, discard state changes in `msg_buffer`, discard the events, and
+    // return the error.
 
     events.authenticate = do_authenticate(
         vm.clone(),
         Box::new(msg_buffer.clone()),
         gas_tracker.clone(),
         block,
-        &tx,
+        tx,
         mode,
     )
     .as_committment();
 
     let request_backrun = match events.authenticate.as_result() {
         Err((_, err)) => {
-            drop(msg_buffer);
-            let err = err.to_string();
-            return process_finalize_fee(
-                vm,
-                fee_buffer,
-                gas_tracker,
-                block,
-                tx,
-                mode,
-                events,
-                Err(err),
-            );
+            return Err(err.to_string());
         },
         Ok(event) => {
             msg_buffer.write_access().commit();
@@ -706,54 +1503,29 @@ where
     // Loop through the messages and execute one by one. Then, call the sender
     // account's `backrun` method.
     //
-    // If everything succeeds, commit state changes in `msg_buffer` into `fee_buffer`,
-    // and record the events emitted.
+    // If everything succeeds, commit state changes in `msg_buffer` into the
+    // underlying storage, and record the events emitted.
     //
-    // If anything fails, discard state changes in `msg_buffer` (but keeping those
-    // in `fee_buffer`), discard the events, and jump to `finalize_fee`.
+    // If anything fails, discard state changes in `msg_buffer`, discard the
+    // events, and return the error.
     events.msgs_and_backrun = process_msgs_then_backrun(
-        vm.clone(),
+        vm,
         msg_buffer.clone(),
-        gas_tracker.clone(),
+        gas_tracker,
         block,
-        &tx,
+        tx,
         mode,
         request_backrun,
     )
     .as_committment();
 
     match events.msgs_and_backrun.maybe_error() {
-        Some(err) => {
-            drop(msg_buffer);
-            let err = err.to_string();
-            return process_finalize_fee(
-                vm,
-                fee_buffer,
-                gas_tracker,
-                block,
-                tx,
-                mode,
-                events,
-                Err(err),
-            );
-        },
+        Some(err) => Err(err.to_string()),
         None => {
             msg_buffer.disassemble().consume();
+            Ok(())
         },
     }
-
-    // Everything so far succeeded. Finally, call the taxman's `finalize_fee`
-    // function.
-    //
-    // If the transaction didn't use up all the gas it has requested, it can get
-    // a refund here (if taxman is programmed to do so).
-    //
-    // Taxman should be designed such that this call always succeeds. This
-    // failing can be considered an "undefined behavior". In such a case, we
-    // discard all previous state changes and events, as if the tx never happened.
-    // Also, print a tracing message at the ERROR level to the CLI, to raise
-    // developer's awareness.
-    process_finalize_fee(vm, fee_buffer, gas_tracker, block, tx, mode, events, Ok(()))
 }
 
 #[inline]
@@ -828,7 +1600,7 @@ where
     let evt_finalize = do_finalize_fee(
         vm,
         Box::new(buffer.clone()),
-        GasTracker::new_limitless(),
+        GasTracker::new_limitless().with_costs(gas_tracker.costs().clone()),
         block,
         &tx,
         &outcome_so_far,
@@ -901,6 +1673,20 @@ where
             let res = do_migrate(vm, storage, gas_tracker, block, msg_depth, sender, msg);
             res.map(Event::Migrate)
         },
+        Message::IbcPacketReceive(msg) => {
+            let res =
+                do_ibc_packet_receive(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+            res.map(Event::IbcPacketReceive)
+        },
+        Message::IbcPacketAck(msg) => {
+            let res = do_ibc_packet_ack(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+            res.map(Event::IbcPacketAck)
+        },
+        Message::IbcPacketTimeout(msg) => {
+            let res =
+                do_ibc_packet_timeout(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+            res.map(Event::IbcPacketTimeout)
+        },
     }
 }
 
@@ -969,6 +1755,10 @@ where
             let res = query_wasm_smart(vm, storage, gas_tracker, block, query_depth, req)?;
             Ok(QueryResponse::WasmSmart(res))
         },
+        Query::IbcClient(req) => {
+            let res = query_ibc_client(vm, storage, gas_tracker, block, query_depth, req)?;
+            Ok(QueryResponse::IbcClient(res))
+        },
         Query::Multi(reqs) => {
             let res = reqs
                 .into_iter()
@@ -1021,6 +1811,50 @@ pub(crate) fn schedule_cronjob(
     NEXT_CRONJOBS.insert(storage, (next_time, contract))
 }
 
+/// Return whether the votes carried in a proposal are exactly those of the last
+/// commit, each with the right voting power.
+fn votes_match_commit(votes: &[ExtendedVote], last_commit: &BTreeMap<[u8; 20], u64>) -> bool {
+    let mut seen = BTreeSet::new();
+
+    votes.len() == last_commit.len()
+        && votes.iter().all(|vote| {
+            seen.insert(vote.validator) && last_commit.get(&vote.validator) == Some(&vote.power)
+        })
+}
+
+/// Return whether the transactions in a proposal are within the block limits.
+///
+/// Transactions that can't be deserialized don't count towards the gas, as
+/// they're rejected without being executed when the block is finalized.
+fn within_block_limits(txs: &[Bytes], limits: BlockLimits) -> bool {
+    if let Some(max_tx_bytes) = limits.max_tx_bytes {
+        let tx_bytes = txs.iter().map(|tx| tx.len() as u64).sum::<u64>();
+
+        if tx_bytes > max_tx_bytes {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(tx_bytes, max_tx_bytes, "Proposal exceeds max tx bytes");
+
+            return false;
+        }
+    }
+
+    if let Some(max_gas) = limits.max_gas {
+        let gas = txs
+            .iter()
+            .filter_map(|tx| tx.deserialize_json::<Tx>().ok())
+            .fold(0_u64, |gas, tx| gas.saturating_add(tx.gas_limit));
+
+        if gas > max_gas {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(gas, max_gas, "Proposal exceeds max gas");
+
+            return false;
+        }
+    }
+
+    true
+}
+
 fn new_outcome(gas_tracker: GasTracker, result: AppResult<()>) -> CheckTxOutcome {
     CheckTxOutcome {
         gas_limit: gas_tracker.limit(),
@@ -1049,3 +1883,104 @@ pub fn into_utc_string(timestamp: Timestamp) -> String {
     chrono::DateTime::from_timestamp_nanos(timestamp.into_nanos() as i64)
         .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        grug_types::{Coins, NonEmpty},
+    };
+
+    fn mock_tx(gas_limit: u64) -> Bytes {
+        Tx {
+            sender: Addr::mock(1),
+            gas_limit,
+            msgs: NonEmpty::new_unchecked(vec![
+                Message::transfer(Addr::mock(2), Coins::new()).unwrap()
+            ]),
+            data: Json::null(),
+            credential: Json::null(),
+        }
+        .to_json_vec()
+        .unwrap()
+        .into()
+    }
+
+    #[test]
+    fn block_limits_are_respected() {
+        let txs = vec![mock_tx(100), mock_tx(200), Bytes::from_static(b"not a tx")];
+        let tx_bytes = txs.iter().map(|tx| tx.len() as u64).sum::<u64>();
+
+        for (limits, expect) in [
+            (BlockLimits::default(), true),
+            (
+                BlockLimits {
+                    max_tx_bytes: Some(tx_bytes),
+                    max_gas: Some(300),
+                },
+                true,
+            ),
+            (
+                BlockLimits {
+                    max_tx_bytes: Some(tx_bytes - 1),
+                    max_gas: None,
+                },
+                false,
+            ),
+            // The undeserializable tx doesn't count towards the gas.
+            (
+                BlockLimits {
+                    max_tx_bytes: None,
+                    max_gas: Some(299),
+                },
+                false,
+            ),
+        ] {
+            assert_eq!(within_block_limits(&txs, limits), expect, "{limits:?}");
+        }
+    }
+
+    #[test]
+    fn votes_must_match_commit() {
+        let vote = |validator: u8, power: u64| ExtendedVote {
+            validator: [validator; 20],
+            power,
+            extension: vec![validator],
+            extension_signature: vec![],
+        };
+
+        let last_commit = BTreeMap::from([([1; 20], 10), ([2; 20], 20)]);
+
+        for (votes, expect) in [
+            (vec![vote(1, 10), vote(2, 20)], true),
+            // A vote is missing.
+            (vec![vote(1, 10)], false),
+            // A vote has the wrong power.
+            (vec![vote(1, 10), vote(2, 30)], false),
+            // A vote is from a validator that didn't vote.
+            (vec![vote(1, 10), vote(3, 20)], false),
+            // A vote is duplicated.
+            (vec![vote(1, 10), vote(1, 10)], false),
+        ] {
+            assert_eq!(
+                votes_match_commit(&votes, &last_commit),
+                expect,
+                "{votes:?}"
+            );
+        }
+
+        // The pseudo-transaction roundtrips, and isn't mistaken for a tx.
+        let extended_commit = ExtendedCommit {
+            votes: vec![vote(1, 10)],
+        };
+        let raw_tx = extended_commit.encode().unwrap();
+
+        assert_eq!(
+            ExtendedCommit::decode(&raw_tx).unwrap().unwrap(),
+            extended_commit
+        );
+        assert!(ExtendedCommit::decode(&mock_tx(100)).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use {
     grug_types::{Addr, Hash256, StdError},
+    std::io,
     thiserror::Error,
 };
 
@@ -17,6 +18,15 @@ pub enum AppError {
     #[error("proposal preparer error: {0}")]
     PrepareProposal(String),
 
+    #[error("proposal rejected: {0}")]
+    ProcessProposal(String),
+
+    #[error("failed to extend vote: {0}")]
+    ExtendVote(String),
+
+    #[error("vote extension rejected: {0}")]
+    VerifyVoteExtension(String),
+
     #[error("indexer error: {0}")]
     Indexer(String),
 
@@ -30,15 +40,36 @@ pub enum AppError {
     #[error("merkle proof is not supported for `/app` query; use `/store` instead")]
     ProofNotSupported,
 
+    #[error("too many keys in `/store_many` query! max: {max}, actual: {actual}")]
+    TooManyStoreKeys { max: usize, actual: usize },
+
     #[error("simulating a transaction at past block height is not supported")]
     PastHeightNotSupported,
 
+    #[error("overriding contract codes in simulations is not enabled")]
+    CodeOverridesNotEnabled,
+
+    #[error("overriding balances in simulations is not enabled")]
+    BalanceOverridesNotEnabled,
+
+    #[error("override code is too large! max: {max}, actual: {actual}")]
+    OverrideCodeTooLarge { max: usize, actual: usize },
+
     #[error("sender does not have permission to perform this action")]
     Unauthorized,
 
     #[error("incorrect block height! expecting: {expect}, actual: {actual}")]
     IncorrectBlockHeight { expect: u64, actual: u64 },
 
+    #[error("gas cost `{name}` is below its minimum")]
+    GasCostBelowMinimum { name: &'static str },
+
+    #[error("upgrade height must be in the future! upgrade height: {height}, current height: {current_height}")]
+    UpgradeHeightNotInFuture { height: u64, current_height: u64 },
+
+    #[error("upgrade `{name}` is needed at height {height}, but this software version doesn't support it")]
+    UpgradeNeeded { name: String, height: u64 },
+
     #[error("sender is not the owner! sender: {sender}, owner: {owner}")]
     NotOwner { sender: Addr, owner: Addr },
 
@@ -48,6 +79,12 @@ pub enum AppError {
     #[error("sender is not the admin! sender: {sender}, admin: {admin}")]
     NotAdmin { sender: Addr, admin: Addr },
 
+    #[error("IBC handler is not set")]
+    IbcHandlerNotSet,
+
+    #[error("sender is not the IBC handler! sender: {sender}, IBC handler: {ibc_handler}")]
+    NotIbcHandler { sender: Addr, ibc_handler: Addr },
+
     #[error("code with hash `{code_hash}` already exists")]
     CodeExists { code_hash: Hash256 },
 
@@ -56,6 +93,34 @@ pub enum AppError {
 
     #[error("max message depth exceeded")]
     ExceedMaxMessageDepth,
+
+    #[error("snapshot I/O error: {0}")]
+    SnapshotIo(String),
+
+    #[error("state sync snapshots are not enabled")]
+    SnapshotsNotEnabled,
+
+    #[error("unsupported snapshot format: {format}")]
+    UnsupportedSnapshotFormat { format: u32 },
+
+    #[error("no snapshot is being restored")]
+    NoSnapshotBeingRestored,
+
+    #[error("snapshot chunk not found! index: {index}, number of chunks: {chunks}")]
+    SnapshotChunkNotFound { index: u32, chunks: usize },
+
+    #[error("snapshot chunk hash mismatch! index: {index}, expecting: {expect}, actual: {actual}")]
+    SnapshotChunkHashMismatch {
+        index: u32,
+        expect: Hash256,
+        actual: Hash256,
+    },
+}
+
+impl From<io::Error> for AppError {
+    fn from(err: io::Error) -> Self {
+        AppError::SnapshotIo(err.to_string())
+    }
 }
 
 pub type AppResult<T> = core::result::Result<T, AppError>;
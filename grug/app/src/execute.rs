@@ -5,6 +5,7 @@ mod cron;
 #[allow(clippy::module_inception)]
 mod execute;
 mod finalize;
+mod ibc;
 mod instantiate;
 mod migrate;
 mod reply;
@@ -13,6 +14,6 @@ mod upload;
 mod withhold;
 
 pub use {
-    authenticate::*, backrun::*, configure::*, cron::*, execute::*, finalize::*, instantiate::*,
-    migrate::*, reply::*, transfer::*, upload::*, withhold::*,
+    authenticate::*, backrun::*, configure::*, cron::*, execute::*, finalize::*, ibc::*,
+    instantiate::*, migrate::*, reply::*, transfer::*, upload::*, withhold::*,
 };
@@ -1,8 +1,9 @@
 use {
     crate::{
         schedule_cronjob, AppError, AppResult, EventResult, APP_CONFIG, CONFIG, NEXT_CRONJOBS,
+        UPGRADE_PLAN,
     },
-    grug_types::{Addr, BlockInfo, EvtConfigure, MsgConfigure, Storage},
+    grug_types::{Addr, BlockInfo, EvtConfigure, GasCosts, MsgConfigure, Storage, UpgradePlan},
 };
 
 pub fn do_configure(
@@ -11,12 +12,23 @@ pub fn do_configure(
     sender: Addr,
     msg: MsgConfigure,
 ) -> EventResult<EvtConfigure> {
-    let evt = EvtConfigure { sender };
+    let mut evt = EvtConfigure {
+        sender,
+        new_gas_costs: None,
+        upgrade: None,
+    };
 
     match _do_configure(storage, block, sender, msg) {
-        Ok(_) => {
+        Ok((new_gas_costs, upgrade)) => {
             #[cfg(feature = "tracing")]
-            tracing::info!("Config updated");
+            tracing::info!(
+                gas_costs_updated = new_gas_costs.is_some(),
+                upgrade_scheduled = upgrade.is_some(),
+                "Config updated"
+            );
+
+            evt.new_gas_costs = new_gas_costs;
+            evt.upgrade = upgrade;
 
             EventResult::Ok(evt)
         },
@@ -34,7 +46,7 @@ fn _do_configure(
     block: BlockInfo,
     sender: Addr,
     msg: MsgConfigure,
-) -> AppResult<()> {
+) -> AppResult<(Option<GasCosts>, Option<UpgradePlan>)> {
     let cfg = CONFIG.load(storage)?;
 
     // Make sure the sender is authorized to set the config.
@@ -45,7 +57,13 @@ fn _do_configure(
         });
     }
 
+    let mut new_gas_costs = None;
+
     if let Some(new_cfg) = msg.new_cfg {
+        if let Some(name) = new_cfg.gas_costs.find_below_minimum() {
+            return Err(AppError::GasCostBelowMinimum { name });
+        }
+
         // If the list of cronjobs has been changed, we have to delete the
         // existing scheduled ones and reschedule.
         if new_cfg.cronjobs != cfg.cronjobs {
@@ -56,6 +74,13 @@ fn _do_configure(
             }
         }
 
+        // If the gas costs have been changed, report them in the event. They
+        // are loaded at the start of each block, so take effect from the next
+        // one.
+        if new_cfg.gas_costs != cfg.gas_costs {
+            new_gas_costs = Some(new_cfg.gas_costs.clone());
+        }
+
         CONFIG.save(storage, &new_cfg)?;
     }
 
@@ -63,5 +88,18 @@ fn _do_configure(
         APP_CONFIG.save(storage, &new_app_cfg)?;
     }
 
-    Ok(())
+    // The upgrade takes place at the beginning of the block at the scheduled
+    // height, so it can't be the current height, which has already begun.
+    if let Some(upgrade) = &msg.upgrade {
+        if upgrade.height <= block.height {
+            return Err(AppError::UpgradeHeightNotInFuture {
+                height: upgrade.height,
+                current_height: block.height,
+            });
+        }
+
+        UPGRADE_PLAN.save(storage, upgrade)?;
+    }
+
+    Ok((new_gas_costs, msg.upgrade))
 }
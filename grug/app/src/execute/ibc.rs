@@ -0,0 +1,276 @@
+use {
+    crate::{
+        call_in_1_out_1_handle_response, catch_and_update_event, catch_event, AppError, AppResult,
+        EventResult, GasTracker, Vm, CHAIN_ID, CONFIG, CONTRACTS,
+    },
+    grug_types::{
+        Addr, BlockInfo, Coins, Context, EvtIbcPacketAck, EvtIbcPacketReceive, EvtIbcPacketTimeout,
+        Hash256, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, MsgIbcPacketAck,
+        MsgIbcPacketReceive, MsgIbcPacketTimeout, Storage,
+    },
+};
+
+pub fn do_ibc_packet_receive<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketReceive,
+) -> EventResult<EvtIbcPacketReceive>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let evt = _do_ibc_packet_receive(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+
+    #[cfg(feature = "tracing")]
+    evt.debug(
+        |evt| {
+            tracing::info!(
+                contract = evt.contract.to_string(),
+                sequence = evt.packet.sequence,
+                "Received IBC packet"
+            );
+        },
+        "Failed to receive IBC packet",
+    );
+
+    evt
+}
+
+fn _do_ibc_packet_receive<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketReceive,
+) -> EventResult<EvtIbcPacketReceive>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let mut evt = EvtIbcPacketReceive::base(sender, msg.contract, msg.packet.clone(), msg.relayer);
+
+    let (code_hash, ctx) = catch_event! {
+        {
+            prepare_ibc_call(&storage, block, sender, msg.contract)
+        },
+        evt
+    };
+
+    catch_and_update_event! {
+        call_in_1_out_1_handle_response(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            0,
+            true,
+            "ibc_packet_receive",
+            code_hash,
+            &ctx,
+            &IbcPacketReceiveMsg {
+                packet: msg.packet,
+                relayer: msg.relayer,
+            },
+        ),
+        evt => guest_event
+    }
+
+    EventResult::Ok(evt)
+}
+
+pub fn do_ibc_packet_ack<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketAck,
+) -> EventResult<EvtIbcPacketAck>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let evt = _do_ibc_packet_ack(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+
+    #[cfg(feature = "tracing")]
+    evt.debug(
+        |evt| {
+            tracing::info!(
+                contract = evt.contract.to_string(),
+                sequence = evt.packet.sequence,
+                "Acknowledged IBC packet"
+            );
+        },
+        "Failed to acknowledge IBC packet",
+    );
+
+    evt
+}
+
+fn _do_ibc_packet_ack<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketAck,
+) -> EventResult<EvtIbcPacketAck>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let mut evt = EvtIbcPacketAck::base(
+        sender,
+        msg.contract,
+        msg.packet.clone(),
+        msg.acknowledgement.clone(),
+        msg.relayer,
+    );
+
+    let (code_hash, ctx) = catch_event! {
+        {
+            prepare_ibc_call(&storage, block, sender, msg.contract)
+        },
+        evt
+    };
+
+    catch_and_update_event! {
+        call_in_1_out_1_handle_response(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            0,
+            true,
+            "ibc_packet_ack",
+            code_hash,
+            &ctx,
+            &IbcPacketAckMsg {
+                packet: msg.packet,
+                acknowledgement: msg.acknowledgement,
+                relayer: msg.relayer,
+            },
+        ),
+        evt => guest_event
+    }
+
+    EventResult::Ok(evt)
+}
+
+pub fn do_ibc_packet_timeout<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketTimeout,
+) -> EventResult<EvtIbcPacketTimeout>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let evt = _do_ibc_packet_timeout(vm, storage, gas_tracker, block, msg_depth, sender, msg);
+
+    #[cfg(feature = "tracing")]
+    evt.debug(
+        |evt| {
+            tracing::info!(
+                contract = evt.contract.to_string(),
+                sequence = evt.packet.sequence,
+                "Timed out IBC packet"
+            );
+        },
+        "Failed to time out IBC packet",
+    );
+
+    evt
+}
+
+fn _do_ibc_packet_timeout<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    msg_depth: usize,
+    sender: Addr,
+    msg: MsgIbcPacketTimeout,
+) -> EventResult<EvtIbcPacketTimeout>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let mut evt = EvtIbcPacketTimeout::base(sender, msg.contract, msg.packet.clone(), msg.relayer);
+
+    let (code_hash, ctx) = catch_event! {
+        {
+            prepare_ibc_call(&storage, block, sender, msg.contract)
+        },
+        evt
+    };
+
+    catch_and_update_event! {
+        call_in_1_out_1_handle_response(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            0,
+            true,
+            "ibc_packet_timeout",
+            code_hash,
+            &ctx,
+            &IbcPacketTimeoutMsg {
+                packet: msg.packet,
+                relayer: msg.relayer,
+            },
+        ),
+        evt => guest_event
+    }
+
+    EventResult::Ok(evt)
+}
+
+/// Ensure the sender is the chain's IBC handler, and prepare the code hash and
+/// context for calling the IBC application contract.
+fn prepare_ibc_call(
+    storage: &dyn Storage,
+    block: BlockInfo,
+    sender: Addr,
+    contract: Addr,
+) -> AppResult<(Hash256, Context)> {
+    let cfg = CONFIG.load(storage)?;
+
+    let Some(ibc_handler) = cfg.ibc_handler else {
+        return Err(AppError::IbcHandlerNotSet);
+    };
+
+    if sender != ibc_handler {
+        return Err(AppError::NotIbcHandler {
+            sender,
+            ibc_handler,
+        });
+    }
+
+    let code_hash = CONTRACTS.load(storage, contract)?.code_hash;
+    let chain_id = CHAIN_ID.load(storage)?;
+
+    let ctx = Context {
+        chain_id,
+        block,
+        contract,
+        sender: Some(sender),
+        funds: Some(Coins::new()),
+        mode: None,
+    };
+
+    Ok((code_hash, ctx))
+}
@@ -58,12 +58,14 @@ fn _do_upload(
         return Err(AppError::CodeExists { code_hash });
     }
 
-    CODES.save_with_gas(storage, gas_tracker, code_hash, &Code {
+    let code = Code {
         code: msg.code,
         status: CodeStatus::Orphaned {
             since: block.timestamp,
         },
-    })?;
+    };
+
+    CODES.save_with_gas(storage, gas_tracker, code_hash, &code)?;
 
     Ok(())
 }
@@ -1,5 +1,5 @@
-mod costs;
+mod profiler;
 mod storage;
 mod tracker;
 
-pub use {costs::*, storage::*, tracker::*};
+pub use {profiler::*, storage::*, tracker::*};
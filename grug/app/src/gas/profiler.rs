@@ -0,0 +1,80 @@
+use grug_types::{Addr, GasBreakdown, GasCategory, GasProfile};
+
+/// Records how much gas is consumed by each call during the processing of a
+/// transaction, producing a [`GasProfile`](grug_types::GasProfile).
+///
+/// Calls being made are kept in a stack. Gas consumed is attributed to the
+/// call at the top of the stack. Once a call returns, it's popped from the
+/// stack and becomes a child of the call that made it.
+pub struct GasProfiler {
+    stack: Vec<GasProfile>,
+}
+
+impl GasProfiler {
+    pub fn new() -> Self {
+        Self {
+            stack: vec![GasProfile::root()],
+        }
+    }
+
+    /// Start recording a call.
+    ///
+    /// `msg_depth` is `None` if the call is made by a query, in which case it's
+    /// the same as that of the call making the query.
+    pub fn enter(
+        &mut self,
+        contract: Addr,
+        name: &'static str,
+        msg_depth: Option<usize>,
+        query_depth: usize,
+    ) {
+        let msg_depth = msg_depth.unwrap_or_else(|| self.current().msg_depth);
+
+        self.stack.push(GasProfile {
+            contract: Some(contract),
+            name: name.to_string(),
+            msg_depth,
+            query_depth,
+            gas: GasBreakdown::default(),
+            children: Vec::new(),
+        });
+    }
+
+    /// Finish recording the current call.
+    pub fn exit(&mut self) {
+        // The root is never popped.
+        if self.stack.len() > 1 {
+            let call = self.stack.pop().unwrap();
+            self.current().children.push(call);
+        }
+    }
+
+    /// Attribute consumed gas to the current call.
+    pub fn record(&mut self, consumed: u64, category: GasCategory) {
+        self.current().gas.add(category, consumed);
+    }
+
+    /// Return the profile recorded so far. Calls that haven't returned yet are
+    /// included as if they have.
+    pub fn profile(&self) -> GasProfile {
+        let mut stack = self.stack.clone();
+
+        while stack.len() > 1 {
+            let call = stack.pop().unwrap();
+            stack.last_mut().unwrap().children.push(call);
+        }
+
+        stack.pop().unwrap()
+    }
+
+    fn current(&mut self) -> &mut GasProfile {
+        // The stack always contains at least the root.
+        self.stack.last_mut().unwrap()
+    }
+}
+
+impl Default for GasProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,7 +1,8 @@
 use {
-    crate::{GasTracker, GAS_COSTS},
-    grug_storage::{Codec, IndexedMap, Item, Map, PrimaryKey},
-    grug_types::{Bound, Order, Record, StdResult, Storage},
+    crate::GasTracker,
+    grug_storage::{Codec, Deque, IndexedMap, Item, Map, PrimaryKey, SnapshotItem, SnapshotMap},
+    grug_types::{Bound, GasCategory, Order, Record, StdResult, Storage},
+    std::fmt::Debug,
 };
 
 // ---------------------------------- storage ----------------------------------
@@ -27,10 +28,18 @@ where
 
         match &maybe_data {
             Some(data) => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(data.len()), "db_read/found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data.len()),
+                    GasCategory::StorageRead,
+                    "db_read/found",
+                )?;
             },
             None => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(0),
+                    GasCategory::StorageRead,
+                    "db_read/not_found",
+                )?;
             },
         }
 
@@ -45,7 +54,11 @@ where
         order: Order,
     ) -> StdResult<Box<dyn Iterator<Item = StdResult<Record>> + 'a>> {
         // Gas cost for creating an iterator.
-        gas_tracker.consume(GAS_COSTS.db_scan, "db_scan")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_scan,
+            GasCategory::StorageRead,
+            "db_scan",
+        )?;
 
         Ok(Box::new(self.scan(min, max, order).metered(gas_tracker)))
     }
@@ -64,7 +77,11 @@ where
     fn load_with_gas(&self, storage: &dyn Storage, gas_tracker: GasTracker) -> StdResult<T> {
         let data_raw = self.load_raw(storage)?;
 
-        gas_tracker.consume(GAS_COSTS.db_read.cost(data_raw.len()), "db_read/found")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_read.cost(data_raw.len()),
+            GasCategory::StorageRead,
+            "db_read/found",
+        )?;
 
         C::decode(&data_raw)
     }
@@ -119,7 +136,11 @@ where
     ) -> StdResult<T> {
         let data_raw = self.path(key).load_raw(storage)?;
 
-        gas_tracker.consume(GAS_COSTS.db_read.cost(data_raw.len()), "db_read/found")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_read.cost(data_raw.len()),
+            GasCategory::StorageRead,
+            "db_read/found",
+        )?;
 
         C::decode(&data_raw)
     }
@@ -132,11 +153,19 @@ where
     ) -> StdResult<bool> {
         match self.path(key).may_load_raw(storage) {
             Some(data) => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(data.len()), "db_read/found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(data.len()),
+                    GasCategory::StorageRead,
+                    "db_read/found",
+                )?;
                 Ok(true)
             },
             None => {
-                gas_tracker.consume(GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+                gas_tracker.consume(
+                    gas_tracker.costs().db_read.cost(0),
+                    GasCategory::StorageRead,
+                    "db_read/not_found",
+                )?;
                 Ok(false)
             },
         }
@@ -154,7 +183,11 @@ where
         T: 'b,
     {
         // Gas cost for creating an iterator.
-        gas_tracker.consume(GAS_COSTS.db_scan, "db_scan")?;
+        gas_tracker.consume(
+            gas_tracker.costs().db_scan,
+            GasCategory::StorageRead,
+            "db_scan",
+        )?;
 
         let iter = self
             .range_raw(storage, min, max, order)
@@ -179,13 +212,14 @@ where
         let data_raw = C::encode(value)?;
         let path = self.path(key);
 
-        let gas_cost = GAS_COSTS
+        let gas_cost = gas_tracker
+            .costs()
             .db_write
             .cost(data_raw.len() + path.storage_key().len());
 
         // Charge gas before writing the data, such that if run out of gas,
         // the data isn't written.
-        gas_tracker.consume(gas_cost, "db_write")?;
+        gas_tracker.consume(gas_cost, GasCategory::StorageWrite, "db_write")?;
 
         path.save_raw(storage, &data_raw);
 
@@ -246,6 +280,293 @@ where
     }
 }
 
+// -------------------------------- snapshot map -------------------------------
+
+pub trait MeteredSnapshotMap<K, T, H> {
+    fn may_load_at_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        key: K,
+        height: H,
+    ) -> StdResult<Option<T>>;
+}
+
+impl<K, T, H, C> MeteredSnapshotMap<K, T, H> for SnapshotMap<'_, K, T, H, C>
+where
+    K: PrimaryKey + Copy,
+    H: PrimaryKey<Output = H> + Copy + Ord + Debug,
+    C: Codec<T>,
+{
+    fn may_load_at_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        key: K,
+        height: H,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.may_load_at_raw(storage, key, height)?;
+
+        consume_snapshot_read(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        maybe_data_raw
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+}
+
+// ------------------------------- snapshot item -------------------------------
+
+pub trait MeteredSnapshotItem<T, H> {
+    fn may_load_at_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        height: H,
+    ) -> StdResult<Option<T>>;
+}
+
+impl<T, H, C> MeteredSnapshotItem<T, H> for SnapshotItem<'_, T, H, C>
+where
+    H: PrimaryKey<Output = H> + Copy + Ord + Debug,
+    C: Codec<T>,
+{
+    fn may_load_at_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        height: H,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.may_load_at_raw(storage, height)?;
+
+        consume_snapshot_read(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        maybe_data_raw
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+}
+
+/// Charge gas for loading a value at a height, which involves looking up the
+/// changelog with an iterator, then reading the value.
+fn consume_snapshot_read(gas_tracker: &GasTracker, maybe_data_raw: Option<&[u8]>) -> StdResult<()> {
+    let costs = gas_tracker.costs();
+
+    match maybe_data_raw {
+        Some(data_raw) => gas_tracker.consume(
+            costs.db_scan + costs.db_next + costs.db_read.cost(data_raw.len()),
+            GasCategory::StorageRead,
+            "db_read/found",
+        ),
+        None => gas_tracker.consume(
+            costs.db_scan + costs.db_next + costs.db_read.cost(0),
+            GasCategory::StorageRead,
+            "db_read/not_found",
+        ),
+    }
+}
+
+// ----------------------------------- deque -----------------------------------
+
+/// Size of a deque's head and tail indexes, in bytes.
+const DEQUE_INDEX_LEN: usize = 4;
+
+pub trait MeteredDeque<T> {
+    fn get_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        pos: u32,
+    ) -> StdResult<Option<T>>;
+
+    fn front_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>>;
+
+    fn back_with_gas(&self, storage: &dyn Storage, gas_tracker: GasTracker)
+        -> StdResult<Option<T>>;
+
+    fn push_back_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+        value: &T,
+    ) -> StdResult<()>;
+
+    fn push_front_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+        value: &T,
+    ) -> StdResult<()>;
+
+    fn pop_front_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>>;
+
+    fn pop_back_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>>;
+}
+
+impl<T, C> MeteredDeque<T> for Deque<'_, T, C>
+where
+    C: Codec<T>,
+{
+    fn get_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+        pos: u32,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.get_raw(storage, pos)?;
+
+        consume_deque_read(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        maybe_data_raw
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    fn front_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>> {
+        self.get_with_gas(storage, gas_tracker, 0)
+    }
+
+    fn back_with_gas(
+        &self,
+        storage: &dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.back_raw(storage)?;
+
+        consume_deque_read(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        maybe_data_raw
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    fn push_back_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+        value: &T,
+    ) -> StdResult<()> {
+        let data_raw = C::encode(value)?;
+
+        // Charge gas before writing the data, such that if run out of gas,
+        // the data isn't written.
+        consume_deque_push(&gas_tracker, &data_raw)?;
+
+        self.unsafe_push_back_raw(storage, &data_raw)
+    }
+
+    fn push_front_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+        value: &T,
+    ) -> StdResult<()> {
+        let data_raw = C::encode(value)?;
+
+        consume_deque_push(&gas_tracker, &data_raw)?;
+
+        self.unsafe_push_front_raw(storage, &data_raw)
+    }
+
+    fn pop_front_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.front_raw(storage)?;
+
+        // Charge gas before removing the data, such that if run out of gas,
+        // the data isn't removed.
+        consume_deque_pop(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        self.pop_front(storage)
+    }
+
+    fn pop_back_with_gas(
+        &self,
+        storage: &mut dyn Storage,
+        gas_tracker: GasTracker,
+    ) -> StdResult<Option<T>> {
+        let maybe_data_raw = self.back_raw(storage)?;
+
+        consume_deque_pop(&gas_tracker, maybe_data_raw.as_deref())?;
+
+        self.pop_back(storage)
+    }
+}
+
+/// Charge gas for reading a deque's head and tail indexes, then an element.
+fn consume_deque_read(gas_tracker: &GasTracker, maybe_data_raw: Option<&[u8]>) -> StdResult<()> {
+    let costs = gas_tracker.costs();
+    let index_cost = costs.db_read.cost(DEQUE_INDEX_LEN) * 2;
+
+    match maybe_data_raw {
+        Some(data_raw) => gas_tracker.consume(
+            index_cost + costs.db_read.cost(data_raw.len()),
+            GasCategory::StorageRead,
+            "db_read/found",
+        ),
+        None => gas_tracker.consume(
+            index_cost + costs.db_read.cost(0),
+            GasCategory::StorageRead,
+            "db_read/not_found",
+        ),
+    }
+}
+
+/// Charge gas for pushing an element to a deque, which involves reading the
+/// indexes, writing the element, and updating one of the indexes.
+fn consume_deque_push(gas_tracker: &GasTracker, data_raw: &[u8]) -> StdResult<()> {
+    let costs = gas_tracker.costs();
+
+    gas_tracker.consume(
+        costs.db_read.cost(DEQUE_INDEX_LEN) * 2,
+        GasCategory::StorageRead,
+        "db_read/found",
+    )?;
+    gas_tracker.consume(
+        costs.db_write.cost(data_raw.len()) + costs.db_write.cost(DEQUE_INDEX_LEN),
+        GasCategory::StorageWrite,
+        "db_write",
+    )
+}
+
+/// Charge gas for popping an element from a deque, which involves reading the
+/// indexes and the element, removing the element, and updating one of the
+/// indexes. Popping from an empty deque only involves reading the indexes.
+fn consume_deque_pop(gas_tracker: &GasTracker, maybe_data_raw: Option<&[u8]>) -> StdResult<()> {
+    consume_deque_read(gas_tracker, maybe_data_raw)?;
+
+    if maybe_data_raw.is_some() {
+        let costs = gas_tracker.costs();
+
+        gas_tracker.consume(costs.db_remove, GasCategory::StorageWrite, "storage_remove")?;
+        gas_tracker.consume(
+            costs.db_write.cost(DEQUE_INDEX_LEN),
+            GasCategory::StorageWrite,
+            "db_write",
+        )?;
+    }
+
+    Ok(())
+}
+
 // --------------------------------- iterator ----------------------------------
 
 pub trait MeteredIterator: Sized {
@@ -274,18 +595,25 @@ where
         if let Some((k_raw, v_raw)) = self.iter.next() {
             // A record is found. We charge both the cost for advancing the
             // iterator (`db_next`) and for reading the record (`db_read`).
-            let cost = GAS_COSTS.db_next + GAS_COSTS.db_read.cost(k_raw.len() + v_raw.len());
+            let costs = self.gas_tracker.costs();
+            let cost = costs.db_next + costs.db_read.cost(k_raw.len() + v_raw.len());
 
-            match self.gas_tracker.consume(cost, "db_next/found") {
+            match self
+                .gas_tracker
+                .consume(cost, GasCategory::StorageRead, "db_next/found")
+            {
                 Ok(()) => Some(Ok((k_raw, v_raw))),
                 Err(err) => Some(Err(err)),
             }
         } else {
             // No record is found; iterator has reached its end.
             // Charge only the cost for advanding iterator.
-            let cost = GAS_COSTS.db_next;
+            let cost = self.gas_tracker.costs().db_next;
 
-            match self.gas_tracker.consume(cost, "db_next/not_found") {
+            match self
+                .gas_tracker
+                .consume(cost, GasCategory::StorageRead, "db_next/not_found")
+            {
                 Ok(()) => None,
                 Err(err) => Some(Err(err)),
             }
@@ -1,7 +1,10 @@
 use {
-    crate::Shared,
-    grug_types::{StdError, StdResult},
-    std::fmt::{self, Display},
+    crate::{GasProfiler, Shared},
+    grug_types::{Addr, GasCategory, GasCosts, GasProfile, StdError, StdResult},
+    std::{
+        fmt::{self, Display},
+        sync::Arc,
+    },
 };
 
 struct GasTrackerInner {
@@ -15,6 +18,10 @@ struct GasTrackerInner {
 #[derive(Clone)]
 pub struct GasTracker {
     inner: Shared<GasTrackerInner>,
+    costs: Arc<GasCosts>,
+    // `None` means profiling is disabled, which is the case unless requested,
+    // e.g. when simulating a transaction.
+    profiler: Option<Shared<GasProfiler>>,
 }
 
 impl GasTracker {
@@ -25,6 +32,8 @@ impl GasTracker {
                 limit: maybe_limit,
                 used: 0,
             }),
+            costs: Arc::new(GasCosts::DEFAULT),
+            profiler: None,
         }
     }
 
@@ -35,6 +44,8 @@ impl GasTracker {
                 limit: None,
                 used: 0,
             }),
+            costs: Arc::new(GasCosts::DEFAULT),
+            profiler: None,
         }
     }
 
@@ -45,9 +56,69 @@ impl GasTracker {
                 limit: Some(limit),
                 used: 0,
             }),
+            costs: Arc::new(GasCosts::DEFAULT),
+            profiler: None,
         }
     }
 
+    /// Use the given gas costs, instead of the default ones.
+    pub fn with_costs(mut self, costs: GasCosts) -> Self {
+        self.costs = Arc::new(costs);
+        self
+    }
+
+    /// Record a profile of the gas consumed by each call.
+    pub fn with_profiler(mut self) -> Self {
+        self.profiler = Some(Shared::new(GasProfiler::new()));
+        self
+    }
+
+    /// Return the gas profile recorded so far. `None` if profiling is disabled.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn profile(&self) -> Option<GasProfile> {
+        self.profiler
+            .as_ref()
+            .map(|profiler| profiler.read_access().profile())
+    }
+
+    /// Perform the given call to a contract, and, if profiling is enabled,
+    /// attribute the gas consumed during it to the call.
+    ///
+    /// `msg_depth` is `None` if the call is made by a query.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn profile_call<F, T>(
+        &self,
+        contract: Addr,
+        name: &'static str,
+        msg_depth: Option<usize>,
+        query_depth: usize,
+        callback: F,
+    ) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        let Some(profiler) = &self.profiler else {
+            return callback();
+        };
+
+        profiler
+            .write_access()
+            .enter(contract, name, msg_depth, query_depth);
+
+        let output = callback();
+
+        profiler.write_access().exit();
+
+        output
+    }
+
+    /// Return the gas costs used for metering.
+    pub fn costs(&self) -> &GasCosts {
+        &self.costs
+    }
+
     /// Return the gas limit. `None` if there isn't a limit.
     ///
     /// Panics if lock is poisoned.
@@ -72,10 +143,16 @@ impl GasTracker {
         })
     }
 
-    /// Consume the given amount of gas. Error if the limit is exceeded.
+    /// Consume the given amount of gas, spent on the given category of
+    /// operations. Error if the limit is exceeded.
     ///
     /// Panics if lock is poisoned.
-    pub fn consume(&self, consumed: u64, comment: &'static str) -> StdResult<()> {
+    pub fn consume(
+        &self,
+        consumed: u64,
+        category: GasCategory,
+        comment: &'static str,
+    ) -> StdResult<()> {
         self.inner.write_with(|mut inner| {
             let used = inner.used + consumed;
 
@@ -98,6 +175,10 @@ impl GasTracker {
 
             inner.used = used;
 
+            if let Some(profiler) = &self.profiler {
+                profiler.write_access().record(consumed, category);
+            }
+
             Ok(())
         })
     }
@@ -8,16 +8,25 @@ mod execute;
 mod gas;
 mod indexer;
 mod macros;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod proposal_preparer;
 mod providers;
 mod query;
 mod shared;
+mod snapshot;
 mod state;
 mod submessage;
 mod traits;
+mod upgrade;
+mod validators;
 mod vm;
 
 pub use crate::{
     app::*, buffer::*, error::*, event::*, execute::*, gas::*, indexer::*, proposal_preparer::*,
-    providers::*, query::*, shared::*, state::*, submessage::*, traits::*, vm::*,
+    providers::*, query::*, shared::*, snapshot::*, state::*, submessage::*, traits::*, upgrade::*,
+    validators::*, vm::*,
 };
+
+#[cfg(feature = "parallel")]
+pub use crate::parallel::*;
@@ -0,0 +1,411 @@
+use {
+    crate::{
+        process_authenticate_then_msgs, process_tx, process_tx_with, AppError, Buffer, GasTracker,
+        Shared, Vm,
+    },
+    grug_types::{
+        AuthMode, Batch, BlockInfo, CommitmentStatus, EvtAuthenticate, GasCosts, GenericResult,
+        MsgsAndBackrunEvents, Order, Record, Storage, Tx, TxOutcome,
+    },
+    std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+    },
+};
+
+/// Process the transactions in a block, optimistically in parallel, producing
+/// the same outcomes and state changes as if they're processed sequentially.
+///
+/// This happens in two phases:
+///
+/// 1. All transactions are executed in parallel, each against the state prior
+///    to the transactions. The storage reads each transaction's authentication
+///    and messages make are recorded, and their writes are kept in a buffer.
+/// 2. Going through the transactions sequentially, each transaction's fee is
+///    withheld against the state as it is after all preceding transactions.
+///    Then, its recorded reads are validated against this state. If every read
+///    yields the same result, the transaction would have behaved the same in
+///    sequential execution, so its writes are applied directly, and its fee
+///    finalized. Otherwise, it conflicts with a preceding transaction, and is
+///    re-executed against the up-to-date state.
+///
+/// Fee handling is always done in the second phase, because every transaction
+/// typically moves tokens into and out of the taxman's account, so recording
+/// it in the first phase would make every transaction conflict with the one
+/// preceding it. This is cheap compared to authentication, which usually
+/// involves verifying a signature, and processing of the messages.
+///
+/// Blocks consisting of mostly independent transactions, e.g. transfers between
+/// different accounts, or swaps in different pools, benefit the most from this.
+/// In the worst case, where every transaction conflicts with the previous one,
+/// this is as slow as sequential execution plus the wasted parallel phase.
+pub fn process_txs_in_parallel<S, VM>(
+    vm: VM,
+    storage: Shared<Buffer<S>>,
+    gas_costs: &GasCosts,
+    block: BlockInfo,
+    txs: Vec<Tx>,
+) -> Vec<TxOutcome>
+where
+    S: Storage + Clone + 'static,
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let executions = execute_in_parallel(vm.clone(), storage.clone(), gas_costs, block, &txs);
+
+    txs.into_iter()
+        .zip(executions)
+        .enumerate()
+        .map(|(_idx, (tx, execution))| {
+            let outcome = process_tx_with(
+                vm.clone(),
+                storage.clone(),
+                execution.gas_tracker,
+                block,
+                tx.clone(),
+                AuthMode::Finalize,
+                |fee_buffer, events| {
+                    let speculation = execution.speculation?;
+
+                    if !speculation
+                        .reads
+                        .iter()
+                        .all(|read| read.is_valid(&fee_buffer))
+                    {
+                        return None;
+                    }
+
+                    fee_buffer.write_access().flush(speculation.writes);
+
+                    events.authenticate = speculation.authenticate;
+                    events.msgs_and_backrun = speculation.msgs_and_backrun;
+
+                    Some(speculation.result)
+                },
+            );
+
+            if let Some(outcome) = outcome {
+                return outcome;
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(idx = _idx, "Re-executing conflicting transaction");
+
+            process_tx(
+                vm.clone(),
+                storage.clone(),
+                GasTracker::new_limited(tx.gas_limit).with_costs(gas_costs.clone()),
+                block,
+                tx,
+                AuthMode::Finalize,
+            )
+        })
+        .collect()
+}
+
+/// Execute the transactions in parallel, each against the given storage, using
+/// as many threads as there are available CPU cores.
+fn execute_in_parallel<S, VM>(
+    vm: VM,
+    storage: Shared<Buffer<S>>,
+    gas_costs: &GasCosts,
+    block: BlockInfo,
+    txs: &[Tx],
+) -> Vec<Execution>
+where
+    S: Storage + Clone + 'static,
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(txs.len());
+
+    // Each thread takes the next transaction that hasn't been taken, until
+    // all have been.
+    let next = AtomicUsize::new(0);
+
+    let mut executions = thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|_| {
+                let vm = vm.clone();
+                let storage = storage.clone();
+                let next = &next;
+
+                scope.spawn(move || {
+                    let mut executions = Vec::new();
+
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(tx) = txs.get(idx) else {
+                            break;
+                        };
+
+                        let execution =
+                            Execution::new(vm.clone(), storage.clone(), gas_costs, block, tx);
+
+                        executions.push((idx, execution));
+                    }
+
+                    executions
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| panic!("transaction execution thread panicked"))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    executions.sort_by_key(|(idx, _)| *idx);
+    executions
+        .into_iter()
+        .map(|(_, execution)| execution)
+        .collect()
+}
+
+// --------------------------------- execution ---------------------------------
+
+/// Result of optimistically executing a transaction.
+struct Execution {
+    /// The gas tracker used in authentication and processing of the messages.
+    gas_tracker: GasTracker,
+    /// The authentication and processing of the messages. `None` if they
+    /// weren't reached, i.e. withholding the fee failed.
+    speculation: Option<Speculation>,
+}
+
+/// Result of optimistically authenticating a transaction and processing its
+/// messages.
+struct Speculation {
+    authenticate: CommitmentStatus<EvtAuthenticate>,
+    msgs_and_backrun: CommitmentStatus<MsgsAndBackrunEvents>,
+    result: GenericResult<()>,
+    reads: Vec<Read>,
+    writes: Batch,
+}
+
+impl Execution {
+    fn new<S, VM>(vm: VM, storage: S, gas_costs: &GasCosts, block: BlockInfo, tx: &Tx) -> Self
+    where
+        S: Storage + Clone + 'static,
+        VM: Vm + Clone + 'static,
+        AppError: From<VM::Error>,
+    {
+        let gas_tracker = GasTracker::new_limited(tx.gas_limit).with_costs(gas_costs.clone());
+        let mut speculation = None;
+
+        // Fee handling is done here too, as authentication and the messages
+        // may depend on the state changes made in withholding the fee, but its
+        // outcome is discarded. The throwaway buffer keeps its state changes
+        // from being flushed into the given storage.
+        process_tx_with(
+            vm.clone(),
+            Buffer::new(storage, None),
+            gas_tracker.clone(),
+            block,
+            tx.clone(),
+            AuthMode::Finalize,
+            |fee_buffer, events| {
+                let reads = Shared::new(Vec::new());
+                let buffer = Shared::new(Buffer::new(
+                    RecordingStorage {
+                        base: fee_buffer.clone(),
+                        reads: reads.clone(),
+                    },
+                    None,
+                ));
+
+                let result = process_authenticate_then_msgs(
+                    vm,
+                    buffer.clone(),
+                    gas_tracker.clone(),
+                    block,
+                    tx,
+                    AuthMode::Finalize,
+                    events,
+                );
+
+                let (_, writes) = buffer.disassemble().disassemble();
+
+                fee_buffer.write_access().flush(writes.clone());
+
+                speculation = Some(Speculation {
+                    authenticate: events.authenticate.clone(),
+                    msgs_and_backrun: events.msgs_and_backrun.clone(),
+                    result: result.clone(),
+                    reads: reads.disassemble(),
+                    writes,
+                });
+
+                Some(result)
+            },
+        );
+
+        Self {
+            gas_tracker,
+            speculation,
+        }
+    }
+}
+
+// ----------------------------------- reads -----------------------------------
+
+/// A storage read made by a transaction, and the result it yielded.
+enum Read {
+    Key {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    Scan {
+        min: Option<Vec<u8>>,
+        max: Option<Vec<u8>>,
+        order: Order,
+        /// The records yielded by the iterator. The transaction may not have
+        /// iterated through all records in the range.
+        records: Vec<Record>,
+        /// Whether the iterator was iterated until it was exhausted.
+        exhausted: bool,
+    },
+}
+
+impl Read {
+    /// Return whether the read yields the same result against the given
+    /// storage.
+    fn is_valid(&self, storage: &dyn Storage) -> bool {
+        match self {
+            Read::Key { key, value } => storage.read(key) == *value,
+            Read::Scan {
+                min,
+                max,
+                order,
+                records,
+                exhausted,
+            } => {
+                let mut iter = storage.scan(min.as_deref(), max.as_deref(), *order);
+
+                for record in records {
+                    if iter.next().as_ref() != Some(record) {
+                        return false;
+                    }
+                }
+
+                !exhausted || iter.next().is_none()
+            },
+        }
+    }
+}
+
+/// A read-only storage that records the reads made against it.
+#[derive(Clone)]
+struct RecordingStorage<S> {
+    base: S,
+    reads: Shared<Vec<Read>>,
+}
+
+impl<S> Storage for RecordingStorage<S>
+where
+    S: Storage + Clone,
+{
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let value = self.base.read(key);
+
+        self.reads.write_access().push(Read::Key {
+            key: key.to_vec(),
+            value: value.clone(),
+        });
+
+        value
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let mut reads = self.reads.write_access();
+        let index = reads.len();
+
+        reads.push(Read::Scan {
+            min: min.map(|bytes| bytes.to_vec()),
+            max: max.map(|bytes| bytes.to_vec()),
+            order,
+            records: Vec::new(),
+            exhausted: false,
+        });
+
+        Box::new(RecordingIter {
+            inner: self.base.scan(min, max, order),
+            reads: self.reads.clone(),
+            index,
+        })
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.scan(min, max, order).map(|(k, _)| k))
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        Box::new(self.scan(min, max, order).map(|(_, v)| v))
+    }
+
+    // Transactions are executed in buffers on top of this storage, and their
+    // writes are taken out of the buffers. Nothing should be written directly.
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("attempted to write to a recording storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("attempted to remove from a recording storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("attempted to remove from a recording storage");
+    }
+}
+
+/// An iterator that records the records it yields into the scan read at the
+/// given index.
+struct RecordingIter<'a> {
+    inner: Box<dyn Iterator<Item = Record> + 'a>,
+    reads: Shared<Vec<Read>>,
+    index: usize,
+}
+
+impl Iterator for RecordingIter<'_> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.inner.next();
+
+        if let Read::Scan {
+            records, exhausted, ..
+        } = &mut self.reads.write_access()[self.index]
+        {
+            match &record {
+                Some(record) => records.push(record.clone()),
+                None => *exhausted = true,
+            }
+        }
+
+        record
+    }
+}
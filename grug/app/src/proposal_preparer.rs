@@ -1,7 +1,7 @@
 #[cfg(feature = "tracing")]
 use tracing::info;
 use {
-    crate::{AppError, ProposalPreparer},
+    crate::{AppError, ExtendedVote, ProposalPreparer},
     grug_types::{Querier, QuerierWrapper, Query, QueryResponse, StdResult},
     prost::bytes::Bytes,
     std::{
@@ -26,6 +26,7 @@ impl ProposalPreparer for NaiveProposalPreparer {
         _querier: QuerierWrapper,
         mut txs: Vec<Bytes>,
         max_tx_bytes: usize,
+        _votes: &[ExtendedVote],
     ) -> Result<Vec<Bytes>, Self::Error> {
         let mut total_tx_bytes: usize = txs
             .iter()
@@ -5,16 +5,23 @@ use {
     },
     grug_types::{
         Addr, BankQuery, BankQueryResponse, Binary, BlockInfo, Bound, Code, Coin, Coins, Config,
-        Context, ContractInfo, GenericResult, Hash256, Json, Order, QueryBalanceRequest,
-        QueryBalancesRequest, QueryCodeRequest, QueryCodesRequest, QueryContractRequest,
-        QueryContractsRequest, QuerySuppliesRequest, QuerySupplyRequest, QueryWasmRawRequest,
-        QueryWasmScanRequest, QueryWasmSmartRequest, StdResult, Storage,
+        Context, ContractInfo, GenericResult, Hash256, IbcClientQueryResponse, Json, Order,
+        QueryBalanceRequest, QueryBalancesRequest, QueryCodeRequest, QueryCodesRequest,
+        QueryContractRequest, QueryContractsRequest, QueryIbcClientRequest, QuerySuppliesRequest,
+        QuerySupplyRequest, QueryWasmRawRequest, QueryWasmScanRequest, QueryWasmSmartRequest,
+        StdResult, Storage,
     },
     std::collections::BTreeMap,
 };
 
 const DEFAULT_PAGE_LIMIT: u32 = 30;
 
+/// The maximum number of records returned by a single `/store_scan` query.
+pub const MAX_STORE_SCAN_LIMIT: u32 = 1000;
+
+/// The maximum number of keys that can be read in a single `/store_many` query.
+pub const MAX_STORE_MANY_KEYS: usize = 100;
+
 pub fn query_config(storage: &dyn Storage, gas_tracker: GasTracker) -> StdResult<Config> {
     CONFIG.load_with_gas(storage, gas_tracker)
 }
@@ -140,22 +147,26 @@ where
         mode: None,
     };
 
-    call_in_1_out_1::<_, _, GenericResult<BankQueryResponse>>(
-        vm,
-        storage,
-        gas_tracker,
-        query_depth,
-        false,
-        "bank_query",
-        code_hash,
-        &ctx,
-        msg,
-    )?
-    .map_err(|msg| AppError::Guest {
-        address: ctx.contract,
-        name: "bank_query",
-        msg,
-    })
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, "bank_query", None, query_depth, || {
+            call_in_1_out_1::<_, _, GenericResult<BankQueryResponse>>(
+                vm,
+                storage,
+                gas_tracker,
+                query_depth,
+                false,
+                "bank_query",
+                code_hash,
+                &ctx,
+                msg,
+            )
+        })?
+        .map_err(|msg| AppError::Guest {
+            address: ctx.contract,
+            name: "bank_query",
+            msg,
+        })
 }
 
 pub fn query_code(
@@ -256,20 +267,70 @@ where
         mode: None,
     };
 
-    call_in_1_out_1::<_, _, GenericResult<Json>>(
-        vm,
-        storage,
-        gas_tracker,
-        query_depth,
-        false,
-        "query",
-        code_hash,
-        &ctx,
-        &req.msg,
-    )?
-    .map_err(|msg| AppError::Guest {
-        address: ctx.contract,
-        name: "query",
-        msg,
-    })
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, "query", None, query_depth, || {
+            call_in_1_out_1::<_, _, GenericResult<Json>>(
+                vm,
+                storage,
+                gas_tracker,
+                query_depth,
+                false,
+                "query",
+                code_hash,
+                &ctx,
+                &req.msg,
+            )
+        })?
+        .map_err(|msg| AppError::Guest {
+            address: ctx.contract,
+            name: "query",
+            msg,
+        })
+}
+
+pub fn query_ibc_client<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    query_depth: usize,
+    req: QueryIbcClientRequest,
+) -> AppResult<IbcClientQueryResponse>
+where
+    VM: Vm + Clone + 'static,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let code_hash = CONTRACTS.load(&storage, req.client)?.code_hash;
+
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: req.client,
+        sender: None,
+        funds: None,
+        mode: None,
+    };
+
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, "ibc_client_query", None, query_depth, || {
+            call_in_1_out_1::<_, _, GenericResult<IbcClientQueryResponse>>(
+                vm,
+                storage,
+                gas_tracker,
+                query_depth,
+                false,
+                "ibc_client_query",
+                code_hash,
+                &ctx,
+                &req.msg,
+            )
+        })?
+        .map_err(|msg| AppError::Guest {
+            address: ctx.contract,
+            name: "ibc_client_query",
+            msg,
+        })
 }
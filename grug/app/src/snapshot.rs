@@ -0,0 +1,331 @@
+use {
+    crate::{AppError, AppResult},
+    borsh::{BorshDeserialize, BorshSerialize},
+    grug_types::{Batch, BorshDeExt, BorshSerExt, Hash256, HashExt, Op, Order, Record, Storage},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex, MutexGuard},
+        thread::{self, JoinHandle},
+    },
+};
+
+/// The snapshot format this version of the software creates and restores.
+///
+/// Format 2 splits the key-value pairs in the state storage, sorted by keys
+/// ascendingly, into chunks of about `SNAPSHOT_CHUNK_SIZE` bytes. Each chunk is
+/// the Borsh encoding of its key-value pairs, so that it can be decoded on its
+/// own.
+pub const SNAPSHOT_FORMAT: u32 = 2;
+
+/// Size of each snapshot chunk, in bytes. A chunk is only bigger if it consists
+/// of a single key-value pair that is bigger.
+pub const SNAPSHOT_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+
+/// Name of the file in a snapshot's directory that stores its metadata.
+/// Chunks are stored in files named by their indexes.
+const METADATA_FILE_NAME: &str = "metadata";
+
+/// A snapshot of the state at a block height, for use in state sync.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub height: u64,
+    pub format: u32,
+    /// Hashes of the chunks, in order.
+    pub chunk_hashes: Vec<Hash256>,
+}
+
+impl Snapshot {
+    /// Return the snapshot's hash, which is the hash of its chunk hashes
+    /// concatenated.
+    pub fn hash(&self) -> Hash256 {
+        let mut preimage = Vec::with_capacity(self.chunk_hashes.len() * Hash256::LENGTH);
+        for chunk_hash in &self.chunk_hashes {
+            preimage.extend_from_slice(chunk_hash);
+        }
+        preimage.hash256()
+    }
+}
+
+/// A snapshot being restored, and the records of the chunks received so far.
+///
+/// Chunks are decoded as they're received. The records are kept in memory
+/// until all chunks are received, because the Merkle tree can only be built
+/// from the entire state.
+struct Restoration {
+    snapshot: Snapshot,
+    app_hash: Hash256,
+    received: Vec<bool>,
+    batch: Batch,
+}
+
+/// Creates snapshots of the state at regular block intervals, stores them on
+/// disk, and keeps track of a snapshot being restored.
+///
+/// Snapshots are created in a background thread, so that a large state doesn't
+/// delay the commit. At most one snapshot is created at a time.
+#[derive(Clone)]
+pub struct SnapshotManager {
+    dir: PathBuf,
+    interval: u64,
+    keep_recent: usize,
+    creation: Arc<Mutex<Option<JoinHandle<()>>>>,
+    restoration: Arc<Mutex<Option<Restoration>>>,
+}
+
+impl SnapshotManager {
+    /// Create a new snapshot manager storing snapshots in the given directory.
+    ///
+    /// A snapshot is created every `interval` blocks, and the most recent
+    /// `keep_recent` of them are kept. An interval of zero means to not create
+    /// snapshots, while still allowing restoring from them; `keep_recent` of
+    /// zero means to keep all snapshots.
+    pub fn new<P>(dir: P, interval: u64, keep_recent: usize) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            dir: dir.into(),
+            interval,
+            keep_recent,
+            creation: Arc::new(Mutex::new(None)),
+            restoration: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Return whether a snapshot should be created at the given block height.
+    pub fn should_create(&self, height: u64) -> bool {
+        self.interval > 0 && height % self.interval == 0
+    }
+
+    /// Start creating a snapshot of the given state storage in the background.
+    ///
+    /// See [`create`](Self::create). If a snapshot is still being created, this
+    /// one is skipped, and `false` is returned.
+    pub fn spawn_create<S>(&self, storage: S, height: u64) -> bool
+    where
+        S: Storage + 'static,
+    {
+        let mut creation = self.lock_creation();
+
+        if creation
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
+            return false;
+        }
+
+        let manager = self.clone();
+
+        *creation = Some(thread::spawn(move || {
+            // Failing to create a snapshot shouldn't halt the chain.
+            match manager.create(&storage, height) {
+                Ok(_snapshot) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::info!(
+                        height,
+                        chunks = _snapshot.chunk_hashes.len(),
+                        "Created snapshot"
+                    );
+                },
+                Err(_err) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::error!(height, err = _err.to_string(), "Failed to create snapshot");
+                },
+            }
+        }));
+
+        true
+    }
+
+    /// Block until the snapshot being created in the background, if any, is
+    /// done.
+    pub fn wait_for_creation(&self) {
+        if let Some(handle) = self.lock_creation().take() {
+            handle.join().unwrap_or_else(|err| {
+                panic!("snapshot creation panicked: {err:?}");
+            });
+        }
+    }
+
+    /// Create a snapshot of the given state storage, which must be that at the
+    /// given height, then delete old snapshots in excess of `keep_recent`.
+    ///
+    /// Records are read from the storage and written to chunk files one chunk
+    /// at a time, so that the state is never held in memory in its entirety.
+    pub fn create(&self, storage: &dyn Storage, height: u64) -> AppResult<Snapshot> {
+        let dir = self.snapshot_dir(height);
+        fs::create_dir_all(&dir)?;
+
+        let mut chunk_hashes = Vec::new();
+        let mut chunk = Vec::new();
+        let mut chunk_size = 0;
+
+        for record in storage.scan(None, None, Order::Ascending) {
+            let record_size = record.0.len() + record.1.len();
+
+            if !chunk.is_empty() && chunk_size + record_size > SNAPSHOT_CHUNK_SIZE {
+                chunk_hashes.push(write_chunk(&dir, chunk_hashes.len(), &chunk)?);
+                chunk.clear();
+                chunk_size = 0;
+            }
+
+            chunk.push(record);
+            chunk_size += record_size;
+        }
+
+        if !chunk.is_empty() {
+            chunk_hashes.push(write_chunk(&dir, chunk_hashes.len(), &chunk)?);
+        }
+
+        let snapshot = Snapshot {
+            height,
+            format: SNAPSHOT_FORMAT,
+            chunk_hashes,
+        };
+
+        // Write the metadata last. A snapshot is only listed if its metadata
+        // exists, so that we never serve a snapshot with missing chunks if the
+        // node crashes halfway.
+        fs::write(dir.join(METADATA_FILE_NAME), snapshot.to_borsh_vec()?)?;
+
+        if self.keep_recent > 0 {
+            for old_snapshot in self.list()?.into_iter().skip(self.keep_recent) {
+                fs::remove_dir_all(self.snapshot_dir(old_snapshot.height))?;
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// List the snapshots available, from the newest to the oldest.
+    pub fn list(&self) -> AppResult<Vec<Snapshot>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path().join(METADATA_FILE_NAME);
+            if path.exists() {
+                snapshots.push(fs::read(path)?.deserialize_borsh::<Snapshot>()?);
+            }
+        }
+
+        snapshots.sort_by(|a, b| b.height.cmp(&a.height));
+
+        Ok(snapshots)
+    }
+
+    /// Load a chunk of a snapshot. `None` if the snapshot or chunk doesn't
+    /// exist.
+    pub fn load_chunk(&self, height: u64, format: u32, index: u32) -> AppResult<Option<Vec<u8>>> {
+        if format != SNAPSHOT_FORMAT {
+            return Ok(None);
+        }
+
+        let path = self.snapshot_dir(height).join(index.to_string());
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(fs::read(path)?))
+    }
+
+    /// Start restoring a snapshot, which is expected to result in the given
+    /// app hash. Abandon the snapshot being restored, if any.
+    pub fn offer(&self, snapshot: Snapshot, app_hash: Hash256) -> AppResult<()> {
+        if snapshot.format != SNAPSHOT_FORMAT {
+            return Err(AppError::UnsupportedSnapshotFormat {
+                format: snapshot.format,
+            });
+        }
+
+        *self.lock_restoration() = Some(Restoration {
+            received: vec![false; snapshot.chunk_hashes.len()],
+            batch: Batch::new(),
+            snapshot,
+            app_hash,
+        });
+
+        Ok(())
+    }
+
+    /// Accept a chunk of the snapshot being restored, after verifying it
+    /// against the chunk hash, and decode its records.
+    ///
+    /// Once all chunks are received, return the block height, the expected app
+    /// hash, and the batch of state storage writes to restore.
+    pub fn apply_chunk(
+        &self,
+        index: u32,
+        chunk: Vec<u8>,
+    ) -> AppResult<Option<(u64, Hash256, Batch)>> {
+        let mut restoration = self.lock_restoration();
+
+        let Some(inner) = restoration.as_mut() else {
+            return Err(AppError::NoSnapshotBeingRestored);
+        };
+
+        let Some(expect) = inner.snapshot.chunk_hashes.get(index as usize).copied() else {
+            return Err(AppError::SnapshotChunkNotFound {
+                index,
+                chunks: inner.snapshot.chunk_hashes.len(),
+            });
+        };
+
+        let actual = chunk.hash256();
+        if actual != expect {
+            return Err(AppError::SnapshotChunkHashMismatch {
+                index,
+                expect,
+                actual,
+            });
+        }
+
+        if !inner.received[index as usize] {
+            let records = chunk.deserialize_borsh::<Vec<Record>>()?;
+
+            inner.batch.extend(
+                records
+                    .into_iter()
+                    .map(|(key, value)| (key, Op::Insert(value))),
+            );
+            inner.received[index as usize] = true;
+        }
+
+        if inner.received.contains(&false) {
+            return Ok(None);
+        }
+
+        // All chunks have been received. The restoration is done, whether the
+        // snapshot turns out to be valid or not.
+        let inner = restoration.take().unwrap();
+
+        Ok(Some((inner.snapshot.height, inner.app_hash, inner.batch)))
+    }
+
+    fn snapshot_dir(&self, height: u64) -> PathBuf {
+        self.dir.join(height.to_string())
+    }
+
+    fn lock_creation(&self) -> MutexGuard<Option<JoinHandle<()>>> {
+        self.creation.lock().unwrap_or_else(|err| {
+            panic!("snapshot creation is poisoned: {err:?}");
+        })
+    }
+
+    fn lock_restoration(&self) -> MutexGuard<Option<Restoration>> {
+        self.restoration.lock().unwrap_or_else(|err| {
+            panic!("snapshot restoration is poisoned: {err:?}");
+        })
+    }
+}
+
+/// Write a chunk of records to the file named by its index, and return its hash.
+fn write_chunk(dir: &Path, index: usize, records: &[Record]) -> AppResult<Hash256> {
+    let bytes = records.to_borsh_vec()?;
+    fs::write(dir.join(index.to_string()), &bytes)?;
+
+    Ok(bytes.hash256())
+}
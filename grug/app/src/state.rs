@@ -1,8 +1,11 @@
 use {
+    borsh::{BorshDeserialize, BorshSerialize},
     grug_storage::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, Set},
     grug_types::{
-        Addr, BlockInfo, Code, CodeStatus, Config, ContractInfo, Hash256, Json, Timestamp,
+        Addr, BlockInfo, Code, CodeStatus, Config, ContractInfo, Duration, Hash160, Hash256, Json,
+        Permissions, Timestamp, UpgradePlan,
     },
+    std::collections::BTreeMap,
 };
 
 /// A string that identifies the chain
@@ -11,12 +14,28 @@ pub const CHAIN_ID: Item<String> = Item::new("chain_id");
 /// Chain-level configuration
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Chain-level configuration as stored by previous versions, under the same key
+/// as `CONFIG`, before the IBC handler and the gas costs were added.
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
 /// Application-specific configuration.
 pub const APP_CONFIG: Item<Json> = Item::new("app_config");
 
+/// Limits to the size of blocks, as set in the consensus parameters at genesis.
+pub const BLOCK_LIMITS: Item<BlockLimits> = Item::new("block_limits");
+
 /// The most recently finalized block
 pub const LAST_FINALIZED_BLOCK: Item<BlockInfo> = Item::new("last_finalized_block");
 
+/// The validators' Ed25519 consensus public keys: address => public key
+///
+/// Recorded from the validator set at genesis, and from the validator updates
+/// returned to CometBFT, so that vote extensions can be verified.
+pub const VALIDATORS: Map<Hash160, [u8; 32]> = Map::new("validator");
+
+/// The scheduled chain upgrade, if any. Removed once the upgrade is done.
+pub const UPGRADE_PLAN: Item<UpgradePlan> = Item::new("upgrade_plan");
+
 /// Scheduled cronjobs.
 ///
 /// This needs to be a `Set` instead of `Map<Timestamp, Addr>` because there can
@@ -35,6 +54,26 @@ pub const CONTRACTS: Map<Addr, ContractInfo> = Map::new("contract");
 /// A key in a contract's substore is prefixed by the word "wasm" + contract address.
 pub const CONTRACT_NAMESPACE: &[u8] = b"wasm";
 
+/// Limits to the size of blocks, which proposals must respect. `None` means
+/// unlimited.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockLimits {
+    /// The maximum total size of the transactions in a block, in bytes.
+    pub max_tx_bytes: Option<u64>,
+    /// The maximum total gas limit of the transactions in a block.
+    pub max_gas: Option<u64>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub bank: Addr,
+    pub taxman: Addr,
+    pub cronjobs: BTreeMap<Addr, Duration>,
+    pub permissions: Permissions,
+    pub max_orphan_age: Duration,
+}
+
 pub struct CodeIndexes<'a> {
     pub status: MultiIndex<'a, Hash256, CodeStatus, Code>,
 }
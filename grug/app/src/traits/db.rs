@@ -41,6 +41,9 @@ pub trait Db {
     /// Type of the Merkle proof. The DB can choose any Merkle tree scheme.
     type Proof: BorshSerialize + BorshDeserialize;
 
+    /// Type of the Merkle proof of multiple keys at once.
+    type MultiProof: BorshSerialize + BorshDeserialize;
+
     /// Return the state commitment.
     fn state_commitment(&self) -> Self::StateCommitment;
 
@@ -70,6 +73,20 @@ pub trait Db {
     /// _membership_ proof; otherwise, it should be a _non-membership_ proof.
     fn prove(&self, key: &[u8], version: Option<u64>) -> Result<Self::Proof, Self::Error>;
 
+    /// Generate Merkle proof of the given keys at the given version, proving
+    /// the membership or non-membership of each key.
+    ///
+    /// If version is unspecified, use the latest version.
+    ///
+    /// Note that only individual keys can be proven, not ranges of keys: keys
+    /// are hashed in the Merkle tree, so keys that are adjacent in the state
+    /// storage aren't adjacent in the tree.
+    fn prove_many(
+        &self,
+        keys: Vec<Vec<u8>>,
+        version: Option<u64>,
+    ) -> Result<Self::MultiProof, Self::Error>;
+
     /// Generate ICS-23 compatible Merkle proof of the given key at the given
     /// version.
     ///
@@ -87,6 +104,19 @@ pub trait Db {
         version: Option<u64>,
     ) -> Result<CommitmentProof, Self::Error>;
 
+    /// Generate ICS-23 compatible batch proof of the given keys at the given
+    /// version.
+    ///
+    /// If version is unspecified, use the latest version.
+    ///
+    /// The proof is compressed, such that inner nodes shared by the keys' paths
+    /// are only included once. ICS-23 verifiers decompress it automatically.
+    fn ics23_prove_batch(
+        &self,
+        keys: Vec<Vec<u8>>,
+        version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error>;
+
     /// Accept a batch ops (an op is either a DB insertion or a deletion), keep
     /// them in the memory, but do not persist to disk yet; also, increment the
     /// version.
@@ -125,3 +155,15 @@ pub trait PrunableDb: Db {
     /// database post pruning.
     fn prune(&self, up_to_version: u64) -> Result<(), Self::Error>;
 }
+
+/// Represents a database that can be restored from a state sync snapshot.
+///
+/// Creating snapshots only requires reading the state storage, which `Db`
+/// already supports, so only restoring is in this trait.
+pub trait SnapshotDb: Db {
+    /// Write the given batch as the state at the given version, provided that
+    /// its Merkle root hash matches the one given. Nothing is written otherwise.
+    ///
+    /// The database must not have had any version committed.
+    fn restore(&self, version: u64, batch: Batch, root_hash: Hash256) -> Result<(), Self::Error>;
+}
@@ -1,17 +1,122 @@
-use {grug_types::QuerierWrapper, prost::bytes::Bytes};
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    grug_types::{BorshDeExt, BorshSerExt, QuerierWrapper, StdResult},
+    prost::bytes::Bytes,
+};
 
-/// Represents a worker that processes the ABCI++ `PrepareProposal` request.
+/// A validator's vote for the previous block, along with the vote extension it
+/// attached.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedVote {
+    /// The validator's address, derived from its consensus public key.
+    pub validator: [u8; 20],
+    /// The validator's voting power.
+    pub power: u64,
+    /// The vote extension, as returned by `extend_vote`. Empty if the validator
+    /// didn't attach one.
+    pub extension: Vec<u8>,
+    /// The validator's signature over the vote extension, by its consensus key.
+    /// See [`vote_extension_sign_bytes`](crate::vote_extension_sign_bytes) for
+    /// the bytes that are signed.
+    pub extension_signature: Vec<u8>,
+}
+
+/// The votes for the previous block, which the proposer places in front of the
+/// transactions of its proposal as a pseudo-transaction.
+///
+/// Vote extensions are only provided to the proposer. Carrying them in the
+/// proposal allows the other validators to verify that the proposer made use
+/// of them correctly. The pseudo-transaction isn't executed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedCommit {
+    pub votes: Vec<ExtendedVote>,
+}
+
+impl ExtendedCommit {
+    /// Prefix that distinguishes the pseudo-transaction from regular ones,
+    /// which are JSON-encoded.
+    const PREFIX: &'static [u8] = b"extended_commit:";
+
+    /// Encode the pseudo-transaction.
+    pub fn encode(&self) -> StdResult<Bytes> {
+        let mut bytes = Self::PREFIX.to_vec();
+        bytes.extend(self.to_borsh_vec()?);
+
+        Ok(bytes.into())
+    }
+
+    /// Decode a pseudo-transaction. `None` if the transaction isn't one.
+    pub fn decode(raw_tx: &[u8]) -> Option<StdResult<Self>> {
+        raw_tx
+            .strip_prefix(Self::PREFIX)
+            .map(|bytes| bytes.deserialize_borsh())
+    }
+}
+
+/// Represents a worker that processes the ABCI++ `PrepareProposal`,
+/// `ProcessProposal`, `ExtendVote`, and `VerifyVoteExtension` requests.
 pub trait ProposalPreparer {
     type Error: ToString;
 
     /// Process the ABCI++ `PrepareProposal` request.
     ///
     /// The preparer is provided with a querier so that it can do its work based
-    /// on the state of the chain.
+    /// on the state of the chain, and the votes for the previous block, along
+    /// with the vote extensions attached by the validators.
     fn prepare_proposal(
         &self,
         querier: QuerierWrapper,
         txs: Vec<Bytes>,
         max_tx_bytes: usize,
+        votes: &[ExtendedVote],
     ) -> Result<Vec<Bytes>, Self::Error>;
+
+    /// Process the ABCI++ `ProcessProposal` request.
+    ///
+    /// Validate a block proposed by another node, presumably prepared by the
+    /// same logic as `prepare_proposal`, given the same votes. Return whether
+    /// the proposal should be accepted.
+    ///
+    /// The votes carried in the proposal have been checked to match the last
+    /// commit, and their extensions to be signed by the validators that cast
+    /// them.
+    ///
+    /// An error means this node failed to validate the proposal, rather than
+    /// the proposal being invalid. In this case, the proposal is accepted, so
+    /// that a fault on this node's side doesn't hinder consensus.
+    ///
+    /// By default, all proposals are accepted.
+    fn process_proposal(
+        &self,
+        _querier: QuerierWrapper,
+        _txs: &[Bytes],
+        _votes: &[ExtendedVote],
+    ) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+
+    /// Process the ABCI++ `ExtendVote` request.
+    ///
+    /// Return the data to be attached to this node's vote for the block at the
+    /// given height, which will be provided to the next block's proposer.
+    ///
+    /// By default, no data is attached.
+    fn extend_vote(&self, _querier: QuerierWrapper, _height: u64) -> Result<Vec<u8>, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    /// Process the ABCI++ `VerifyVoteExtension` request.
+    ///
+    /// Validate the data another validator attached to its vote for the block
+    /// at the given height. Returning an error means the vote is rejected.
+    ///
+    /// By default, all vote extensions are accepted.
+    fn verify_vote_extension(
+        &self,
+        _querier: QuerierWrapper,
+        _height: u64,
+        _extension: &[u8],
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
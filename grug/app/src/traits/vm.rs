@@ -1,10 +1,14 @@
 use {
     crate::{GasTracker, QuerierProvider, StorageProvider},
-    grug_types::{Context, Hash256},
+    grug_types::{Context, GasCosts, Hash256},
+    std::collections::BTreeSet,
 };
 
 /// Represents a virtual machine that can execute programs.
-pub trait Vm: Sized {
+///
+/// Must be `Send`, such that transactions can be executed in parallel, with
+/// each thread using its own clone of the VM.
+pub trait Vm: Sized + Send {
     type Error: ToString;
     type Instance: Instance<Error = Self::Error>;
 
@@ -23,6 +27,32 @@ pub trait Vm: Sized {
         query_depth: usize,
         gas_tracker: GasTracker,
     ) -> Result<Self::Instance, Self::Error>;
+
+    /// Prepare a program ahead of time, such that building instances of it
+    /// later is faster, e.g. by compiling it and caching the result.
+    ///
+    /// This is called at the end of the block in which the program is uploaded,
+    /// when the block is finalized; not during `CheckTx` or simulations, as
+    /// preparing a program isn't metered. It's an optimization only, so an
+    /// error here doesn't fail the upload. By default, this does nothing.
+    fn warm_up(
+        &mut self,
+        _code: &[u8],
+        _code_hash: Hash256,
+        _gas_costs: &GasCosts,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Return a VM that doesn't cache the programs of the given hashes.
+    ///
+    /// This is used for programs that are only used once, e.g. ones that a
+    /// simulation overrides contracts' programs with, such that they don't
+    /// take the place of other programs in the cache. By default, this returns
+    /// the VM unchanged.
+    fn without_caching(self, _code_hashes: BTreeSet<Hash256>) -> Self {
+        self
+    }
 }
 
 pub trait Instance {
@@ -0,0 +1,150 @@
+use {
+    crate::{AppError, AppResult, CONFIG, LEGACY_CONFIG, UPGRADE_PLAN},
+    grug_types::{BlockInfo, Config, GasCosts, Storage},
+    std::{collections::BTreeMap, sync::Arc},
+};
+
+/// A function that migrates the chain's state during a chain upgrade, e.g. by
+/// re-encoding the values in a map after their type has changed.
+///
+/// Invoked at the beginning of the block at the upgrade height, before any
+/// cronjob or transaction. An error is fatal: the node halts without finalizing
+/// the block.
+pub type UpgradeHandler = Arc<dyn Fn(&mut dyn Storage, BlockInfo) -> AppResult<()> + Send + Sync>;
+
+/// If an upgrade is scheduled at the current block height, run its handler.
+///
+/// Error if the handler isn't found, which means this software version doesn't
+/// support the upgrade, so the node must halt and be switched to one that does.
+pub(crate) fn do_upgrade(
+    storage: &mut dyn Storage,
+    block: BlockInfo,
+    handlers: &BTreeMap<String, UpgradeHandler>,
+) -> AppResult<()> {
+    let Some(plan) = UPGRADE_PLAN.may_load(storage)? else {
+        return Ok(());
+    };
+
+    if plan.height != block.height {
+        return Ok(());
+    }
+
+    let Some(handler) = handlers.get(&plan.name) else {
+        #[cfg(feature = "tracing")]
+        tracing::error!(
+            name = plan.name,
+            height = plan.height,
+            "UPGRADE NEEDED! Halting the node."
+        );
+
+        return Err(AppError::UpgradeNeeded {
+            name: plan.name,
+            height: plan.height,
+        });
+    };
+
+    #[cfg(feature = "tracing")]
+    tracing::info!(name = plan.name, height = plan.height, "Performing upgrade");
+
+    handler(storage, block)?;
+
+    // Remove the plan, so that the handler is only run once.
+    UPGRADE_PLAN.remove(storage);
+
+    #[cfg(feature = "tracing")]
+    tracing::info!(name = plan.name, "Completed upgrade");
+
+    Ok(())
+}
+
+/// An upgrade handler that migrates the chain config from the layout stored by
+/// previous versions, before the IBC handler and the gas costs were added. IBC
+/// is left disabled, and the gas costs take their default values.
+///
+/// Chains upgrading from such a version must register this handler, or one
+/// that calls it, for the upgrade. Otherwise, the config can't be loaded.
+pub fn migrate_legacy_config(storage: &mut dyn Storage, _block: BlockInfo) -> AppResult<()> {
+    // A config already in the current layout fails to deserialize as the
+    // legacy layout, so it's left as is. This makes the migration idempotent.
+    let Ok(legacy) = LEGACY_CONFIG.load(storage) else {
+        return Ok(());
+    };
+
+    CONFIG.save(storage, &Config {
+        owner: legacy.owner,
+        bank: legacy.bank,
+        taxman: legacy.taxman,
+        ibc_handler: None,
+        cronjobs: legacy.cronjobs,
+        permissions: legacy.permissions,
+        max_orphan_age: legacy.max_orphan_age,
+        gas_costs: GasCosts::DEFAULT,
+    })?;
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::migrate_legacy_config,
+        crate::{LegacyConfig, CONFIG, LEGACY_CONFIG},
+        grug_types::{
+            btree_map, Addr, BlockInfo, Config, Duration, GasCosts, Hash256, MockStorage,
+            Permission, Permissions,
+        },
+    };
+
+    const BLOCK: BlockInfo = BlockInfo {
+        height: 100,
+        timestamp: Duration::from_seconds(100),
+        hash: Hash256::ZERO,
+    };
+
+    #[test]
+    fn migrating_legacy_config() {
+        let mut storage = MockStorage::new();
+
+        LEGACY_CONFIG
+            .save(&mut storage, &LegacyConfig {
+                owner: Addr::mock(1),
+                bank: Addr::mock(2),
+                taxman: Addr::mock(3),
+                cronjobs: btree_map! { Addr::mock(4) => Duration::from_seconds(60) },
+                permissions: Permissions {
+                    upload: Permission::Nobody,
+                    instantiate: Permission::Everybody,
+                },
+                max_orphan_age: Duration::from_seconds(3600),
+            })
+            .unwrap();
+
+        // The legacy config can't be loaded as the current one.
+        assert!(CONFIG.load(&storage).is_err());
+
+        migrate_legacy_config(&mut storage, BLOCK).unwrap();
+
+        let cfg = Config {
+            owner: Addr::mock(1),
+            bank: Addr::mock(2),
+            taxman: Addr::mock(3),
+            ibc_handler: None,
+            cronjobs: btree_map! { Addr::mock(4) => Duration::from_seconds(60) },
+            permissions: Permissions {
+                upload: Permission::Nobody,
+                instantiate: Permission::Everybody,
+            },
+            max_orphan_age: Duration::from_seconds(3600),
+            gas_costs: GasCosts::DEFAULT,
+        };
+
+        assert_eq!(CONFIG.load(&storage).unwrap(), cfg);
+
+        // Migrating again leaves the config as is.
+        migrate_legacy_config(&mut storage, BLOCK).unwrap();
+
+        assert_eq!(CONFIG.load(&storage).unwrap(), cfg);
+    }
+}
@@ -0,0 +1,190 @@
+use {
+    crate::{ExtendedVote, VALIDATORS},
+    grug_types::{Api, Hash160, HashExt, NativeApi, StdResult, Storage},
+    prost::Message,
+};
+
+/// An update to the validator set, as reported by CometBFT in `InitChain`, or
+/// returned to it in `FinalizeBlock`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidatorUpdate {
+    /// The validator's Ed25519 consensus public key.
+    pub pubkey: [u8; 32],
+    /// The validator's new voting power. Zero removes it from the set.
+    pub power: u64,
+}
+
+/// CometBFT's `CanonicalVoteExtension`, the Protobuf message that validators
+/// sign over when attaching an extension to their vote.
+#[derive(Message)]
+struct CanonicalVoteExtension {
+    #[prost(bytes = "vec", tag = "1")]
+    extension: Vec<u8>,
+    #[prost(sfixed64, tag = "2")]
+    height: i64,
+    #[prost(sfixed64, tag = "3")]
+    round: i64,
+    #[prost(string, tag = "4")]
+    chain_id: String,
+}
+
+/// Return the address CometBFT derives from a validator's Ed25519 consensus
+/// public key: the first 20 bytes of its SHA-256 hash.
+pub fn validator_address(pubkey: &[u8; 32]) -> [u8; 20] {
+    pubkey.hash256()[..20].try_into().unwrap()
+}
+
+/// Return the bytes a validator signs when attaching the given extension to its
+/// vote for the block at `height`, cast in `round`.
+pub fn vote_extension_sign_bytes(
+    chain_id: &str,
+    height: u64,
+    round: u32,
+    extension: &[u8],
+) -> Vec<u8> {
+    CanonicalVoteExtension {
+        extension: extension.to_vec(),
+        height: height as i64,
+        round: round.into(),
+        chain_id: chain_id.to_string(),
+    }
+    .encode_length_delimited_to_vec()
+}
+
+/// Record the consensus public keys of the validators in the given updates, so
+/// that their vote extensions can be verified.
+pub(crate) fn update_validators(
+    storage: &mut dyn Storage,
+    updates: &[ValidatorUpdate],
+) -> StdResult<()> {
+    for update in updates {
+        let address = Hash160::from_inner(validator_address(&update.pubkey));
+
+        if update.power == 0 {
+            VALIDATORS.remove(storage, address);
+        } else {
+            VALIDATORS.save(storage, address, &update.pubkey)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Return whether the extension of each vote for the block at `height`, cast
+/// in `round`, is signed by the consensus key of the validator that cast it.
+///
+/// A vote from a validator whose key isn't recorded can't be verified, so it's
+/// considered not signed.
+pub(crate) fn verify_vote_extensions(
+    storage: &dyn Storage,
+    chain_id: &str,
+    height: u64,
+    round: u32,
+    votes: &[ExtendedVote],
+) -> StdResult<bool> {
+    for vote in votes {
+        let Some(pubkey) = VALIDATORS.may_load(storage, Hash160::from_inner(vote.validator))?
+        else {
+            return Ok(false);
+        };
+
+        let sign_bytes = vote_extension_sign_bytes(chain_id, height, round, &vote.extension);
+
+        if NativeApi
+            .ed25519_pure_verify(&sign_bytes, &vote.extension_signature, &pubkey)
+            .is_err()
+        {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ed25519_dalek::{Signer, SigningKey},
+        grug_types::MockStorage,
+    };
+
+    #[test]
+    fn sign_bytes_are_canonical() {
+        let sign_bytes = vote_extension_sign_bytes("dev-1", 5, 1, b"ext");
+
+        #[rustfmt::skip]
+        assert_eq!(sign_bytes, [
+            // Length prefix.
+            30,
+            // Field 1: extension, as length-delimited bytes.
+            0x0a, 3, b'e', b'x', b't',
+            // Field 2: height, as a little-endian fixed 64-bit integer.
+            0x11, 5, 0, 0, 0, 0, 0, 0, 0,
+            // Field 3: round, as a little-endian fixed 64-bit integer.
+            0x19, 1, 0, 0, 0, 0, 0, 0, 0,
+            // Field 4: chain ID, as a length-delimited string.
+            0x22, 5, b'd', b'e', b'v', b'-', b'1',
+        ]);
+
+        // Fields with default values are omitted.
+        assert_eq!(vote_extension_sign_bytes("", 0, 0, b""), [0]);
+    }
+
+    #[test]
+    fn vote_extensions_must_be_signed() {
+        let mut storage = MockStorage::new();
+
+        let sks = [1, 2, 3].map(|i| SigningKey::from_bytes(&[i; 32]));
+        let pubkeys = sks.each_ref().map(|sk| sk.verifying_key().to_bytes());
+
+        update_validators(
+            &mut storage,
+            &pubkeys.map(|pubkey| ValidatorUpdate { pubkey, power: 1 }),
+        )
+        .unwrap();
+
+        let vote = |sk: &SigningKey, extension: &[u8]| {
+            let sign_bytes = vote_extension_sign_bytes("dev-1", 5, 1, extension);
+
+            ExtendedVote {
+                validator: validator_address(&sk.verifying_key().to_bytes()),
+                power: 1,
+                extension: extension.to_vec(),
+                extension_signature: sk.sign(&sign_bytes).to_bytes().to_vec(),
+            }
+        };
+
+        let votes = vec![vote(&sks[0], b"foo"), vote(&sks[1], b"")];
+
+        assert!(verify_vote_extensions(&storage, "dev-1", 5, 1, &votes).unwrap());
+
+        // The signature must be over the same chain ID, height, and round.
+        for (chain_id, height, round) in [("dev-2", 5, 1), ("dev-1", 6, 1), ("dev-1", 5, 0)] {
+            assert!(!verify_vote_extensions(&storage, chain_id, height, round, &votes).unwrap());
+        }
+
+        // The extension is tampered with.
+        let mut tampered = votes.clone();
+        tampered[0].extension = b"bar".to_vec();
+
+        assert!(!verify_vote_extensions(&storage, "dev-1", 5, 1, &tampered).unwrap());
+
+        // The extension is signed by another validator.
+        let mut tampered = votes.clone();
+        tampered[0].extension_signature = vote(&sks[2], b"foo").extension_signature;
+
+        assert!(!verify_vote_extensions(&storage, "dev-1", 5, 1, &tampered).unwrap());
+
+        // The validator is removed from the set, so its key is no longer known.
+        update_validators(&mut storage, &[ValidatorUpdate {
+            pubkey: pubkeys[1],
+            power: 0,
+        }])
+        .unwrap();
+
+        assert!(!verify_vote_extensions(&storage, "dev-1", 5, 1, &votes).unwrap());
+    }
+}
@@ -149,30 +149,34 @@ where
     VM: Vm + Clone + 'static,
     AppError: From<VM::Error>,
 {
-    let evt = EvtGuest::base(ctx.contract, name);
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, name, Some(msg_depth), query_depth, || {
+            let evt = EvtGuest::base(ctx.contract, name);
 
-    let response = catch_event! {
-        {
-            call_in_0_out_1::<_, GenericResult<Response>>(
-                vm.clone(),
-            storage.clone(),
-            gas_tracker.clone(),
-                query_depth,
-                state_mutable,
-                name,
-                code_hash,
-                ctx,
-            )?
-            .map_err(|msg| AppError::Guest {
-                address: ctx.contract,
-                name,
-                msg,
-            })
-        },
-        evt
-    };
+            let response = catch_event! {
+                {
+                    call_in_0_out_1::<_, GenericResult<Response>>(
+                        vm.clone(),
+                    storage.clone(),
+                    gas_tracker.clone(),
+                        query_depth,
+                        state_mutable,
+                        name,
+                        code_hash,
+                        ctx,
+                    )?
+                    .map_err(|msg| AppError::Guest {
+                        address: ctx.contract,
+                        name,
+                        msg,
+                    })
+                },
+                evt
+            };
 
-    handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+            handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+        })
 }
 
 /// Create a VM instance, call a function that takes exactly one parameter and
@@ -195,31 +199,35 @@ where
     VM: Vm + Clone + 'static,
     AppError: From<VM::Error>,
 {
-    let evt = EvtGuest::base(ctx.contract, name);
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, name, Some(msg_depth), query_depth, || {
+            let evt = EvtGuest::base(ctx.contract, name);
 
-    let response = catch_event! {
-        {
-            call_in_1_out_1::<_, _, GenericResult<Response>>(
-                vm.clone(),
-                storage.clone(),
-                gas_tracker.clone(),
-                query_depth,
-                state_mutable,
-                name,
-                code_hash,
-                ctx,
-                param,
-            )?
-            .map_err(|msg| AppError::Guest {
-                address: ctx.contract,
-                name,
-                msg,
-            })
-        },
-        evt
-    };
+            let response = catch_event! {
+                {
+                    call_in_1_out_1::<_, _, GenericResult<Response>>(
+                        vm.clone(),
+                        storage.clone(),
+                        gas_tracker.clone(),
+                        query_depth,
+                        state_mutable,
+                        name,
+                        code_hash,
+                        ctx,
+                        param,
+                    )?
+                    .map_err(|msg| AppError::Guest {
+                        address: ctx.contract,
+                        name,
+                        msg,
+                    })
+                },
+                evt
+            };
 
-    handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+            handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+        })
 }
 
 pub fn call_in_1_out_1_handle_auth_response<VM, P>(
@@ -240,41 +248,45 @@ where
     VM: Vm + Clone + 'static,
     AppError: From<VM::Error>,
 {
-    let evt = EvtGuest::base(ctx.contract, name);
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, name, Some(msg_depth), query_depth, || {
+            let evt = EvtGuest::base(ctx.contract, name);
 
-    let auth_response = catch_event! {
-        {
-            call_in_1_out_1::<_, _, GenericResult<AuthResponse>>(
-                vm.clone(),
-                storage.clone(),
-                gas_tracker.clone(),
-                query_depth,
-                state_mutable,
-                name,
-                code_hash,
-                ctx,
-                param,
-            )?
-            .map_err(|msg| AppError::Guest {
-                address: ctx.contract,
-                name,
-                msg,
-            })
-        },
-        evt
-    };
+            let auth_response = catch_event! {
+                {
+                    call_in_1_out_1::<_, _, GenericResult<AuthResponse>>(
+                        vm.clone(),
+                        storage.clone(),
+                        gas_tracker.clone(),
+                        query_depth,
+                        state_mutable,
+                        name,
+                        code_hash,
+                        ctx,
+                        param,
+                    )?
+                    .map_err(|msg| AppError::Guest {
+                        address: ctx.contract,
+                        name,
+                        msg,
+                    })
+                },
+                evt
+            };
 
-    *backrun = auth_response.request_backrun;
+            *backrun = auth_response.request_backrun;
 
-    handle_response(
-        vm,
-        storage,
-        gas_tracker,
-        msg_depth,
-        ctx,
-        auth_response.response,
-        evt,
-    )
+            handle_response(
+                vm,
+                storage,
+                gas_tracker,
+                msg_depth,
+                ctx,
+                auth_response.response,
+                evt,
+            )
+        })
 }
 
 /// Create a VM instance, call a function that takes exactly two parameter and
@@ -299,32 +311,36 @@ where
     VM: Vm + Clone + 'static,
     AppError: From<VM::Error>,
 {
-    let evt = EvtGuest::base(ctx.contract, name);
+    gas_tracker
+        .clone()
+        .profile_call(ctx.contract, name, Some(msg_depth), query_depth, || {
+            let evt = EvtGuest::base(ctx.contract, name);
 
-    let response = catch_event! {
-        {
-            call_in_2_out_1::<_, _, _, GenericResult<Response>>(
-                vm.clone(),
-                storage.clone(),
-                gas_tracker.clone(),
-                query_depth,
-                state_mutable,
-                name,
-                code_hash,
-                ctx,
-                param1,
-                param2
-            )?
-            .map_err(|msg| AppError::Guest {
-                address: ctx.contract,
-                name,
-                msg,
-            })
-        },
-        evt
-    };
+            let response = catch_event! {
+                {
+                    call_in_2_out_1::<_, _, _, GenericResult<Response>>(
+                        vm.clone(),
+                        storage.clone(),
+                        gas_tracker.clone(),
+                        query_depth,
+                        state_mutable,
+                        name,
+                        code_hash,
+                        ctx,
+                        param1,
+                        param2
+                    )?
+                    .map_err(|msg| AppError::Guest {
+                        address: ctx.contract,
+                        name,
+                        msg,
+                    })
+                },
+                evt
+            };
 
-    handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+            handle_response(vm, storage, gas_tracker, msg_depth, ctx, response, evt)
+        })
 }
 
 fn create_vm_instance<VM>(
@@ -1,12 +1,13 @@
 use {
     crate::{AdminOption, GasOption},
     anyhow::{bail, ensure},
-    grug_jmt::Proof,
+    grug_jmt::{MultiProof, Proof},
     grug_math::Inner,
     grug_types::{
-        Addr, Binary, Code, Coin, Coins, Config, ContractInfo, Denom, GenericResult, Hash256,
-        HashExt, JsonDeExt, JsonSerExt, Message, NonEmpty, Query, QueryResponse, Signer, StdError,
-        Tx, TxOutcome, UnsignedTx,
+        Addr, Binary, BorshDeExt, Code, Coin, Coins, Config, ContractInfo, Denom, GenericResult,
+        Hash256, HashExt, JsonDeExt, JsonSerExt, Message, NonEmpty, Query, QueryResponse,
+        QueryStoreManyRequest, QueryStoreScanRequest, Signer, SimulateRequest, StateOverrides,
+        StdError, Tx, TxOutcome, UnsignedTx,
     },
     serde::{de::DeserializeOwned, ser::Serialize},
     std::{any::type_name, collections::BTreeMap, ops::Deref},
@@ -156,6 +157,92 @@ impl Client {
         Ok((value, proof))
     }
 
+    /// Make raw queries of multiple keys at the Grug app's storage at once.
+    ///
+    /// ## Parameters
+    ///
+    /// - `keys`: The raw storage keys. The node may reject the query if there
+    ///   are too many keys.
+    /// - `height`: The block height to perform the queries. If unspecified, the
+    ///   latest height is used.
+    /// - `proof`: Whether to request a Merkle proof. If so, a multi-proof of all
+    ///   keys is returned, in which sibling nodes shared by the keys are only
+    ///   included once.
+    ///
+    /// Values are returned in the same order as the keys, and so are the items
+    /// in the multi-proof.
+    pub async fn query_store_many(
+        &self,
+        keys: Vec<Vec<u8>>,
+        height: Option<u64>,
+        prove: bool,
+    ) -> anyhow::Result<(Vec<Option<Vec<u8>>>, Option<MultiProof>)> {
+        let num_keys = keys.len();
+        let req = QueryStoreManyRequest {
+            keys: keys.into_iter().map(Into::into).collect(),
+        };
+
+        let res = self
+            .query("/store_many", req.to_json_vec()?, height, prove)
+            .await?;
+
+        let values = res
+            .value
+            .deserialize_json::<Vec<Option<Binary>>>()?
+            .into_iter()
+            .map(|value| value.map(Binary::into_inner))
+            .collect::<Vec<_>>();
+
+        ensure!(values.len() == num_keys);
+
+        // Do some basic sanity checks of the Merkle proof returned, and
+        // deserialize it.
+        // If the Grug app works properly, these should always succeed.
+        let proof = if prove {
+            ensure!(res.proof.is_some());
+            let proof = res.proof.unwrap();
+            ensure!(proof.ops.len() == 1);
+            ensure!(proof.ops[0].field_type == type_name::<MultiProof>());
+            Some(proof.ops[0].data.deserialize_borsh()?)
+        } else {
+            ensure!(res.proof.is_none());
+            None
+        };
+
+        Ok((values, proof))
+    }
+
+    /// Enumerate raw key-value pairs in the Grug app's storage, in ascending
+    /// order.
+    ///
+    /// ## Parameters
+    ///
+    /// - `min`: The lower bound of the keys, inclusive.
+    /// - `max`: The upper bound of the keys, exclusive.
+    /// - `limit`: The maximum number of records to return. The node may return
+    ///   fewer than this even if more records exist.
+    /// - `height`: The block height to perform the query. If unspecified, the
+    ///   latest height is used.
+    pub async fn query_store_scan(
+        &self,
+        min: Option<Vec<u8>>,
+        max: Option<Vec<u8>>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> anyhow::Result<BTreeMap<Binary, Binary>> {
+        let req = QueryStoreScanRequest {
+            min: min.map(Into::into),
+            max: max.map(Into::into),
+            limit,
+        };
+
+        self.query("/store_scan", req.to_json_vec()?, height, false)
+            .await?
+            .value
+            .deserialize_json()
+            .map_err(Into::into)
+    }
+
     /// Query the Grug app.
     ///
     /// Used internally. Use the `query_{info,balance,wasm_smart,...}` methods
@@ -300,7 +387,23 @@ impl Client {
 
     /// Simulate the gas usage of a transaction.
     pub async fn simulate(&self, unsigned_tx: &UnsignedTx) -> anyhow::Result<TxOutcome> {
-        self.query("/simulate", unsigned_tx.to_json_vec()?, None, false)
+        self.simulate_with_overrides(unsigned_tx, &StateOverrides::default())
+            .await
+    }
+
+    /// Simulate the gas usage of a transaction, against the state with the
+    /// given overrides applied. The overrides only affect this simulation.
+    pub async fn simulate_with_overrides(
+        &self,
+        unsigned_tx: &UnsignedTx,
+        overrides: &StateOverrides,
+    ) -> anyhow::Result<TxOutcome> {
+        let req = SimulateRequest {
+            tx: unsigned_tx.clone(),
+            overrides: overrides.clone(),
+        };
+
+        self.query("/simulate", req.to_json_vec()?, None, false)
             .await?
             .value
             .deserialize_json()
@@ -1,13 +1,21 @@
 use {
+    ark_bls12_381::{g2, Fr, G1Affine, G2Affine, G2Projective},
+    ark_ec::{
+        hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
+        AffineRepr,
+    },
+    ark_ff::field_hashers::DefaultFieldHasher,
+    ark_serialize::CanonicalSerialize,
     criterion::{
         black_box, criterion_group, criterion_main, AxisScale, BatchSize, BenchmarkId, Criterion,
         PlotConfiguration,
     },
     ed25519_dalek::Signer,
     grug_crypto::{
-        blake2b_512, blake2s_256, blake3, ed25519_batch_verify, ed25519_verify, keccak256,
-        secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify, sha2_256, sha2_512, sha3_256,
-        sha3_512, Identity256, Identity512,
+        blake2b_512, blake2s_256, blake3, bls12_381_aggregate_verify, bls12_381_pairing_equality,
+        bls12_381_verify, ed25519_batch_verify, ed25519_verify, keccak256,
+        secp256k1_pubkey_recover, secp256k1_schnorr_verify, secp256k1_verify, secp256r1_verify,
+        sha2_256, sha2_512, sha3_256, sha3_512, Identity256, Identity512,
     },
     p256::ecdsa::signature::DigestSigner,
     rand::{rngs::OsRng, RngCore},
@@ -49,12 +57,43 @@ const SIGN_MSG_LEN: usize = 10;
 /// to 150 validators, so we choose a number of batch sizes up to that.
 const ED25519_BATCH_SIZES: [usize; 6] = [25, 50, 75, 100, 125, 150];
 
+/// Numbers of public keys for benchmarking `bls12_381_aggregate_verify`.
+///
+/// Ethereum sync committees have 512 members, but typically only a fraction of
+/// them is needed to be provided for a light client update.
+const BLS12_381_AGGREGATE_SIZES: [usize; 4] = [1, 16, 64, 256];
+
+/// Numbers of pairs for benchmarking `bls12_381_pairing_equality`.
+const BLS12_381_PAIRING_SIZES: [usize; 4] = [1, 4, 8, 16];
+
+/// Domain separation tag used by Ethereum for BLS12-381 signatures.
+const BLS12_381_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
 fn generate_random_msg(i: usize) -> Vec<u8> {
     let mut vec = vec![0; i];
     OsRng.fill_bytes(&mut vec);
     vec
 }
 
+fn generate_random_scalar() -> Fr {
+    Fr::from(OsRng.next_u64())
+}
+
+fn bls12_381_hash_to_g2(msg: &[u8]) -> G2Affine {
+    MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<sha2::Sha256>, WBMap<g2::Config>>::new(
+        BLS12_381_DST,
+    )
+    .unwrap()
+    .hash(msg)
+    .unwrap()
+}
+
+fn bls12_381_serialize<T: CanonicalSerialize>(point: T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    point.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
 fn bench_hashers(c: &mut Criterion) {
     let mut group = c.benchmark_group("hashers");
 
@@ -217,6 +256,27 @@ fn bench_verifiers(c: &mut Criterion) {
         );
     });
 
+    group.bench_function("secp256k1_schnorr_verify", |b| {
+        b.iter_batched(
+            || {
+                let msg = generate_random_msg(SIGN_MSG_LEN);
+                let msg_hash = sha2_256(&msg);
+                let sk = k256::schnorr::SigningKey::random(&mut OsRng);
+                let sig = sk.sign_raw(&msg_hash, &[0; 32]).unwrap();
+
+                (
+                    msg_hash.to_vec(),
+                    sig.to_bytes().to_vec(),
+                    sk.verifying_key().to_bytes().to_vec(),
+                )
+            },
+            |(msg_hash, sig, vk)| {
+                assert!(secp256k1_schnorr_verify(&msg_hash, &sig, &vk).is_ok());
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
     group.bench_function("ed25519_verify", |b| {
         b.iter_batched(
             || {
@@ -278,6 +338,94 @@ fn bench_verifiers(c: &mut Criterion) {
         );
     }
 
+    group.bench_function("bls12_381_verify", |b| {
+        b.iter_batched(
+            || {
+                let msg = generate_random_msg(SIGN_MSG_LEN);
+                let sk = generate_random_scalar();
+                let pk = bls12_381_serialize(G1Affine::generator() * sk);
+                let sig = bls12_381_serialize(bls12_381_hash_to_g2(&msg) * sk);
+
+                (msg, sig, pk)
+            },
+            |(msg, sig, pk)| {
+                assert!(bls12_381_verify(&msg, BLS12_381_DST, &sig, &pk).is_ok());
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    for size in BLS12_381_AGGREGATE_SIZES {
+        group.bench_with_input(
+            BenchmarkId::new("bls12_381_aggregate_verify", size),
+            &size,
+            |b, size| {
+                b.iter_batched(
+                    || {
+                        let msg = generate_random_msg(SIGN_MSG_LEN);
+                        let mut agg_sk = Fr::from(0);
+                        let mut pks = vec![];
+
+                        for _ in 0..*size {
+                            let sk = generate_random_scalar();
+                            agg_sk += sk;
+                            pks.push(bls12_381_serialize(G1Affine::generator() * sk));
+                        }
+
+                        // Signing with the sum of the private keys is equivalent
+                        // to aggregating the individual signatures.
+                        let sig = bls12_381_serialize(bls12_381_hash_to_g2(&msg) * agg_sk);
+
+                        (msg, sig, pks)
+                    },
+                    |(msg, sig, pks)| {
+                        let pks: Vec<_> = pks.iter().map(|k| k.as_slice()).collect();
+                        assert!(
+                            bls12_381_aggregate_verify(&msg, BLS12_381_DST, &sig, &pks).is_ok()
+                        );
+                    },
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+
+    for size in BLS12_381_PAIRING_SIZES {
+        group.bench_with_input(
+            BenchmarkId::new("bls12_381_pairing_equality", size),
+            &size,
+            |b, size| {
+                b.iter_batched(
+                    || {
+                        let mut sum = Fr::from(0);
+                        let mut ps = vec![];
+                        let mut qs = vec![];
+
+                        // e(a1 * G1, b1 * G2) * ... * e(an * G1, bn * G2)
+                        // = e((a1 * b1 + ... + an * bn) * G1, G2)
+                        for _ in 0..*size {
+                            let (a, b) = (generate_random_scalar(), generate_random_scalar());
+                            sum += a * b;
+                            ps.push(bls12_381_serialize(G1Affine::generator() * a));
+                            qs.push(bls12_381_serialize(G2Affine::generator() * b));
+                        }
+
+                        let r = bls12_381_serialize(G1Affine::generator() * sum);
+                        let s = bls12_381_serialize(G2Affine::generator());
+
+                        (ps, qs, r, s)
+                    },
+                    |(ps, qs, r, s)| {
+                        let ps: Vec<_> = ps.iter().map(|p| p.as_slice()).collect();
+                        let qs: Vec<_> = qs.iter().map(|q| q.as_slice()).collect();
+                        assert!(bls12_381_pairing_equality(&ps, &qs, &r, &s).is_ok());
+                    },
+                    BatchSize::SmallInput,
+                );
+            },
+        );
+    }
+
     group.finish();
 }
 
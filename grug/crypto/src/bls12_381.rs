@@ -0,0 +1,275 @@
+use {
+    crate::{to_sized, CryptoError, CryptoResult},
+    ark_bls12_381::{g2, Bls12_381, G1Affine, G1Projective, G2Affine, G2Projective},
+    ark_ec::{
+        hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
+        pairing::Pairing,
+        AffineRepr, CurveGroup,
+    },
+    ark_ff::{field_hashers::DefaultFieldHasher, Zero},
+    ark_serialize::CanonicalDeserialize,
+    sha2::Sha256,
+};
+
+const BLS12_381_G1_LEN: usize = 48;
+const BLS12_381_G2_LEN: usize = 96;
+
+/// Verify a BLS12-381 signature with the given message and public key, in the
+/// "minimal public key size" variant used by Ethereum: public keys are G1
+/// points, and signatures are G2 points, both in compressed form.
+///
+/// The message is hashed to G2 with the `BLS12381G2_XMD:SHA-256_SSWU_RO_` hash
+/// to curve suite, using the given domain separation tag (DST). For Ethereum,
+/// the DST is `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`.
+///
+/// NOTE: Unlike the other signature verifiers in this crate, this one takes the
+/// prehash message, as hashing to the curve is part of the signature scheme.
+pub fn bls12_381_verify(msg: &[u8], dst: &[u8], sig: &[u8], pk: &[u8]) -> CryptoResult<()> {
+    let pk = g1_from_bytes(pk)?;
+
+    if pk.is_zero() {
+        return Err(CryptoError::InvalidPoint);
+    }
+
+    verify(msg, dst, sig, pk.into_group())
+}
+
+/// Verify an aggregate BLS12-381 signature of the same message signed by
+/// multiple public keys, such as an Ethereum sync committee signature. Public
+/// keys and signature are in the same format as [`bls12_381_verify`].
+///
+/// This is the `FastAggregateVerify` function of the
+/// [IETF draft](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-05#section-3.3.4),
+/// which is only secure if each public key has come with a proof of possession
+/// of the private key. It is up to the caller to ensure this.
+pub fn bls12_381_aggregate_verify(
+    msg: &[u8],
+    dst: &[u8],
+    sig: &[u8],
+    pks: &[&[u8]],
+) -> CryptoResult<()> {
+    if pks.is_empty() {
+        return Err(signature::Error::new().into());
+    }
+
+    let mut agg_pk = G1Projective::zero();
+
+    for pk in pks {
+        let pk = g1_from_bytes(pk)?;
+
+        if pk.is_zero() {
+            return Err(CryptoError::InvalidPoint);
+        }
+
+        agg_pk += pk;
+    }
+
+    verify(msg, dst, sig, agg_pk)
+}
+
+/// Check whether the pairing equality `e(p1, q1) * ... * e(pn, qn) = e(r, s)`
+/// holds, where `ps` and `r` are compressed G1 points, and `qs` and `s` are
+/// compressed G2 points.
+///
+/// This can be used to verify signature schemes other than those supported by
+/// [`bls12_381_verify`], e.g. threshold signatures with public keys in G2.
+pub fn bls12_381_pairing_equality(
+    ps: &[&[u8]],
+    qs: &[&[u8]],
+    r: &[u8],
+    s: &[u8],
+) -> CryptoResult<()> {
+    if ps.len() != qs.len() {
+        return Err(CryptoError::IncorrectLength {
+            expect: ps.len(),
+            actual: qs.len(),
+        });
+    }
+
+    let mut g1s = ps
+        .iter()
+        .map(|p| g1_from_bytes(p))
+        .collect::<CryptoResult<Vec<_>>>()?;
+    let mut g2s = qs
+        .iter()
+        .map(|q| g2_from_bytes(q))
+        .collect::<CryptoResult<Vec<_>>>()?;
+
+    // e(p1, q1) * ... * e(pn, qn) = e(r, s)
+    // is equivalent to
+    // e(p1, q1) * ... * e(pn, qn) * e(-r, s) = 1
+    g1s.push(-g1_from_bytes(r)?);
+    g2s.push(g2_from_bytes(s)?);
+
+    if Bls12_381::multi_pairing(g1s, g2s).is_zero() {
+        Ok(())
+    } else {
+        Err(signature::Error::new().into())
+    }
+}
+
+/// Verify a signature against a public key that is already deserialized.
+fn verify(msg: &[u8], dst: &[u8], sig: &[u8], pk: G1Projective) -> CryptoResult<()> {
+    // An aggregate public key at infinity would accept the signature at
+    // infinity for any message.
+    if pk.is_zero() {
+        return Err(signature::Error::new().into());
+    }
+
+    let sig = g2_from_bytes(sig)?;
+    let msg_point = hash_to_g2(msg, dst)?;
+
+    // e(pk, H(msg)) = e(G1, sig)
+    // is equivalent to
+    // e(pk, H(msg)) * e(-G1, sig) = 1
+    let g1s = [pk.into_affine(), -G1Affine::generator()];
+    let g2s = [msg_point, sig];
+
+    if Bls12_381::multi_pairing(g1s, g2s).is_zero() {
+        Ok(())
+    } else {
+        Err(signature::Error::new().into())
+    }
+}
+
+fn hash_to_g2(msg: &[u8], dst: &[u8]) -> CryptoResult<G2Affine> {
+    MapToCurveBasedHasher::<G2Projective, DefaultFieldHasher<Sha256>, WBMap<g2::Config>>::new(dst)
+        .and_then(|hasher| hasher.hash(msg))
+        .map_err(|_| CryptoError::InvalidPoint)
+}
+
+/// Deserialize a compressed G1 point, ensuring it's on the curve and in the
+/// prime-order subgroup.
+fn g1_from_bytes(bytes: &[u8]) -> CryptoResult<G1Affine> {
+    let bytes = to_sized::<BLS12_381_G1_LEN>(bytes)?;
+
+    G1Affine::deserialize_compressed(bytes.as_slice()).map_err(|_| CryptoError::InvalidPoint)
+}
+
+/// Deserialize a compressed G2 point, ensuring it's on the curve and in the
+/// prime-order subgroup.
+fn g2_from_bytes(bytes: &[u8]) -> CryptoResult<G2Affine> {
+    let bytes = to_sized::<BLS12_381_G2_LEN>(bytes)?;
+
+    G2Affine::deserialize_compressed(bytes.as_slice()).map_err(|_| CryptoError::InvalidPoint)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        ark_bls12_381::Fr,
+        ark_serialize::CanonicalSerialize,
+    };
+
+    const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+    #[test]
+    fn verify_bls12_381() {
+        let (sk, pk) = keypair(1);
+        let msg = b"Jake";
+        let sig = sign(sk, msg);
+
+        // Valid signature
+        {
+            assert!(bls12_381_verify(msg, DST, &sig, &pk).is_ok());
+        }
+
+        // Incorrect private key
+        {
+            let (false_sk, _) = keypair(2);
+            let false_sig = sign(false_sk, msg);
+            assert!(bls12_381_verify(msg, DST, &false_sig, &pk).is_err());
+        }
+
+        // Incorrect message
+        {
+            let false_msg = b"Larry";
+            assert!(bls12_381_verify(false_msg, DST, &sig, &pk).is_err());
+        }
+    }
+
+    #[test]
+    fn verify_aggregate_bls12_381() {
+        let msg = b"Jake";
+        let (sk1, pk1) = keypair(1);
+        let (sk2, pk2) = keypair(2);
+        let (sk3, pk3) = keypair(3);
+
+        // The aggregate signature is the signature of the sum of the private keys.
+        let sig = sign(sk1 + sk2 + sk3, msg);
+
+        // Valid signature
+        {
+            assert!(bls12_381_aggregate_verify(msg, DST, &sig, &[&pk1, &pk2, &pk3]).is_ok());
+        }
+
+        // Missing signer
+        {
+            assert!(bls12_381_aggregate_verify(msg, DST, &sig, &[&pk1, &pk2]).is_err());
+        }
+
+        // No signer
+        {
+            assert!(bls12_381_aggregate_verify(msg, DST, &sig, &[]).is_err());
+        }
+    }
+
+    #[test]
+    fn pairing_equality_bls12_381() {
+        let (a, b) = (Fr::from(3), Fr::from(5));
+        let g1 = |s: Fr| serialize(G1Affine::generator() * s);
+        let g2 = |s: Fr| serialize(G2Affine::generator() * s);
+
+        // e(3 * G1, 5 * G2) * e(G1, G2) = e(16 * G1, G2)
+        {
+            assert!(bls12_381_pairing_equality(
+                &[&g1(a), &g1(Fr::from(1))],
+                &[&g2(b), &g2(Fr::from(1))],
+                &g1(Fr::from(16)),
+                &g2(Fr::from(1)),
+            )
+            .is_ok());
+        }
+
+        // e(3 * G1, 5 * G2) != e(16 * G1, G2)
+        {
+            assert!(bls12_381_pairing_equality(
+                &[&g1(a)],
+                &[&g2(b)],
+                &g1(Fr::from(16)),
+                &g2(Fr::from(1)),
+            )
+            .is_err());
+        }
+
+        // Wrong len
+        {
+            assert!(bls12_381_pairing_equality(
+                &[&g1(a), &g1(a)],
+                &[&g2(b)],
+                &g1(Fr::from(15)),
+                &g2(Fr::from(1)),
+            )
+            .is_err());
+        }
+    }
+
+    fn keypair(seed: u64) -> (Fr, Vec<u8>) {
+        let sk = Fr::from(seed * 1_000_003);
+        let pk = serialize(G1Affine::generator() * sk);
+        (sk, pk)
+    }
+
+    fn sign(sk: Fr, msg: &[u8]) -> Vec<u8> {
+        serialize(hash_to_g2(msg, DST).unwrap() * sk)
+    }
+
+    fn serialize<T: CanonicalSerialize>(point: T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        point.serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+}
@@ -24,6 +24,25 @@ pub fn ed25519_verify(msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> CryptoResult<()
     vk.verify_digest(msg_hash, &sig).map_err(Into::into)
 }
 
+/// Verify a pure Ed25519 signature, as specified in RFC 8032, with the given
+/// message and public key.
+///
+/// The signature is checked with the strict, cofactorless verification
+/// equation, which rejects signatures with a non-canonical `R` or `s`, and
+/// public keys of small order.
+///
+/// NOTE: Unlike `ed25519_verify`, this function takes the prehash message, not
+/// its hash, as hashing the message is part of the signature scheme.
+pub fn ed25519_pure_verify(msg: &[u8], sig: &[u8], pk: &[u8]) -> CryptoResult<()> {
+    let sig = to_sized::<ED25519_SIGNATURE_LEN>(sig)?;
+    let sig = Signature::from(sig);
+
+    let vk = to_sized::<ED25519_PUBKEY_LEN>(pk)?;
+    let vk = VerifyingKey::from_bytes(&vk)?;
+
+    vk.verify_strict(msg, &sig).map_err(Into::into)
+}
+
 /// Verify a batch of Ed25519 signatures with the given _prehash_ messages and
 /// and public keys.
 ///
@@ -94,6 +113,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn verify_pure_ed25519() {
+        let (msg, sig, vk) = ed25519_sign("Jake");
+
+        // Valid signature
+        {
+            assert!(ed25519_pure_verify(&msg, &sig, &vk).is_ok());
+        }
+
+        // Incorrect private key
+        {
+            let (_, false_sig, _) = ed25519_sign("Jake");
+            assert!(ed25519_pure_verify(&msg, &false_sig, &vk).is_err());
+        }
+
+        // Incorrect message
+        {
+            assert!(ed25519_pure_verify(b"Larry", &sig, &vk).is_err());
+        }
+
+        // Ed25519ph signature of the same message, by the same key
+        {
+            let sk = SigningKey::generate(&mut OsRng);
+            let vk = VerifyingKey::from(&sk);
+            let pure_sig = sk.sign(&msg);
+            let ph_sig = sk.sign_digest(Identity512::from(sha2_512(&msg)));
+            assert!(ed25519_pure_verify(&msg, &pure_sig.to_bytes(), vk.as_bytes()).is_ok());
+            assert!(ed25519_pure_verify(&msg, &ph_sig.to_bytes(), vk.as_bytes()).is_err());
+        }
+    }
+
     #[test]
     fn verify_batch_ed25519() {
         let (prehash_msg1, sig1, vk1) = ed25519_sign("Jake");
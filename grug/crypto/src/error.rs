@@ -16,6 +16,9 @@ pub enum CryptoError {
 
     #[error("invalid recovery id {recovery_id}")]
     InvalidRecoveryId { recovery_id: u8 },
+
+    #[error("invalid elliptic curve point")]
+    InvalidPoint,
 }
 
 impl CryptoError {
@@ -26,6 +29,7 @@ impl CryptoError {
             Self::IncorrectLength { .. } | Self::IncorrectLengths { .. } => 1,
             Self::InvalidRecoveryId { .. } => 2,
             Self::Signature(_) => 3,
+            Self::InvalidPoint => 4,
         }
     }
 }
@@ -1,11 +1,15 @@
 use {
     crate::{to_sized, CryptoError, CryptoResult, Identity256},
-    k256::ecdsa::{signature::DigestVerifier, RecoveryId, Signature, VerifyingKey},
+    k256::{
+        ecdsa::{signature::DigestVerifier, RecoveryId, Signature, VerifyingKey},
+        schnorr,
+    },
 };
 
 const SECP256K1_DIGEST_LEN: usize = 32;
 const SECP256K1_PUBKEY_LENS: [usize; 2] = [33, 65]; // compressed, uncompressed
 const SECP256K1_SIGNATURE_LEN: usize = 64;
+const SECP256K1_SCHNORR_PUBKEY_LEN: usize = 32; // x-only
 
 /// NOTE: This function takes the hash of the message, not the prehash.
 pub fn secp256k1_verify(msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> CryptoResult<()> {
@@ -75,6 +79,23 @@ pub fn secp256k1_pubkey_recover(
         .map_err(Into::into)
 }
 
+/// Verify a [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki)
+/// Schnorr signature, as used by Bitcoin Taproot, with the given hashed message
+/// and x-only public key.
+///
+/// NOTE: This function takes the hash of the message, not the prehash.
+pub fn secp256k1_schnorr_verify(msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> CryptoResult<()> {
+    let msg_hash = to_sized::<SECP256K1_DIGEST_LEN>(msg_hash)?;
+
+    let sig = to_sized::<SECP256K1_SIGNATURE_LEN>(sig)?;
+    let sig = schnorr::Signature::try_from(sig.as_slice())?;
+
+    let pk = to_sized::<SECP256K1_SCHNORR_PUBKEY_LEN>(pk)?;
+    let vk = schnorr::VerifyingKey::from_bytes(&pk)?;
+
+    vk.verify_raw(&msg_hash, &sig).map_err(Into::into)
+}
+
 // ----------------------------------- tests -----------------------------------
 
 #[cfg(test)]
@@ -82,6 +103,7 @@ mod tests {
     use {
         super::*,
         crate::sha2_256,
+        hex_literal::hex,
         k256::ecdsa::{signature::DigestSigner, Signature, SigningKey},
         rand::rngs::OsRng,
     };
@@ -153,4 +175,51 @@ mod tests {
             assert_eq!(recovered_pk, vk.to_encoded_point(false).as_bytes());
         }
     }
+
+    #[test]
+    fn verifying_secp256k1_schnorr() {
+        // Generate a valid signature
+        let sk = schnorr::SigningKey::random(&mut OsRng);
+        let vk = sk.verifying_key().to_bytes();
+        let msg = b"Jake";
+        let msg_hash = sha2_256(msg);
+        let sig = sk.sign_raw(&msg_hash, &[0; 32]).unwrap().to_bytes();
+
+        // Valid signature
+        {
+            assert!(secp256k1_schnorr_verify(&msg_hash, &sig, &vk).is_ok());
+        }
+
+        // Incorrect private key
+        {
+            let false_sk = schnorr::SigningKey::random(&mut OsRng);
+            let false_sig = false_sk.sign_raw(&msg_hash, &[0; 32]).unwrap().to_bytes();
+            assert!(secp256k1_schnorr_verify(&msg_hash, &false_sig, &vk).is_err());
+        }
+
+        // Incorrect message
+        {
+            let false_msg_hash = sha2_256(b"Larry");
+            assert!(secp256k1_schnorr_verify(&false_msg_hash, &sig, &vk).is_err());
+        }
+
+        // Public key in compressed SEC1 form instead of x-only
+        {
+            let compressed_vk = [&[0x02], vk.as_slice()].concat();
+            assert!(secp256k1_schnorr_verify(&msg_hash, &sig, &compressed_vk).is_err());
+        }
+    }
+
+    /// Test vector 0 from BIP-340.
+    #[test]
+    fn verifying_secp256k1_schnorr_bip340_vector() {
+        let pk = hex!("F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9");
+        let msg = [0; 32];
+        let sig = hex!(
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215"
+            "25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0"
+        );
+
+        assert!(secp256k1_schnorr_verify(&msg, &sig, &pk).is_ok());
+    }
 }
@@ -0,0 +1,161 @@
+use {
+    grug_crypto::{bls12_381_aggregate_verify, bls12_381_pairing_equality, bls12_381_verify},
+    serde::{de::DeserializeOwned, Deserialize},
+    std::{fmt::Debug, fs::File, io::BufReader},
+};
+
+const VERIFY_FILE: &str = "./testdata/bls12_381/bls12_381_verify_test.json";
+const AGGREGATE_VERIFY_FILE: &str = "./testdata/bls12_381/bls12_381_aggregate_verify_test.json";
+const PAIRING_EQUALITY_FILE: &str = "./testdata/bls12_381/bls12_381_pairing_equality_test.json";
+
+// -------------------------------- file struct --------------------------------
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestFile<T> {
+    pub number_of_tests: usize,
+    pub test_groups: Vec<TestGroup<T>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestGroup<T> {
+    #[serde(default)]
+    pub dst: String,
+    pub tests: Vec<T>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyTestCase {
+    pub tc_id: usize,
+    pub comment: String,
+    #[serde(with = "hex")]
+    pub pk: Vec<u8>,
+    #[serde(with = "hex")]
+    pub msg: Vec<u8>,
+    #[serde(with = "hex")]
+    pub sig: Vec<u8>,
+    // "valid" or "invalid"
+    pub result: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregateVerifyTestCase {
+    pub tc_id: usize,
+    pub comment: String,
+    pub pks: Vec<String>,
+    #[serde(with = "hex")]
+    pub msg: Vec<u8>,
+    #[serde(with = "hex")]
+    pub sig: Vec<u8>,
+    pub result: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingEqualityTestCase {
+    pub tc_id: usize,
+    pub comment: String,
+    pub ps: Vec<String>,
+    pub qs: Vec<String>,
+    #[serde(with = "hex")]
+    pub r: Vec<u8>,
+    #[serde(with = "hex")]
+    pub s: Vec<u8>,
+    pub result: String,
+}
+
+fn read_file<T>(path: &str) -> TestFile<T>
+where
+    T: DeserializeOwned,
+{
+    let file = File::open(path).unwrap();
+    let reader = BufReader::new(file);
+
+    serde_json::from_reader(reader).unwrap()
+}
+
+fn decode_all(hexes: &[String]) -> Vec<Vec<u8>> {
+    hexes.iter().map(|h| hex::decode(h).unwrap()).collect()
+}
+
+fn assert_result<E>(tc_id: usize, comment: &str, result: &str, outcome: Result<(), E>)
+where
+    E: Debug,
+{
+    eprintln!("Test case ID: {tc_id} ({comment})");
+
+    match result {
+        "valid" => outcome.unwrap(),
+        "invalid" => {
+            outcome.unwrap_err();
+        },
+        _ => panic!("Found unexpected result value"),
+    }
+}
+
+// ------------------------------ test definition ------------------------------
+
+#[test]
+fn bls12_381_verify_works() {
+    let mut tested = 0;
+    let file = read_file::<VerifyTestCase>(VERIFY_FILE);
+
+    for group in file.test_groups {
+        for tc in group.tests {
+            tested += 1;
+            assert_eq!(tc.tc_id, tested);
+
+            let outcome = bls12_381_verify(&tc.msg, group.dst.as_bytes(), &tc.sig, &tc.pk);
+            assert_result(tc.tc_id, &tc.comment, &tc.result, outcome);
+        }
+    }
+
+    assert_eq!(tested, file.number_of_tests);
+}
+
+#[test]
+fn bls12_381_aggregate_verify_works() {
+    let mut tested = 0;
+    let file = read_file::<AggregateVerifyTestCase>(AGGREGATE_VERIFY_FILE);
+
+    for group in file.test_groups {
+        for tc in group.tests {
+            tested += 1;
+            assert_eq!(tc.tc_id, tested);
+
+            let pks = decode_all(&tc.pks);
+            let pks = pks.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+            let outcome = bls12_381_aggregate_verify(&tc.msg, group.dst.as_bytes(), &tc.sig, &pks);
+            assert_result(tc.tc_id, &tc.comment, &tc.result, outcome);
+        }
+    }
+
+    assert_eq!(tested, file.number_of_tests);
+}
+
+#[test]
+fn bls12_381_pairing_equality_works() {
+    let mut tested = 0;
+    let file = read_file::<PairingEqualityTestCase>(PAIRING_EQUALITY_FILE);
+
+    for group in file.test_groups {
+        for tc in group.tests {
+            tested += 1;
+            assert_eq!(tc.tc_id, tested);
+
+            let ps = decode_all(&tc.ps);
+            let ps = ps.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            let qs = decode_all(&tc.qs);
+            let qs = qs.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+            let outcome = bls12_381_pairing_equality(&ps, &qs, &tc.r, &tc.s);
+            assert_result(tc.tc_id, &tc.comment, &tc.result, outcome);
+        }
+    }
+
+    assert_eq!(tested, file.number_of_tests);
+}
@@ -1,11 +1,11 @@
 use {
     crate::{DbError, DbResult, U64Comparator, U64Timestamp},
-    grug_app::{Buffer, Db, PrunableDb},
-    grug_jmt::{MerkleTree, Proof, ICS23_PROOF_SPEC},
+    grug_app::{Buffer, Db, PrunableDb, SnapshotDb},
+    grug_jmt::{MerkleTree, MultiProof, Proof, ICS23_PROOF_SPEC},
     grug_types::{Batch, Hash256, HashExt, Op, Order, Record, Storage},
     ics23::{
-        commitment_proof::Proof as CommitmentProofInner, CommitmentProof, ExistenceProof,
-        NonExistenceProof,
+        batch_entry, commitment_proof::Proof as CommitmentProofInner, BatchEntry, BatchProof,
+        CommitmentProof, ExistenceProof, NonExistenceProof,
     },
     rocksdb::{
         BoundColumnFamily, DBWithThreadMode, Direction, IteratorMode, MultiThreaded, Options,
@@ -119,6 +119,75 @@ impl DiskDb {
             }),
         })
     }
+
+    /// Generate an ICS-23 existence or non-existence proof of the given key at
+    /// the given version, as an entry of a batch proof.
+    fn ics23_prove_entry(&self, key: Vec<u8>, version: u64) -> DbResult<batch_entry::Proof> {
+        let state_storage = self.state_storage(Some(version))?;
+        let state_commitment = self.state_commitment();
+
+        let generate_existence_proof = |key: Vec<u8>, value| -> DbResult<_> {
+            let key_hash = key.hash256();
+            let path = MERKLE_TREE.ics23_prove_existence(&state_commitment, version, key_hash)?;
+
+            Ok(ExistenceProof {
+                key,
+                value,
+                leaf: ICS23_PROOF_SPEC.leaf_spec.clone(),
+                path,
+            })
+        };
+
+        let proof = match state_storage.read(&key) {
+            // Value is found. Generate an ICS-23 existence proof.
+            Some(value) => batch_entry::Proof::Exist(generate_existence_proof(key, value)?),
+            // Value is not found.
+            //
+            // Here, unlike Diem or Penumbra's implementation, which walks the
+            // tree to find the left and right neighbors, we use an approach
+            // similar to SeiDB's:
+            // https://github.com/sei-protocol/sei-db/blob/v0.0.43/sc/memiavl/proof.go#L41-L76
+            //
+            // We simply look up the state storage to find the left and right
+            // neighbors, and generate existence proof of them.
+            None => {
+                let cf = cf_preimages(&self.inner.db);
+                let key_hash = key.hash256();
+
+                let opts = new_read_options(Some(version), None, None);
+                let mode = IteratorMode::From(&key_hash, Direction::Reverse);
+                let left = self
+                    .inner
+                    .db
+                    .iterator_cf_opt(&cf, opts, mode)
+                    .next()
+                    .map(|res| {
+                        let (_, key) = res?;
+                        let value = state_storage.read(&key).unwrap();
+                        generate_existence_proof(key.to_vec(), value)
+                    })
+                    .transpose()?;
+
+                let opts = new_read_options(Some(version), None, None);
+                let mode = IteratorMode::From(&key_hash, Direction::Forward);
+                let right = self
+                    .inner
+                    .db
+                    .iterator_cf_opt(&cf, opts, mode)
+                    .next()
+                    .map(|res| {
+                        let (_, key) = res?;
+                        let value = state_storage.read(&key).unwrap();
+                        generate_existence_proof(key.to_vec(), value)
+                    })
+                    .transpose()?;
+
+                batch_entry::Proof::Nonexist(NonExistenceProof { key, left, right })
+            },
+        };
+
+        Ok(proof)
+    }
 }
 
 impl Clone for DiskDb {
@@ -131,6 +200,7 @@ impl Clone for DiskDb {
 
 impl Db for DiskDb {
     type Error = DbError;
+    type MultiProof = MultiProof;
     type Proof = Proof;
     type StateCommitment = StateCommitment;
     type StateStorage = StateStorage;
@@ -208,76 +278,48 @@ impl Db for DiskDb {
         Ok(MERKLE_TREE.prove(&self.state_commitment(), key.hash256(), version)?)
     }
 
+    fn prove_many(&self, keys: Vec<Vec<u8>>, version: Option<u64>) -> DbResult<MultiProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        let key_hashes = keys.iter().map(|key| key.hash256()).collect::<Vec<_>>();
+        Ok(MERKLE_TREE.prove_many(&self.state_commitment(), &key_hashes, version)?)
+    }
+
     fn ics23_prove(
         &self,
         key: Vec<u8>,
         version: Option<u64>,
     ) -> Result<CommitmentProof, Self::Error> {
         let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
-        let state_storage = self.state_storage(Some(version))?;
-        let state_commitment = self.state_commitment();
-
-        let generate_existence_proof = |key: Vec<u8>, value| -> DbResult<_> {
-            let key_hash = key.hash256();
-            let path = MERKLE_TREE.ics23_prove_existence(&state_commitment, version, key_hash)?;
 
-            Ok(ExistenceProof {
-                key,
-                value,
-                leaf: ICS23_PROOF_SPEC.leaf_spec.clone(),
-                path,
-            })
+        let proof = match self.ics23_prove_entry(key, version)? {
+            batch_entry::Proof::Exist(proof) => CommitmentProofInner::Exist(proof),
+            batch_entry::Proof::Nonexist(proof) => CommitmentProofInner::Nonexist(proof),
         };
 
-        let proof = match state_storage.read(&key) {
-            // Value is found. Generate an ICS-23 existence proof.
-            Some(value) => CommitmentProofInner::Exist(generate_existence_proof(key, value)?),
-            // Value is not found.
-            //
-            // Here, unlike Diem or Penumbra's implementation, which walks the
-            // tree to find the left and right neighbors, we use an approach
-            // similar to SeiDB's:
-            // https://github.com/sei-protocol/sei-db/blob/v0.0.43/sc/memiavl/proof.go#L41-L76
-            //
-            // We simply look up the state storage to find the left and right
-            // neighbors, and generate existence proof of them.
-            None => {
-                let cf = cf_preimages(&self.inner.db);
-                let key_hash = key.hash256();
-
-                let opts = new_read_options(Some(version), None, None);
-                let mode = IteratorMode::From(&key_hash, Direction::Reverse);
-                let left = self
-                    .inner
-                    .db
-                    .iterator_cf_opt(&cf, opts, mode)
-                    .next()
-                    .map(|res| {
-                        let (_, key) = res?;
-                        let value = state_storage.read(&key).unwrap();
-                        generate_existence_proof(key.to_vec(), value)
-                    })
-                    .transpose()?;
+        Ok(CommitmentProof { proof: Some(proof) })
+    }
 
-                let opts = new_read_options(Some(version), None, None);
-                let mode = IteratorMode::From(&key_hash, Direction::Forward);
-                let right = self
-                    .inner
-                    .db
-                    .iterator_cf_opt(&cf, opts, mode)
-                    .next()
-                    .map(|res| {
-                        let (_, key) = res?;
-                        let value = state_storage.read(&key).unwrap();
-                        generate_existence_proof(key.to_vec(), value)
-                    })
-                    .transpose()?;
+    fn ics23_prove_batch(
+        &self,
+        keys: Vec<Vec<u8>>,
+        version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error> {
+        // Determine the version upfront, so that all keys are proven at the
+        // same version even if a new one is committed in the meantime.
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
 
-                CommitmentProofInner::Nonexist(NonExistenceProof { key, left, right })
-            },
-        };
+        let entries = keys
+            .into_iter()
+            .map(|key| {
+                Ok(BatchEntry {
+                    proof: Some(self.ics23_prove_entry(key, version)?),
+                })
+            })
+            .collect::<DbResult<Vec<_>>>()?;
 
-        Ok(CommitmentProof { proof: Some(proof) })
+        Ok(ics23::compress(&CommitmentProof {
+            proof: Some(CommitmentProofInner::Batch(BatchProof { entries })),
+        }))
     }
 
     fn flush_but_not_commit(&self, batch: Batch) -> DbResult<(u64, Option<Hash256>)> {
@@ -424,6 +466,47 @@ impl PrunableDb for DiskDb {
     }
 }
 
+impl SnapshotDb for DiskDb {
+    fn restore(&self, version: u64, batch: Batch, root_hash: Hash256) -> DbResult<()> {
+        if let Some(latest_version) = self.latest_version() {
+            return Err(DbError::RestoreNonEmpty { latest_version });
+        }
+
+        // Rebuild the Merkle tree from scratch. Node hashes don't depend on the
+        // versions of the nodes, so this results in the same root hash as the
+        // tree of the node that created the snapshot, if the data is the same.
+        let mut buffer = Buffer::new(self.state_commitment(), None);
+        let old_version = version.saturating_sub(1);
+        let actual = MERKLE_TREE.apply_raw(&mut buffer, old_version, version, &batch)?;
+
+        if actual != Some(root_hash) {
+            return Err(DbError::RootHashMismatch {
+                expect: root_hash,
+                actual,
+            });
+        }
+
+        let (_, pending) = buffer.disassemble();
+
+        *(self.inner.pending_data.write()?) = Some(PendingData {
+            version,
+            state_commitment: pending,
+            state_storage: batch,
+        });
+
+        self.commit()?;
+
+        // Versions older than the snapshot's are unavailable, as if they had
+        // been pruned.
+        let cf = cf_default(&self.inner.db);
+        self.inner
+            .db
+            .put_cf(&cf, OLDEST_VERSION_KEY, version.to_le_bytes())?;
+
+        Ok(())
+    }
+}
+
 // ----------------------------- state commitment ------------------------------
 
 pub struct StateCommitment {
@@ -702,7 +785,8 @@ mod tests {
         crate::{DiskDb, TempDataDir},
         grug_app::{Db, PrunableDb},
         grug_jmt::{
-            verify_proof, MembershipProof, NonMembershipProof, Proof, ProofNode, ICS23_PROOF_SPEC,
+            verify_multi_proof, verify_proof, MembershipProof, NonMembershipProof, Proof,
+            ProofNode, ICS23_PROOF_SPEC,
         },
         grug_types::{Batch, Hash256, HashExt, Op, Order, Storage},
         hex_literal::hex,
@@ -1151,6 +1235,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ics23_prove_batch_works() {
+        let path = TempDataDir::new("_grug_disk_db_ics23_prove_batch_works");
+        let db = DiskDb::open(&path).unwrap();
+
+        let (_, maybe_root) = db
+            .flush_and_commit(Batch::from([
+                (b"r".to_vec(), Op::Insert(b"foo".to_vec())),
+                (b"m".to_vec(), Op::Insert(b"bar".to_vec())),
+                (b"L".to_vec(), Op::Insert(b"fuzz".to_vec())),
+                (b"a".to_vec(), Op::Insert(b"buzz".to_vec())),
+            ]))
+            .unwrap();
+        let root = maybe_root.unwrap().to_vec();
+
+        // Prove existing and non-existing keys together in one batch.
+        let keys = ["r", "m", "L", "a", "b", "o"];
+        let proof = db
+            .ics23_prove_batch(keys.map(|key| key.as_bytes().to_vec()).to_vec(), None)
+            .unwrap();
+
+        assert!(ics23::is_compressed(&proof));
+
+        assert!(ics23::verify_batch_membership::<HostFunctionsManager>(
+            &proof,
+            &ICS23_PROOF_SPEC,
+            &root,
+            BTreeMap::from([
+                (b"r".as_slice(), b"foo".as_slice()),
+                (b"m".as_slice(), b"bar".as_slice()),
+                (b"L".as_slice(), b"fuzz".as_slice()),
+                (b"a".as_slice(), b"buzz".as_slice()),
+            ]),
+        ));
+
+        assert!(ics23::verify_batch_non_membership::<HostFunctionsManager>(
+            &proof,
+            &ICS23_PROOF_SPEC,
+            &root,
+            &[b"b".as_slice(), b"o".as_slice()],
+        ));
+    }
+
+    #[test]
+    fn prove_many_works() {
+        let path = TempDataDir::new("_grug_disk_db_prove_many_works");
+        let db = DiskDb::open(&path).unwrap();
+
+        let (_, maybe_root) = db
+            .flush_and_commit(Batch::from([
+                (b"r".to_vec(), Op::Insert(b"foo".to_vec())),
+                (b"m".to_vec(), Op::Insert(b"bar".to_vec())),
+                (b"L".to_vec(), Op::Insert(b"fuzz".to_vec())),
+                (b"a".to_vec(), Op::Insert(b"buzz".to_vec())),
+            ]))
+            .unwrap();
+        let root = maybe_root.unwrap();
+
+        // Prove existing and non-existing keys together.
+        let keys_values = [
+            ("r", Some("foo")),
+            ("b", None),
+            ("L", Some("fuzz")),
+            ("o", None),
+        ];
+
+        let proof = db
+            .prove_many(
+                keys_values
+                    .iter()
+                    .map(|(key, _)| key.as_bytes().to_vec())
+                    .collect(),
+                None,
+            )
+            .unwrap();
+
+        let key_value_hashes = keys_values
+            .iter()
+            .map(|(key, value)| (key.hash256(), value.map(|value| value.hash256())))
+            .collect::<Vec<_>>();
+
+        verify_multi_proof(root, &key_value_hashes, &proof).unwrap();
+
+        // The proof doesn't verify if a value is changed.
+        let mut key_value_hashes = key_value_hashes;
+        key_value_hashes[0].1 = Some("bar".hash256());
+
+        assert!(verify_multi_proof(root, &key_value_hashes, &proof).is_err());
+    }
+
     /// Testing a coding mistake found in the Zellic audit (finding 3.1).
     ///
     /// If a batch contains deletions, we forgot to also delete the keys from
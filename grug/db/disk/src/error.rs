@@ -1,7 +1,7 @@
 use {
     crate::PendingData,
     grug_app::AppError,
-    grug_types::StdError,
+    grug_types::{Hash256, StdError},
     std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard},
     thiserror::Error,
 };
@@ -28,6 +28,19 @@ pub enum DbError {
 
     #[error("requested version ({version}) is older than the oldest available version ({oldest_version})")]
     VersionTooOld { version: u64, oldest_version: u64 },
+
+    #[error(
+        "cannot restore from snapshot when the DB isn't empty; latest version: {latest_version}"
+    )]
+    RestoreNonEmpty { latest_version: u64 },
+
+    #[error(
+        "root hash mismatch when restoring from snapshot! expecting: {expect}, actual: {actual:?}"
+    )]
+    RootHashMismatch {
+        expect: Hash256,
+        actual: Option<Hash256>,
+    },
 }
 
 impl<'a> From<PoisonError<RwLockReadGuard<'a, Option<PendingData>>>> for DbError {
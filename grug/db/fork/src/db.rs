@@ -0,0 +1,619 @@
+use {
+    crate::{DbError, DbResult},
+    grug_app::{Buffer, Db},
+    grug_client::Client,
+    grug_jmt::{MerkleTree, MultiProof, Proof},
+    grug_math::Inner,
+    grug_types::{Batch, Hash256, HashExt, Op, Order, Record, Storage},
+    ics23::CommitmentProof,
+    std::{
+        collections::{BTreeMap, HashMap},
+        future::Future,
+        ops::Bound,
+        panic,
+        sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+        thread,
+    },
+    tokio::{
+        runtime::{Handle, Runtime, RuntimeFlavor},
+        task,
+    },
+};
+
+const MERKLE_TREE: MerkleTree = MerkleTree::new_default();
+
+/// The number of records to request per page when fetching a range of keys
+/// from the remote node.
+const REMOTE_SCAN_PAGE_LIMIT: u32 = 1000;
+
+/// A range of keys, with inclusive lower and exclusive upper bounds. `None`
+/// means unbounded.
+type KeyRange = (Option<Vec<u8>>, Option<Vec<u8>>);
+
+struct ChangeSet {
+    version: u64,
+    state_commitment: Batch,
+    state_storage: Batch,
+}
+
+/// The remote archive node that the DB is forked from.
+struct Remote {
+    client: Client,
+    /// Runtime for driving the async client from within the synchronous
+    /// `Storage` methods.
+    ///
+    /// Always `Some`, except while being dropped.
+    runtime: Option<Runtime>,
+    /// Values fetched from the remote node at the fork height.
+    ///
+    /// `None` means the key is known to not exist at the fork height, so that
+    /// we don't query it again.
+    cache: RwLock<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+    /// Ranges of keys that have been fully fetched from the remote node, such
+    /// that iterating them again doesn't require querying the remote node.
+    fetched_ranges: RwLock<Vec<KeyRange>>,
+}
+
+impl Remote {
+    /// Drive the given future to completion on the remote's runtime.
+    ///
+    /// `Storage` methods are synchronous, but may be called from within an
+    /// async context (e.g. a `#[tokio::test]`), where blocking the thread on
+    /// another runtime panics. In that case, the future is driven on a separate
+    /// thread, while the current one is blocked; if the current runtime is
+    /// multi-threaded, it's notified so that it can move its other tasks off
+    /// the current thread.
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        let runtime = self.runtime.as_ref().unwrap();
+
+        let Ok(handle) = Handle::try_current() else {
+            return runtime.block_on(future);
+        };
+
+        let run_on_another_thread = || {
+            thread::scope(|scope| {
+                scope
+                    .spawn(|| runtime.block_on(future))
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+        };
+
+        match handle.runtime_flavor() {
+            RuntimeFlavor::MultiThread => task::block_in_place(run_on_another_thread),
+            _ => run_on_another_thread(),
+        }
+    }
+}
+
+impl Drop for Remote {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks the thread until its tasks are shut down,
+        // which panics in an async context. The client doesn't spawn tasks that
+        // need to be waited for, so shut it down without blocking instead.
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+struct ForkDbInner {
+    /// Block height of the remote chain at which the DB is forked.
+    ///
+    /// All data not written locally are fetched from the remote node at this
+    /// height.
+    fork_height: u64,
+    /// Version of the DB. Initialized to the fork height, and incremented by 1
+    /// each time a batch of data is committed.
+    latest_version: u64,
+    /// A key-value store backing the Merkle tree.
+    ///
+    /// The Merkle tree only covers data written _after_ the fork, so the root
+    /// hash doesn't match that of the remote chain.
+    state_commitment: HashMap<Vec<u8>, Vec<u8>>,
+    /// Data written locally after the fork: key => (version => op)
+    ///
+    /// We keep the ops instead of the values, such that a deletion can shadow
+    /// a value that exists in the remote chain.
+    state_storage: BTreeMap<Vec<u8>, BTreeMap<u64, Op<Vec<u8>>>>,
+    /// Uncommitted changes
+    changeset: Option<ChangeSet>,
+}
+
+impl ForkDbInner {
+    /// Find the most recent local op of the given key, at or before the given
+    /// version. `None` if the key hasn't been touched locally.
+    fn get_op(&self, key: &[u8], version: u64) -> Option<&Op<Vec<u8>>> {
+        self.state_storage
+            .get(key)?
+            .range(..=version)
+            .last()
+            .map(|(_, op)| op)
+    }
+}
+
+/// A DB for running tests against mainnet data ("mainnet forking").
+///
+/// Data are lazily fetched from a remote archive node, at a pinned block
+/// height, the first time they're read, and cached locally. Data written after
+/// the fork are kept in memory, layered on top of the remote state.
+///
+/// ## Note
+///
+/// Iterating a range of keys fetches the entire range from the remote node the
+/// first time it's iterated, through the node's `/store_scan` ABCI query, so
+/// iterating over a large range (e.g. the entire state) can be slow.
+pub struct ForkDb {
+    inner: Arc<RwLock<ForkDbInner>>,
+    remote: Arc<Remote>,
+}
+
+impl ForkDb {
+    /// Create a new DB forked from the chain that the client connects to.
+    ///
+    /// If height is unspecified, the latest height of the remote chain is used.
+    pub fn new(client: Client, fork_height: Option<u64>) -> DbResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        let remote = Remote {
+            client,
+            runtime: Some(runtime),
+            cache: RwLock::new(BTreeMap::new()),
+            fetched_ranges: RwLock::new(Vec::new()),
+        };
+
+        let fork_height = match fork_height {
+            Some(height) => height,
+            None => remote
+                .block_on(remote.client.query_status())
+                .map_err(|err| DbError::Remote(err.to_string()))?
+                .sync_info
+                .latest_block_height
+                .value(),
+        };
+
+        Ok(Self {
+            inner: Arc::new(RwLock::new(ForkDbInner {
+                fork_height,
+                latest_version: fork_height,
+                state_commitment: HashMap::new(),
+                state_storage: BTreeMap::new(),
+                changeset: None,
+            })),
+            remote: Arc::new(remote),
+        })
+    }
+
+    /// Return the block height of the remote chain at which the DB is forked.
+    pub fn fork_height(&self) -> u64 {
+        self.with_read(|inner| inner.fork_height)
+    }
+
+    fn with_read<C, T>(&self, callback: C) -> T
+    where
+        C: FnOnce(RwLockReadGuard<ForkDbInner>) -> T,
+    {
+        let lock = self.inner.read().unwrap_or_else(|err| {
+            panic!("ForkDb is poisoned: {err:?}");
+        });
+        callback(lock)
+    }
+
+    fn with_write<C, T>(&self, callback: C) -> T
+    where
+        C: FnOnce(RwLockWriteGuard<ForkDbInner>) -> T,
+    {
+        let lock = self.inner.write().unwrap_or_else(|err| {
+            panic!("ForkDb is poisoned: {err:?}");
+        });
+        callback(lock)
+    }
+
+    /// Read a value from the remote node at the fork height, or from the cache
+    /// if it has already been fetched.
+    fn read_remote(&self, key: &[u8], fork_height: u64) -> Option<Vec<u8>> {
+        if let Some(value) = self
+            .remote
+            .cache
+            .read()
+            .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"))
+            .get(key)
+        {
+            return value.clone();
+        }
+
+        let (value, _) = self
+            .remote
+            .block_on(
+                self.remote
+                    .client
+                    .query_store(key.to_vec(), Some(fork_height), false),
+            )
+            .unwrap_or_else(|err| {
+                panic!("failed to fetch key from remote node at height {fork_height}: {err}");
+            });
+
+        self.remote
+            .cache
+            .write()
+            .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"))
+            .insert(key.to_vec(), value.clone());
+
+        value
+    }
+
+    /// Fetch all key-value pairs in the given range from the remote node at the
+    /// fork height into the cache, unless the range has already been fetched.
+    fn fetch_remote_range(&self, min: Option<&[u8]>, max: Option<&[u8]>, fork_height: u64) {
+        let covered = self
+            .remote
+            .fetched_ranges
+            .read()
+            .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"))
+            .iter()
+            .any(|(fetched_min, fetched_max)| {
+                let min_covered = match (fetched_min, min) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some(fetched_min), Some(min)) => fetched_min.as_slice() <= min,
+                };
+                let max_covered = match (fetched_max, max) {
+                    (None, _) => true,
+                    (Some(_), None) => false,
+                    (Some(fetched_max), Some(max)) => max <= fetched_max.as_slice(),
+                };
+                min_covered && max_covered
+            });
+
+        if covered {
+            return;
+        }
+
+        let mut start = min.map(|min| min.to_vec());
+
+        // The node may cap the number of records per page below the limit we
+        // request, so keep going until an empty page is returned.
+        loop {
+            let records = self
+                .remote
+                .block_on(self.remote.client.query_store_scan(
+                    start.clone(),
+                    max.map(|max| max.to_vec()),
+                    Some(REMOTE_SCAN_PAGE_LIMIT),
+                    Some(fork_height),
+                ))
+                .unwrap_or_else(|err| {
+                    panic!("failed to fetch range from remote node at height {fork_height}: {err}");
+                });
+
+            let Some((last_key, _)) = records.last_key_value() else {
+                break;
+            };
+
+            // The next page starts right after the last key of this page.
+            let mut next_start = last_key.to_vec();
+            next_start.push(0);
+
+            let mut cache = self
+                .remote
+                .cache
+                .write()
+                .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"));
+
+            for (key, value) in records {
+                cache.insert(key.into_inner(), Some(value.into_inner()));
+            }
+
+            start = Some(next_start);
+        }
+
+        self.remote
+            .fetched_ranges
+            .write()
+            .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"))
+            .push((min.map(|min| min.to_vec()), max.map(|max| max.to_vec())));
+    }
+}
+
+impl Clone for ForkDb {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            remote: Arc::clone(&self.remote),
+        }
+    }
+}
+
+impl Db for ForkDb {
+    type Error = DbError;
+    type MultiProof = MultiProof;
+    type Proof = Proof;
+    type StateCommitment = StateCommitment;
+    type StateStorage = StateStorage;
+
+    fn state_commitment(&self) -> StateCommitment {
+        StateCommitment { db: self.clone() }
+    }
+
+    fn state_storage(&self, version: Option<u64>) -> DbResult<StateStorage> {
+        let (fork_height, latest_version) =
+            self.with_read(|inner| (inner.fork_height, inner.latest_version));
+
+        let version = version.unwrap_or(latest_version);
+
+        if version > latest_version {
+            return Err(DbError::VersionTooNew {
+                version,
+                latest_version,
+            });
+        }
+
+        // Versions before the fork would need to be fetched at a different
+        // remote height, which the cache isn't designed for.
+        if version < fork_height {
+            return Err(DbError::VersionTooOld {
+                version,
+                fork_height,
+            });
+        }
+
+        Ok(StateStorage {
+            db: self.clone(),
+            version,
+        })
+    }
+
+    fn latest_version(&self) -> Option<u64> {
+        Some(self.with_read(|inner| inner.latest_version))
+    }
+
+    fn root_hash(&self, version: Option<u64>) -> DbResult<Option<Hash256>> {
+        let version = version.unwrap_or_else(|| self.with_read(|inner| inner.latest_version));
+        Ok(MERKLE_TREE.root_hash(&self.state_commitment(), version)?)
+    }
+
+    fn prove(&self, key: &[u8], version: Option<u64>) -> DbResult<Proof> {
+        let version = version.unwrap_or_else(|| self.with_read(|inner| inner.latest_version));
+        Ok(MERKLE_TREE.prove(&self.state_commitment(), key.hash256(), version)?)
+    }
+
+    fn prove_many(&self, keys: Vec<Vec<u8>>, version: Option<u64>) -> DbResult<MultiProof> {
+        let version = version.unwrap_or_else(|| self.with_read(|inner| inner.latest_version));
+        let key_hashes = keys.iter().map(|key| key.hash256()).collect::<Vec<_>>();
+        Ok(MERKLE_TREE.prove_many(&self.state_commitment(), &key_hashes, version)?)
+    }
+
+    fn ics23_prove(
+        &self,
+        _key: Vec<u8>,
+        _version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error> {
+        unimplemented!("don't need this for testing")
+    }
+
+    fn ics23_prove_batch(
+        &self,
+        _keys: Vec<Vec<u8>>,
+        _version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error> {
+        unimplemented!("don't need this for testing")
+    }
+
+    // Same as in `MemDb`, we must not attempt to lock the DB inside the
+    // `with_write` callback, so do everything that requires a read lock first.
+    fn flush_but_not_commit(&self, batch: Batch) -> DbResult<(u64, Option<Hash256>)> {
+        let (new_version, root_hash, changeset) = self.with_read(|inner| {
+            if inner.changeset.is_some() {
+                return Err(DbError::ChangeSetAlreadySet);
+            }
+
+            let old_version = inner.latest_version;
+            let new_version = old_version + 1;
+
+            let mut cache = Buffer::new(self.state_commitment(), None);
+            let root_hash = MERKLE_TREE.apply_raw(&mut cache, old_version, new_version, &batch)?;
+            let (_, changeset) = cache.disassemble();
+
+            Ok((new_version, root_hash, changeset))
+        })?;
+
+        self.with_write(|mut inner| {
+            inner.changeset = Some(ChangeSet {
+                version: new_version,
+                state_commitment: changeset,
+                state_storage: batch,
+            });
+        });
+
+        Ok((new_version, root_hash))
+    }
+
+    fn commit(&self) -> DbResult<()> {
+        self.with_write(|mut inner| {
+            let changeset = inner.changeset.take().ok_or(DbError::ChangeSetNotSet)?;
+
+            // Update the version
+            inner.latest_version = changeset.version;
+
+            // Write changes to state commitment
+            for (key, op) in changeset.state_commitment {
+                if let Op::Insert(value) = op {
+                    inner.state_commitment.insert(key, value);
+                } else {
+                    inner.state_commitment.remove(&key);
+                }
+            }
+
+            // Write changes to state storage
+            for (key, op) in changeset.state_storage {
+                inner
+                    .state_storage
+                    .entry(key)
+                    .or_default()
+                    .insert(changeset.version, op);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+// ----------------------------- state commitment ------------------------------
+
+#[derive(Clone)]
+pub struct StateCommitment {
+    db: ForkDb,
+}
+
+impl Storage for StateCommitment {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.db
+            .with_read(|inner| inner.state_commitment.get(key).cloned())
+    }
+
+    fn scan<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        _min: Option<&[u8]>,
+        _max: Option<&[u8]>,
+        _order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        unimplemented!("this isn't used by the Merkle tree");
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+}
+
+// ------------------------------- state storage -------------------------------
+
+#[derive(Clone)]
+pub struct StateStorage {
+    db: ForkDb,
+    version: u64,
+}
+
+impl Storage for StateStorage {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let (local, fork_height) = self.db.with_read(|inner| {
+            let local = inner.get_op(key, self.version).cloned();
+            (local, inner.fork_height)
+        });
+
+        // If the key has been written or deleted locally, the local op takes
+        // precedence. Otherwise, fall back to the remote state.
+        match local {
+            Some(op) => op.into_option(),
+            None => self.db.read_remote(key, fork_height),
+        }
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let fork_height = self.db.with_read(|inner| inner.fork_height);
+
+        self.db.fetch_remote_range(min, max, fork_height);
+
+        let min = min.map_or(Bound::Unbounded, Bound::Included);
+        let max = max.map_or(Bound::Unbounded, Bound::Excluded);
+
+        // Start with the remote values...
+        let mut merged = self
+            .db
+            .remote
+            .cache
+            .read()
+            .unwrap_or_else(|err| panic!("ForkDb cache is poisoned: {err:?}"))
+            .range::<[u8], _>((min, max))
+            .filter_map(|(k, v)| Some((k.clone(), v.clone()?)))
+            .collect::<BTreeMap<_, _>>();
+
+        // ...then layer the local ops on top of them.
+        self.db.with_read(|inner| {
+            for (key, ops) in inner.state_storage.range::<[u8], _>((min, max)) {
+                match ops.range(..=self.version).last() {
+                    Some((_, Op::Insert(value))) => {
+                        merged.insert(key.clone(), value.clone());
+                    },
+                    Some((_, Op::Delete)) => {
+                        merged.remove(key);
+                    },
+                    None => {},
+                }
+            }
+        });
+
+        match order {
+            Order::Ascending => Box::new(merged.into_iter()),
+            Order::Descending => Box::new(merged.into_iter().rev()),
+        }
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let iter = self.scan(min, max, order).map(|(k, _)| k);
+        Box::new(iter)
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        let iter = self.scan(min, max, order).map(|(_, v)| v);
+        Box::new(iter)
+    }
+
+    fn write(&mut self, _key: &[u8], _value: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unreachable!("write function called on read-only storage");
+    }
+
+    fn remove_range(&mut self, _min: Option<&[u8]>, _max: Option<&[u8]>) {
+        unreachable!("write function called on read-only storage");
+    }
+}
@@ -0,0 +1,33 @@
+use {grug_app::AppError, grug_types::StdError, thiserror::Error};
+
+#[derive(Debug, Error)]
+pub enum DbError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error("failed to create async runtime: {0}")]
+    Runtime(#[from] std::io::Error),
+
+    #[error("remote node error: {0}")]
+    Remote(String),
+
+    #[error("cannot flush when changeset is already set")]
+    ChangeSetAlreadySet,
+
+    #[error("cannot commit when changeset is not yet set")]
+    ChangeSetNotSet,
+
+    #[error("requested version ({version}) is newer than the latest version ({latest_version})")]
+    VersionTooNew { version: u64, latest_version: u64 },
+
+    #[error("requested version ({version}) is older than the fork height ({fork_height})")]
+    VersionTooOld { version: u64, fork_height: u64 },
+}
+
+impl From<DbError> for AppError {
+    fn from(err: DbError) -> Self {
+        AppError::Db(err.to_string())
+    }
+}
+
+pub type DbResult<T> = core::result::Result<T, DbError>;
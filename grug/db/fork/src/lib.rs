@@ -0,0 +1,4 @@
+mod db;
+mod error;
+
+pub use {db::*, error::*};
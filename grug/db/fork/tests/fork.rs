@@ -0,0 +1,263 @@
+use {
+    grug_app::{Db, NaiveProposalPreparer, NullIndexer},
+    grug_client::Client,
+    grug_db_fork::ForkDb,
+    grug_db_memory::MemDb,
+    grug_math::Uint128,
+    grug_testing::{serve_http, TestBuilder, TestSuite},
+    grug_types::{
+        Batch, Binary, Coins, JsonDeExt, JsonSerExt, Op, Order, QueryStoreScanRequest, ResultExt,
+        Storage,
+    },
+    grug_vm_rust::RustVm,
+    serde_json::{json, Value},
+    std::{
+        collections::BTreeMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    },
+};
+
+/// A stand-in for a node's Tendermint RPC endpoint, which serves ABCI `/store`
+/// and `/store_scan` queries out of the given DB.
+///
+/// Returns the endpoint URL, and a counter of how many queries were served.
+fn serve_store_queries(db: MemDb) -> (String, Arc<AtomicUsize>) {
+    serve_http(move |req| {
+        let req: Value = serde_json::from_slice(&req.body).unwrap();
+        assert_eq!(req["method"], "abci_query");
+
+        let data = hex::decode(req["params"]["data"].as_str().unwrap()).unwrap();
+        // Zero or null height means the latest height.
+        let height = match &req["params"]["height"] {
+            Value::String(h) => h.parse::<u64>().ok(),
+            h => h.as_u64(),
+        }
+        .filter(|h| *h > 0);
+        let storage = db.state_storage(height).unwrap();
+
+        let value = match req["params"]["path"].as_str().unwrap() {
+            "/store" => storage.read(&data),
+            "/store_scan" => {
+                let req: QueryStoreScanRequest = data.deserialize_json().unwrap();
+                let records = storage
+                    .scan(req.min.as_deref(), req.max.as_deref(), Order::Ascending)
+                    .take(req.limit.unwrap() as usize)
+                    .map(|(k, v)| (Binary::from(k), Binary::from(v)))
+                    .collect::<BTreeMap<_, _>>();
+                Some(records.to_json_vec().unwrap())
+            },
+            path => panic!("unexpected query path: {path}"),
+        };
+
+        json!({
+            "jsonrpc": "2.0",
+            "id": req["id"],
+            "result": {
+                "response": {
+                    "code": 0,
+                    "log": "",
+                    "info": "",
+                    "index": "0",
+                    "key": Binary::from(data).to_json_value().unwrap(),
+                    "value": Binary::from(value.unwrap_or_default()).to_json_value().unwrap(),
+                    "proofOps": null,
+                    "height": height.unwrap_or_default().to_string(),
+                    "codespace": "",
+                },
+            },
+        })
+        .to_string()
+    })
+}
+
+#[test]
+fn forking_works() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("sender", Coins::one("uatom", 100).unwrap())
+        .add_account("receiver", Coins::new())
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite
+        .transfer(
+            &mut accounts["sender"],
+            accounts["receiver"].address,
+            Coins::one("uatom", 10).unwrap(),
+        )
+        .should_succeed();
+
+    // Fork the chain at the current height.
+    let fork_height = suite.block.height;
+    let (url, counter) = serve_store_queries(suite.app.db.clone());
+    let db = ForkDb::new(Client::connect(url.as_str()).unwrap(), Some(fork_height)).unwrap();
+
+    let mut fork = TestSuite::new_with_existing_state(
+        db,
+        RustVm::new(),
+        NaiveProposalPreparer,
+        NullIndexer,
+        suite.block_time,
+        suite.default_gas_limit,
+    );
+
+    assert_eq!(fork.chain_id, suite.chain_id);
+    assert_eq!(fork.block, suite.block);
+
+    // Replay a transaction on the fork.
+    fork.transfer(
+        &mut accounts["sender"],
+        accounts["receiver"].address,
+        Coins::one("uatom", 20).unwrap(),
+    )
+    .should_succeed();
+
+    fork.query_balance(&accounts["sender"], "uatom")
+        .should_succeed_and_equal(Uint128::new(70));
+    fork.query_balance(&accounts["receiver"], "uatom")
+        .should_succeed_and_equal(Uint128::new(30));
+
+    // The original chain should be unaffected.
+    suite
+        .query_balance(&accounts["receiver"], "uatom")
+        .should_succeed_and_equal(Uint128::new(10));
+
+    // Make another block on the original chain. The fork is pinned at the fork
+    // height, so it shouldn't see this.
+    suite
+        .transfer(
+            &mut accounts["sender"],
+            accounts["receiver"].address,
+            Coins::one("uatom", 5).unwrap(),
+        )
+        .should_succeed();
+
+    fork.query_balance(&accounts["receiver"], "uatom")
+        .should_succeed_and_equal(Uint128::new(30));
+
+    // Keys that have already been fetched shouldn't be fetched again.
+    let queries = counter.load(Ordering::SeqCst);
+    fork.query_balance(&accounts["sender"], "uatom")
+        .should_succeed_and_equal(Uint128::new(70));
+    assert_eq!(counter.load(Ordering::SeqCst), queries);
+}
+
+#[test]
+fn local_writes_shadow_remote_state() {
+    let (mut suite, _) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite.make_empty_block();
+
+    let fork_height = suite.block.height;
+    let (url, _) = serve_store_queries(suite.app.db.clone());
+    let db = ForkDb::new(Client::connect(url.as_str()).unwrap(), Some(fork_height)).unwrap();
+
+    let chain_id = db.state_storage(None).unwrap().read(b"chain_id");
+    assert!(chain_id.is_some());
+
+    // Delete a key that exists remotely, and insert a new one.
+    let batch = Batch::from([
+        (b"chain_id".to_vec(), Op::Delete),
+        (b"chain_id_2".to_vec(), Op::Insert(b"hello".to_vec())),
+    ]);
+    let (version, root_hash) = db.flush_and_commit(batch).unwrap();
+    assert_eq!(version, fork_height + 1);
+    assert!(root_hash.is_some());
+
+    // At the latest version, the remote value is shadowed by the deletion.
+    let storage = db.state_storage(None).unwrap();
+    assert_eq!(storage.read(b"chain_id"), None);
+    assert_eq!(storage.read(b"chain_id_2"), Some(b"hello".to_vec()));
+    assert!(storage
+        .scan(Some(b"chain_id"), Some(b"chain_id_3"), Order::Ascending)
+        .eq([(b"chain_id_2".to_vec(), b"hello".to_vec())]));
+
+    // At the fork height, the remote value is still visible.
+    let storage = db.state_storage(Some(fork_height)).unwrap();
+    assert_eq!(storage.read(b"chain_id"), chain_id);
+    assert_eq!(storage.read(b"chain_id_2"), None);
+    assert!(storage
+        .scan(Some(b"chain_id"), Some(b"chain_id_3"), Order::Ascending)
+        .eq([(b"chain_id".to_vec(), chain_id.unwrap())]));
+
+    // Versions older than the fork height can't be loaded.
+    assert!(db.state_storage(Some(fork_height - 1)).is_err());
+}
+
+#[test]
+fn iterating_fetches_remote_ranges() {
+    let (mut suite, _) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite.make_empty_block();
+
+    let fork_height = suite.block.height;
+    let (url, counter) = serve_store_queries(suite.app.db.clone());
+    let db = ForkDb::new(Client::connect(url.as_str()).unwrap(), Some(fork_height)).unwrap();
+
+    // Iterate over the entire state, without having read any key before. All
+    // remote keys should be visible.
+    let expected = suite
+        .app
+        .db
+        .state_storage(Some(fork_height))
+        .unwrap()
+        .scan(None, None, Order::Ascending)
+        .collect::<Vec<_>>();
+    assert!(!expected.is_empty());
+
+    let storage = db.state_storage(None).unwrap();
+    assert!(storage
+        .scan(None, None, Order::Ascending)
+        .eq(expected.clone()));
+
+    // Ranges that have already been fetched shouldn't be fetched again.
+    let queries = counter.load(Ordering::SeqCst);
+    assert!(storage
+        .scan(Some(b"chain_id"), Some(b"chain_id_3"), Order::Descending)
+        .eq(expected
+            .into_iter()
+            .filter(|(k, _)| k.as_slice() >= b"chain_id" && k.as_slice() < b"chain_id_3")
+            .rev()));
+    assert_eq!(counter.load(Ordering::SeqCst), queries);
+}
+
+#[tokio::test]
+async fn reading_inside_async_runtime_works() {
+    let (mut suite, _) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite.make_empty_block();
+
+    let fork_height = suite.block.height;
+    let (url, _) = serve_store_queries(suite.app.db.clone());
+    let db = ForkDb::new(Client::connect(url.as_str()).unwrap(), Some(fork_height)).unwrap();
+
+    assert!(db.state_storage(None).unwrap().read(b"chain_id").is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reading_inside_multi_threaded_async_runtime_works() {
+    let (mut suite, _) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite.make_empty_block();
+
+    let fork_height = suite.block.height;
+    let (url, _) = serve_store_queries(suite.app.db.clone());
+    let db = ForkDb::new(Client::connect(url.as_str()).unwrap(), Some(fork_height)).unwrap();
+
+    assert!(db.state_storage(None).unwrap().read(b"chain_id").is_some());
+}
@@ -1,7 +1,7 @@
 use {
     crate::{DbError, DbResult, VersionedMap},
-    grug_app::{Buffer, Db},
-    grug_jmt::{MerkleTree, Proof},
+    grug_app::{Buffer, Db, SnapshotDb},
+    grug_jmt::{MerkleTree, MultiProof, Proof},
     grug_types::{Batch, Hash256, HashExt, Op, Order, Record, Storage},
     ics23::CommitmentProof,
     std::{
@@ -88,6 +88,7 @@ impl Clone for MemDb {
 
 impl Db for MemDb {
     type Error = DbError;
+    type MultiProof = MultiProof;
     type Proof = Proof;
     type StateCommitment = StateCommitment;
     type StateStorage = StateStorage;
@@ -117,6 +118,12 @@ impl Db for MemDb {
         Ok(MERKLE_TREE.prove(&self.state_commitment(), key.hash256(), version)?)
     }
 
+    fn prove_many(&self, keys: Vec<Vec<u8>>, version: Option<u64>) -> DbResult<MultiProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        let key_hashes = keys.iter().map(|key| key.hash256()).collect::<Vec<_>>();
+        Ok(MERKLE_TREE.prove_many(&self.state_commitment(), &key_hashes, version)?)
+    }
+
     fn ics23_prove(
         &self,
         _key: Vec<u8>,
@@ -125,6 +132,14 @@ impl Db for MemDb {
         unimplemented!("don't need this for testing")
     }
 
+    fn ics23_prove_batch(
+        &self,
+        _keys: Vec<Vec<u8>>,
+        _version: Option<u64>,
+    ) -> Result<CommitmentProof, Self::Error> {
+        unimplemented!("don't need this for testing")
+    }
+
     // Note on implementing this function: We must make sure that we don't
     // attempt to lock the DB (either read or write) inside the `with_write`
     // callback. Doing so will result in error:
@@ -187,6 +202,37 @@ impl Db for MemDb {
     }
 }
 
+impl SnapshotDb for MemDb {
+    fn restore(&self, version: u64, batch: Batch, root_hash: Hash256) -> DbResult<()> {
+        if let Some(latest_version) = self.latest_version() {
+            return Err(DbError::RestoreNonEmpty { latest_version });
+        }
+
+        let mut cache = Buffer::new(self.state_commitment(), None);
+        let old_version = version.saturating_sub(1);
+        let actual = MERKLE_TREE.apply_raw(&mut cache, old_version, version, &batch)?;
+
+        if actual != Some(root_hash) {
+            return Err(DbError::RootHashMismatch {
+                expect: root_hash,
+                actual,
+            });
+        }
+
+        let (_, changeset) = cache.disassemble();
+
+        self.with_write(|mut inner| {
+            inner.changeset = Some(ChangeSet {
+                version,
+                state_commitment: changeset,
+                state_storage: batch,
+            });
+        });
+
+        self.commit()
+    }
+}
+
 // ----------------------------- state commitment ------------------------------
 
 #[derive(Clone)]
@@ -1,4 +1,8 @@
-use {grug_app::AppError, grug_types::StdError, thiserror::Error};
+use {
+    grug_app::AppError,
+    grug_types::{Hash256, StdError},
+    thiserror::Error,
+};
 
 #[derive(Debug, Error)]
 pub enum DbError {
@@ -10,6 +14,19 @@ pub enum DbError {
 
     #[error("cannot commit when changeset is not yet set")]
     ChangeSetNotSet,
+
+    #[error(
+        "cannot restore from snapshot when the DB isn't empty; latest version: {latest_version}"
+    )]
+    RestoreNonEmpty { latest_version: u64 },
+
+    #[error(
+        "root hash mismatch when restoring from snapshot! expecting: {expect}, actual: {actual:?}"
+    )]
+    RootHashMismatch {
+        expect: Hash256,
+        actual: Option<Hash256>,
+    },
 }
 
 impl From<DbError> for AppError {
@@ -3,8 +3,10 @@ use {
     grug_types::{
         make_auth_ctx, make_immutable_ctx, make_mutable_ctx, make_sudo_ctx,
         unwrap_into_generic_result, AuthCtx, AuthResponse, BankMsg, BankQuery, BankQueryResponse,
-        BorshDeExt, BorshSerExt, Context, GenericResult, GenericResultExt, ImmutableCtx, Json,
-        JsonDeExt, MutableCtx, QuerierWrapper, Response, SubMsgResult, SudoCtx, Tx, TxOutcome,
+        BorshDeExt, BorshSerExt, Context, GenericResult, GenericResultExt, IbcClientQuery,
+        IbcClientQueryResponse, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg,
+        ImmutableCtx, Json, JsonDeExt, MutableCtx, QuerierWrapper, Response, SubMsgResult, SudoCtx,
+        Tx, TxOutcome,
     },
     serde::de::DeserializeOwned,
     std::fmt::Display,
@@ -355,3 +357,99 @@ where
 
     Region::release_buffer(res_bytes) as usize
 }
+
+pub fn do_ibc_client_query<E>(
+    query_fn: &dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<IbcClientQueryResponse, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: Display,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = (|| {
+        let ctx: Context = unwrap_into_generic_result!(ctx_bytes.deserialize_borsh());
+        let ctx = make_immutable_ctx!(ctx, &ExternalStorage, &ExternalApi, &ExternalQuerier);
+        let msg = unwrap_into_generic_result!(msg_bytes.deserialize_borsh());
+
+        query_fn(ctx, msg).into_generic_result()
+    })();
+
+    let res_bytes = res.to_borsh_vec().unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+pub fn do_ibc_packet_receive<E>(
+    receive_fn: &dyn Fn(MutableCtx, IbcPacketReceiveMsg) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: Display,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = (|| {
+        let ctx: Context = unwrap_into_generic_result!(ctx_bytes.deserialize_borsh());
+        let ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+        let msg = unwrap_into_generic_result!(msg_bytes.deserialize_borsh());
+
+        receive_fn(ctx, msg).into_generic_result()
+    })();
+
+    let res_bytes = res.to_borsh_vec().unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+pub fn do_ibc_packet_ack<E>(
+    ack_fn: &dyn Fn(MutableCtx, IbcPacketAckMsg) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: Display,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = (|| {
+        let ctx: Context = unwrap_into_generic_result!(ctx_bytes.deserialize_borsh());
+        let ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+        let msg = unwrap_into_generic_result!(msg_bytes.deserialize_borsh());
+
+        ack_fn(ctx, msg).into_generic_result()
+    })();
+
+    let res_bytes = res.to_borsh_vec().unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+pub fn do_ibc_packet_timeout<E>(
+    timeout_fn: &dyn Fn(MutableCtx, IbcPacketTimeoutMsg) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: Display,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = (|| {
+        let ctx: Context = unwrap_into_generic_result!(ctx_bytes.deserialize_borsh());
+        let ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+        let msg = unwrap_into_generic_result!(msg_bytes.deserialize_borsh());
+
+        timeout_fn(ctx, msg).into_generic_result()
+    })();
+
+    let res_bytes = res.to_borsh_vec().unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
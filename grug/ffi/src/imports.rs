@@ -34,8 +34,18 @@ extern "C" {
         recovery_id: u8,
         compressed: u8,
     ) -> u64;
+    fn secp256k1_schnorr_verify(msg_hash_ptr: usize, sig_ptr: usize, pk_ptr: usize) -> u32;
     fn ed25519_verify(msg_hash_ptr: usize, sig_ptr: usize, pk_ptr: usize) -> u32;
+    fn ed25519_pure_verify(msg_ptr: usize, sig_ptr: usize, pk_ptr: usize) -> u32;
     fn ed25519_batch_verify(prehash_msgs_ptr: usize, sigs_ptr: usize, pks_ptr: usize) -> u32;
+    fn bls12_381_verify(msg_ptr: usize, dst_ptr: usize, sig_ptr: usize, pk_ptr: usize) -> u32;
+    fn bls12_381_aggregate_verify(
+        msg_ptr: usize,
+        dst_ptr: usize,
+        sig_ptr: usize,
+        pks_ptr: usize,
+    ) -> u32;
+    fn bls12_381_pairing_equality(ps_ptr: usize, qs_ptr: usize, r_ptr: usize, s_ptr: usize) -> u32;
 
     // Hashes
     fn sha2_256(data_ptr: usize) -> usize;
@@ -384,6 +394,27 @@ impl Api for ExternalApi {
         }
     }
 
+    fn secp256k1_schnorr_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        let msg_hash_region = Region::build(msg_hash);
+        let msg_hash_ptr = &*msg_hash_region as *const Region;
+
+        let sig_region = Region::build(sig);
+        let sig_ptr = &*sig_region as *const Region;
+
+        let pk_region = Region::build(pk);
+        let pk_ptr = &*pk_region as *const Region;
+
+        let return_value = unsafe {
+            secp256k1_schnorr_verify(msg_hash_ptr as usize, sig_ptr as usize, pk_ptr as usize)
+        };
+
+        if return_value == 0 {
+            Ok(())
+        } else {
+            Err(VerificationError::from_error_code(return_value).into())
+        }
+    }
+
     fn ed25519_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
         let msg_hash_region = Region::build(msg_hash);
         let msg_hash_ptr = &*msg_hash_region as *const Region;
@@ -404,6 +435,26 @@ impl Api for ExternalApi {
         }
     }
 
+    fn ed25519_pure_verify(&self, msg: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        let msg_region = Region::build(msg);
+        let msg_ptr = &*msg_region as *const Region;
+
+        let sig_region = Region::build(sig);
+        let sig_ptr = &*sig_region as *const Region;
+
+        let pk_region = Region::build(pk);
+        let pk_ptr = &*pk_region as *const Region;
+
+        let return_value =
+            unsafe { ed25519_pure_verify(msg_ptr as usize, sig_ptr as usize, pk_ptr as usize) };
+
+        if return_value == 0 {
+            Ok(())
+        } else {
+            Err(VerificationError::from_error_code(return_value).into())
+        }
+    }
+
     fn ed25519_batch_verify(
         &self,
         prehash_msgs: &[&[u8]],
@@ -436,6 +487,108 @@ impl Api for ExternalApi {
             Err(VerificationError::from_error_code(return_value).into())
         }
     }
+
+    fn bls12_381_verify(&self, msg: &[u8], dst: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        let msg_region = Region::build(msg);
+        let msg_ptr = &*msg_region as *const Region;
+
+        let dst_region = Region::build(dst);
+        let dst_ptr = &*dst_region as *const Region;
+
+        let sig_region = Region::build(sig);
+        let sig_ptr = &*sig_region as *const Region;
+
+        let pk_region = Region::build(pk);
+        let pk_ptr = &*pk_region as *const Region;
+
+        let return_value = unsafe {
+            bls12_381_verify(
+                msg_ptr as usize,
+                dst_ptr as usize,
+                sig_ptr as usize,
+                pk_ptr as usize,
+            )
+        };
+
+        if return_value == 0 {
+            Ok(())
+        } else {
+            Err(VerificationError::from_error_code(return_value).into())
+        }
+    }
+
+    fn bls12_381_aggregate_verify(
+        &self,
+        msg: &[u8],
+        dst: &[u8],
+        sig: &[u8],
+        pks: &[&[u8]],
+    ) -> StdResult<()> {
+        let msg_region = Region::build(msg);
+        let msg_ptr = &*msg_region as *const Region;
+
+        let dst_region = Region::build(dst);
+        let dst_ptr = &*dst_region as *const Region;
+
+        let sig_region = Region::build(sig);
+        let sig_ptr = &*sig_region as *const Region;
+
+        let pks = encode_sections(pks)?;
+        let pks_region = Region::build(&pks);
+        let pks_ptr = &*pks_region as *const Region;
+
+        let return_value = unsafe {
+            bls12_381_aggregate_verify(
+                msg_ptr as usize,
+                dst_ptr as usize,
+                sig_ptr as usize,
+                pks_ptr as usize,
+            )
+        };
+
+        if return_value == 0 {
+            Ok(())
+        } else {
+            Err(VerificationError::from_error_code(return_value).into())
+        }
+    }
+
+    fn bls12_381_pairing_equality(
+        &self,
+        ps: &[&[u8]],
+        qs: &[&[u8]],
+        r: &[u8],
+        s: &[u8],
+    ) -> StdResult<()> {
+        let ps = encode_sections(ps)?;
+        let ps_region = Region::build(&ps);
+        let ps_ptr = &*ps_region as *const Region;
+
+        let qs = encode_sections(qs)?;
+        let qs_region = Region::build(&qs);
+        let qs_ptr = &*qs_region as *const Region;
+
+        let r_region = Region::build(r);
+        let r_ptr = &*r_region as *const Region;
+
+        let s_region = Region::build(s);
+        let s_ptr = &*s_region as *const Region;
+
+        let return_value = unsafe {
+            bls12_381_pairing_equality(
+                ps_ptr as usize,
+                qs_ptr as usize,
+                r_ptr as usize,
+                s_ptr as usize,
+            )
+        };
+
+        if return_value == 0 {
+            Ok(())
+        } else {
+            Err(VerificationError::from_error_code(return_value).into())
+        }
+    }
 }
 
 // ---------------------------------- querier ----------------------------------
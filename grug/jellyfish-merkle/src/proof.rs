@@ -3,6 +3,7 @@ use {
     borsh::{BorshDeserialize, BorshSerialize},
     grug_types::{Hash256, Order},
     serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
     thiserror::Error,
 };
 
@@ -24,6 +25,24 @@ pub enum ProofError {
     // TODO: add more details to the error message?
     #[error("expecting bitarrays to share a common prefix but they do not")]
     NotCommonPrefix,
+
+    #[error("proof depth {depth} exceeds the maximum of {max}")]
+    DepthTooLarge { depth: usize, max: usize },
+
+    #[error("incorrect number of items in multi-proof, expect {expect}, got {actual}")]
+    IncorrectNumberOfItems { expect: usize, actual: usize },
+
+    #[error("multi-proof doesn't contain any item")]
+    EmptyMultiProof,
+
+    #[error("multi-proof doesn't contain enough sibling hashes")]
+    MissingSiblingHash,
+
+    #[error("multi-proof contains more sibling hashes than needed")]
+    UnusedSiblingHash,
+
+    #[error("inconsistent hashes computed for the same node! first: {first}, second: {second}")]
+    InconsistentNodeHash { first: Hash256, second: Hash256 },
 }
 
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
@@ -44,6 +63,92 @@ pub struct NonMembershipProof {
     pub sibling_hashes: Vec<Option<Hash256>>,
 }
 
+/// A proof of the membership or non-membership of multiple keys.
+///
+/// Compared to proving each key individually, sibling nodes that are shared by
+/// the keys' paths, or that can be computed from the other keys, are only
+/// included once.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    /// One item for each key being proven, in the same order as the keys.
+    pub items: Vec<MultiProofItem>,
+    /// Hashes of the sibling nodes that can't be computed from the items, in
+    /// the order they are used during verification: from the deepest level to
+    /// the root, and within each level, from left to right.
+    pub sibling_hashes: Vec<Option<Hash256>>,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultiProofItem {
+    /// Depth of the node where the key's path ends; that is, the number of
+    /// sibling hashes the key would have in an individual proof.
+    pub depth: usize,
+    /// `None` if the key exists, in which case the node is the key's leaf.
+    /// Otherwise, the node proving the key doesn't exist, same as in a
+    /// non-membership proof.
+    pub node: Option<ProofNode>,
+}
+
+impl MultiProof {
+    /// Combine proofs of individual keys into a multi-proof.
+    ///
+    /// The proofs must be generated from the same tree at the same version,
+    /// otherwise the resulting multi-proof won't pass verification. An empty
+    /// list of proofs results in an empty multi-proof, which never passes
+    /// verification.
+    pub fn from_proofs(proofs: Vec<(Hash256, Proof)>) -> Result<Self, ProofError> {
+        let mut items = Vec::with_capacity(proofs.len());
+        let mut individual_sibling_hashes = Vec::with_capacity(proofs.len());
+        let mut nodes = Vec::with_capacity(proofs.len());
+
+        for (idx, (key_hash, proof)) in proofs.iter().enumerate() {
+            let (node, sibling_hashes) = match proof {
+                Proof::Membership(proof) => (None, &proof.sibling_hashes),
+                Proof::NonMembership(proof) => (Some(proof.node.clone()), &proof.sibling_hashes),
+            };
+
+            let depth = sibling_hashes.len();
+
+            nodes.push((bit_path(BitArray::from_bytes(key_hash), depth)?, idx));
+            items.push(MultiProofItem { depth, node });
+            individual_sibling_hashes.push(sibling_hashes);
+        }
+
+        if nodes.is_empty() {
+            return Ok(Self {
+                items,
+                sibling_hashes: vec![],
+            });
+        }
+
+        // Walk the tree the same way the verifier does. Each node is represented
+        // by the index of a key whose path goes through it. Whenever the verifier
+        // needs a sibling hash, take it from that key's individual proof.
+        let mut sibling_hashes = vec![];
+
+        walk_to_root(
+            nodes,
+            |depth, _, idx, sibling| {
+                // Individual proofs' sibling hashes go from bottom up, so the
+                // sibling at this depth is found by counting from the key's
+                // own depth.
+                if sibling.is_none() {
+                    let individual = individual_sibling_hashes[idx];
+                    sibling_hashes.push(individual[individual.len() - depth]);
+                }
+
+                Ok(idx)
+            },
+            |idx, _| Ok(idx),
+        )?;
+
+        Ok(Self {
+            items,
+            sibling_hashes,
+        })
+    }
+}
+
 /// `ProofNode` is just like `Node`, but for internal nodes it omits the child
 /// versions, which aren't needed for proving, only including child node hashes.
 /// This reduces proof sizes.
@@ -101,7 +206,102 @@ pub fn verify_non_membership_proof(
     proof: &NonMembershipProof,
 ) -> Result<(), ProofError> {
     let bitarray = BitArray::from_bytes(&key_hash);
-    let hash = match proof.node {
+    let hash = hash_non_membership_node(bitarray, proof.sibling_hashes.len(), &proof.node)?;
+
+    compute_and_compare_root_hash(root_hash, bitarray, &proof.sibling_hashes, hash)
+}
+
+/// Verify a multi-proof, given the keys being proven and their values, in the
+/// same order as the proof's items. `None` for a value means the key is proven
+/// to not exist.
+pub fn verify_multi_proof(
+    root_hash: Hash256,
+    key_value_hashes: &[(Hash256, Option<Hash256>)],
+    proof: &MultiProof,
+) -> Result<(), ProofError> {
+    if key_value_hashes.len() != proof.items.len() {
+        return Err(ProofError::IncorrectNumberOfItems {
+            expect: key_value_hashes.len(),
+            actual: proof.items.len(),
+        });
+    }
+
+    let nodes = key_value_hashes
+        .iter()
+        .zip(&proof.items)
+        .map(|((key_hash, value_hash), item)| {
+            let bitarray = BitArray::from_bytes(key_hash);
+            let bits = bit_path(bitarray, item.depth)?;
+            let hash = match (value_hash, &item.node) {
+                (Some(value_hash), None) => hash_leaf_node(*key_hash, *value_hash),
+                (None, Some(node)) => hash_non_membership_node(bitarray, item.depth, node)?,
+                (Some(_), Some(_)) => {
+                    return Err(ProofError::IncorrectProofType {
+                        expect: "membership",
+                        actual: "non-membership",
+                    });
+                },
+                (None, None) => {
+                    return Err(ProofError::IncorrectProofType {
+                        expect: "non-membership",
+                        actual: "membership",
+                    });
+                },
+            };
+
+            Ok((bits, hash))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sibling_hashes = proof.sibling_hashes.iter();
+
+    let hash = walk_to_root(
+        nodes,
+        |_, bit, hash, sibling_hash| {
+            let sibling_hash = match sibling_hash {
+                Some(sibling_hash) => Some(sibling_hash),
+                None => *sibling_hashes
+                    .next()
+                    .ok_or(ProofError::MissingSiblingHash)?,
+            };
+
+            if bit == 0 {
+                Ok(hash_internal_node(Some(hash), sibling_hash))
+            } else {
+                Ok(hash_internal_node(sibling_hash, Some(hash)))
+            }
+        },
+        |first, second| {
+            if first != second {
+                return Err(ProofError::InconsistentNodeHash { first, second });
+            }
+
+            Ok(first)
+        },
+    )?;
+
+    if sibling_hashes.next().is_some() {
+        return Err(ProofError::UnusedSiblingHash);
+    }
+
+    if hash != root_hash {
+        return Err(ProofError::RootHashMismatch {
+            computed: hash,
+            actual: root_hash,
+        });
+    }
+
+    Ok(())
+}
+
+/// Compute the hash of the node in a non-membership proof, after checking that
+/// it indeed proves the key doesn't exist.
+fn hash_non_membership_node(
+    bitarray: BitArray,
+    depth: usize,
+    node: &ProofNode,
+) -> Result<Hash256, ProofError> {
+    match *node {
         // If the node given is an internal node, we check the bit at the depth.
         // If the bit is a 0, it must not have a left child; if the bit is a 1,
         // it must not have a right child.
@@ -109,15 +309,18 @@ pub fn verify_non_membership_proof(
             left_hash,
             right_hash,
         } => {
-            match (
-                bitarray.bit_at_index(proof.sibling_hashes.len()),
-                left_hash,
-                right_hash,
-            ) {
-                (0, Some(_), _) | (1, _, Some(_)) => {
-                    return Err(ProofError::UnexpectedChild);
-                },
-                _ => hash_internal_node(left_hash, right_hash),
+            // An internal node can't be at the maximum depth, where there is no
+            // next bit to check.
+            if depth >= bitarray.num_bits {
+                return Err(ProofError::DepthTooLarge {
+                    depth,
+                    max: bitarray.num_bits - 1,
+                });
+            }
+
+            match (bitarray.bit_at_index(depth), left_hash, right_hash) {
+                (0, Some(_), _) | (1, _, Some(_)) => Err(ProofError::UnexpectedChild),
+                _ => Ok(hash_internal_node(left_hash, right_hash)),
             }
         },
         // If the node given is a leaf, it's bit path must share a common prefix
@@ -127,18 +330,114 @@ pub fn verify_non_membership_proof(
             value_hash,
         } => {
             let non_exist_bitarray = BitArray::from_bytes(&key_hash);
-            let exist_bits =
-                bitarray.range(None, Some(proof.sibling_hashes.len()), Order::Descending);
-            let non_exist_bits =
-                non_exist_bitarray.range(None, Some(proof.sibling_hashes.len()), Order::Descending);
-            if exist_bits.zip(non_exist_bits).any(|(a, b)| a != b) {
+            let exist_bits = bit_path(bitarray, depth)?;
+            let non_exist_bits = bit_path(non_exist_bitarray, depth)?;
+            if exist_bits != non_exist_bits {
                 return Err(ProofError::NotCommonPrefix);
             }
-            hash_leaf_node(key_hash, value_hash)
+            Ok(hash_leaf_node(key_hash, value_hash))
         },
+    }
+}
+
+/// Return the first `depth` bits of the bitarray, i.e. the bit path of the
+/// node at that depth along the key's path.
+fn bit_path(bitarray: BitArray, depth: usize) -> Result<Vec<u8>, ProofError> {
+    if depth > bitarray.num_bits {
+        return Err(ProofError::DepthTooLarge {
+            depth,
+            max: bitarray.num_bits,
+        });
+    }
+
+    Ok(bitarray
+        .range(None, Some(depth), Order::Ascending)
+        .collect())
+}
+
+/// Walk from the given nodes, identified by their bit paths, up to the root,
+/// one level at a time, and return what's computed for the root.
+///
+/// Within each level, nodes are visited from left to right. For each node,
+/// `combine` is called with the node's depth, the last bit of its bit path,
+/// the node itself, and its sibling if the sibling is also known, and returns
+/// the parent. If a node is known from more than one source (which happens when
+/// one key's path ends at an internal node that another key's path goes
+/// through), `reconcile` is called to merge them into one.
+///
+/// This determines the order of sibling hashes in a multi-proof, so proving and
+/// verifying must both use it.
+fn walk_to_root<T, C, R>(
+    nodes: Vec<(Vec<u8>, T)>,
+    mut combine: C,
+    mut reconcile: R,
+) -> Result<T, ProofError>
+where
+    C: FnMut(usize, u8, T, Option<T>) -> Result<T, ProofError>,
+    R: FnMut(T, T) -> Result<T, ProofError>,
+{
+    // Known nodes, indexed by depth, then by bit path.
+    let mut levels = BTreeMap::<usize, BTreeMap<Vec<u8>, T>>::new();
+
+    for (bits, node) in nodes {
+        insert_node(&mut levels, bits, node, &mut reconcile)?;
+    }
+
+    loop {
+        let (depth, level) = levels.pop_last().ok_or(ProofError::EmptyMultiProof)?;
+
+        // The only node at depth zero is the root.
+        if depth == 0 {
+            return level
+                .into_values()
+                .next()
+                .ok_or(ProofError::EmptyMultiProof);
+        }
+
+        let mut level = level.into_iter().peekable();
+
+        while let Some((mut bits, node)) = level.next() {
+            let bit = bits[depth - 1];
+            bits.truncate(depth - 1);
+
+            // Nodes are sorted by bit path, so if this node is a left child and
+            // its sibling is known, the sibling must be the next one.
+            let sibling = if bit == 0
+                && level
+                    .peek()
+                    .is_some_and(|(next, _)| next.starts_with(&bits))
+            {
+                level.next().map(|(_, sibling)| sibling)
+            } else {
+                None
+            };
+
+            let parent = combine(depth, bit, node, sibling)?;
+
+            insert_node(&mut levels, bits, parent, &mut reconcile)?;
+        }
+    }
+}
+
+fn insert_node<T, R>(
+    levels: &mut BTreeMap<usize, BTreeMap<Vec<u8>, T>>,
+    bits: Vec<u8>,
+    node: T,
+    reconcile: &mut R,
+) -> Result<(), ProofError>
+where
+    R: FnMut(T, T) -> Result<T, ProofError>,
+{
+    let level = levels.entry(bits.len()).or_default();
+
+    let node = match level.remove(&bits) {
+        Some(existing) => reconcile(existing, node)?,
+        None => node,
     };
 
-    compute_and_compare_root_hash(root_hash, bitarray, &proof.sibling_hashes, hash)
+    level.insert(bits, node);
+
+    Ok(())
 }
 
 fn compute_and_compare_root_hash(
@@ -298,5 +597,166 @@ mod tests {
         assert!(verify_non_membership_proof(HASH_ROOT, key.as_bytes().hash256(), &proof).is_ok());
     }
 
+    fn multi_proof_of_m_and_l() -> MultiProof {
+        MultiProof {
+            items: vec![
+                MultiProofItem {
+                    depth: 4,
+                    node: None,
+                },
+                MultiProofItem {
+                    depth: 4,
+                    node: None,
+                },
+            ],
+            sibling_hashes: vec![Some(HASH_010), None, Some(HASH_1)],
+        }
+    }
+
+    #[test_case(
+        vec![("m", Some("bar")), ("L", Some("fuzz"))],
+        multi_proof_of_m_and_l();
+        "proving (m, bar) and (L, fuzz)"
+    )]
+    #[test_case(
+        vec![("r", Some("foo")), ("a", Some("buzz")), ("b", None)],
+        MultiProof {
+            items: vec![
+                MultiProofItem {
+                    depth: 3,
+                    node: None,
+                },
+                MultiProofItem {
+                    depth: 1,
+                    node: None,
+                },
+                MultiProofItem {
+                    depth: 1,
+                    node: Some(ProofNode::Internal {
+                        left_hash:  None,
+                        right_hash: Some(HASH_01),
+                    }),
+                },
+            ],
+            sibling_hashes: vec![Some(HASH_011), None],
+        };
+        "proving (r, foo), (a, buzz), and b"
+    )]
+    #[test_case(
+        vec![("o", None), ("m", Some("bar"))],
+        MultiProof {
+            items: vec![
+                MultiProofItem {
+                    depth: 4,
+                    node: Some(ProofNode::Leaf {
+                        key_hash:   HASH_M,
+                        value_hash: HASH_BAR,
+                    }),
+                },
+                MultiProofItem {
+                    depth: 4,
+                    node: None,
+                },
+            ],
+            sibling_hashes: vec![
+                Some(HASH_0111),
+                Some(HASH_010),
+                None,
+                Some(HASH_1),
+            ],
+        };
+        "proving o and (m, bar)"
+    )]
+    fn verifying_multi(keys_values: Vec<(&str, Option<&str>)>, proof: MultiProof) {
+        let key_value_hashes = keys_values
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key.as_bytes().hash256(),
+                    value.map(|v| v.as_bytes().hash256()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert!(verify_multi_proof(HASH_ROOT, &key_value_hashes, &proof).is_ok());
+    }
+
+    #[test]
+    fn verifying_multi_fails() {
+        let key_value_hashes = [
+            (b"m".hash256(), Some(b"bar".hash256())),
+            (b"L".hash256(), Some(b"fuzz".hash256())),
+        ];
+
+        // Incorrect value
+        assert!(matches!(
+            verify_multi_proof(
+                HASH_ROOT,
+                &[
+                    key_value_hashes[0],
+                    (b"L".hash256(), Some(b"buzz".hash256()))
+                ],
+                &multi_proof_of_m_and_l(),
+            ),
+            Err(ProofError::RootHashMismatch { .. })
+        ));
+
+        // Incorrect number of keys
+        assert!(matches!(
+            verify_multi_proof(HASH_ROOT, &key_value_hashes[..1], &multi_proof_of_m_and_l()),
+            Err(ProofError::IncorrectNumberOfItems {
+                expect: 1,
+                actual: 2,
+            })
+        ));
+
+        // Missing a sibling hash
+        let mut proof = multi_proof_of_m_and_l();
+        proof.sibling_hashes.pop();
+        assert!(matches!(
+            verify_multi_proof(HASH_ROOT, &key_value_hashes, &proof),
+            Err(ProofError::MissingSiblingHash)
+        ));
+
+        // An extra sibling hash
+        let mut proof = multi_proof_of_m_and_l();
+        proof.sibling_hashes.push(None);
+        assert!(matches!(
+            verify_multi_proof(HASH_ROOT, &key_value_hashes, &proof),
+            Err(ProofError::UnusedSiblingHash)
+        ));
+
+        // Depth beyond the length of the key hash
+        let mut proof = multi_proof_of_m_and_l();
+        proof.items[0].depth = 257;
+        assert!(matches!(
+            verify_multi_proof(HASH_ROOT, &key_value_hashes, &proof),
+            Err(ProofError::DepthTooLarge { depth: 257, .. })
+        ));
+    }
+
+    #[test]
+    fn combining_proofs() {
+        let proofs = vec![
+            (
+                b"m".hash256(),
+                Proof::Membership(MembershipProof {
+                    sibling_hashes: vec![Some(HASH_0111), Some(HASH_010), None, Some(HASH_1)],
+                }),
+            ),
+            (
+                b"L".hash256(),
+                Proof::Membership(MembershipProof {
+                    sibling_hashes: vec![Some(HASH_0110), Some(HASH_010), None, Some(HASH_1)],
+                }),
+            ),
+        ];
+
+        assert_eq!(
+            MultiProof::from_proofs(proofs).unwrap(),
+            multi_proof_of_m_and_l()
+        );
+    }
+
     // TODO: add fail cases for proofs
 }
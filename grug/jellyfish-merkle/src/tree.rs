@@ -1,7 +1,7 @@
 use {
     crate::{
-        BitArray, Child, InternalNode, LeafNode, MembershipProof, Node, NonMembershipProof, Proof,
-        ProofNode,
+        BitArray, Child, InternalNode, LeafNode, MembershipProof, MultiProof, Node,
+        NonMembershipProof, Proof, ProofNode,
     },
     grug_storage::{Map, PrefixBound, Set},
     grug_types::{Batch, Hash256, HashExt, Op, Order, StdResult, Storage},
@@ -550,6 +550,29 @@ impl<'a> MerkleTree<'a> {
         }
     }
 
+    /// Generate Merkle proof for the membership or non-membership of multiple
+    /// key hashes at once.
+    ///
+    /// The proof is smaller than the individual proofs combined, as sibling
+    /// nodes shared by the keys' paths are only included once. The same caveats
+    /// as `prove` apply.
+    pub fn prove_many(
+        &self,
+        storage: &dyn Storage,
+        key_hashes: &[Hash256],
+        version: u64,
+    ) -> StdResult<MultiProof> {
+        let proofs = key_hashes
+            .iter()
+            .map(|key_hash| Ok((*key_hash, self.prove(storage, *key_hash, version)?)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // The proofs are all generated from this tree at this version, so they
+        // can always be combined.
+        Ok(MultiProof::from_proofs(proofs)
+            .unwrap_or_else(|err| unreachable!("failed to combine proofs: {err}")))
+    }
+
     /// Delete nodes that are no longer part of the tree as of `up_to_version`.
     ///
     /// Note: We must make sure `up_to_version` is smaller or equal to the
@@ -758,6 +781,7 @@ fn into_child(version: u64, outcome: Outcome) -> Option<Child> {
 mod tests {
     use {
         super::*,
+        crate::{verify_multi_proof, MultiProofItem},
         grug_types::{MockStorage, ResultExt, StdError},
         hex_literal::hex,
         test_case::test_case,
@@ -1013,6 +1037,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proving_many() {
+        let (storage, _) = build_test_case().unwrap();
+
+        // Keys are proven in the given order. Sibling hashes that can be
+        // computed from other keys, e.g. node 1 from key "a", are omitted.
+        let key_hashes = [b"r".hash256(), b"a".hash256(), b"b".hash256()];
+        let proof = TREE.prove_many(&storage, &key_hashes, 0).unwrap();
+
+        assert_eq!(proof, MultiProof {
+            items: vec![
+                MultiProofItem {
+                    depth: 3,
+                    node: None,
+                },
+                MultiProofItem {
+                    depth: 1,
+                    node: None,
+                },
+                MultiProofItem {
+                    depth: 1,
+                    node: Some(ProofNode::Internal {
+                        left_hash: None,
+                        right_hash: Some(HASH_01),
+                    }),
+                },
+            ],
+            sibling_hashes: vec![Some(HASH_011), None],
+        });
+
+        assert!(verify_multi_proof(
+            HASH_ROOT,
+            &[
+                (key_hashes[0], Some(b"foo".hash256())),
+                (key_hashes[1], Some(b"buzz".hash256())),
+                (key_hashes[2], None),
+            ],
+            &proof,
+        )
+        .is_ok());
+    }
+
     /// An edge case found in the Zellic audit.
     ///
     /// Attempting to generate proofs in an empty tree would fail with a "data
@@ -44,6 +44,9 @@ pub enum MathError {
         b: String,
     },
 
+    #[error("exponential overflow: exp({a}) (type: {ty})")]
+    OverflowExp { ty: &'static str, a: String },
+
     #[error("left shift overflow: {a} << {b}")]
     OverflowShl { a: String, b: u32 },
 
@@ -78,6 +81,9 @@ pub enum MathError {
 
     #[error("logarithm of zero")]
     ZeroLog,
+
+    #[error("logarithm of negative: ln({a})")]
+    NegativeLog { a: String },
 }
 
 impl MathError {
@@ -148,6 +154,27 @@ impl MathError {
         }
     }
 
+    pub fn overflow_pow_dec<T>(a: T, b: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::OverflowPow {
+            ty: type_name::<T>(),
+            a: a.to_string(),
+            b: b.to_string(),
+        }
+    }
+
+    pub fn overflow_exp<T>(a: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::OverflowExp {
+            ty: type_name::<T>(),
+            a: a.to_string(),
+        }
+    }
+
     pub fn overflow_shl<T>(a: T, b: u32) -> Self
     where
         T: ToString,
@@ -216,6 +243,13 @@ impl MathError {
     pub fn zero_log() -> Self {
         Self::ZeroLog
     }
+
+    pub fn negative_log<T>(a: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::NegativeLog { a: a.to_string() }
+    }
 }
 
 pub type MathResult<T> = Result<T, MathError>;
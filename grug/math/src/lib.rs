@@ -17,6 +17,7 @@ mod number_const;
 mod prev;
 mod sign;
 mod signed;
+mod transcendental;
 mod unsigned;
 mod utils;
 
@@ -26,5 +27,5 @@ mod test_utils;
 pub use {
     bytable::*, dec::*, decimal::*, error::*, fixed_point::*, fraction::*, inner::*, int::*,
     integer::*, is_zero::*, multiply_fraction::*, multiply_ratio::*, next::*, number::*,
-    number_const::*, prev::*, sign::*, signed::*, unsigned::*,
+    number_const::*, prev::*, sign::*, signed::*, transcendental::*, unsigned::*,
 };
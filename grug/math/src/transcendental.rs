@@ -0,0 +1,917 @@
+use {
+    crate::{
+        Dec, Int, Int256, Int512, IsZero, MathError, MathResult, NextNumber, Number, NumberConst,
+        PrevNumber, Sign, Uint256, Uint512,
+    },
+    std::fmt::Display,
+};
+
+/// Number of decimal places used in intermediate computations.
+const WORKING_DECIMAL_PLACES: u32 = 36;
+
+/// ln(2), truncated to [`WORKING_DECIMAL_PLACES`] decimal places.
+const LN_2: u128 = 693147180559945309417232121458176568;
+
+/// Describes transcendental functions of decimal types: logarithms, the
+/// exponential function, and powers with decimal exponents.
+///
+/// ## Precision
+///
+/// Intermediate values are computed with 36 decimal places, using the integer
+/// type of the next word size, and the result is truncated toward zero to the
+/// type's own decimal places. The computation is done purely in integers, so
+/// the result is deterministic across platforms.
+///
+/// For decimals with 18 decimal places:
+///
+/// - `checked_ln` and `checked_log2` are off from the exact value by less than
+///   one [`TICK`](crate::FixedPoint::TICK).
+/// - `checked_exp` is off by less than one tick plus 10<sup>-33</sup> times
+///   the result.
+/// - `checked_pow_dec` is off by less than one tick plus
+///   10<sup>-33</sup> × (1 + |exp|) times the result. For example,
+///   1.0001<sup>887272</sup> ≈ 3.4 × 10<sup>38</sup> (the price at the
+///   maximum tick of a concentrated liquidity pool) is off by less than one
+///   part in 10<sup>27</sup>.
+pub trait Transcendental: Sized + Copy {
+    /// Natural logarithm.
+    ///
+    /// Errors if `self` is not positive, or if the result is negative and the
+    /// type is unsigned.
+    fn checked_ln(self) -> MathResult<Self>;
+
+    /// Logarithm with base 2.
+    ///
+    /// Errors if `self` is not positive, or if the result is negative and the
+    /// type is unsigned.
+    fn checked_log2(self) -> MathResult<Self>;
+
+    /// The exponential function, i.e. `e ^ self`.
+    ///
+    /// Errors if the result overflows. A result too small to be represented
+    /// is rounded to zero.
+    fn checked_exp(self) -> MathResult<Self>;
+
+    /// Raise `self` to a decimal power, computed as `exp(exp * ln(self))`.
+    ///
+    /// Zero to the power of zero is one. Errors if `self` is negative, if
+    /// `self` is zero and `exp` is negative, or if the result overflows.
+    fn checked_pow_dec(self, exp: Self) -> MathResult<Self>;
+
+    /// Natural logarithm, returning `Self::MIN` where
+    /// [`checked_ln`](Self::checked_ln) errors, i.e. if `self` is not positive,
+    /// or if the result is negative and the type is unsigned.
+    fn saturating_ln(self) -> Self;
+
+    /// Logarithm with base 2, returning `Self::MIN` where
+    /// [`checked_log2`](Self::checked_log2) errors, i.e. if `self` is not
+    /// positive, or if the result is negative and the type is unsigned.
+    fn saturating_log2(self) -> Self;
+
+    /// The exponential function, returning `Self::MAX` if the result
+    /// overflows.
+    fn saturating_exp(self) -> Self;
+
+    /// Raise `self` to a decimal power, returning `Self::MAX` where
+    /// [`checked_pow_dec`](Self::checked_pow_dec) errors, i.e. if the result
+    /// overflows, if `self` is zero and `exp` is negative, or if `self` is
+    /// negative.
+    fn saturating_pow_dec(self, exp: Self) -> Self;
+}
+
+impl<U, const S: u32> Transcendental for Dec<U, S>
+where
+    Self: NumberConst + Sign + IsZero + Display + PartialOrd,
+    Int<U>: NextNumber + Number + NumberConst,
+    <Int<U> as NextNumber>::Next:
+        Number + NumberConst + IsZero + PrevNumber<Prev = Int<U>> + WorkingConst + PartialOrd,
+{
+    fn checked_ln(self) -> MathResult<Self> {
+        let working = Working::new(S)?;
+        let (negative, k, ln_y) = working.ln_parts(working.scale_up(self)?)?;
+        let ln = k.checked_mul(working.ln_2)?.checked_add(ln_y)?;
+
+        working.scale_down(negative, ln)
+    }
+
+    fn checked_log2(self) -> MathResult<Self> {
+        let working = Working::new(S)?;
+        let (negative, k, ln_y) = working.ln_parts(working.scale_up(self)?)?;
+        let log2 = k
+            .checked_mul(working.one)?
+            .checked_add(working.div(ln_y, working.ln_2)?)?;
+
+        working.scale_down(negative, log2)
+    }
+
+    fn checked_exp(self) -> MathResult<Self> {
+        (|| {
+            let working = Working::new(S)?;
+            let exp = working.exp(self.is_negative(), working.scale_up_abs(self)?)?;
+
+            working.scale_down(false, exp)
+        })()
+        .map_err(|_| MathError::overflow_exp(self))
+    }
+
+    fn checked_pow_dec(self, exp: Self) -> MathResult<Self> {
+        if exp.is_zero() || self == Self::ONE {
+            return Ok(Self::ONE);
+        }
+
+        if self.is_zero() {
+            if exp.is_negative() {
+                return Err(MathError::division_by_zero(Self::ONE));
+            }
+
+            return Ok(Self::ZERO);
+        }
+
+        let working = Working::new(S)?;
+        let (ln_negative, k, ln_y) = working.ln_parts(working.scale_up(self)?)?;
+        let ln = k.checked_mul(working.ln_2)?.checked_add(ln_y)?;
+        let negative = ln_negative != exp.is_negative();
+
+        (|| {
+            let power = match working.mul(ln, working.scale_up_abs(exp)?) {
+                Ok(power) => power,
+                // The exponent is so big in magnitude that the result either
+                // overflows or rounds to zero.
+                Err(_) if negative => return Ok(Self::ZERO),
+                Err(err) => return Err(err),
+            };
+
+            working.scale_down(false, working.exp(negative, power)?)
+        })()
+        .map_err(|_| MathError::overflow_pow_dec(self, exp))
+    }
+
+    fn saturating_ln(self) -> Self {
+        self.checked_ln().unwrap_or(Self::MIN)
+    }
+
+    fn saturating_log2(self) -> Self {
+        self.checked_log2().unwrap_or(Self::MIN)
+    }
+
+    fn saturating_exp(self) -> Self {
+        self.checked_exp().unwrap_or(Self::MAX)
+    }
+
+    fn saturating_pow_dec(self, exp: Self) -> Self {
+        self.checked_pow_dec(exp).unwrap_or(Self::MAX)
+    }
+}
+
+// ---------------------------------- working ----------------------------------
+
+/// Fixed-point arithmetic with [`WORKING_DECIMAL_PLACES`] decimal places, on
+/// non-negative values of the integer type `W`.
+struct Working<W> {
+    /// One, i.e. 10^36.
+    one: W,
+    /// ln(2).
+    ln_2: W,
+    /// Factor between the working precision and the decimal's precision.
+    scale: W,
+}
+
+impl<W> Working<W>
+where
+    W: Number + NumberConst + IsZero + WorkingConst + PartialOrd,
+{
+    /// Errors if the decimal has more decimal places than the working
+    /// precision.
+    fn new(decimal_places: u32) -> MathResult<Self> {
+        let scale_places = WORKING_DECIMAL_PLACES
+            .checked_sub(decimal_places)
+            .ok_or_else(|| MathError::overflow_sub(WORKING_DECIMAL_PLACES, decimal_places))?;
+
+        Ok(Self {
+            one: W::POWERS_OF_TEN[WORKING_DECIMAL_PLACES as usize],
+            ln_2: W::LN_2,
+            scale: W::POWERS_OF_TEN[scale_places as usize],
+        })
+    }
+
+    /// Convert a decimal to the working precision, for taking its logarithm.
+    /// Errors if the decimal isn't positive.
+    fn scale_up<U, const S: u32>(&self, dec: Dec<U, S>) -> MathResult<W>
+    where
+        Dec<U, S>: Sign + IsZero + Display,
+        Int<U>: NextNumber<Next = W>,
+    {
+        if dec.is_zero() {
+            return Err(MathError::zero_log());
+        }
+
+        if dec.is_negative() {
+            return Err(MathError::negative_log(dec));
+        }
+
+        dec.0.into_next().checked_mul(self.scale)
+    }
+
+    /// Convert a decimal to its absolute value in the working precision.
+    fn scale_up_abs<U, const S: u32>(&self, dec: Dec<U, S>) -> MathResult<W>
+    where
+        Dec<U, S>: Sign,
+        Int<U>: NextNumber<Next = W>,
+    {
+        let negative = dec.is_negative();
+        let value = dec.0.into_next().checked_mul(self.scale)?;
+
+        if negative {
+            W::ZERO.checked_sub(value)
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Convert an absolute value in the working precision, and its sign, to a
+    /// decimal, truncating the extra decimal places.
+    fn scale_down<U, const S: u32>(&self, negative: bool, value: W) -> MathResult<Dec<U, S>>
+    where
+        Int<U>: Number + NumberConst,
+        W: PrevNumber<Prev = Int<U>>,
+    {
+        let value = value.checked_div(self.scale)?.checked_into_prev()?;
+
+        if negative {
+            Int::ZERO.checked_sub(value).map(Dec::raw)
+        } else {
+            Ok(Dec::raw(value))
+        }
+    }
+
+    fn mul(&self, a: W, b: W) -> MathResult<W> {
+        a.checked_mul(b)?.checked_div(self.one)
+    }
+
+    fn div(&self, a: W, b: W) -> MathResult<W> {
+        a.checked_mul(self.one)?.checked_div(b)
+    }
+
+    /// Natural logarithm of a positive value `x`, returned as a tuple
+    /// `(negative, k, ln_y)` such that `|ln(x)| = k * ln(2) + ln_y`, where `k`
+    /// is an integer (_not_ in the working precision) and `0 <= ln_y < ln(2)`.
+    fn ln_parts(&self, x: W) -> MathResult<(bool, W, W)> {
+        // For x < 1, ln(x) = -ln(1 / x).
+        let (negative, x) = if x < self.one {
+            (true, self.div(self.one, x)?)
+        } else {
+            (false, x)
+        };
+
+        // Find the integer k such that x = 2^k * y, where 1 <= y < 2.
+        let two = W::ONE.checked_add(W::ONE)?;
+        let two_fixed = self.one.checked_mul(two)?;
+        let mut k = W::ZERO;
+        let mut pow = W::ONE;
+
+        while x.checked_div(pow)? >= two_fixed {
+            k.checked_add_assign(W::ONE)?;
+            pow.checked_mul_assign(two)?;
+        }
+
+        let y = x.checked_div(pow)?;
+
+        // ln(y) = 2 * atanh(z) = 2 * (z + z^3 / 3 + z^5 / 5 + ...),
+        // where z = (y - 1) / (y + 1) is in [0, 1/3).
+        let z = self.div(y.checked_sub(self.one)?, y.checked_add(self.one)?)?;
+        let z_squared = self.mul(z, z)?;
+        let mut power = z;
+        let mut sum = z;
+        let mut denominator = W::ONE;
+
+        loop {
+            power = self.mul(power, z_squared)?;
+            denominator.checked_add_assign(two)?;
+
+            let term = power.checked_div(denominator)?;
+
+            if term.is_zero() {
+                break;
+            }
+
+            sum.checked_add_assign(term)?;
+        }
+
+        Ok((negative, k, sum.checked_mul(two)?))
+    }
+
+    /// The exponential function of `x`, or of `-x` if `negative` is true.
+    fn exp(&self, negative: bool, x: W) -> MathResult<W> {
+        if !negative {
+            return self.exp_non_negative(x);
+        }
+
+        // For x < 0, exp(x) = 1 / exp(-x). If exp(-x) overflows, the result
+        // rounds to zero.
+        match self.exp_non_negative(x) {
+            Ok(exp) => self.div(self.one, exp),
+            Err(_) => Ok(W::ZERO),
+        }
+    }
+
+    fn exp_non_negative(&self, x: W) -> MathResult<W> {
+        // Reduce the argument: x = k * ln(2) + r, where k is an integer and
+        // 0 <= r < ln(2), so that exp(x) = 2^k * exp(r).
+        let k = x.checked_div(self.ln_2)?;
+        let r = x.checked_sub(k.checked_mul(self.ln_2)?)?;
+
+        // Compute 2^k. The multiplication overflows after at most as many
+        // iterations as the number of bits in `W`, so the loop is bounded.
+        let two = W::ONE.checked_add(W::ONE)?;
+        let mut pow = W::ONE;
+        let mut i = W::ZERO;
+
+        while i < k {
+            pow.checked_mul_assign(two)?;
+            i.checked_add_assign(W::ONE)?;
+        }
+
+        // exp(r) = 1 + r + r^2 / 2! + r^3 / 3! + ...
+        let mut term = self.one;
+        let mut sum = self.one;
+        let mut n = W::ONE;
+
+        loop {
+            term = self.mul(term, r)?.checked_div(n)?;
+
+            if term.is_zero() {
+                break;
+            }
+
+            sum.checked_add_assign(term)?;
+            n.checked_add_assign(W::ONE)?;
+        }
+
+        sum.checked_mul(pow)
+    }
+}
+
+// ------------------------------- working const -------------------------------
+
+/// Constants of the working precision, computed at compile time.
+trait WorkingConst: Sized {
+    /// 10^i, for i from 0 to [`WORKING_DECIMAL_PLACES`].
+    const POWERS_OF_TEN: [Self; WORKING_DECIMAL_PLACES as usize + 1];
+
+    /// ln(2), i.e. [`LN_2`].
+    const LN_2: Self;
+}
+
+macro_rules! impl_working_const {
+    ($t:ty, $new:ident, $prim:ty) => {
+        impl WorkingConst for $t {
+            const LN_2: Self = <$t>::$new(LN_2 as $prim);
+            const POWERS_OF_TEN: [Self; WORKING_DECIMAL_PLACES as usize + 1] = {
+                let mut powers = [<$t as NumberConst>::ONE; WORKING_DECIMAL_PLACES as usize + 1];
+                let mut i = 0;
+                while i < powers.len() {
+                    powers[i] = <$t>::$new((10 as $prim).pow(i as u32));
+                    i += 1;
+                }
+                powers
+            };
+        }
+    };
+}
+
+impl_working_const!(Uint256, new_from_u128, u128);
+impl_working_const!(Uint512, new_from_u128, u128);
+impl_working_const!(Int256, new_from_i128, i128);
+impl_working_const!(Int512, new_from_i128, i128);
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::{Working, WORKING_DECIMAL_PLACES},
+        crate::{
+            dec_test, test_utils::bt, Dec, MathError, Number, NumberConst, Transcendental, Udec128,
+            Udec256, Uint128, Uint256,
+        },
+        proptest::prelude::*,
+        std::str::FromStr,
+    };
+
+    // Expected values are computed with 100 significant digits using mpmath,
+    // then truncated toward zero to 18 decimal places.
+
+    dec_test!( checked_ln
+        inputs = {
+            udec128 = {
+                passing: [
+                    ("1", "0"),
+                    ("2", "0.693147180559945309"),
+                    ("10", "2.302585092994045684"),
+                    ("340282366920938463463.374607431768211455", "47.276307437780177293"),
+                ],
+                failing: [
+                    "0",
+                    "0.5",
+                ]
+            }
+            udec256 = {
+                passing: [
+                    ("1", "0"),
+                    ("2", "0.693147180559945309"),
+                    ("10", "2.302585092994045684"),
+                    ("340282366920938463463.374607431768211455", "47.276307437780177293"),
+                ],
+                failing: [
+                    "0",
+                    "0.5",
+                ]
+            }
+            dec128 = {
+                passing: [
+                    ("1", "0"),
+                    ("2", "0.693147180559945309"),
+                    ("10", "2.302585092994045684"),
+                    ("0.5", "-0.693147180559945309"),
+                    ("0.000000000000000001", "-41.446531673892822312"),
+                ],
+                failing: [
+                    "0",
+                    "-1",
+                ]
+            }
+            dec256 = {
+                passing: [
+                    ("1", "0"),
+                    ("2", "0.693147180559945309"),
+                    ("10", "2.302585092994045684"),
+                    ("0.5", "-0.693147180559945309"),
+                    ("0.000000000000000001", "-41.446531673892822312"),
+                ],
+                failing: [
+                    "0",
+                    "-1",
+                ]
+            }
+        }
+        method = |_0d, passing, failing| {
+            for (input, expected) in passing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert_eq!(input.checked_ln().unwrap(), Dec::from_str(expected).unwrap());
+                assert_eq!(input.saturating_ln(), Dec::from_str(expected).unwrap());
+            }
+
+            for input in failing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert!(input.checked_ln().is_err());
+                assert_eq!(input.saturating_ln(), Dec::MIN);
+            }
+        }
+    );
+
+    dec_test!( checked_log2
+        inputs = {
+            udec128 = {
+                passing: [
+                    ("1", "0"),
+                    ("8", "3"),
+                    ("3", "1.584962500721156181"),
+                ],
+                failing: [
+                    "0",
+                    "0.125",
+                ]
+            }
+            udec256 = {
+                passing: [
+                    ("1", "0"),
+                    ("8", "3"),
+                    ("3", "1.584962500721156181"),
+                ],
+                failing: [
+                    "0",
+                    "0.125",
+                ]
+            }
+            dec128 = {
+                passing: [
+                    ("1", "0"),
+                    ("8", "3"),
+                    ("3", "1.584962500721156181"),
+                    ("0.125", "-3"),
+                    ("0.1", "-3.321928094887362347"),
+                ],
+                failing: [
+                    "0",
+                    "-8",
+                ]
+            }
+            dec256 = {
+                passing: [
+                    ("1", "0"),
+                    ("8", "3"),
+                    ("3", "1.584962500721156181"),
+                    ("0.125", "-3"),
+                    ("0.1", "-3.321928094887362347"),
+                ],
+                failing: [
+                    "0",
+                    "-8",
+                ]
+            }
+        }
+        method = |_0d, passing, failing| {
+            for (input, expected) in passing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert_eq!(input.checked_log2().unwrap(), Dec::from_str(expected).unwrap());
+                assert_eq!(input.saturating_log2(), Dec::from_str(expected).unwrap());
+            }
+
+            for input in failing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert!(input.checked_log2().is_err());
+                assert_eq!(input.saturating_log2(), Dec::MIN);
+            }
+        }
+    );
+
+    dec_test!( checked_exp
+        inputs = {
+            udec128 = {
+                passing: [
+                    ("0", "1"),
+                    ("1", "2.718281828459045235"),
+                    ("10", "22026.465794806716516957"),
+                ],
+                failing: [
+                    "48",
+                ]
+            }
+            udec256 = {
+                passing: [
+                    ("0", "1"),
+                    ("1", "2.718281828459045235"),
+                    ("10", "22026.465794806716516957"),
+                ],
+                failing: [
+                    "136",
+                ]
+            }
+            dec128 = {
+                passing: [
+                    ("0", "1"),
+                    ("1", "2.718281828459045235"),
+                    ("10", "22026.465794806716516957"),
+                    ("-1", "0.367879441171442321"),
+                    ("-41", "0.000000000000000001"),
+                    ("-42", "0"),
+                ],
+                failing: [
+                    "47",
+                ]
+            }
+            dec256 = {
+                passing: [
+                    ("0", "1"),
+                    ("1", "2.718281828459045235"),
+                    ("10", "22026.465794806716516957"),
+                    ("-1", "0.367879441171442321"),
+                    ("-41", "0.000000000000000001"),
+                    ("-42", "0"),
+                ],
+                failing: [
+                    "136",
+                ]
+            }
+        }
+        method = |_0d, passing, failing| {
+            for (input, expected) in passing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert_eq!(input.checked_exp().unwrap(), Dec::from_str(expected).unwrap());
+            }
+
+            for input in failing {
+                let input = bt(_0d, Dec::from_str(input).unwrap());
+                assert!(matches!(input.checked_exp(), Err(MathError::OverflowExp { .. })));
+                assert_eq!(input.saturating_exp(), Dec::MAX);
+            }
+        }
+    );
+
+    dec_test!( checked_pow_dec
+        inputs = {
+            udec128 = {
+                passing: [
+                    ("0", "0", "1"),
+                    ("0", "2", "0"),
+                    ("2", "0.5", "1.414213562373095048"),
+                    ("0.5", "2.5", "0.176776695296636881"),
+                    ("1.0001", "100", "1.010049662092876568"),
+                    // Off by one tick, as the result is truncated.
+                    ("9", "0.5", "2.999999999999999999"),
+                    ("1.05", "12.5", "1.840205135548584653"),
+                ],
+                failing: [
+                    ("10", "21"),
+                ]
+            }
+            udec256 = {
+                passing: [
+                    ("0", "0", "1"),
+                    ("0", "2", "0"),
+                    ("2", "0.5", "1.414213562373095048"),
+                    ("0.5", "2.5", "0.176776695296636881"),
+                    ("1.0001", "100", "1.010049662092876568"),
+                    // Off by one tick, as the result is truncated.
+                    ("9", "0.5", "2.999999999999999999"),
+                    ("1.05", "12.5", "1.840205135548584653"),
+                ],
+                failing: [
+                    ("10", "60"),
+                ]
+            }
+            dec128 = {
+                passing: [
+                    ("0", "0", "1"),
+                    ("0", "2", "0"),
+                    ("2", "0.5", "1.414213562373095048"),
+                    ("0.5", "2.5", "0.176776695296636881"),
+                    ("1.0001", "100", "1.010049662092876568"),
+                    // Off by one tick, as the result is truncated.
+                    ("9", "0.5", "2.999999999999999999"),
+                    ("1.05", "12.5", "1.840205135548584653"),
+                    ("2", "-0.5", "0.707106781186547524"),
+                    ("1.0001", "-100", "0.990050328741209481"),
+                    ("1.0001", "-887272", "0"),
+                ],
+                failing: [
+                    ("10", "21"),
+                    ("0", "-1"),
+                    ("-2", "0.5"),
+                ]
+            }
+            dec256 = {
+                passing: [
+                    ("0", "0", "1"),
+                    ("0", "2", "0"),
+                    ("2", "0.5", "1.414213562373095048"),
+                    ("0.5", "2.5", "0.176776695296636881"),
+                    ("1.0001", "100", "1.010049662092876568"),
+                    // Off by one tick, as the result is truncated.
+                    ("9", "0.5", "2.999999999999999999"),
+                    ("1.05", "12.5", "1.840205135548584653"),
+                    ("2", "-0.5", "0.707106781186547524"),
+                    ("1.0001", "-100", "0.990050328741209481"),
+                    ("1.0001", "-887272", "0"),
+                ],
+                failing: [
+                    ("10", "59"),
+                    ("0", "-1"),
+                    ("-2", "0.5"),
+                ]
+            }
+        }
+        method = |_0d, passing, failing| {
+            for (base, exp, expected) in passing {
+                let base = bt(_0d, Dec::from_str(base).unwrap());
+                let exp = Dec::from_str(exp).unwrap();
+                assert_eq!(base.checked_pow_dec(exp).unwrap(), Dec::from_str(expected).unwrap());
+                assert_eq!(base.saturating_pow_dec(exp), Dec::from_str(expected).unwrap());
+            }
+
+            for (base, exp) in failing {
+                let base = bt(_0d, Dec::from_str(base).unwrap());
+                let exp = Dec::from_str(exp).unwrap();
+                assert!(base.checked_pow_dec(exp).is_err());
+                assert_eq!(base.saturating_pow_dec(exp), Dec::MAX);
+            }
+        }
+    );
+
+    /// Assert that `actual` differs from `expected` by no more than `ticks`
+    /// ticks plus `expected / 10^rel_digits`.
+    fn assert_close(actual: Udec256, expected: Udec256, ticks: u128, rel_digits: u32) {
+        let diff = if actual > expected {
+            actual - expected
+        } else {
+            expected - actual
+        };
+        let tolerance = Udec256::raw(
+            expected.0 / Uint256::TEN.checked_pow(rel_digits).unwrap()
+                + Uint256::new_from_u128(ticks),
+        );
+
+        assert!(
+            diff <= tolerance,
+            "actual: {actual}, expected: {expected}, diff: {diff}"
+        );
+    }
+
+    /// The price at the maximum tick of a concentrated liquidity pool.
+    #[test]
+    fn concentrated_liquidity_max_tick() {
+        let price = Udec256::from_str("1.0001")
+            .unwrap()
+            .checked_pow_dec(Udec256::new(887272))
+            .unwrap();
+
+        assert_close(
+            price,
+            Udec256::from_str("340256786836388094050805785052946541066.751507546701582068")
+                .unwrap(),
+            1,
+            27,
+        );
+    }
+
+    /// Pseudo-random inputs, with expected values computed with 100 significant
+    /// digits using mpmath, then truncated toward zero to 18 decimal places.
+    #[test]
+    fn matches_mpmath() {
+        for (x, expected) in [
+            (
+                "5571312880517536969034105145.928800715168284672",
+                "63.887428242662656383",
+            ),
+            (
+                "28719266243070229367646172780.559902383305392128",
+                "65.52736570605326888",
+            ),
+            (
+                "592925763453392450781976864.696363261150363648",
+                "61.647111434912559278",
+            ),
+            ("321.011288570141933568", "5.771476289396852817"),
+            (
+                "576523223958058459.285295432151859200",
+                "40.895792018210934075",
+            ),
+            ("5157298242591.346243535225487360", "29.271433961900532824"),
+            (
+                "7991720811717398.598387573716942848",
+                "36.617182502177955854",
+            ),
+            ("8111.258409395125485568", "9.001008302691829592"),
+            ("575326.960665223072055296", "13.26269378541614241"),
+            ("21737380934553.541682886139183104", "30.710054518172604816"),
+        ] {
+            let actual = Udec256::from_str(x).unwrap().checked_ln().unwrap();
+            assert_close(actual, Udec256::from_str(expected).unwrap(), 1, 77);
+        }
+
+        for (x, expected) in [
+            (
+                "66.780175127358604798",
+                "100522101276359159673437502592.644541072814969638",
+            ),
+            ("0.456869960209338122", "1.579123522004867903"),
+            (
+                "110.335038317705644636",
+                "827748308532872750471079438575823904857258887075.68534892816119356",
+            ),
+            ("7.770660812104709841", "2370.036920089582521719"),
+            (
+                "103.812110958406138371",
+                "1216261456102444190778979215303190397355775823.367019636389687156",
+            ),
+            (
+                "117.302778370674214099",
+                "878920022791235402676479055613716904527329044354777.988772782005140143",
+            ),
+            (
+                "114.473608382410122448",
+                "51910647262391373280280321188250102209807811992595.720684831850558649",
+            ),
+            (
+                "47.760898344492862977",
+                "552452065636983013530.593837204720607328",
+            ),
+            (
+                "76.958536361285842868",
+                "2646474276585471124234450215781036.448446272787293328",
+            ),
+            (
+                "110.140624669401595482",
+                "681499477652907239227791278784955873286932011019.201248161096190914",
+            ),
+        ] {
+            let actual = Udec256::from_str(x).unwrap().checked_exp().unwrap();
+            assert_close(actual, Udec256::from_str(expected).unwrap(), 1, 33);
+        }
+
+        for (base, exp, expected) in [
+            (
+                "0.282598644182687104",
+                "5.188588836991641711",
+                "0.00142019236028207",
+            ),
+            (
+                "4.386604067614944768",
+                "10.569718541743550357",
+                "6125151.665131100786909485",
+            ),
+            (
+                "0.022026449477014480",
+                "3.276727240458267347",
+                "0.000003717757816028",
+            ),
+            (
+                "7.441423389826652160",
+                "14.600424932227822184",
+                "5327803685037.921798854819012104",
+            ),
+            (
+                "0.616319965919492608",
+                "3.017607418922330719",
+                "0.23212276846839025",
+            ),
+            (
+                "0.053470822422557688",
+                "9.842936991513365319",
+                "0.000000000000302645",
+            ),
+            (
+                "0.196629826272935136",
+                "9.593552819174839766",
+                "0.000000167335531291",
+            ),
+            (
+                "0.060594249555503520",
+                "1.518667627101345808",
+                "0.014155264877949871",
+            ),
+            (
+                "0.024798007783485512",
+                "10.477937125259445814",
+                "0.000000000000000015",
+            ),
+            (
+                "0.126548746001969776",
+                "5.175804759464244411",
+                "0.000022566379455183",
+            ),
+        ] {
+            let actual = Udec256::from_str(base)
+                .unwrap()
+                .checked_pow_dec(Udec256::from_str(exp).unwrap())
+                .unwrap();
+            assert_close(actual, Udec256::from_str(expected).unwrap(), 1, 31);
+        }
+    }
+
+    /// Decimals with more decimal places than the working precision aren't
+    /// supported.
+    #[test]
+    fn working_precision_exceeded() {
+        assert!(matches!(
+            Working::<Uint256>::new(WORKING_DECIMAL_PLACES + 1),
+            Err(MathError::OverflowSub { .. })
+        ));
+        assert!(Working::<Uint256>::new(WORKING_DECIMAL_PLACES).is_ok());
+    }
+
+    proptest! {
+        /// `exp(ln(x)) = x`, up to the error in `ln(x)`, which is less than one
+        /// tick, amplified by `exp`.
+        #[test]
+        fn exp_inverts_ln(raw in 1_000_000_000_000_u128..=u128::MAX) {
+            let x = Udec128::raw(Uint128::new(raw));
+            let ln = x.checked_ln().unwrap();
+            let exp = ln.checked_exp().unwrap();
+            let diff = if exp > x { exp - x } else { x - exp };
+            // |exp(ln(x) - 1 tick) - x| ≈ x * 10^-18
+            let tolerance = Udec128::raw(x.0 / Uint128::new(10_u128.pow(18)) + Uint128::new(2));
+
+            prop_assert!(diff <= tolerance, "x: {x}, exp(ln(x)): {exp}");
+        }
+
+        /// `log2(2^n) = n` exactly.
+        #[test]
+        fn log2_of_powers_of_two(n in 0_u32..128) {
+            let x = Udec256::new(2).checked_pow(n).unwrap();
+            prop_assert_eq!(x.checked_log2().unwrap(), Udec256::new(n.into()));
+        }
+
+        /// With an integer exponent, `checked_pow_dec` agrees with the exact
+        /// integer power.
+        #[test]
+        fn pow_dec_agrees_with_pow(base in 1_u128..1_000_000, exp in 0_u32..5) {
+            let base = Udec256::new(base);
+            let expected = base.checked_pow(exp).unwrap();
+            let actual = base.checked_pow_dec(Udec256::new(exp.into())).unwrap();
+
+            assert_close(actual, expected, 1, 32);
+        }
+
+        /// `ln(a * b) = ln(a) + ln(b)`, up to the truncation of each logarithm.
+        #[test]
+        fn ln_of_product(a in 1_u128..u64::MAX.into(), b in 1_u128..u64::MAX.into()) {
+            let a = Udec256::new(a);
+            let b = Udec256::new(b);
+            let lhs = (a * b).checked_ln().unwrap();
+            let rhs = a.checked_ln().unwrap() + b.checked_ln().unwrap();
+
+            assert_close(lhs, rhs, 2, 77);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use {
     crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
     anyhow::ensure,
     grug_math::{IsZero, Number, Uint128},
-    grug_types::{Addr, Coins, Denom, MutableCtx, Response, StdResult, Storage},
+    grug_types::{Addr, Coins, Denom, MutableCtx, Order, Response, StdResult, Storage},
     std::collections::HashMap,
 };
 
@@ -112,6 +112,30 @@ pub fn transfer(
     Ok(Response::new())
 }
 
+/// Set an account's balances to the given coins, adjusting the total supplies
+/// accordingly. Balances of denoms not among the coins are set to zero.
+///
+/// This is for overriding balances in simulations, by registering it with
+/// `grug_app::App::with_balance_overrides`.
+pub fn override_balances(storage: &mut dyn Storage, address: Addr, coins: &Coins) -> StdResult<()> {
+    let old_balances = BALANCES_BY_ADDR
+        .prefix(address)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (denom, amount) in old_balances {
+        decrease_supply(storage, &denom, amount)?;
+        decrease_balance(storage, address, &denom, amount)?;
+    }
+
+    for coin in coins {
+        increase_supply(storage, coin.denom, *coin.amount)?;
+        increase_balance(storage, address, coin.denom, *coin.amount)?;
+    }
+
+    Ok(())
+}
+
 /// Increase the total supply of a token by the given amount.
 /// Return the total supply value after the increase.
 fn increase_supply(
@@ -0,0 +1,379 @@
+use {
+    crate::{Borsh, Codec, Path, RawKey},
+    grug_math::Number,
+    grug_types::{Bound, Order, StdResult, Storage},
+    std::{iter, marker::PhantomData},
+};
+
+/// Storage key of the index of the first element, relative to the namespace.
+///
+/// Must be of a different length than the element keys (4 bytes), so that they
+/// don't collide.
+const HEAD_KEY: &[u8] = b"h";
+
+/// Storage key of the index after the last element, relative to the namespace.
+const TAIL_KEY: &[u8] = b"t";
+
+/// A double-ended queue, which can be pushed to and popped from at both ends.
+///
+/// Internally, each element is stored under a `u32` index, along with the
+/// indexes of the first element (the "head") and of the one after the last
+/// element (the "tail"). Indexes wrap around, so that pushing to the front of
+/// the queue doesn't require moving the existing elements.
+///
+/// Can hold up to `u32::MAX` elements.
+pub struct Deque<'a, T, C = Borsh>
+where
+    C: Codec<T>,
+{
+    namespace: &'a [u8],
+    data: PhantomData<T>,
+    codec: PhantomData<C>,
+}
+
+impl<'a, T, C> Deque<'a, T, C>
+where
+    C: Codec<T>,
+{
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            namespace: namespace.as_bytes(),
+            data: PhantomData,
+            codec: PhantomData,
+        }
+    }
+}
+
+impl<T, C> Deque<'_, T, C>
+where
+    C: Codec<T>,
+{
+    fn head_path(&self) -> Path<u32, Borsh> {
+        Path::new(self.namespace, &[], Some(RawKey::Borrowed(HEAD_KEY)))
+    }
+
+    fn tail_path(&self) -> Path<u32, Borsh> {
+        Path::new(self.namespace, &[], Some(RawKey::Borrowed(TAIL_KEY)))
+    }
+
+    fn element_path(&self, index: u32) -> Path<T, C> {
+        element_path(self.namespace, index)
+    }
+
+    /// Load the head and tail indexes.
+    fn bounds(&self, storage: &dyn Storage) -> StdResult<(u32, u32)> {
+        let head = self.head_path().may_load(storage)?.unwrap_or(0);
+        let tail = self.tail_path().may_load(storage)?.unwrap_or(0);
+
+        Ok((head, tail))
+    }
+
+    pub fn len(&self, storage: &dyn Storage) -> StdResult<u32> {
+        self.bounds(storage)
+            .map(|(head, tail)| tail.wrapping_sub(head))
+    }
+
+    pub fn is_empty(&self, storage: &dyn Storage) -> StdResult<bool> {
+        self.len(storage).map(|len| len == 0)
+    }
+
+    // ---------------------- methods for single elements ----------------------
+
+    /// Load the raw element at the given position, counting from the front.
+    pub fn get_raw(&self, storage: &dyn Storage, pos: u32) -> StdResult<Option<Vec<u8>>> {
+        let (head, tail) = self.bounds(storage)?;
+
+        if pos >= tail.wrapping_sub(head) {
+            return Ok(None);
+        }
+
+        Ok(self
+            .element_path(head.wrapping_add(pos))
+            .may_load_raw(storage))
+    }
+
+    /// Load the element at the given position, counting from the front.
+    pub fn get(&self, storage: &dyn Storage, pos: u32) -> StdResult<Option<T>> {
+        self.get_raw(storage, pos)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    pub fn front_raw(&self, storage: &dyn Storage) -> StdResult<Option<Vec<u8>>> {
+        self.get_raw(storage, 0)
+    }
+
+    pub fn front(&self, storage: &dyn Storage) -> StdResult<Option<T>> {
+        self.get(storage, 0)
+    }
+
+    pub fn back_raw(&self, storage: &dyn Storage) -> StdResult<Option<Vec<u8>>> {
+        let (head, tail) = self.bounds(storage)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        Ok(self
+            .element_path(tail.wrapping_sub(1))
+            .may_load_raw(storage))
+    }
+
+    pub fn back(&self, storage: &dyn Storage) -> StdResult<Option<T>> {
+        self.back_raw(storage)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    /// Using this function is not recommended. If the data isn't properly
+    /// serialized, later when you read the data, it will fail to deserialize
+    /// and error.
+    ///
+    /// We prefix the function name with the word "unsafe" to highlight this.
+    pub fn unsafe_push_back_raw(
+        &self,
+        storage: &mut dyn Storage,
+        data_raw: &[u8],
+    ) -> StdResult<()> {
+        let (head, tail) = self.bounds(storage)?;
+
+        // Error if the deque is full.
+        Number::checked_add(tail.wrapping_sub(head), 1)?;
+
+        self.element_path(tail).save_raw(storage, data_raw);
+        self.tail_path().save(storage, &tail.wrapping_add(1))
+    }
+
+    pub fn push_back(&self, storage: &mut dyn Storage, data: &T) -> StdResult<()> {
+        self.unsafe_push_back_raw(storage, &C::encode(data)?)
+    }
+
+    /// Using this function is not recommended. If the data isn't properly
+    /// serialized, later when you read the data, it will fail to deserialize
+    /// and error.
+    ///
+    /// We prefix the function name with the word "unsafe" to highlight this.
+    pub fn unsafe_push_front_raw(
+        &self,
+        storage: &mut dyn Storage,
+        data_raw: &[u8],
+    ) -> StdResult<()> {
+        let (head, tail) = self.bounds(storage)?;
+
+        // Error if the deque is full.
+        Number::checked_add(tail.wrapping_sub(head), 1)?;
+
+        let head = head.wrapping_sub(1);
+
+        self.element_path(head).save_raw(storage, data_raw);
+        self.head_path().save(storage, &head)
+    }
+
+    pub fn push_front(&self, storage: &mut dyn Storage, data: &T) -> StdResult<()> {
+        self.unsafe_push_front_raw(storage, &C::encode(data)?)
+    }
+
+    pub fn pop_front_raw(&self, storage: &mut dyn Storage) -> StdResult<Option<Vec<u8>>> {
+        let (head, tail) = self.bounds(storage)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        let data_raw = self.element_path(head).take_raw(storage)?;
+
+        self.head_path().save(storage, &head.wrapping_add(1))?;
+
+        Ok(Some(data_raw))
+    }
+
+    pub fn pop_front(&self, storage: &mut dyn Storage) -> StdResult<Option<T>> {
+        self.pop_front_raw(storage)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    pub fn pop_back_raw(&self, storage: &mut dyn Storage) -> StdResult<Option<Vec<u8>>> {
+        let (head, tail) = self.bounds(storage)?;
+
+        if head == tail {
+            return Ok(None);
+        }
+
+        let tail = tail.wrapping_sub(1);
+        let data_raw = self.element_path(tail).take_raw(storage)?;
+
+        self.tail_path().save(storage, &tail)?;
+
+        Ok(Some(data_raw))
+    }
+
+    pub fn pop_back(&self, storage: &mut dyn Storage) -> StdResult<Option<T>> {
+        self.pop_back_raw(storage)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    // --------------------------- iteration methods ---------------------------
+
+    /// Iterate the raw elements between the given positions, counting from the
+    /// front.
+    pub fn range_raw<'b>(
+        &self,
+        storage: &'b dyn Storage,
+        min: Option<Bound<u32>>,
+        max: Option<Bound<u32>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<Vec<u8>>> + 'b> {
+        let (head, tail) = match self.bounds(storage) {
+            Ok(bounds) => bounds,
+            Err(err) => return Box::new(iter::once(Err(err))),
+        };
+
+        let len = tail.wrapping_sub(head);
+
+        let start = match min {
+            Some(Bound::Inclusive(pos)) => pos,
+            Some(Bound::Exclusive(pos)) => pos.saturating_add(1),
+            None => 0,
+        };
+
+        let end = match max {
+            Some(Bound::Inclusive(pos)) => pos.saturating_add(1).min(len),
+            Some(Bound::Exclusive(pos)) => pos.min(len),
+            None => len,
+        };
+
+        // The namespace is copied, so that the iterator can live longer than
+        // `&self`.
+        let namespace = self.namespace.to_vec();
+        let load = move |pos: u32| {
+            element_path::<T, C>(&namespace, head.wrapping_add(pos)).load_raw(storage)
+        };
+
+        match order {
+            Order::Ascending => Box::new((start..end).map(load)),
+            Order::Descending => Box::new((start..end).rev().map(load)),
+        }
+    }
+
+    /// Iterate the elements between the given positions, counting from the
+    /// front.
+    pub fn range<'b>(
+        &self,
+        storage: &'b dyn Storage,
+        min: Option<Bound<u32>>,
+        max: Option<Bound<u32>>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = StdResult<T>> + 'b> {
+        let iter = self
+            .range_raw(storage, min, max, order)
+            .map(|data_raw| C::decode(&data_raw?));
+
+        Box::new(iter)
+    }
+
+    /// Iterate all elements, from the front to the back.
+    pub fn iter<'b>(
+        &self,
+        storage: &'b dyn Storage,
+    ) -> Box<dyn Iterator<Item = StdResult<T>> + 'b> {
+        self.range(storage, None, None, Order::Ascending)
+    }
+}
+
+fn element_path<T, C>(namespace: &[u8], index: u32) -> Path<'static, T, C>
+where
+    C: Codec<T>,
+{
+    Path::new(namespace, &[], Some(RawKey::Fixed32(index.to_be_bytes())))
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::Deque,
+        grug_types::{Bound, MockStorage, Order, StdResult},
+    };
+
+    const DEQUE: Deque<u32> = Deque::new("deque");
+
+    #[test]
+    fn push_and_pop() {
+        let mut storage = MockStorage::new();
+
+        assert!(DEQUE.is_empty(&storage).unwrap());
+        assert_eq!(DEQUE.front(&storage).unwrap(), None);
+        assert_eq!(DEQUE.back(&storage).unwrap(), None);
+
+        // Pushing to the front wraps the head index around.
+        DEQUE.push_back(&mut storage, &2).unwrap();
+        DEQUE.push_back(&mut storage, &3).unwrap();
+        DEQUE.push_front(&mut storage, &1).unwrap();
+        DEQUE.push_front(&mut storage, &0).unwrap();
+
+        assert_eq!(DEQUE.len(&storage).unwrap(), 4);
+        assert_eq!(DEQUE.front(&storage).unwrap(), Some(0));
+        assert_eq!(DEQUE.back(&storage).unwrap(), Some(3));
+        assert_eq!(DEQUE.get(&storage, 1).unwrap(), Some(1));
+        assert_eq!(DEQUE.get(&storage, 4).unwrap(), None);
+
+        assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), Some(0));
+        assert_eq!(DEQUE.pop_back(&mut storage).unwrap(), Some(3));
+        assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), Some(1));
+        assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), Some(2));
+        assert_eq!(DEQUE.pop_front(&mut storage).unwrap(), None);
+        assert_eq!(DEQUE.pop_back(&mut storage).unwrap(), None);
+
+        assert!(DEQUE.is_empty(&storage).unwrap());
+    }
+
+    #[test]
+    fn range() {
+        let mut storage = MockStorage::new();
+
+        for i in 0..10 {
+            DEQUE.push_back(&mut storage, &i).unwrap();
+        }
+
+        for _ in 0..3 {
+            DEQUE.pop_front(&mut storage).unwrap();
+        }
+
+        // No bound
+        {
+            let data = DEQUE.iter(&storage).collect::<StdResult<Vec<_>>>().unwrap();
+            assert_eq!(data, [3, 4, 5, 6, 7, 8, 9]);
+        }
+
+        // Min Max bound
+        {
+            let data = DEQUE
+                .range(
+                    &storage,
+                    Some(Bound::Exclusive(1)),
+                    Some(Bound::Inclusive(3)),
+                    Order::Ascending,
+                )
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap();
+            assert_eq!(data, [5, 6]);
+        }
+
+        // Max bound beyond the back, descending
+        {
+            let data = DEQUE
+                .range(
+                    &storage,
+                    None,
+                    Some(Bound::Exclusive(100)),
+                    Order::Descending,
+                )
+                .collect::<StdResult<Vec<_>>>()
+                .unwrap();
+            assert_eq!(data, [9, 8, 7, 6, 5, 4, 3]);
+        }
+    }
+}
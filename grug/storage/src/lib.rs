@@ -1,6 +1,7 @@
 mod bound;
 mod codec;
 mod counter;
+mod deque;
 mod index;
 mod item;
 mod map;
@@ -10,8 +11,9 @@ mod prefixer;
 mod primary_key;
 mod raw_key;
 mod set;
+mod snapshot;
 
 pub use {
-    bound::*, codec::*, counter::*, index::*, item::*, map::*, path::*, prefix::*, prefixer::*,
-    primary_key::*, raw_key::*, set::*,
+    bound::*, codec::*, counter::*, deque::*, index::*, item::*, map::*, path::*, prefix::*,
+    prefixer::*, primary_key::*, raw_key::*, set::*, snapshot::*,
 };
@@ -0,0 +1,595 @@
+use {
+    crate::{Borsh, Codec, Item, Map, PrimaryKey},
+    grug_types::{Bound, Order, StdError, StdResult, Storage},
+    std::fmt::Debug,
+};
+
+/// Describes at which heights a [`SnapshotMap`](crate::SnapshotMap) or
+/// [`SnapshotItem`](crate::SnapshotItem) records the values it had, such that
+/// they can be loaded later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Record at every height, so that values can be loaded at any height.
+    EveryBlock,
+    /// Only record at heights that have been checkpointed, so that values can
+    /// only be loaded at these heights.
+    ///
+    /// This saves storage if past values are only of interest at a few heights
+    /// known in advance, e.g. the ones at which governance proposals are
+    /// created.
+    Selected,
+}
+
+/// The values of a snapshot map or item prior to the changes made at a height.
+///
+/// The data is encoded using the map or item's codec. `None` means the data
+/// didn't exist.
+type ChangeSet = Option<Vec<u8>>;
+
+// -------------------------------- checkpoints --------------------------------
+
+/// Heights at which snapshots are to be recorded, and how many times each has
+/// been checkpointed.
+struct Checkpoints<'a, H> {
+    map: Map<'a, H, u32>,
+}
+
+impl<'a, H> Checkpoints<'a, H> {
+    const fn new(namespace: &'a str) -> Self {
+        Self {
+            map: Map::new(namespace),
+        }
+    }
+}
+
+impl<H> Checkpoints<'_, H>
+where
+    H: PrimaryKey<Output = H> + Copy,
+{
+    fn add(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.map
+            .may_update(storage, height, |count| -> StdResult<_> {
+                Ok(count.unwrap_or(0) + 1)
+            })
+            .map(|_| ())
+    }
+
+    fn remove(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.map
+            .may_modify(storage, height, |count| -> StdResult<_> {
+                Ok(count.filter(|count| *count > 1).map(|count| count - 1))
+            })
+            .map(|_| ())
+    }
+
+    fn has(&self, storage: &dyn Storage, height: H) -> bool {
+        self.map.has(storage, height)
+    }
+
+    fn latest(&self, storage: &dyn Storage) -> StdResult<Option<H>> {
+        self.map
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()
+    }
+
+    fn prune(&self, storage: &mut dyn Storage, height: H) {
+        self.map
+            .clear(storage, None, Some(Bound::Exclusive(height)))
+    }
+}
+
+// -------------------------------- snapshot map -------------------------------
+
+/// A map that remembers the values it had in the past, so that they can be
+/// loaded "as of" a given height.
+///
+/// A height can be either a block height (`u64`), or a block timestamp, or any
+/// other key that only increases over time. Values loaded at a height are the
+/// ones prior to any changes made at that height.
+///
+/// Internally, in addition to the map holding the current values, it records
+/// a changelog, which is the old value of each key before the first change at
+/// each height, depending on the [`Strategy`].
+pub struct SnapshotMap<'a, K, T, H = u64, C = Borsh>
+where
+    C: Codec<T>,
+{
+    primary: Map<'a, K, T, C>,
+    changelog: Map<'a, (K, H), ChangeSet>,
+    checkpoints: Checkpoints<'a, H>,
+    strategy: Strategy,
+}
+
+impl<'a, K, T, H, C> SnapshotMap<'a, K, T, H, C>
+where
+    C: Codec<T>,
+{
+    pub const fn new(
+        namespace: &'a str,
+        checkpoints_namespace: &'a str,
+        changelog_namespace: &'a str,
+        strategy: Strategy,
+    ) -> Self {
+        Self {
+            primary: Map::new(namespace),
+            changelog: Map::new(changelog_namespace),
+            checkpoints: Checkpoints::new(checkpoints_namespace),
+            strategy,
+        }
+    }
+}
+
+impl<K, T, H, C> SnapshotMap<'_, K, T, H, C>
+where
+    K: PrimaryKey + Copy,
+    H: PrimaryKey<Output = H> + Copy + Ord + Debug,
+    C: Codec<T>,
+{
+    pub fn has(&self, storage: &dyn Storage, key: K) -> bool {
+        self.primary.has(storage, key)
+    }
+
+    pub fn may_load(&self, storage: &dyn Storage, key: K) -> StdResult<Option<T>> {
+        self.primary.may_load(storage, key)
+    }
+
+    pub fn load(&self, storage: &dyn Storage, key: K) -> StdResult<T> {
+        self.primary.load(storage, key)
+    }
+
+    /// Load the raw value under the given key as of the given height.
+    ///
+    /// Error if using the [`Strategy::Selected`] strategy, and the height isn't
+    /// checkpointed.
+    pub fn may_load_at_raw(
+        &self,
+        storage: &dyn Storage,
+        key: K,
+        height: H,
+    ) -> StdResult<Option<Vec<u8>>> {
+        self.assert_checkpointed(storage, height)?;
+
+        // The value as of this height is the one prior to the first change made
+        // at or after this height. If there isn't any such change, then the
+        // value hasn't changed since, so it's the current value.
+        match self
+            .changelog
+            .prefix(key)
+            .range(
+                storage,
+                Some(Bound::Inclusive(height)),
+                None,
+                Order::Ascending,
+            )
+            .next()
+        {
+            Some(record) => record.map(|(_, old)| old),
+            None => Ok(self.primary.path(key).may_load_raw(storage)),
+        }
+    }
+
+    /// Load the value under the given key as of the given height.
+    ///
+    /// Error if using the [`Strategy::Selected`] strategy, and the height isn't
+    /// checkpointed.
+    pub fn may_load_at(&self, storage: &dyn Storage, key: K, height: H) -> StdResult<Option<T>> {
+        self.may_load_at_raw(storage, key, height)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, key: K, data: &T, height: H) -> StdResult<()> {
+        self.record(storage, key, height)?;
+        self.primary.save(storage, key, data)
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, key: K, height: H) -> StdResult<()> {
+        self.record(storage, key, height)?;
+        self.primary.remove(storage, key);
+
+        Ok(())
+    }
+
+    /// Mark a height at which values should be recorded. Only relevant for the
+    /// [`Strategy::Selected`] strategy.
+    ///
+    /// Must be the current height or later; values changed before it's added
+    /// can't be recorded.
+    ///
+    /// A height can be checkpointed multiple times, in which case it needs to
+    /// be removed the same number of times.
+    pub fn add_checkpoint(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.checkpoints.add(storage, height)
+    }
+
+    pub fn remove_checkpoint(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.checkpoints.remove(storage, height)
+    }
+
+    /// Delete changelog entries and checkpoints before the given height.
+    ///
+    /// Afterwards, values can't be correctly loaded at heights before it. With
+    /// the [`Strategy::Selected`] strategy, attempting to do so errors, as the
+    /// checkpoints are gone; otherwise, it's up to the caller not to do so.
+    ///
+    /// This iterates through the entire changelog, so should be done
+    /// sparingly, e.g. in a cronjob.
+    pub fn prune(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        let stale_keys = self
+            .changelog
+            .keys_raw(storage, None, None, Order::Ascending)
+            .filter_map(|key_raw| match <(K, H)>::from_slice(&key_raw) {
+                Ok((_, changed_at)) if changed_at < height => Some(Ok(key_raw)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for key_raw in stale_keys {
+            self.changelog.remove_raw(storage, &key_raw);
+        }
+
+        self.checkpoints.prune(storage, height);
+
+        Ok(())
+    }
+
+    fn assert_checkpointed(&self, storage: &dyn Storage, height: H) -> StdResult<()> {
+        match self.strategy {
+            Strategy::EveryBlock => Ok(()),
+            Strategy::Selected if self.checkpoints.has(storage, height) => Ok(()),
+            Strategy::Selected => Err(StdError::not_checkpointed(height)),
+        }
+    }
+
+    /// Record the current value under the given key, if it needs to be before
+    /// it's changed at the given height.
+    fn record(&self, storage: &mut dyn Storage, key: K, height: H) -> StdResult<()> {
+        // Only the value prior to the first change at a height is recorded.
+        if self.changelog.has(storage, (key, height)) {
+            return Ok(());
+        }
+
+        // For selected heights, the value as of the latest checkpoint is the
+        // one prior to the first change after it, so only the first change
+        // needs to be recorded. Earlier checkpoints already have theirs.
+        let should_record = match self.strategy {
+            Strategy::EveryBlock => true,
+            Strategy::Selected => match self.checkpoints.latest(storage)? {
+                Some(checkpoint) => self
+                    .changelog
+                    .prefix(key)
+                    .keys_raw(
+                        storage,
+                        Some(Bound::Inclusive(checkpoint)),
+                        None,
+                        Order::Ascending,
+                    )
+                    .next()
+                    .is_none(),
+                None => false,
+            },
+        };
+
+        if should_record {
+            let old = self.primary.path(key).may_load_raw(storage);
+            self.changelog.save(storage, (key, height), &old)?;
+        }
+
+        Ok(())
+    }
+}
+
+// ------------------------------- snapshot item -------------------------------
+
+/// An item that remembers the values it had in the past, so that they can be
+/// loaded "as of" a given height.
+///
+/// See [`SnapshotMap`](crate::SnapshotMap) for details.
+pub struct SnapshotItem<'a, T, H = u64, C = Borsh>
+where
+    C: Codec<T>,
+{
+    item: Item<'a, T, C>,
+    changelog: Map<'a, H, ChangeSet>,
+    checkpoints: Checkpoints<'a, H>,
+    strategy: Strategy,
+}
+
+impl<'a, T, H, C> SnapshotItem<'a, T, H, C>
+where
+    C: Codec<T>,
+{
+    pub const fn new(
+        storage_key: &'a str,
+        checkpoints_namespace: &'a str,
+        changelog_namespace: &'a str,
+        strategy: Strategy,
+    ) -> Self {
+        Self {
+            item: Item::new(storage_key),
+            changelog: Map::new(changelog_namespace),
+            checkpoints: Checkpoints::new(checkpoints_namespace),
+            strategy,
+        }
+    }
+}
+
+impl<T, H, C> SnapshotItem<'_, T, H, C>
+where
+    H: PrimaryKey<Output = H> + Copy + Ord + Debug,
+    C: Codec<T>,
+{
+    pub fn exists(&self, storage: &dyn Storage) -> bool {
+        self.item.exists(storage)
+    }
+
+    pub fn may_load(&self, storage: &dyn Storage) -> StdResult<Option<T>> {
+        self.item.may_load(storage)
+    }
+
+    pub fn load(&self, storage: &dyn Storage) -> StdResult<T> {
+        self.item.load(storage)
+    }
+
+    /// Load the raw value as of the given height.
+    ///
+    /// Error if using the [`Strategy::Selected`] strategy, and the height isn't
+    /// checkpointed.
+    pub fn may_load_at_raw(&self, storage: &dyn Storage, height: H) -> StdResult<Option<Vec<u8>>> {
+        self.assert_checkpointed(storage, height)?;
+
+        match self
+            .changelog
+            .range(
+                storage,
+                Some(Bound::Inclusive(height)),
+                None,
+                Order::Ascending,
+            )
+            .next()
+        {
+            Some(record) => record.map(|(_, old)| old),
+            None => Ok(self.item.may_load_raw(storage)),
+        }
+    }
+
+    /// Load the value as of the given height.
+    ///
+    /// Error if using the [`Strategy::Selected`] strategy, and the height isn't
+    /// checkpointed.
+    pub fn may_load_at(&self, storage: &dyn Storage, height: H) -> StdResult<Option<T>> {
+        self.may_load_at_raw(storage, height)?
+            .map(|data_raw| C::decode(&data_raw))
+            .transpose()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, data: &T, height: H) -> StdResult<()> {
+        self.record(storage, height)?;
+        self.item.save(storage, data)
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.record(storage, height)?;
+        self.item.remove(storage);
+
+        Ok(())
+    }
+
+    /// Mark a height at which the value should be recorded. See
+    /// [`SnapshotMap::add_checkpoint`](crate::SnapshotMap::add_checkpoint).
+    pub fn add_checkpoint(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.checkpoints.add(storage, height)
+    }
+
+    pub fn remove_checkpoint(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        self.checkpoints.remove(storage, height)
+    }
+
+    /// Delete changelog entries and checkpoints before the given height. See
+    /// [`SnapshotMap::prune`](crate::SnapshotMap::prune).
+    pub fn prune(&self, storage: &mut dyn Storage, height: H) {
+        self.changelog
+            .clear(storage, None, Some(Bound::Exclusive(height)));
+        self.checkpoints.prune(storage, height);
+    }
+
+    fn assert_checkpointed(&self, storage: &dyn Storage, height: H) -> StdResult<()> {
+        match self.strategy {
+            Strategy::EveryBlock => Ok(()),
+            Strategy::Selected if self.checkpoints.has(storage, height) => Ok(()),
+            Strategy::Selected => Err(StdError::not_checkpointed(height)),
+        }
+    }
+
+    fn record(&self, storage: &mut dyn Storage, height: H) -> StdResult<()> {
+        if self.changelog.has(storage, height) {
+            return Ok(());
+        }
+
+        let should_record = match self.strategy {
+            Strategy::EveryBlock => true,
+            Strategy::Selected => match self.checkpoints.latest(storage)? {
+                Some(checkpoint) => self
+                    .changelog
+                    .keys_raw(
+                        storage,
+                        Some(Bound::Inclusive(checkpoint)),
+                        None,
+                        Order::Ascending,
+                    )
+                    .next()
+                    .is_none(),
+                None => false,
+            },
+        };
+
+        if should_record {
+            let old = self.item.may_load_raw(storage);
+            self.changelog.save(storage, height, &old)?;
+        }
+
+        Ok(())
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        crate::{SnapshotItem, SnapshotMap, Strategy},
+        grug_types::{MockStorage, Order, StdError, Timestamp},
+        test_case::test_case,
+    };
+
+    const EVERY: SnapshotMap<&str, u64> = SnapshotMap::new(
+        "every",
+        "every__checkpoints",
+        "every__changelog",
+        Strategy::EveryBlock,
+    );
+
+    const SELECTED: SnapshotMap<&str, u64> = SnapshotMap::new(
+        "selected",
+        "selected__checkpoints",
+        "selected__changelog",
+        Strategy::Selected,
+    );
+
+    const TOTAL: SnapshotItem<u64, Timestamp> = SnapshotItem::new(
+        "total",
+        "total__checkpoints",
+        "total__changelog",
+        Strategy::EveryBlock,
+    );
+
+    /// Make the following changes:
+    ///
+    /// | height | changes               |
+    /// | ------ | --------------------- |
+    /// | 1      | A = 5, B = 7          |
+    /// | 2      | A = 8, A = 9, C = 13  |
+    /// | 4      | B removed, C = 14     |
+    fn make_changes(map: &SnapshotMap<&str, u64>, storage: &mut MockStorage) {
+        map.save(storage, "A", &5, 1).unwrap();
+        map.save(storage, "B", &7, 1).unwrap();
+
+        map.save(storage, "A", &8, 2).unwrap();
+        map.save(storage, "A", &9, 2).unwrap();
+        map.save(storage, "C", &13, 2).unwrap();
+
+        map.remove(storage, "B", 4).unwrap();
+        map.save(storage, "C", &14, 4).unwrap();
+    }
+
+    #[test_case(
+        "A",
+        [None, None, Some(5), Some(9), Some(9), Some(9)];
+        "changed within a height"
+    )]
+    #[test_case(
+        "B",
+        [None, None, Some(7), Some(7), Some(7), None];
+        "removed"
+    )]
+    #[test_case(
+        "C",
+        [None, None, None, Some(13), Some(13), Some(14)];
+        "changed at multiple heights"
+    )]
+    #[test_case(
+        "D",
+        [None; 6];
+        "never saved"
+    )]
+    fn loading_at_every_height(key: &str, expected: [Option<u64>; 6]) {
+        let mut storage = MockStorage::new();
+
+        make_changes(&EVERY, &mut storage);
+
+        for (height, value) in expected.into_iter().enumerate() {
+            assert_eq!(
+                EVERY.may_load_at(&storage, key, height as u64).unwrap(),
+                value
+            );
+        }
+
+        assert_eq!(EVERY.may_load(&storage, key).unwrap(), expected[5]);
+    }
+
+    #[test]
+    fn loading_at_selected_heights() {
+        let mut storage = MockStorage::new();
+
+        SELECTED.add_checkpoint(&mut storage, 2).unwrap();
+
+        make_changes(&SELECTED, &mut storage);
+
+        assert_eq!(SELECTED.may_load_at(&storage, "A", 2).unwrap(), Some(5));
+        assert_eq!(SELECTED.may_load_at(&storage, "B", 2).unwrap(), Some(7));
+        assert_eq!(SELECTED.may_load_at(&storage, "C", 2).unwrap(), None);
+
+        // Heights that aren't checkpointed can't be loaded at.
+        assert!(matches!(
+            SELECTED.may_load_at(&storage, "A", 3),
+            Err(StdError::NotCheckpointed { .. })
+        ));
+
+        // A height checkpointed twice needs to be removed twice.
+        SELECTED.add_checkpoint(&mut storage, 2).unwrap();
+        SELECTED.remove_checkpoint(&mut storage, 2).unwrap();
+
+        assert!(SELECTED.may_load_at(&storage, "A", 2).is_ok());
+
+        SELECTED.remove_checkpoint(&mut storage, 2).unwrap();
+
+        assert!(SELECTED.may_load_at(&storage, "A", 2).is_err());
+    }
+
+    #[test]
+    fn pruning() {
+        let mut storage = MockStorage::new();
+
+        make_changes(&EVERY, &mut storage);
+
+        EVERY.prune(&mut storage, 3).unwrap();
+
+        // Changes at height 4 are still recorded.
+        assert_eq!(EVERY.may_load_at(&storage, "B", 4).unwrap(), Some(7));
+        assert_eq!(EVERY.may_load_at(&storage, "C", 4).unwrap(), Some(13));
+
+        // Changes at earlier heights are gone.
+        assert_eq!(
+            EVERY
+                .changelog
+                .range(&storage, None, None, Order::Ascending)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn snapshot_item_works() {
+        let mut storage = MockStorage::new();
+
+        let t1 = Timestamp::from_seconds(1);
+        let t2 = Timestamp::from_seconds(2);
+        let t3 = Timestamp::from_seconds(3);
+
+        TOTAL.save(&mut storage, &10, t1).unwrap();
+        TOTAL.save(&mut storage, &20, t2).unwrap();
+        TOTAL.save(&mut storage, &30, t2).unwrap();
+        TOTAL.remove(&mut storage, t3).unwrap();
+
+        assert_eq!(TOTAL.may_load_at(&storage, t1).unwrap(), None);
+        assert_eq!(TOTAL.may_load_at(&storage, t2).unwrap(), Some(10));
+        assert_eq!(TOTAL.may_load_at(&storage, t3).unwrap(), Some(30));
+        assert_eq!(TOTAL.may_load(&storage).unwrap(), None);
+
+        TOTAL.prune(&mut storage, t3);
+
+        assert_eq!(TOTAL.may_load_at(&storage, t3).unwrap(), Some(30));
+    }
+}
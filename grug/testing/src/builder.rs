@@ -4,9 +4,9 @@ use {
     grug_db_memory::MemDb,
     grug_math::Udec128,
     grug_types::{
-        Addr, Binary, BlockInfo, Coins, Config, Defined, Denom, Duration, GenesisState, HashExt,
-        Json, JsonSerExt, MaybeDefined, Message, Permission, Permissions, StdResult, Timestamp,
-        Undefined, GENESIS_BLOCK_HASH, GENESIS_BLOCK_HEIGHT, GENESIS_SENDER,
+        Addr, Binary, BlockInfo, Coins, Config, Defined, Denom, Duration, GasCosts, GenesisState,
+        HashExt, Json, JsonSerExt, MaybeDefined, Message, Permission, Permissions, StdResult,
+        Timestamp, Undefined, GENESIS_BLOCK_HASH, GENESIS_BLOCK_HEIGHT, GENESIS_SENDER,
     },
     grug_vm_rust::RustVm,
     serde::Serialize,
@@ -639,12 +639,14 @@ where
             owner: self.owner.into_inner(),
             bank,
             taxman,
+            ibc_handler: None,
             cronjobs: BTreeMap::new(),
             permissions: Permissions {
                 upload: Permission::Everybody,
                 instantiate: Permission::Everybody,
             },
             max_orphan_age: self.max_orphan_age.unwrap_or(DEFAULT_MAX_ORPHAN_AGE),
+            gas_costs: GasCosts::DEFAULT,
         };
 
         let genesis_state = GenesisState {
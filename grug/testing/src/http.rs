@@ -0,0 +1,111 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// An HTTP request received by a stand-in server started with [`serve_http`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    /// The request method, e.g. `GET`.
+    pub method: String,
+    /// The request target, i.e. the path and the query string.
+    pub target: String,
+    /// The request body. Empty if the request has no `Content-Length` header.
+    pub body: Vec<u8>,
+}
+
+/// Start a minimal HTTP/1.1 server on a random local port, which stands in for
+/// an external service in tests, e.g. a node's RPC endpoint or a price API.
+///
+/// Every request is answered with `200 OK` and the JSON body returned by the
+/// handler. Keep-alive connections are supported; each one is served in its
+/// own thread with its own clone of the handler.
+///
+/// Returns the server URL, and a counter of how many requests were served. The
+/// counter is incremented before the response is written, so it's up to date
+/// by the time the client receives the response.
+pub fn serve_http<F>(handler: F) -> (String, Arc<AtomicUsize>)
+where
+    F: Fn(HttpRequest) -> String + Clone + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    {
+        let counter = counter.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let handler = handler.clone();
+                let counter = counter.clone();
+                thread::spawn(move || handle_connection(stream.unwrap(), handler, counter));
+            }
+        });
+    }
+
+    (url, counter)
+}
+
+fn handle_connection<F>(stream: TcpStream, handler: F, counter: Arc<AtomicUsize>)
+where
+    F: Fn(HttpRequest) -> String,
+{
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+
+    // Serve requests one by one until the client closes the connection.
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap() == 0 {
+            return;
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap().to_string();
+        let target = parts.next().unwrap().to_string();
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                return;
+            }
+
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        let res = handler(HttpRequest {
+            method,
+            target,
+            body,
+        });
+
+        counter.fetch_add(1, Ordering::SeqCst);
+
+        write!(
+            writer,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            res.len(),
+            res
+        )
+        .unwrap();
+        writer.flush().unwrap();
+    }
+}
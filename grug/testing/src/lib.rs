@@ -1,10 +1,11 @@
 mod account;
 mod builder;
+mod http;
 mod suite;
 mod tracing;
 mod vm;
 
-pub use {account::*, builder::*, suite::*, tracing::*, vm::*};
+pub use {account::*, builder::*, http::*, suite::*, tracing::*, vm::*};
 
 // Re-export the Rust VM contract builder.
 pub use grug_vm_rust::{ContractBuilder, ContractWrapper};
@@ -1,16 +1,18 @@
 use {
+    ed25519_dalek::{Signer as _, SigningKey},
     grug_app::{
-        App, AppError, AppResult, Db, Indexer, NaiveProposalPreparer, NullIndexer,
-        ProposalPreparer, Vm,
+        validator_address, vote_extension_sign_bytes, App, AppError, AppResult, BlockLimits, Db,
+        ExtendedCommit, ExtendedVote, Indexer, NaiveProposalPreparer, NullIndexer,
+        ProposalPreparer, ValidatorUpdate, Vm, CHAIN_ID, LAST_FINALIZED_BLOCK,
     },
     grug_crypto::sha2_256,
     grug_db_memory::MemDb,
     grug_math::Uint128,
     grug_types::{
         Addr, Addressable, Binary, Block, BlockInfo, BlockOutcome, CheckTxOutcome, Code, Coins,
-        Config, ContractInfo, Denom, Duration, GenesisState, Hash256, JsonDeExt, JsonSerExt,
-        Message, NonEmpty, Query, QueryRequest, ResultExt, Signer, StdError, Tx, TxError,
-        TxOutcome, TxSuccess, UnsignedTx,
+        Config, ContractInfo, Denom, Duration, GasProfile, GenesisState, Hash256, IbcClientQuery,
+        IbcClientQueryResponse, JsonDeExt, JsonSerExt, Message, NonEmpty, Query, QueryRequest,
+        ResultExt, Signer, StateOverrides, StdError, Tx, TxError, TxOutcome, TxSuccess, UnsignedTx,
     },
     grug_vm_rust::RustVm,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -109,6 +111,22 @@ impl ResultExt for UploadAndInstantiateOutcome {
 
 // --------------------------------- TestSuite ---------------------------------
 
+/// Number of validators the test suite simulates. Their consensus keys are
+/// registered at genesis, so that they can cast votes with signed extensions
+/// using [`TestSuite::extended_vote`].
+pub const NUM_VALIDATORS: usize = 4;
+
+/// Return the consensus key of the `index`-th validator simulated by the test
+/// suite.
+fn validator_key(index: usize) -> SigningKey {
+    assert!(
+        index < NUM_VALIDATORS,
+        "the test suite only simulates {NUM_VALIDATORS} validators"
+    );
+
+    SigningKey::from_bytes(&[index as u8 + 1; 32])
+}
+
 pub struct TestSuite<DB = MemDb, VM = RustVm, PP = NaiveProposalPreparer, ID = NullIndexer>
 where
     DB: Db,
@@ -259,15 +277,77 @@ where
         // Use `u64::MAX` as query gas limit so that there's practically no limit.
         let app = App::new(db, vm, pp, id, u64::MAX);
 
-        app.do_init_chain(chain_id.clone(), genesis_block, genesis_state)
+        let validators = (0..NUM_VALIDATORS)
+            .map(|index| ValidatorUpdate {
+                pubkey: validator_key(index).verifying_key().to_bytes(),
+                power: 1,
+            })
+            .collect::<Vec<_>>();
+
+        app.do_init_chain(
+            chain_id.clone(),
+            genesis_block,
+            BlockLimits::default(),
+            &validators,
+            genesis_state,
+        )
+        .unwrap_or_else(|err| {
+            panic!("fatal error while initializing chain: {err}");
+        });
+
+        Self {
+            app,
+            chain_id,
+            block: genesis_block,
+            block_time,
+            default_gas_limit,
+        }
+    }
+
+    /// Create a new test suite on top of a DB that already contains the state
+    /// of a chain (e.g. a DB forked from a live network), without running
+    /// genesis.
+    ///
+    /// The chain ID and the last finalized block are loaded from the DB.
+    pub fn new_with_existing_state(
+        db: DB,
+        vm: VM,
+        pp: PP,
+        mut id: ID,
+        block_time: Duration,
+        default_gas_limit: u64,
+    ) -> Self {
+        let state_storage = db.state_storage(None).unwrap_or_else(|err| {
+            panic!(
+                "Fatal error while getting the state storage: {}",
+                err.to_string()
+            );
+        });
+
+        let chain_id = CHAIN_ID.load(&state_storage).unwrap_or_else(|err| {
+            panic!("fatal error while loading chain ID: {err}");
+        });
+
+        let block = LAST_FINALIZED_BLOCK
+            .load(&state_storage)
             .unwrap_or_else(|err| {
-                panic!("fatal error while initializing chain: {err}");
+                panic!("fatal error while loading last finalized block: {err}");
             });
 
+        id.start(&state_storage).unwrap_or_else(|err| {
+            panic!(
+                "fatal error while running indexer start: {}",
+                err.to_string()
+            );
+        });
+
+        // Use `u64::MAX` as query gas limit so that there's practically no limit.
+        let app = App::new(db, vm, pp, id, u64::MAX);
+
         Self {
             app,
             chain_id,
-            block: genesis_block,
+            block,
             block_time,
             default_gas_limit,
         }
@@ -275,13 +355,35 @@ where
 
     /// Simulate the gas cost and event outputs of an unsigned transaction.
     pub fn simulate_tx(&self, unsigned_tx: UnsignedTx) -> TxOutcome {
+        self.simulate_tx_with_overrides(unsigned_tx, StateOverrides::default())
+    }
+
+    /// Simulate an unsigned transaction against the state with the given
+    /// overrides applied. The actual state isn't affected.
+    pub fn simulate_tx_with_overrides(
+        &self,
+        unsigned_tx: UnsignedTx,
+        overrides: StateOverrides,
+    ) -> TxOutcome {
         self.app
-            .do_simulate(unsigned_tx, 0, false)
+            .do_simulate(unsigned_tx, overrides, 0, false, false)
+            .map(|(outcome, _)| outcome)
             .unwrap_or_else(|err| {
                 panic!("fatal error while simulating tx: {err}");
             })
     }
 
+    /// Simulate an unsigned transaction, and return a breakdown of the gas
+    /// consumed by each call made during it, together with the outcome.
+    pub fn profile_tx(&self, unsigned_tx: UnsignedTx) -> (TxOutcome, GasProfile) {
+        self.app
+            .do_simulate(unsigned_tx, StateOverrides::default(), 0, false, true)
+            .map(|(outcome, profile)| (outcome, profile.unwrap()))
+            .unwrap_or_else(|err| {
+                panic!("fatal error while profiling tx: {err}");
+            })
+    }
+
     /// Perform ABCI `CheckTx` call of a transaction.
     pub fn check_tx(&self, tx: Tx) -> CheckTxOutcome {
         self.app
@@ -296,6 +398,51 @@ where
 
     /// Make a new block with the given transactions.
     pub fn make_block(&mut self, txs: Vec<Tx>) -> BlockOutcome {
+        // Call ABCI `ExtendVote` and `VerifyVoteExtension` methods on the last
+        // finalized block. The test suite acts as the first of the simulated
+        // validators, which is the only one to vote.
+        let extension = self.app.do_extend_vote(self.block.height);
+
+        assert!(
+            self.app
+                .do_verify_vote_extension(self.block.height, &extension),
+            "vote extension was rejected"
+        );
+
+        let votes = vec![self.extended_vote(0, 1, extension)];
+
+        self.make_block_with_votes(txs, votes)
+    }
+
+    /// Return the vote of the `index`-th simulated validator for the last
+    /// finalized block, with the given voting power and vote extension, signed
+    /// by the validator's consensus key.
+    pub fn extended_vote(&self, index: usize, power: u64, extension: Vec<u8>) -> ExtendedVote {
+        let sk = validator_key(index);
+        let sign_bytes =
+            vote_extension_sign_bytes(&self.chain_id, self.block.height, 0, &extension);
+
+        ExtendedVote {
+            validator: validator_address(&sk.verifying_key().to_bytes()),
+            power,
+            extension,
+            extension_signature: sk.sign(&sign_bytes).to_bytes().to_vec(),
+        }
+    }
+
+    /// Make a new block with the given transactions, as if the given votes,
+    /// along with their vote extensions, had been cast for the last finalized
+    /// block, in round zero.
+    pub fn make_block_with_votes(
+        &mut self,
+        txs: Vec<Tx>,
+        votes: Vec<ExtendedVote>,
+    ) -> BlockOutcome {
+        let last_commit = votes
+            .iter()
+            .map(|vote| (vote.validator, vote.power))
+            .collect::<BTreeMap<_, _>>();
+
         // Advance block height and time
         self.block.height += 1;
         self.block.timestamp = self.block.timestamp + self.block_time;
@@ -305,10 +452,19 @@ where
             .into_iter()
             .map(|tx| tx.to_json_vec().unwrap().into())
             .collect();
-        let txs = self
-            .app
-            .do_prepare_proposal(raw_txs, usize::MAX)
+        let raw_txs = self.app.do_prepare_proposal(raw_txs, usize::MAX, 0, &votes);
+
+        // Call ABCI `ProcessProposal` method
+        assert!(
+            self.app
+                .do_process_proposal(raw_txs.clone(), 0, &last_commit),
+            "proposal was rejected"
+        );
+
+        // The extended commit pseudo-transaction isn't executed.
+        let txs = raw_txs
             .into_iter()
+            .filter(|raw_tx| ExtendedCommit::decode(raw_tx).is_none())
             .map(|raw_tx| raw_tx.deserialize_json().unwrap())
             .collect();
 
@@ -775,4 +931,14 @@ where
             .do_query_app(Query::wasm_smart(contract, &msg)?, 0, false)
             .map(|res| res.as_wasm_smart().deserialize_json().unwrap())
     }
+
+    pub fn query_ibc_client(
+        &self,
+        client: Addr,
+        msg: IbcClientQuery,
+    ) -> AppResult<IbcClientQueryResponse> {
+        self.app
+            .do_query_app(Query::ibc_client(client, msg), 0, false)
+            .map(|res| res.as_ibc_client())
+    }
 }
@@ -0,0 +1,107 @@
+use {
+    grug_app::AppError,
+    grug_testing::TestBuilder,
+    grug_types::{
+        Binary, Coins, CommitmentStatus, Config, Empty, Event, EventStatus, EvtConfigure, GasCosts,
+        LinearGasCost, ResultExt,
+    },
+};
+
+#[test]
+fn configuring_gas_costs() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    // The chain starts with the default gas costs.
+    let mut cfg = suite.query_config().should_succeed();
+    assert_eq!(cfg.gas_costs, GasCosts::DEFAULT);
+
+    // Uploading a code costs gas for the storage accesses it makes. The
+    // `RustVm` doesn't meter contract execution, so this is all the gas used.
+    let gas_used = suite
+        .upload(&mut accounts["owner"], Binary::from(*b"code1"))
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    assert!(gas_used > 0);
+
+    // Storage accesses can't be made free.
+    let free_storage = GasCosts {
+        db_read: LinearGasCost::new(0, 0),
+        db_scan: 0,
+        db_next: 0,
+        db_write: LinearGasCost::new(0, 0),
+        db_remove: 0,
+        ..GasCosts::DEFAULT
+    };
+
+    suite
+        .configure(
+            &mut accounts["owner"],
+            Some(Config {
+                gas_costs: free_storage,
+                ..cfg.clone()
+            }),
+            None::<Empty>,
+        )
+        .should_fail_with_error(AppError::GasCostBelowMinimum { name: "db_read" });
+
+    // Make storage accesses as cheap as possible.
+    cfg.gas_costs = GasCosts {
+        db_read: GasCosts::MINIMUM.db_read,
+        db_scan: GasCosts::MINIMUM.db_scan,
+        db_next: GasCosts::MINIMUM.db_next,
+        db_write: GasCosts::MINIMUM.db_write,
+        db_remove: GasCosts::MINIMUM.db_remove,
+        ..GasCosts::DEFAULT
+    };
+
+    let events = suite
+        .configure(&mut accounts["owner"], Some(cfg.clone()), None::<Empty>)
+        .should_succeed()
+        .events;
+
+    // The new gas costs should be reported in the event.
+    let CommitmentStatus::Committed(events) = events.msgs_and_backrun else {
+        panic!("expected msgs and backrun to be committed");
+    };
+
+    assert_eq!(events.msgs, vec![EventStatus::Ok(Event::Configure(
+        EvtConfigure {
+            sender: accounts["owner"].address,
+            new_gas_costs: Some(cfg.gas_costs.clone()),
+            upgrade: None,
+        }
+    ))]);
+
+    // The new gas costs take effect from the next block.
+    let cheaper_gas_used = suite
+        .upload(&mut accounts["owner"], Binary::from(*b"code2"))
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    assert!(cheaper_gas_used > 0);
+    assert!(cheaper_gas_used < gas_used);
+
+    // Configuring without changing the gas costs doesn't report them.
+    let events = suite
+        .configure(&mut accounts["owner"], Some(cfg), None::<Empty>)
+        .should_succeed()
+        .events;
+
+    let CommitmentStatus::Committed(events) = events.msgs_and_backrun else {
+        panic!("expected msgs and backrun to be committed");
+    };
+
+    assert_eq!(events.msgs, vec![EventStatus::Ok(Event::Configure(
+        EvtConfigure {
+            sender: accounts["owner"].address,
+            new_gas_costs: None,
+            upgrade: None,
+        }
+    ))]);
+}
@@ -0,0 +1,39 @@
+use {
+    grug_testing::TestBuilder,
+    grug_types::{Binary, Coins, Message, NonEmpty, Signer},
+};
+
+#[test]
+fn profiling_gas() {
+    let (suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new())
+        .build();
+
+    let unsigned_tx = accounts["sender"]
+        .unsigned_transaction(
+            NonEmpty::new_unchecked(vec![Message::upload(Binary::from(*b"code"))]),
+            &suite.chain_id,
+        )
+        .unwrap();
+
+    let (outcome, profile) = suite.profile_tx(unsigned_tx);
+
+    // The profile should account for all the gas used.
+    assert!(outcome.result.is_ok());
+    assert_eq!(profile.total(), outcome.gas_used);
+
+    // Uploading is done by the host, so its storage accesses are attributed to
+    // the tx itself. The `RustVm` doesn't meter contract execution, so no gas
+    // is spent on Wasm.
+    assert_eq!(profile.name, "tx");
+    assert!(profile.gas.storage_read > 0);
+    assert!(profile.gas.storage_write > 0);
+    assert_eq!(profile.gas.wasm, 0);
+
+    // The sender is called to authenticate the tx.
+    let authenticate = &profile.children[0];
+    assert_eq!(authenticate.contract, Some(accounts["sender"].address));
+    assert_eq!(authenticate.name, "authenticate");
+    assert_eq!(authenticate.msg_depth, 0);
+    assert_eq!(authenticate.query_depth, 0);
+}
@@ -0,0 +1,270 @@
+use {
+    grug_testing::TestBuilder,
+    grug_types::{
+        Addr, Binary, Coins, Empty, IbcClientQuery, IbcClientQueryResponse, IbcClientStatus,
+        IbcEndpoint, IbcPacket, Message, MsgIbcPacketReceive, ResultExt,
+    },
+    grug_vm_rust::{ContractBuilder, ContractWrapper},
+};
+
+/// A light client that considers a proof valid if it's equal to the value.
+mod light_client {
+    use grug_types::{
+        Empty, IbcClientQuery, IbcClientQueryResponse, IbcClientStatus, ImmutableCtx, MutableCtx,
+        Response, StdError, StdResult,
+    };
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn ibc_client_query(
+        _ctx: ImmutableCtx,
+        msg: IbcClientQuery,
+    ) -> StdResult<IbcClientQueryResponse> {
+        match msg {
+            IbcClientQuery::Status {} => {
+                Ok(IbcClientQueryResponse::Status(IbcClientStatus::Active))
+            },
+            IbcClientQuery::VerifyMembership { value, proof, .. } if value == proof => {
+                Ok(IbcClientQueryResponse::VerifyMembership)
+            },
+            _ => Err(StdError::host("invalid proof".to_string())),
+        }
+    }
+}
+
+/// An IBC handler that verifies packets against a light client before
+/// delivering them to the application.
+mod handler {
+    use {
+        grug_types::{
+            Addr, Binary, Empty, IbcClientQuery, IbcPacket, MsgIbcPacketReceive,
+            MsgIbcPacketTimeout, MutableCtx, Response, StdResult,
+        },
+        serde::{Deserialize, Serialize},
+    };
+
+    #[derive(Serialize, Deserialize)]
+    pub enum ExecuteMsg {
+        Receive {
+            client: Addr,
+            app: Addr,
+            packet: IbcPacket,
+            proof: Binary,
+        },
+        Timeout {
+            app: Addr,
+            packet: IbcPacket,
+        },
+    }
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
+        match msg {
+            ExecuteMsg::Receive {
+                client,
+                app,
+                packet,
+                proof,
+            } => {
+                ctx.querier
+                    .query_ibc_client(client, IbcClientQuery::VerifyMembership {
+                        height: 1,
+                        key: Binary::from(packet.sequence.to_be_bytes()),
+                        value: packet.data.clone(),
+                        proof,
+                    })?;
+
+                Ok(Response::new().add_message(MsgIbcPacketReceive {
+                    contract: app,
+                    packet,
+                    relayer: ctx.sender,
+                }))
+            },
+            ExecuteMsg::Timeout { app, packet } => {
+                Ok(Response::new().add_message(MsgIbcPacketTimeout {
+                    contract: app,
+                    packet,
+                    relayer: ctx.sender,
+                }))
+            },
+        }
+    }
+}
+
+/// An IBC application that writes the data of the latest received packet to
+/// its storage, and deletes it if the packet times out.
+mod app {
+    use grug_types::{
+        Empty, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, MutableCtx, Response, StdResult,
+    };
+
+    pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    pub fn ibc_packet_receive(ctx: MutableCtx, msg: IbcPacketReceiveMsg) -> StdResult<Response> {
+        ctx.storage.write(b"data", &msg.packet.data);
+
+        Ok(Response::new())
+    }
+
+    pub fn ibc_packet_timeout(ctx: MutableCtx, _msg: IbcPacketTimeoutMsg) -> StdResult<Response> {
+        ctx.storage.remove(b"data");
+
+        Ok(Response::new())
+    }
+}
+
+fn mock_packet() -> IbcPacket {
+    IbcPacket {
+        source: IbcEndpoint {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-0".to_string(),
+        },
+        destination: IbcEndpoint {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-1".to_string(),
+        },
+        sequence: 1,
+        data: Binary::from(*b"hello"),
+        timeout_height: None,
+        timeout_timestamp: None,
+    }
+}
+
+#[test]
+fn ibc_packet_lifecycle() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .add_account("relayer", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let client_code = ContractBuilder::new(Box::new(light_client::instantiate))
+        .with_ibc_client_query(Box::new(light_client::ibc_client_query))
+        .build();
+
+    let handler_code = ContractBuilder::new(Box::new(handler::instantiate))
+        .with_execute(Box::new(handler::execute))
+        .build();
+
+    let app_code = ContractBuilder::new(Box::new(app::instantiate))
+        .with_ibc_packet_receive(Box::new(app::ibc_packet_receive))
+        .with_ibc_packet_timeout(Box::new(app::ibc_packet_timeout))
+        .build();
+
+    let relayer = accounts["relayer"].address;
+
+    let mut deploy = |code: ContractWrapper, salt: &str| -> Addr {
+        suite
+            .upload_and_instantiate(
+                &mut accounts["owner"],
+                code,
+                &Empty {},
+                salt,
+                None::<String>,
+                None,
+                Coins::new(),
+            )
+            .should_succeed()
+            .address
+    };
+
+    let client = deploy(client_code, "client");
+    let handler = deploy(handler_code, "handler");
+    let app = deploy(app_code, "app");
+
+    // Query the light client directly.
+    suite
+        .query_ibc_client(client, IbcClientQuery::Status {})
+        .should_succeed_and_equal(IbcClientQueryResponse::Status(IbcClientStatus::Active));
+
+    // Packets can't be delivered before an IBC handler is set.
+    suite
+        .send_message(
+            &mut accounts["relayer"],
+            Message::IbcPacketReceive(MsgIbcPacketReceive {
+                contract: app,
+                packet: mock_packet(),
+                relayer,
+            }),
+        )
+        .should_fail_with_error("IBC handler is not set");
+
+    // Set the IBC handler.
+    let mut cfg = suite.query_config().should_succeed();
+    cfg.ibc_handler = Some(handler);
+
+    suite
+        .configure(&mut accounts["owner"], Some(cfg), None::<Empty>)
+        .should_succeed();
+
+    // Only the IBC handler can deliver packets.
+    suite
+        .send_message(
+            &mut accounts["relayer"],
+            Message::IbcPacketReceive(MsgIbcPacketReceive {
+                contract: app,
+                packet: mock_packet(),
+                relayer,
+            }),
+        )
+        .should_fail_with_error("sender is not the IBC handler");
+
+    // The handler rejects packets with invalid proofs.
+    suite
+        .execute(
+            &mut accounts["relayer"],
+            handler,
+            &handler::ExecuteMsg::Receive {
+                client,
+                app,
+                packet: mock_packet(),
+                proof: Binary::from(*b"world"),
+            },
+            Coins::new(),
+        )
+        .should_fail_with_error("invalid proof");
+
+    // Deliver a packet with a valid proof.
+    suite
+        .execute(
+            &mut accounts["relayer"],
+            handler,
+            &handler::ExecuteMsg::Receive {
+                client,
+                app,
+                packet: mock_packet(),
+                proof: Binary::from(*b"hello"),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_raw(app, *b"data")
+        .should_succeed_and_equal(Some(Binary::from(*b"hello")));
+
+    // The app doesn't implement `ibc_packet_ack`, but does implement
+    // `ibc_packet_timeout`.
+    suite
+        .execute(
+            &mut accounts["relayer"],
+            handler,
+            &handler::ExecuteMsg::Timeout {
+                app,
+                packet: mock_packet(),
+            },
+            Coins::new(),
+        )
+        .should_succeed();
+
+    suite
+        .query_wasm_raw(app, *b"data")
+        .should_succeed_and_equal(None);
+}
@@ -0,0 +1,217 @@
+use {
+    grug_app::{App, Db, NaiveProposalPreparer, NullIndexer, SnapshotManager},
+    grug_db_memory::MemDb,
+    grug_math::{IsZero, Udec128, Uint128},
+    grug_testing::{TestAccounts, TestBuilder, TestSuite},
+    grug_types::{coins, Addr, Coins, Message, NonEmpty, ResultExt, Tx},
+    grug_vm_rust::RustVm,
+    std::{path::Path, str::FromStr},
+};
+
+/// Create a test suite with the same state as the given one, by restoring it
+/// from the given suite's latest snapshot.
+fn fork(suite: &TestSuite, restore_dir: &Path) -> TestSuite {
+    let snapshot = suite
+        .app
+        .do_list_snapshots()
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let app_hash = suite
+        .app
+        .db
+        .root_hash(Some(snapshot.height))
+        .unwrap()
+        .unwrap();
+
+    let app = App::new(
+        MemDb::new(),
+        RustVm::new(),
+        NaiveProposalPreparer,
+        NullIndexer,
+        u64::MAX,
+    )
+    .with_snapshots(SnapshotManager::new(restore_dir, 0, 0));
+
+    app.do_offer_snapshot(snapshot.clone(), app_hash).unwrap();
+
+    for index in 0..snapshot.chunk_hashes.len() as u32 {
+        let chunk = suite
+            .app
+            .do_load_snapshot_chunk(snapshot.height, snapshot.format, index)
+            .unwrap()
+            .unwrap();
+        app.do_apply_snapshot_chunk(index, chunk).unwrap();
+    }
+
+    TestSuite::new_with_existing_state(
+        app.db,
+        RustVm::new(),
+        NaiveProposalPreparer,
+        NullIndexer,
+        suite.block_time,
+        suite.default_gas_limit,
+    )
+}
+
+fn transfer(
+    suite: &TestSuite,
+    accounts: &mut TestAccounts,
+    from: &'static str,
+    to: Addr,
+    amount: u128,
+) -> Tx {
+    suite.sign_transaction(
+        &mut accounts[from],
+        NonEmpty::new_unchecked(vec![Message::transfer(
+            to,
+            Coins::one("ugrug", amount).unwrap(),
+        )
+        .unwrap()]),
+    )
+}
+
+/// Set up a suite that executes transactions sequentially, and a fork of it
+/// that executes transactions in parallel. The accounts are funded with 100
+/// `ugrug` for transfers, and 1,000,000 `uusdc` for fees.
+fn setup(
+    snapshot_dir: &Path,
+    restore_dir: &Path,
+    fee_rate: &str,
+) -> (TestSuite, TestSuite, TestAccounts) {
+    let balance = coins! { "ugrug" => 100, "uusdc" => 1_000_000 };
+
+    let (mut sequential, accounts) = TestBuilder::new()
+        .add_account("alice", balance.clone())
+        .add_account("bob", balance.clone())
+        .add_account("charlie", balance.clone())
+        .add_account("dave", balance.clone())
+        .add_account("eve", balance.clone())
+        .add_account("frank", balance)
+        .set_owner("alice")
+        .set_fee_denom("uusdc")
+        .set_fee_rate(Udec128::from_str(fee_rate).unwrap())
+        .build();
+
+    // Create a snapshot of the state, from which to fork a suite that executes
+    // transactions in parallel.
+    let snapshots = SnapshotManager::new(snapshot_dir, 1, 1);
+
+    sequential.app = sequential.app.clone().with_snapshots(snapshots.clone());
+
+    sequential.make_empty_block();
+    snapshots.wait_for_creation();
+
+    let mut parallel = fork(&sequential, restore_dir);
+    parallel.app = parallel.app.clone().with_parallel_execution();
+
+    (sequential, parallel, accounts)
+}
+
+#[test]
+fn parallel_execution_is_deterministic() {
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let restore_dir = tempfile::tempdir().unwrap();
+
+    let (mut sequential, mut parallel, mut accounts) =
+        setup(snapshot_dir.path(), restore_dir.path(), "0");
+
+    let receiver1 = Addr::mock(1);
+    let receiver2 = Addr::mock(2);
+    let receiver3 = Addr::mock(3);
+    let frank = accounts["frank"].address;
+
+    let txs = vec![
+        // Independent transfers.
+        transfer(&sequential, &mut accounts, "alice", receiver1, 10),
+        transfer(&sequential, &mut accounts, "bob", receiver2, 20),
+        // Transfers to the same receiver.
+        transfer(&sequential, &mut accounts, "charlie", receiver3, 30),
+        transfer(&sequential, &mut accounts, "dave", receiver3, 40),
+        // Transfers from the same sender, with increasing sequence numbers.
+        transfer(&sequential, &mut accounts, "eve", receiver1, 50),
+        transfer(&sequential, &mut accounts, "eve", receiver2, 50),
+        // A transfer that only succeeds if it's executed after the previous
+        // one, which gives the sender enough tokens.
+        transfer(&sequential, &mut accounts, "alice", frank, 80),
+        transfer(&sequential, &mut accounts, "frank", receiver3, 150),
+        // A transfer that fails for insufficient balance.
+        transfer(&sequential, &mut accounts, "bob", receiver1, 1000),
+    ];
+
+    let sequential_outcome = sequential.make_block(txs.clone());
+    let parallel_outcome = parallel.make_block(txs);
+
+    // Both the outcomes and the app hash should be identical.
+    assert_eq!(parallel_outcome, sequential_outcome);
+
+    for (idx, tx_outcome) in parallel_outcome.tx_outcomes.into_iter().enumerate() {
+        if idx == 8 {
+            tx_outcome.should_fail();
+        } else {
+            tx_outcome.should_succeed();
+        }
+    }
+
+    for (account, balance) in [(receiver1, 60), (receiver2, 70), (receiver3, 220)] {
+        parallel
+            .query_balance(&account, "ugrug")
+            .should_succeed_and_equal(Uint128::new(balance));
+    }
+}
+
+#[test]
+fn parallel_execution_with_fees() {
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let restore_dir = tempfile::tempdir().unwrap();
+
+    // Every transaction moves fees into and out of the taxman's account.
+    let (mut sequential, mut parallel, mut accounts) =
+        setup(snapshot_dir.path(), restore_dir.path(), "0.1");
+
+    let taxman = sequential.query_config().should_succeed().taxman;
+    let receiver = Addr::mock(1);
+    let frank = accounts["frank"].address;
+
+    let txs = vec![
+        // Independent transfers, which only conflict through the fees.
+        transfer(&sequential, &mut accounts, "alice", receiver, 10),
+        transfer(&sequential, &mut accounts, "bob", receiver, 20),
+        // Transfers from the same sender, which conflict through both the
+        // sender's balance and sequence number.
+        transfer(&sequential, &mut accounts, "charlie", receiver, 30),
+        transfer(&sequential, &mut accounts, "charlie", receiver, 40),
+        // A transfer into an account, followed by a transfer out of it that
+        // only succeeds after the former.
+        transfer(&sequential, &mut accounts, "dave", frank, 80),
+        transfer(&sequential, &mut accounts, "frank", receiver, 150),
+        // A transfer that fails for insufficient balance.
+        transfer(&sequential, &mut accounts, "eve", receiver, 1000),
+    ];
+
+    let sequential_outcome = sequential.make_block(txs.clone());
+    let parallel_outcome = parallel.make_block(txs);
+
+    // Both the outcomes and the app hash should be identical.
+    assert_eq!(parallel_outcome, sequential_outcome);
+
+    for (idx, tx_outcome) in parallel_outcome.tx_outcomes.into_iter().enumerate() {
+        if idx == 6 {
+            tx_outcome.should_fail();
+        } else {
+            tx_outcome.should_succeed();
+        }
+    }
+
+    parallel
+        .query_balance(&receiver, "ugrug")
+        .should_succeed_and_equal(Uint128::new(250));
+
+    // Fees should have been charged.
+    assert!(!parallel
+        .query_balance(&taxman, "uusdc")
+        .should_succeed()
+        .is_zero());
+}
@@ -1,5 +1,5 @@
 use {
-    grug_app::{AppError, NaiveProposalPreparer, ProposalPreparer},
+    grug_app::{AppError, ExtendedVote, NaiveProposalPreparer, ProposalPreparer},
     grug_testing::TestBuilder,
     grug_types::{
         Addr, Coins, Empty, Json, JsonSerExt, Message, NonEmpty, QuerierWrapper, ResultExt,
@@ -105,6 +105,7 @@ impl ProposalPreparer for CoingeckoPriceFeeder {
         querier: QuerierWrapper,
         mut txs: Vec<Bytes>,
         max_tx_bytes: usize,
+        votes: &[ExtendedVote],
     ) -> Result<Vec<Bytes>, Self::Error> {
         // Check whether the oracle address in app config has been set.
         // If not, then we skip.
@@ -146,7 +147,7 @@ impl ProposalPreparer for CoingeckoPriceFeeder {
 
         // Use the naive preparer to trim the txs to under the max bytes.
         Ok(NaiveProposalPreparer
-            .prepare_proposal(querier, txs, max_tx_bytes)
+            .prepare_proposal(querier, txs, max_tx_bytes, votes)
             .unwrap())
     }
 }
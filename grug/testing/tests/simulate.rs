@@ -0,0 +1,259 @@
+use {
+    grug_app::AppError,
+    grug_math::Uint128,
+    grug_mock_bank::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
+    grug_testing::TestBuilder,
+    grug_types::{
+        coins, Addr, Binary, BorshSerExt, Coins, Denom, Message, MockStorage, NonEmpty, Order,
+        ResultExt, Signer, StateOverrides, StdResult,
+    },
+    std::{collections::BTreeMap, str::FromStr},
+};
+
+#[test]
+fn simulating_with_storage_overrides() {
+    let (suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new())
+        .build();
+
+    let sender = accounts["sender"].address;
+    let bank = suite.query_config().unwrap().bank;
+    let denom = Denom::from_str("ugrug").unwrap();
+
+    let unsigned_tx = accounts["sender"]
+        .unsigned_transaction(
+            NonEmpty::new_unchecked(vec![Message::transfer(
+                Addr::mock(1),
+                Coins::one("ugrug", 100).unwrap(),
+            )
+            .unwrap()]),
+            &suite.chain_id,
+        )
+        .unwrap();
+
+    // The sender doesn't have any token, so the transfer should fail.
+    suite.simulate_tx(unsigned_tx.clone()).should_fail();
+
+    // Give the sender some tokens by overriding the bank contract's storage.
+    let balance = Binary::from(Uint128::new(100).to_borsh_vec().unwrap());
+    let overrides = StateOverrides {
+        contract_storage: BTreeMap::from([(
+            bank,
+            BTreeMap::from([
+                (
+                    Binary::from(
+                        BALANCES_BY_ADDR
+                            .path((sender, &denom))
+                            .storage_key()
+                            .to_vec(),
+                    ),
+                    Some(balance.clone()),
+                ),
+                (
+                    Binary::from(
+                        BALANCES_BY_DENOM
+                            .path((&denom, sender))
+                            .storage_key()
+                            .to_vec(),
+                    ),
+                    Some(balance),
+                ),
+            ]),
+        )]),
+        ..Default::default()
+    };
+
+    suite
+        .simulate_tx_with_overrides(unsigned_tx, overrides)
+        .should_succeed();
+
+    // The overrides shouldn't have been persisted.
+    suite
+        .query_balance(&sender, "ugrug")
+        .should_succeed_and_equal(Uint128::new(0));
+}
+
+#[test]
+fn simulating_with_balance_overrides() {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::one("uatom", 50).unwrap())
+        .build();
+
+    let sender = accounts["sender"].address;
+
+    let transfer = |coins: Coins| {
+        accounts["sender"]
+            .unsigned_transaction(
+                NonEmpty::new_unchecked(vec![Message::transfer(Addr::mock(1), coins).unwrap()]),
+                &suite.chain_id,
+            )
+            .unwrap()
+    };
+
+    // Give the sender 100 ugrug. Its uatom balance isn't listed, so it's set to
+    // zero.
+    let overrides = StateOverrides {
+        balances: BTreeMap::from([(sender, Coins::one("ugrug", 100).unwrap())]),
+        ..Default::default()
+    };
+
+    // Balance overrides aren't enabled by default.
+    suite
+        .app
+        .do_simulate(
+            transfer(Coins::one("ugrug", 100).unwrap()),
+            overrides.clone(),
+            0,
+            false,
+            false,
+        )
+        .map(|(outcome, _)| outcome)
+        .should_fail_with_error(AppError::BalanceOverridesNotEnabled);
+
+    suite.app = suite
+        .app
+        .clone()
+        .with_balance_overrides(grug_mock_bank::override_balances);
+
+    suite
+        .simulate_tx_with_overrides(
+            transfer(Coins::one("ugrug", 100).unwrap()),
+            overrides.clone(),
+        )
+        .should_succeed();
+
+    suite
+        .simulate_tx_with_overrides(
+            transfer(Coins::one("ugrug", 101).unwrap()),
+            overrides.clone(),
+        )
+        .should_fail();
+
+    suite
+        .simulate_tx_with_overrides(transfer(Coins::one("uatom", 1).unwrap()), overrides)
+        .should_fail();
+
+    // The overrides shouldn't have been persisted.
+    suite
+        .query_balance(&sender, "ugrug")
+        .should_succeed_and_equal(Uint128::new(0));
+    suite
+        .query_balance(&sender, "uatom")
+        .should_succeed_and_equal(Uint128::new(50));
+    suite
+        .query_supply("ugrug")
+        .should_succeed_and_equal(Uint128::new(0));
+
+    // The total supplies are adjusted by the difference between the old and
+    // the new balances.
+    let mut storage = MockStorage::new();
+    let other = Addr::mock(2);
+
+    grug_mock_bank::initialize(&mut storage, [
+        (sender, coins! { "uatom" => 50, "ugrug" => 10 }),
+        (other, coins! { "ugrug" => 5 }),
+    ])
+    .unwrap();
+
+    grug_mock_bank::override_balances(
+        &mut storage,
+        sender,
+        &coins! { "ugrug" => 100, "uusdc" => 7 },
+    )
+    .unwrap();
+
+    assert_eq!(
+        SUPPLIES
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<BTreeMap<_, _>>>()
+            .unwrap(),
+        BTreeMap::from([
+            (Denom::from_str("ugrug").unwrap(), Uint128::new(105)),
+            (Denom::from_str("uusdc").unwrap(), Uint128::new(7)),
+        ])
+    );
+    assert_eq!(
+        BALANCES_BY_DENOM
+            .prefix(&Denom::from_str("ugrug").unwrap())
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<BTreeMap<_, _>>>()
+            .unwrap(),
+        BTreeMap::from([(sender, Uint128::new(100)), (other, Uint128::new(5))])
+    );
+}
+
+#[test]
+fn simulating_with_block_overrides() {
+    let (suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let height = suite.block.height + 1;
+
+    let unsigned_tx = accounts["owner"]
+        .unsigned_transaction(
+            NonEmpty::new_unchecked(vec![Message::upgrade("v2", height)]),
+            &suite.chain_id,
+        )
+        .unwrap();
+
+    // Simulated at the latest finalized height, the upgrade height is in the
+    // future, so the transaction should succeed.
+    suite.simulate_tx(unsigned_tx.clone()).should_succeed();
+
+    // Simulated at the upgrade height, it should fail.
+    suite
+        .simulate_tx_with_overrides(unsigned_tx, StateOverrides {
+            block_height: Some(height),
+            ..Default::default()
+        })
+        .should_fail_with_error(AppError::UpgradeHeightNotInFuture {
+            height,
+            current_height: height,
+        });
+}
+
+#[test]
+fn simulating_with_code_overrides() {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new())
+        .build();
+
+    let bank = suite.query_config().unwrap().bank;
+
+    let unsigned_tx = accounts["sender"]
+        .unsigned_transaction(
+            NonEmpty::new_unchecked(vec![Message::transfer(
+                Addr::mock(1),
+                Coins::one("ugrug", 100).unwrap(),
+            )
+            .unwrap()]),
+            &suite.chain_id,
+        )
+        .unwrap();
+
+    let overrides = StateOverrides {
+        contract_code: BTreeMap::from([(bank, Binary::from(vec![0; 32]))]),
+        ..Default::default()
+    };
+
+    // Code overrides aren't enabled by default.
+    suite
+        .app
+        .do_simulate(unsigned_tx.clone(), overrides.clone(), 0, false, false)
+        .map(|(outcome, _)| outcome)
+        .should_fail_with_error(AppError::CodeOverridesNotEnabled);
+
+    // Once enabled, codes larger than the maximum size are rejected.
+    suite.app = suite.app.clone().with_code_overrides(16);
+
+    suite
+        .app
+        .do_simulate(unsigned_tx, overrides, 0, false, false)
+        .map(|(outcome, _)| outcome)
+        .should_fail_with_error(AppError::OverrideCodeTooLarge {
+            max: 16,
+            actual: 32,
+        });
+}
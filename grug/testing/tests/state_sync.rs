@@ -0,0 +1,108 @@
+use {
+    grug_app::{App, AppError, Db, NaiveProposalPreparer, NullIndexer, SnapshotManager},
+    grug_db_memory::MemDb,
+    grug_testing::TestBuilder,
+    grug_types::{Coins, Denom, Query, ResultExt},
+    grug_vm_rust::RustVm,
+    std::str::FromStr,
+};
+
+#[test]
+fn state_sync_from_snapshot() {
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let restore_dir = tempfile::tempdir().unwrap();
+
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("sender", Coins::one("ugrug", 100).unwrap())
+        .add_account("receiver", Coins::new())
+        .set_owner("sender")
+        .build();
+
+    // Create a snapshot at every block, keeping the two most recent ones.
+    let snapshots = SnapshotManager::new(snapshot_dir.path(), 1, 2);
+
+    suite.app = suite.app.clone().with_snapshots(snapshots.clone());
+
+    let receiver = accounts["receiver"].address;
+
+    suite
+        .transfer(
+            &mut accounts["sender"],
+            receiver,
+            Coins::one("ugrug", 30).unwrap(),
+        )
+        .should_succeed();
+
+    // Snapshots are created in the background.
+    snapshots.wait_for_creation();
+
+    let snapshot = suite
+        .app
+        .do_list_snapshots()
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let height = snapshot.height;
+    let app_hash = suite.app.db.root_hash(Some(height)).unwrap().unwrap();
+
+    assert_eq!(height, suite.block.height);
+
+    // A fresh node, which doesn't create snapshots itself, restores the state
+    // from the snapshot.
+    let app = App::new(
+        MemDb::new(),
+        RustVm::new(),
+        NaiveProposalPreparer,
+        NullIndexer,
+        u64::MAX,
+    )
+    .with_snapshots(SnapshotManager::new(restore_dir.path(), 0, 0));
+
+    app.do_offer_snapshot(snapshot.clone(), app_hash).unwrap();
+
+    // A chunk that doesn't match the chunk hash is rejected.
+    assert!(matches!(
+        app.do_apply_snapshot_chunk(0, b"tampered".to_vec()),
+        Err(AppError::SnapshotChunkHashMismatch { index: 0, .. })
+    ));
+
+    let chunks = snapshot.chunk_hashes.len() as u32;
+    for index in 0..chunks {
+        let chunk = suite
+            .app
+            .do_load_snapshot_chunk(height, snapshot.format, index)
+            .unwrap()
+            .unwrap();
+        let done = app.do_apply_snapshot_chunk(index, chunk).unwrap();
+
+        assert_eq!(done, index == chunks - 1);
+    }
+
+    assert_eq!(app.db.latest_version(), Some(height));
+    assert_eq!(app.db.root_hash(Some(height)).unwrap(), Some(app_hash));
+
+    // The restored node serves the same state.
+    let query = Query::balance(receiver, Denom::from_str("ugrug").unwrap());
+
+    assert_eq!(
+        app.do_query_app(query.clone(), 0, false).unwrap(),
+        suite.app.do_query_app(query, 0, false).unwrap(),
+    );
+
+    // The restored node can't restore another snapshot on top of its state.
+    app.do_offer_snapshot(snapshot.clone(), app_hash).unwrap();
+
+    let mut result = Ok(false);
+    for index in 0..chunks {
+        let chunk = suite
+            .app
+            .do_load_snapshot_chunk(height, snapshot.format, index)
+            .unwrap()
+            .unwrap();
+        result = app.do_apply_snapshot_chunk(index, chunk);
+    }
+
+    assert!(result.is_err());
+}
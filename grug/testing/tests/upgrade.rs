@@ -0,0 +1,140 @@
+use {
+    grug_app::{AppError, Db, CONFIG, UPGRADE_PLAN},
+    grug_storage::Item,
+    grug_testing::TestBuilder,
+    grug_types::{
+        Binary, Block, BlockInfo, Coins, GasCosts, LinearGasCost, Message, ResultExt, Storage,
+        UpgradePlan,
+    },
+};
+
+/// Number of times the upgrade handler has been run.
+const UPGRADE_COUNT: Item<u32> = Item::new("upgrade_count");
+
+/// An upgrade handler that migrates the chain config, making storage accesses
+/// free, and counts how many times it has been run.
+fn handle_upgrade(storage: &mut dyn Storage, _block: BlockInfo) -> Result<(), AppError> {
+    let mut cfg = CONFIG.load(storage)?;
+
+    cfg.gas_costs = GasCosts {
+        db_read: LinearGasCost::new(0, 0),
+        db_scan: 0,
+        db_next: 0,
+        db_write: LinearGasCost::new(0, 0),
+        db_remove: 0,
+        ..GasCosts::DEFAULT
+    };
+
+    CONFIG.save(storage, &cfg)?;
+
+    let count = UPGRADE_COUNT.may_load(storage)?.unwrap_or(0);
+    UPGRADE_COUNT.save(storage, &(count + 1))?;
+
+    Ok(())
+}
+
+#[test]
+fn upgrading() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    suite.app = suite.app.clone().with_upgrade_handler("v2", handle_upgrade);
+
+    // Scheduling an upgrade at a height that has already begun should fail.
+    suite
+        .send_message(
+            &mut accounts["owner"],
+            Message::upgrade("v2", suite.block.height + 1),
+        )
+        .should_fail_with_error(AppError::UpgradeHeightNotInFuture {
+            height: suite.block.height,
+            current_height: suite.block.height,
+        });
+
+    // Schedule the upgrade two blocks after the one in which it is scheduled.
+    let height = suite.block.height + 3;
+
+    suite
+        .send_message(&mut accounts["owner"], Message::upgrade("v2", height))
+        .should_succeed();
+
+    let storage = suite.app.db.state_storage(None).unwrap();
+
+    assert_eq!(
+        UPGRADE_PLAN.may_load(&storage).unwrap(),
+        Some(UpgradePlan {
+            name: "v2".to_string(),
+            height,
+        })
+    );
+
+    // Nothing happens before the upgrade height.
+    suite.make_empty_block();
+
+    let storage = suite.app.db.state_storage(None).unwrap();
+
+    assert_eq!(UPGRADE_COUNT.may_load(&storage).unwrap(), None);
+
+    // The upgrade is performed before the transactions in the block, so the
+    // transaction is already subject to the migrated gas costs.
+    let gas_used = suite
+        .upload(&mut accounts["owner"], Binary::from(*b"code"))
+        .should_succeed()
+        .outcome
+        .gas_used;
+
+    assert_eq!(suite.block.height, height);
+    assert_eq!(gas_used, 0);
+
+    // The upgrade is only performed once.
+    suite.make_empty_block();
+
+    let storage = suite.app.db.state_storage(None).unwrap();
+
+    assert_eq!(UPGRADE_COUNT.may_load(&storage).unwrap(), Some(1));
+    assert_eq!(UPGRADE_PLAN.may_load(&storage).unwrap(), None);
+}
+
+#[test]
+fn halting_at_unsupported_upgrade() {
+    let (mut suite, mut accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new())
+        .set_owner("owner")
+        .build();
+
+    let height = suite.block.height + 2;
+
+    suite
+        .send_message(&mut accounts["owner"], Message::upgrade("v2", height))
+        .should_succeed();
+
+    // The app doesn't have a handler for the upgrade, so it should refuse to
+    // finalize the block at the upgrade height.
+    let block = Block {
+        info: BlockInfo {
+            height,
+            timestamp: suite.block.timestamp + suite.block_time,
+            hash: suite.block.hash,
+        },
+        txs: vec![],
+    };
+
+    suite
+        .app
+        .do_finalize_block(block)
+        .should_fail_with_error(AppError::UpgradeNeeded {
+            name: "v2".to_string(),
+            height,
+        });
+
+    // After switching to a software version that supports the upgrade, the
+    // block can be finalized.
+    suite.app = suite.app.clone().with_upgrade_handler("v2", handle_upgrade);
+    suite.make_empty_block();
+
+    let storage = suite.app.db.state_storage(None).unwrap();
+
+    assert_eq!(UPGRADE_COUNT.may_load(&storage).unwrap(), Some(1));
+}
@@ -1,5 +1,5 @@
 use {
-    crate::{Addr, Duration, Hash256, Json, Label, Message, Timestamp, Tx},
+    crate::{Addr, Duration, GasCosts, Hash256, Json, Label, Message, Timestamp, Tx},
     borsh::{BorshDeserialize, BorshSerialize},
     hex_literal::hex,
     serde::{Deserialize, Serialize},
@@ -58,6 +58,10 @@ pub struct Config {
     pub bank: Addr,
     /// The contract that handles transaction fees.
     pub taxman: Addr,
+    /// The contract that implements the IBC core logic. Only this contract can
+    /// deliver packets, acknowledgements, and timeouts to IBC applications.
+    /// `None` if IBC is disabled.
+    pub ibc_handler: Option<Addr>,
     /// A list of contracts that are to be called at regular time intervals.
     pub cronjobs: BTreeMap<Addr, Duration>,
     /// Permissions for certain gated actions.
@@ -66,6 +70,23 @@ pub struct Config {
     /// A code is deleted if it remains orphaned (not used by any contract) for
     /// longer than this duration.
     pub max_orphan_age: Duration,
+    /// Gas costs of host and Wasm operations.
+    pub gas_costs: GasCosts,
+}
+
+/// A plan to upgrade the chain's software at a given block height.
+///
+/// At the beginning of the block at that height, before any cronjob or
+/// transaction, the upgrade handler registered under the plan's name is run to
+/// migrate the state. A node that doesn't have such a handler halts, such that
+/// the operator can switch to a software version that does.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradePlan {
+    /// Name of the upgrade, which identifies its handler.
+    pub name: String,
+    /// The block height at which the upgrade takes place.
+    pub height: u64,
 }
 
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
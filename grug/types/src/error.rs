@@ -2,7 +2,7 @@ use {
     crate::Denom,
     data_encoding::{DecodeError, BASE64},
     grug_math::MathError,
-    std::{any::type_name, array::TryFromSliceError, convert::Infallible},
+    std::{any::type_name, array::TryFromSliceError, convert::Infallible, fmt::Debug},
     thiserror::Error,
 };
 
@@ -16,6 +16,9 @@ pub enum VerificationError {
 
     #[error("signature is unauthentic")]
     Unauthentic,
+
+    #[error("invalid elliptic curve point")]
+    InvalidPoint,
 }
 
 impl VerificationError {
@@ -26,7 +29,8 @@ impl VerificationError {
             1 => Self::IncorrectLength,
             2 => Self::InvalidRecoveryId,
             3 => Self::Unauthentic,
-            _ => unreachable!("unknown verification error code: {error_code}, must be 1-3"),
+            4 => Self::InvalidPoint,
+            _ => unreachable!("unknown verification error code: {error_code}, must be 1-4"),
         }
     }
 }
@@ -93,6 +97,9 @@ pub enum StdError {
         bound: usize,
     },
 
+    #[error("no snapshot was recorded at height {height}")]
+    NotCheckpointed { height: String },
+
     #[error("out of gas! limit: {limit}, used: {used}, comment: {comment}")]
     OutOfGas {
         limit: u64,
@@ -195,6 +202,15 @@ impl StdError {
         }
     }
 
+    pub fn not_checkpointed<H>(height: H) -> Self
+    where
+        H: Debug,
+    {
+        Self::NotCheckpointed {
+            height: format!("{height:?}"),
+        }
+    }
+
     pub fn serialize<T, R>(codec: &'static str, reason: R) -> Self
     where
         R: ToString,
@@ -1,7 +1,7 @@
 use {
     crate::{
-        Addr, Coins, ContractEvent, EventStatus, HandleEventStatus, Hash256, Json, Label, ReplyOn,
-        Timestamp,
+        Addr, Binary, Coins, ContractEvent, EventStatus, GasCosts, HandleEventStatus, Hash256,
+        IbcPacket, Json, Label, ReplyOn, Timestamp, UpgradePlan,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
@@ -35,7 +35,12 @@ pub enum Event {
     Finalize(EvtFinalize),
     /// A cronjob was executed.
     Cron(EvtCron),
-    // TODO: IBC events
+    /// An IBC packet was delivered to the receiving application.
+    IbcPacketReceive(EvtIbcPacketReceive),
+    /// The acknowledgement of an IBC packet was delivered to the sending application.
+    IbcPacketAck(EvtIbcPacketAck),
+    /// The timeout of an IBC packet was delivered to the sending application.
+    IbcPacketTimeout(EvtIbcPacketTimeout),
 }
 
 impl Event {
@@ -63,9 +68,15 @@ impl Event {
 }
 
 /// An event indicating that the chain- or app-level configurations were updated.
+#[skip_serializing_none]
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EvtConfigure {
     pub sender: Addr,
+    /// The new gas costs, if they were changed. They take effect from the next
+    /// block.
+    pub new_gas_costs: Option<GasCosts>,
+    /// The upgrade plan, if one was scheduled.
+    pub upgrade: Option<UpgradePlan>,
     // TODO: not sure what else we need here. the old and new configs?
 }
 
@@ -291,6 +302,82 @@ impl EvtCron {
     }
 }
 
+/// An event indicating that an IBC packet was delivered to the receiving application.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtIbcPacketReceive {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub relayer: Addr,
+    pub guest_event: EventStatus<EvtGuest>,
+}
+
+impl EvtIbcPacketReceive {
+    pub fn base(sender: Addr, contract: Addr, packet: IbcPacket, relayer: Addr) -> Self {
+        Self {
+            sender,
+            contract,
+            packet,
+            relayer,
+            guest_event: EventStatus::NotReached,
+        }
+    }
+}
+
+/// An event indicating that the acknowledgement of an IBC packet was delivered
+/// to the sending application.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtIbcPacketAck {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub acknowledgement: Binary,
+    pub relayer: Addr,
+    pub guest_event: EventStatus<EvtGuest>,
+}
+
+impl EvtIbcPacketAck {
+    pub fn base(
+        sender: Addr,
+        contract: Addr,
+        packet: IbcPacket,
+        acknowledgement: Binary,
+        relayer: Addr,
+    ) -> Self {
+        Self {
+            sender,
+            contract,
+            packet,
+            acknowledgement,
+            relayer,
+            guest_event: EventStatus::NotReached,
+        }
+    }
+}
+
+/// An event indicating that the timeout of an IBC packet was delivered to the
+/// sending application.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvtIbcPacketTimeout {
+    pub sender: Addr,
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub relayer: Addr,
+    pub guest_event: EventStatus<EvtGuest>,
+}
+
+impl EvtIbcPacketTimeout {
+    pub fn base(sender: Addr, contract: Addr, packet: IbcPacket, relayer: Addr) -> Self {
+        Self {
+            sender,
+            contract,
+            packet,
+            relayer,
+            guest_event: EventStatus::NotReached,
+        }
+    }
+}
+
 /// An event indicating that a contract emitted a custom event.
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct EvtGuest {
@@ -0,0 +1,363 @@
+use {
+    crate::Addr,
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
+};
+
+// ----------------------------------- costs -----------------------------------
+
+/// Gas costs of the operations performed by the host, as well as of each
+/// operation performed by a Wasm contract.
+///
+/// These are part of the chain-level config, and can be updated by the owner
+/// with a `MsgConfigure`. The new costs take effect from the next block.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GasCosts {
+    // Wasm
+    pub wasm_operation: u64,
+    // Storage
+    pub db_read: LinearGasCost,
+    pub db_scan: u64,
+    pub db_next: u64,
+    pub db_write: LinearGasCost,
+    pub db_remove: u64,
+    // Signature verifiers
+    pub secp256r1_verify: u64,
+    pub secp256k1_verify: u64,
+    pub secp256k1_pubkey_recover: u64,
+    pub secp256k1_schnorr_verify: u64,
+    pub ed25519_verify: u64,
+    pub ed25519_pure_verify: LinearGasCost,
+    pub ed25519_batch_verify: LinearGasCost,
+    pub bls12_381_verify: u64,
+    pub bls12_381_aggregate_verify: LinearGasCost,
+    pub bls12_381_pairing_equality: LinearGasCost,
+    pub bls12_381_hash_to_curve: LinearGasCost,
+    // Hashers
+    pub sha2_256: LinearGasCost,
+    pub sha2_512: LinearGasCost,
+    pub sha2_512_truncated: LinearGasCost,
+    pub sha3_256: LinearGasCost,
+    pub sha3_512: LinearGasCost,
+    pub sha3_512_truncated: LinearGasCost,
+    pub keccak256: LinearGasCost,
+    pub blake2s_256: LinearGasCost,
+    pub blake2b_512: LinearGasCost,
+    pub blake3: LinearGasCost,
+}
+
+impl GasCosts {
+    /// The default gas costs.
+    pub const DEFAULT: Self = Self {
+        // Wasm.
+        //
+        // Each Wasmer operation costs 1 Wasmer point, which is 1 Grug gas.
+        wasm_operation: 1,
+        // Storage.
+        //
+        // For storage, we take the values from Cosmos SDK:
+        // https://github.com/cosmos/cosmos-sdk/blob/v0.50.7/store/types/gas.go#L232-L242
+        //
+        // Following the conversion:
+        // - 1 Cosmos SDK gas = 100 CosmWasm gas
+        // - 170 CosmWasm gas = 1 Wasmer point
+        // - 1 Wasmer point = 1 Grug gas
+        // This means: 1 Cosmos SDK gas = 0.588 Grug gas
+        db_read: LinearGasCost::new(588, 2),
+        db_scan: 588,
+        db_next: 18,
+        db_write: LinearGasCost::new(1176, 18),
+        db_remove: 588,
+        // Verifiers
+        //
+        // For batch verification, there's a flat setup cost, and a cost per signature.
+        secp256r1_verify: 1_880_000,
+        secp256k1_verify: 770_000,
+        secp256k1_pubkey_recover: 1_580_000,
+        secp256k1_schnorr_verify: 800_000,
+        ed25519_verify: 410_000,
+        // Pure Ed25519 verification hashes the message using SHA-512, so on
+        // top of the cost of `ed25519_verify`, it costs the same per byte of
+        // the message as `sha2_512`.
+        ed25519_pure_verify: LinearGasCost::new(410_000, 16),
+        ed25519_batch_verify: LinearGasCost::new(1_340_000, 188_000),
+        // BLS12-381 aggregate verification is charged per public key, and
+        // pairing equality per pair of points. On top of that, verification
+        // is charged per byte of the message and the domain separation tag,
+        // which are hashed to a curve point using SHA-256, so this costs the
+        // same per byte as `sha2_256`.
+        bls12_381_verify: 26_000_000,
+        bls12_381_aggregate_verify: LinearGasCost::new(28_600_000, 916_000),
+        bls12_381_pairing_equality: LinearGasCost::new(13_300_000, 8_670_000),
+        bls12_381_hash_to_curve: LinearGasCost::new(0, 27),
+        // Hashers.
+        //
+        // For hashers, `per_item` means per byte.
+        // The truncated versions have the same cost as the untruncated counterparts.
+        sha2_256: LinearGasCost::new(0, 27),
+        sha2_512: LinearGasCost::new(0, 16),
+        sha2_512_truncated: LinearGasCost::new(0, 16),
+        sha3_256: LinearGasCost::new(0, 15),
+        sha3_512: LinearGasCost::new(0, 28),
+        sha3_512_truncated: LinearGasCost::new(0, 28),
+        keccak256: LinearGasCost::new(0, 15),
+        blake2s_256: LinearGasCost::new(0, 15),
+        blake2b_512: LinearGasCost::new(0, 9),
+        blake3: LinearGasCost::new(0, 5),
+    };
+    /// The minimum gas costs that can be configured.
+    ///
+    /// Each operation takes the host time to perform, so none of them may be
+    /// free. Otherwise, a contract could perform it indefinitely.
+    pub const MINIMUM: Self = Self {
+        wasm_operation: 1,
+        db_read: LinearGasCost::new(1, 1),
+        db_scan: 1,
+        db_next: 1,
+        db_write: LinearGasCost::new(1, 1),
+        db_remove: 1,
+        secp256r1_verify: 1,
+        secp256k1_verify: 1,
+        secp256k1_pubkey_recover: 1,
+        secp256k1_schnorr_verify: 1,
+        ed25519_verify: 1,
+        ed25519_pure_verify: LinearGasCost::new(1, 1),
+        ed25519_batch_verify: LinearGasCost::new(1, 1),
+        bls12_381_verify: 1,
+        bls12_381_aggregate_verify: LinearGasCost::new(1, 1),
+        bls12_381_pairing_equality: LinearGasCost::new(1, 1),
+        bls12_381_hash_to_curve: LinearGasCost::new(0, 1),
+        sha2_256: LinearGasCost::new(0, 1),
+        sha2_512: LinearGasCost::new(0, 1),
+        sha2_512_truncated: LinearGasCost::new(0, 1),
+        sha3_256: LinearGasCost::new(0, 1),
+        sha3_512: LinearGasCost::new(0, 1),
+        sha3_512_truncated: LinearGasCost::new(0, 1),
+        keccak256: LinearGasCost::new(0, 1),
+        blake2s_256: LinearGasCost::new(0, 1),
+        blake2b_512: LinearGasCost::new(0, 1),
+        blake3: LinearGasCost::new(0, 1),
+    };
+
+    /// Return the name of the first cost that is below its minimum, if any.
+    pub fn find_below_minimum(&self) -> Option<&'static str> {
+        macro_rules! check {
+            ($($field:ident),* $(,)?) => {
+                $(
+                    if !self.$field.is_at_least(&Self::MINIMUM.$field) {
+                        return Some(stringify!($field));
+                    }
+                )*
+            };
+        }
+
+        check!(
+            wasm_operation,
+            db_read,
+            db_scan,
+            db_next,
+            db_write,
+            db_remove,
+            secp256r1_verify,
+            secp256k1_verify,
+            secp256k1_pubkey_recover,
+            secp256k1_schnorr_verify,
+            ed25519_verify,
+            ed25519_pure_verify,
+            ed25519_batch_verify,
+            bls12_381_verify,
+            bls12_381_aggregate_verify,
+            bls12_381_pairing_equality,
+            bls12_381_hash_to_curve,
+            sha2_256,
+            sha2_512,
+            sha2_512_truncated,
+            sha3_256,
+            sha3_512,
+            sha3_512_truncated,
+            keccak256,
+            blake2s_256,
+            blake2b_512,
+            blake3,
+        );
+
+        None
+    }
+}
+
+impl Default for GasCosts {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(deny_unknown_fields)]
+pub struct LinearGasCost {
+    /// The flat part of the cost, charged once per batch.
+    pub base: u64,
+    /// The cost per item, on top of the flat part.
+    pub per_item: u64,
+}
+
+impl LinearGasCost {
+    pub const fn new(base: u64, per_item: u64) -> Self {
+        Self { base, per_item }
+    }
+
+    /// Return the cost of the given number of items, saturating at the max
+    /// `u64` value, which no gas limit can afford.
+    pub fn cost(&self, items: usize) -> u64 {
+        self.per_item
+            .saturating_mul(items.try_into().unwrap_or(u64::MAX))
+            .saturating_add(self.base)
+    }
+}
+
+/// A gas cost that can be compared against its minimum.
+trait AtLeast {
+    fn is_at_least(&self, minimum: &Self) -> bool;
+}
+
+impl AtLeast for u64 {
+    fn is_at_least(&self, minimum: &Self) -> bool {
+        self >= minimum
+    }
+}
+
+impl AtLeast for LinearGasCost {
+    fn is_at_least(&self, minimum: &Self) -> bool {
+        self.base >= minimum.base && self.per_item >= minimum.per_item
+    }
+}
+
+// ---------------------------------- profile ----------------------------------
+
+/// The kind of operation gas is consumed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasCategory {
+    /// Executing Wasm code.
+    Wasm,
+    /// Reading and iterating the storage.
+    StorageRead,
+    /// Writing to and removing from the storage.
+    StorageWrite,
+    /// Signature verifications and hashing.
+    Crypto,
+}
+
+/// Gas consumed, broken down by the kind of operation it was consumed on.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
+)]
+pub struct GasBreakdown {
+    /// Gas consumed on executing Wasm code.
+    pub wasm: u64,
+    /// Gas consumed on reading and iterating the storage.
+    pub storage_read: u64,
+    /// Gas consumed on writing to and removing from the storage.
+    pub storage_write: u64,
+    /// Gas consumed on signature verifications and hashing.
+    pub crypto: u64,
+}
+
+impl GasBreakdown {
+    /// Add gas consumed on the given category of operations.
+    pub fn add(&mut self, category: GasCategory, consumed: u64) {
+        let gas = match category {
+            GasCategory::Wasm => &mut self.wasm,
+            GasCategory::StorageRead => &mut self.storage_read,
+            GasCategory::StorageWrite => &mut self.storage_write,
+            GasCategory::Crypto => &mut self.crypto,
+        };
+
+        *gas = gas.saturating_add(consumed);
+    }
+
+    /// Return the total amount of gas consumed.
+    pub fn total(&self) -> u64 {
+        self.wasm + self.storage_read + self.storage_write + self.crypto
+    }
+}
+
+/// A call in a tree of gas consumption, produced by profiling a transaction.
+///
+/// The root of the tree represents the transaction itself. Each other node
+/// represents a call to a contract, with the submessages, replies, and queries
+/// it triggered as its children.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GasProfile {
+    /// The contract being called. `None` for the root node.
+    pub contract: Option<Addr>,
+    /// The entry point being called, e.g. `execute`, `reply`, or `query`.
+    pub name: String,
+    /// Depth of the submessage that made this call. Zero for messages in the
+    /// transaction itself.
+    pub msg_depth: usize,
+    /// Depth of the query that made this call. Zero if this call isn't a query.
+    pub query_depth: usize,
+    /// Gas consumed by the call itself, excluding its children.
+    pub gas: GasBreakdown,
+    /// Calls made during this call.
+    pub children: Vec<GasProfile>,
+}
+
+impl GasProfile {
+    /// Create the root node of a gas profile.
+    pub fn root() -> Self {
+        Self {
+            contract: None,
+            name: "tx".to_string(),
+            msg_depth: 0,
+            query_depth: 0,
+            gas: GasBreakdown::default(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Return the total amount of gas consumed by the call, including its
+    /// children.
+    pub fn total(&self) -> u64 {
+        self.gas.total() + self.children.iter().map(GasProfile::total).sum::<u64>()
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_gas_cost_saturates() {
+        let cost = LinearGasCost::new(10, 3);
+
+        assert_eq!(cost.cost(0), 10);
+        assert_eq!(cost.cost(5), 25);
+        assert_eq!(cost.cost(usize::MAX), u64::MAX);
+        assert_eq!(LinearGasCost::new(u64::MAX, 1).cost(1), u64::MAX);
+    }
+
+    #[test]
+    fn gas_costs_minimum() {
+        assert_eq!(GasCosts::DEFAULT.find_below_minimum(), None);
+        assert_eq!(GasCosts::MINIMUM.find_below_minimum(), None);
+
+        let costs = GasCosts {
+            wasm_operation: 0,
+            ..GasCosts::DEFAULT
+        };
+        assert_eq!(costs.find_below_minimum(), Some("wasm_operation"));
+
+        let costs = GasCosts {
+            db_write: LinearGasCost::new(1176, 0),
+            ..GasCosts::DEFAULT
+        };
+        assert_eq!(costs.find_below_minimum(), Some("db_write"));
+    }
+}
@@ -0,0 +1,139 @@
+use {
+    crate::{Addr, Binary, Timestamp},
+    borsh::{BorshDeserialize, BorshSerialize},
+    paste::paste,
+    serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
+};
+
+// ---------------------------------- client -----------------------------------
+
+/// The query message that the host provides a light client contract during the
+/// `ibc_client_query` function call.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcClientQuery {
+    /// Query the status of the client.
+    Status {},
+    /// Verify that a key-value pair exists in the counterparty chain's state
+    /// at the given height.
+    VerifyMembership {
+        height: u64,
+        key: Binary,
+        value: Binary,
+        proof: Binary,
+    },
+    /// Verify that a key doesn't exist in the counterparty chain's state at
+    /// the given height.
+    VerifyNonMembership {
+        height: u64,
+        key: Binary,
+        proof: Binary,
+    },
+}
+
+/// The query response that a light client contract must return during the
+/// `ibc_client_query` function call.
+///
+/// Similar to `BankQueryResponse`, the response MUST match the query. A failed
+/// verification is indicated by returning an error, not by the response.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcClientQueryResponse {
+    Status(IbcClientStatus),
+    VerifyMembership,
+    VerifyNonMembership,
+}
+
+macro_rules! generate_downcast {
+    ($id:ident => $ret:ty) => {
+        paste! {
+            pub fn [<as_$id:snake>](self) -> $ret {
+                match self {
+                    IbcClientQueryResponse::$id(value) => value,
+                    _ => panic!("IbcClientQueryResponse is not {}", stringify!($id)),
+                }
+            }
+        }
+    };
+    ($($id:ident => $ret:ty),+ $(,)?) => {
+        $(
+            generate_downcast!($id => $ret);
+        )+
+    };
+}
+
+impl IbcClientQueryResponse {
+    generate_downcast! {
+        Status => IbcClientStatus,
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcClientStatus {
+    /// The client is up to date and can be used to verify proofs.
+    Active,
+    /// The client has been frozen due to a misbehavior of the counterparty.
+    Frozen,
+    /// The client hasn't been updated for longer than its trusting period.
+    Expired,
+}
+
+// ---------------------------------- packet -----------------------------------
+
+/// One end of an IBC channel.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcEndpoint {
+    pub port_id: String,
+    pub channel_id: String,
+}
+
+/// A packet sent from one chain to another over an IBC channel.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacket {
+    pub source: IbcEndpoint,
+    pub destination: IbcEndpoint,
+    pub sequence: u64,
+    pub data: Binary,
+    /// The packet can't be received on the destination chain at or after this
+    /// block height.
+    pub timeout_height: Option<u64>,
+    /// The packet can't be received on the destination chain at or after this
+    /// block time.
+    pub timeout_timestamp: Option<Timestamp>,
+}
+
+/// The message that the host provides an IBC application contract during the
+/// `ibc_packet_receive` function call.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacketReceiveMsg {
+    pub packet: IbcPacket,
+    /// The account that relayed the packet.
+    pub relayer: Addr,
+}
+
+/// The message that the host provides an IBC application contract during the
+/// `ibc_packet_ack` function call.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacketAckMsg {
+    /// The packet that was originally sent by the contract.
+    pub packet: IbcPacket,
+    /// The acknowledgement written by the counterparty chain.
+    pub acknowledgement: Binary,
+    /// The account that relayed the acknowledgement.
+    pub relayer: Addr,
+}
+
+/// The message that the host provides an IBC application contract during the
+/// `ibc_packet_timeout` function call.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacketTimeoutMsg {
+    /// The packet that was originally sent by the contract.
+    pub packet: IbcPacket,
+    /// The account that relayed the timeout.
+    pub relayer: Addr,
+}
@@ -43,12 +43,25 @@ pub trait Api {
         compressed: bool,
     ) -> StdResult<Vec<u8>>;
 
+    /// Verify a BIP-340 Schnorr signature with the given hashed message and
+    /// x-only Secp256k1 public key.
+    ///
+    /// Note: this function takes the hash of the message, not the prehash.
+    fn secp256k1_schnorr_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()>;
+
     /// Verify an ED25519 signature with the given hashed message and public
     /// key.
     ///
     /// NOTE: This function takes the hash of the message, not the prehash.
     fn ed25519_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()>;
 
+    /// Verify a pure Ed25519 signature, as produced by most Ed25519 wallets,
+    /// with the given message and public key.
+    ///
+    /// NOTE: This function takes the prehash message, not the hash, as hashing
+    /// the message is part of the signature scheme.
+    fn ed25519_pure_verify(&self, msg: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()>;
+
     /// Verify a batch of ED25519 signatures with the given hashed message and public
     /// key.
     /// NOTE: This function takes the hash of the messages, not the prehash.
@@ -59,6 +72,38 @@ pub trait Api {
         pks: &[&[u8]],
     ) -> StdResult<()>;
 
+    /// Verify a BLS12-381 signature with the given message, domain separation
+    /// tag, and public key. Public keys are compressed G1 points, signatures
+    /// compressed G2 points.
+    ///
+    /// NOTE: This function takes the prehash message, not the hash, as hashing
+    /// to the curve is part of the signature scheme.
+    fn bls12_381_verify(&self, msg: &[u8], dst: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()>;
+
+    /// Verify a BLS12-381 signature of the same message aggregated over multiple
+    /// public keys, such as an Ethereum sync committee signature.
+    ///
+    /// NOTE: This is only secure if each public key has come with a proof of
+    /// possession of the private key. It's up to the caller to ensure this.
+    fn bls12_381_aggregate_verify(
+        &self,
+        msg: &[u8],
+        dst: &[u8],
+        sig: &[u8],
+        pks: &[&[u8]],
+    ) -> StdResult<()>;
+
+    /// Check whether the pairing equality `e(p1, q1) * ... * e(pn, qn) = e(r, s)`
+    /// holds, where `ps` and `r` are compressed G1 points, and `qs` and `s` are
+    /// compressed G2 points.
+    fn bls12_381_pairing_equality(
+        &self,
+        ps: &[&[u8]],
+        qs: &[&[u8]],
+        r: &[u8],
+        s: &[u8],
+    ) -> StdResult<()>;
+
     /// Perform the SHA2-256 hash.
     fn sha2_256(&self, data: &[u8]) -> [u8; 32];
 
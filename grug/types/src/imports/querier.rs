@@ -1,7 +1,7 @@
 use {
     crate::{
-        Addr, Binary, Code, Coins, Config, ContractInfo, Denom, Hash256, JsonDeExt, Query,
-        QueryRequest, QueryResponse, StdResult,
+        Addr, Binary, Code, Coins, Config, ContractInfo, Denom, Hash256, IbcClientQuery,
+        IbcClientQueryResponse, JsonDeExt, Query, QueryRequest, QueryResponse, StdResult,
     },
     grug_math::Uint128,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -53,6 +53,10 @@ impl<'a> QuerierWrapper<'a> {
         self.query_config().map(|res| res.taxman)
     }
 
+    pub fn query_ibc_handler(&self) -> StdResult<Option<Addr>> {
+        self.query_config().map(|res| res.ibc_handler)
+    }
+
     pub fn query_app_config<T>(&self) -> StdResult<T>
     where
         T: DeserializeOwned,
@@ -149,6 +153,16 @@ impl<'a> QuerierWrapper<'a> {
             .and_then(|res| res.as_wasm_smart().deserialize_json())
     }
 
+    pub fn query_ibc_client(
+        &self,
+        client: Addr,
+        msg: IbcClientQuery,
+    ) -> StdResult<IbcClientQueryResponse> {
+        self.inner
+            .query_chain(Query::ibc_client(client, msg))
+            .map(|res| res.as_ibc_client())
+    }
+
     pub fn query_multi<const N: usize>(
         &self,
         requests: [Query; N],
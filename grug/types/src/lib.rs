@@ -19,8 +19,10 @@ mod encoders;
 mod error;
 mod event;
 mod ffi;
+mod gas;
 mod hash;
 mod hashers;
+mod ibc;
 mod imports;
 mod json;
 mod length_bounded;
@@ -42,11 +44,19 @@ mod utils;
 pub use {
     address::*, app::*, bank::*, binary::*, bound::*, builder::*, bytes::*, changeset::*, code::*,
     coin::*, coin_pair::*, coins::*, context::*, db::*, denom::*, empty::*, encoded_bytes::*,
-    encoders::*, error::*, event::*, ffi::*, hash::*, hashers::*, imports::*, json::*,
-    length_bounded::*, lengthy::*, non_zero::*, outcome::*, query::*, response::*, result::*,
-    serializers::*, signer::*, status::*, time::*, tx::*, unique_vec::*, utils::*,
+    encoders::*, error::*, event::*, ffi::*, gas::*, hash::*, hashers::*, ibc::*, imports::*,
+    json::*, length_bounded::*, lengthy::*, non_zero::*, outcome::*, query::*, response::*,
+    result::*, serializers::*, signer::*, status::*, time::*, tx::*, unique_vec::*, utils::*,
 };
 
+// ---------------------------------- native -----------------------------------
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native_api;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native_api::*;
+
 // ---------------------------------- testing ----------------------------------
 
 #[cfg(not(target_arch = "wasm32"))]
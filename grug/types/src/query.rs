@@ -1,7 +1,7 @@
 use {
     crate::{
         extend_one_byte, Addr, Binary, Bound, Code, Coin, Coins, Config, ContractInfo, Denom,
-        Hash256, Json, JsonSerExt, StdResult,
+        Hash256, IbcClientQuery, IbcClientQueryResponse, Json, JsonSerExt, StdResult,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     grug_math::Inner,
@@ -57,6 +57,9 @@ pub enum Query {
     WasmScan(QueryWasmScanRequest),
     /// Call the contract's query entry point with the given message.
     WasmSmart(QueryWasmSmartRequest),
+    /// Call the light client contract's IBC client query entry point with the
+    /// given message.
+    IbcClient(QueryIbcClientRequest),
     /// Perform multiple queries at once.
     Multi(Vec<Query>),
 }
@@ -154,6 +157,10 @@ impl Query {
         .into())
     }
 
+    pub fn ibc_client(client: Addr, msg: IbcClientQuery) -> Self {
+        QueryIbcClientRequest { client, msg }.into()
+    }
+
     pub fn multi<Q, I>(queries: I) -> Self
     where
         Q: Into<Query>,
@@ -245,6 +252,34 @@ pub struct QueryWasmSmartRequest {
     pub msg: Json,
 }
 
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryIbcClientRequest {
+    pub client: Addr,
+    pub msg: IbcClientQuery,
+}
+
+/// Enumerate raw key-value pairs in the Grug app's storage, in ascending order.
+///
+/// Unlike the other requests, this isn't a variant of [`Query`], but is made
+/// through the ABCI `Query` method at the `/store_scan` path.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryStoreScanRequest {
+    pub min: Option<Binary>, // inclusive
+    pub max: Option<Binary>, // exclusive
+    pub limit: Option<u32>,
+}
+
+/// Read multiple raw keys in the Grug app's storage at once, optionally with a
+/// Merkle proof of all of them.
+///
+/// Unlike the other requests, this isn't a variant of [`Query`], but is made
+/// through the ABCI `Query` method at the `/store_many` path.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct QueryStoreManyRequest {
+    pub keys: Vec<Binary>,
+}
+
 macro_rules! impl_into_query {
     ($variant:ident => $req:ty => $res:ty) => {
         impl From<$req> for Query {
@@ -275,6 +310,7 @@ impl_into_query! {
     WasmRaw    => QueryWasmRawRequest    => Option<Binary>,
     WasmScan   => QueryWasmScanRequest   => BTreeMap<Binary, Binary>,
     WasmSmart  => QueryWasmSmartRequest  => Json,
+    IbcClient  => QueryIbcClientRequest  => IbcClientQueryResponse,
     Multi      => Vec<Query>             => Vec<QueryResponse>,
 }
 
@@ -296,6 +332,7 @@ pub enum QueryResponse {
     WasmRaw(Option<Binary>),
     WasmScan(BTreeMap<Binary, Binary>),
     WasmSmart(Json),
+    IbcClient(IbcClientQueryResponse),
     Multi(Vec<QueryResponse>),
 }
 
@@ -332,6 +369,7 @@ impl QueryResponse {
         WasmRaw    => Option<Binary>,
         WasmScan   => BTreeMap<Binary, Binary>,
         WasmSmart  => Json,
+        IbcClient  => IbcClientQueryResponse,
         Multi      => Vec<QueryResponse>,
     }
 }
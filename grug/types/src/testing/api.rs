@@ -29,11 +29,21 @@ impl Api for MockApi {
             .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
     }
 
+    fn secp256k1_schnorr_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        grug_crypto::secp256k1_schnorr_verify(msg_hash, sig, pk)
+            .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
+    }
+
     fn ed25519_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
         grug_crypto::ed25519_verify(msg_hash, sig, pk)
             .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
     }
 
+    fn ed25519_pure_verify(&self, msg: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        grug_crypto::ed25519_pure_verify(msg, sig, pk)
+            .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
+    }
+
     fn ed25519_batch_verify(
         &self,
         prehash_msgs: &[&[u8]],
@@ -44,6 +54,33 @@ impl Api for MockApi {
             .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
     }
 
+    fn bls12_381_verify(&self, msg: &[u8], dst: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        grug_crypto::bls12_381_verify(msg, dst, sig, pk)
+            .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
+    }
+
+    fn bls12_381_aggregate_verify(
+        &self,
+        msg: &[u8],
+        dst: &[u8],
+        sig: &[u8],
+        pks: &[&[u8]],
+    ) -> StdResult<()> {
+        grug_crypto::bls12_381_aggregate_verify(msg, dst, sig, pks)
+            .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
+    }
+
+    fn bls12_381_pairing_equality(
+        &self,
+        ps: &[&[u8]],
+        qs: &[&[u8]],
+        r: &[u8],
+        s: &[u8],
+    ) -> StdResult<()> {
+        grug_crypto::bls12_381_pairing_equality(ps, qs, r, s)
+            .map_err(|err| VerificationError::from_error_code(err.into_error_code()).into())
+    }
+
     fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
         grug_crypto::sha2_256(data)
     }
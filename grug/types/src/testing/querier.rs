@@ -1,8 +1,8 @@
 use {
     crate::{
         Addr, Binary, Code, CodeStatus, Coin, Config, ContractInfo, Denom, GenericResult, Hash256,
-        HashExt, Json, JsonSerExt, MockStorage, Order, Querier, Query, QueryResponse, StdError,
-        StdResult, Storage,
+        HashExt, IbcClientQuery, IbcClientQueryResponse, Json, JsonSerExt, MockStorage, Order,
+        Querier, Query, QueryResponse, StdError, StdResult, Storage,
     },
     grug_math::{NumberConst, Uint128},
     serde::Serialize,
@@ -12,6 +12,10 @@ use {
 /// A function that handles Wasm smart queries.
 type SmartQueryHandler = Box<dyn Fn(Addr, Json) -> GenericResult<Json>>;
 
+/// A function that handles IBC light client queries.
+type IbcClientQueryHandler =
+    Box<dyn Fn(Addr, IbcClientQuery) -> GenericResult<IbcClientQueryResponse>>;
+
 // ------------------------------- mock querier --------------------------------
 
 /// A mock implementation of the [`Querier`](crate::Querier) trait for testing
@@ -26,6 +30,7 @@ pub struct MockQuerier {
     contracts: BTreeMap<Addr, ContractInfo>,
     raw_query_handler: MockRawQueryHandler,
     smart_query_handler: Option<SmartQueryHandler>,
+    ibc_client_query_handler: Option<IbcClientQueryHandler>,
 }
 
 impl MockQuerier {
@@ -107,6 +112,14 @@ impl MockQuerier {
     {
         self.smart_query_handler = Some(Box::new(handler));
     }
+
+    pub fn with_ibc_client_query_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(Addr, IbcClientQuery) -> GenericResult<IbcClientQueryResponse> + 'static,
+    {
+        self.ibc_client_query_handler = Some(Box::new(handler));
+        self
+    }
 }
 
 impl Querier for MockQuerier {
@@ -251,6 +264,14 @@ impl Querier for MockQuerier {
                 let response = handler(req.contract, req.msg).map_err(StdError::host)?;
                 Ok(QueryResponse::WasmSmart(response))
             },
+            Query::IbcClient(req) => {
+                let handler = self
+                    .ibc_client_query_handler
+                    .as_ref()
+                    .expect("[MockQuerier]: IBC client query handler not set");
+                let response = handler(req.client, req.msg).map_err(StdError::host)?;
+                Ok(QueryResponse::IbcClient(response))
+            },
             Query::Multi(reqs) => {
                 let responses = reqs
                     .into_iter()
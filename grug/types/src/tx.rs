@@ -1,11 +1,12 @@
 use {
     crate::{
-        Addr, Binary, Coins, Config, Hash256, Json, JsonSerExt, LengthBounded, MaxLength, NonEmpty,
-        StdError, StdResult,
+        Addr, Binary, Coins, Config, Hash256, IbcPacket, Json, JsonSerExt, LengthBounded,
+        MaxLength, NonEmpty, StdError, StdResult, Timestamp, UpgradePlan,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeMap,
 };
 
 /// An arbitrary binary data used for deriving address when instantiating a
@@ -40,12 +41,66 @@ pub struct UnsignedTx {
     pub data: Json,
 }
 
+/// A request to simulate an unsigned transaction, optionally with the chain
+/// state overridden.
+///
+/// Serialized the same as the unsigned transaction, with an additional
+/// `overrides` field if there are any.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimulateRequest {
+    #[serde(flatten)]
+    pub tx: UnsignedTx,
+    #[serde(default, skip_serializing_if = "StateOverrides::is_empty")]
+    pub overrides: StateOverrides,
+}
+
+/// Changes to the chain state to apply for a single transaction simulation,
+/// for finding out what would happen if the state were different, e.g. if a
+/// contract were running a different code.
+///
+/// The changes are made in a throw-away buffer, and never persisted.
+#[skip_serializing_none]
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq,
+)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateOverrides {
+    /// Simulate the transaction at this block height, instead of the latest
+    /// finalized block's.
+    pub block_height: Option<u64>,
+    /// Simulate the transaction at this block time, instead of the latest
+    /// finalized block's.
+    pub block_timestamp: Option<Timestamp>,
+    /// Values to write to contracts' storages, indexed first by contract
+    /// addresses, then by raw storage keys. `None` removes the key.
+    pub contract_storage: BTreeMap<Addr, BTreeMap<Binary, Option<Binary>>>,
+    /// Token balances to give accounts, indexed by addresses. An account's
+    /// balances of denoms not listed are set to zero. The total supplies are
+    /// adjusted accordingly.
+    ///
+    /// These are applied after `contract_storage`, so they take precedence.
+    pub balances: BTreeMap<Addr, Coins>,
+    /// Wasm byte codes to run contracts with, indexed by contract addresses.
+    pub contract_code: BTreeMap<Addr, Binary>,
+}
+
+impl StateOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.block_height.is_none()
+            && self.block_timestamp.is_none()
+            && self.contract_storage.is_empty()
+            && self.balances.is_empty()
+            && self.contract_code.is_empty()
+    }
+}
+
 /// A message.
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum Message {
-    /// Update the chain- and app-level configurations.
+    /// Update the chain- and app-level configurations, and/or schedule a chain
+    /// upgrade.
     Configure(MsgConfigure),
     /// Send coins to the given recipient address.
     Transfer(MsgTransfer),
@@ -57,6 +112,12 @@ pub enum Message {
     Execute(MsgExecute),
     /// Update the code hash associated with a contract.
     Migrate(MsgMigrate),
+    /// Deliver an IBC packet to the receiving application.
+    IbcPacketReceive(MsgIbcPacketReceive),
+    /// Deliver the acknowledgement of an IBC packet to the sending application.
+    IbcPacketAck(MsgIbcPacketAck),
+    /// Inform the sending application that an IBC packet has timed out.
+    IbcPacketTimeout(MsgIbcPacketTimeout),
 }
 
 impl Message {
@@ -67,10 +128,26 @@ impl Message {
         Ok(MsgConfigure {
             new_cfg,
             new_app_cfg: new_app_cfg.map(|t| t.to_json_value()).transpose()?,
+            upgrade: None,
         }
         .into())
     }
 
+    pub fn upgrade<N>(name: N, height: u64) -> Self
+    where
+        N: Into<String>,
+    {
+        MsgConfigure {
+            new_cfg: None,
+            new_app_cfg: None,
+            upgrade: Some(UpgradePlan {
+                name: name.into(),
+                height,
+            }),
+        }
+        .into()
+    }
+
     pub fn transfer<C>(to: Addr, coins: C) -> StdResult<Self>
     where
         C: TryInto<Coins>,
@@ -148,6 +225,8 @@ impl Message {
 pub struct MsgConfigure {
     pub new_cfg: Option<Config>,
     pub new_app_cfg: Option<Json>,
+    /// Schedule a chain upgrade, replacing the existing plan if there is one.
+    pub upgrade: Option<UpgradePlan>,
 }
 
 #[skip_serializing_none]
@@ -190,6 +269,28 @@ pub struct MsgMigrate {
     pub msg: Json,
 }
 
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgIbcPacketReceive {
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub relayer: Addr,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgIbcPacketAck {
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub acknowledgement: Binary,
+    pub relayer: Addr,
+}
+
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MsgIbcPacketTimeout {
+    pub contract: Addr,
+    pub packet: IbcPacket,
+    pub relayer: Addr,
+}
+
 macro_rules! impl_into_message {
     ($variant:ident, $msg:ty) => {
         impl From<$msg> for Message {
@@ -207,10 +308,13 @@ macro_rules! impl_into_message {
 }
 
 impl_into_message! {
-    Configure   => MsgConfigure,
-    Transfer    => MsgTransfer,
-    Upload      => MsgUpload,
-    Instantiate => MsgInstantiate,
-    Execute     => MsgExecute,
-    Migrate     => MsgMigrate,
+    Configure        => MsgConfigure,
+    Transfer         => MsgTransfer,
+    Upload           => MsgUpload,
+    Instantiate      => MsgInstantiate,
+    Execute          => MsgExecute,
+    Migrate          => MsgMigrate,
+    IbcPacketReceive => MsgIbcPacketReceive,
+    IbcPacketAck     => MsgIbcPacketAck,
+    IbcPacketTimeout => MsgIbcPacketTimeout,
 }
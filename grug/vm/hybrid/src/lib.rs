@@ -1,9 +1,9 @@
 use {
     grug_app::{AppError, GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
-    grug_types::{Context, Hash256},
+    grug_types::{Context, GasCosts, Hash256},
     grug_vm_rust::{RustInstance, RustVm},
     grug_vm_wasm::{WasmInstance, WasmVm},
-    std::collections::HashSet,
+    std::collections::{BTreeSet, HashSet},
     thiserror::Error,
 };
 
@@ -82,6 +82,26 @@ impl Vm for HybridVm {
             Ok(HybridInstance::Wasm(instance))
         }
     }
+
+    fn warm_up(
+        &mut self,
+        code: &[u8],
+        code_hash: Hash256,
+        gas_costs: &GasCosts,
+    ) -> Result<(), Self::Error> {
+        if self.code_hashes_for_rust.contains(&code_hash) {
+            self.rust.warm_up(code, code_hash, gas_costs)?;
+        } else {
+            self.wasm.warm_up(code, code_hash, gas_costs)?;
+        }
+
+        Ok(())
+    }
+
+    fn without_caching(mut self, code_hashes: BTreeSet<Hash256>) -> Self {
+        self.wasm = self.wasm.without_caching(code_hashes);
+        self
+    }
 }
 
 pub enum HybridInstance {
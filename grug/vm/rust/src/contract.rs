@@ -1,15 +1,16 @@
 use {
     crate::{
         AuthenticateFn, BackrunFn, BankExecuteFn, BankQueryFn, Contract, CronExecuteFn, ExecuteFn,
-        FinalizeFeeFn, InstantiateFn, MigrateFn, QueryFn, ReceiveFn, ReplyFn, VmError, VmResult,
-        WithholdFeeFn,
+        FinalizeFeeFn, IbcClientQueryFn, IbcPacketAckFn, IbcPacketReceiveFn, IbcPacketTimeoutFn,
+        InstantiateFn, MigrateFn, QueryFn, ReceiveFn, ReplyFn, VmError, VmResult, WithholdFeeFn,
     },
     elsa::sync::FrozenVec,
     grug_types::{
         make_auth_ctx, make_immutable_ctx, make_mutable_ctx, make_sudo_ctx, Api, AuthCtx,
         AuthResponse, BankMsg, BankQuery, BankQueryResponse, Binary, BorshDeExt, Context, Empty,
-        GenericResult, GenericResultExt, ImmutableCtx, Json, JsonDeExt, MutableCtx, Querier,
-        QuerierWrapper, Response, StdError, Storage, SubMsgResult, SudoCtx, Tx, TxOutcome,
+        GenericResult, GenericResultExt, IbcClientQuery, IbcClientQueryResponse, IbcPacketAckMsg,
+        IbcPacketReceiveMsg, IbcPacketTimeoutMsg, ImmutableCtx, Json, JsonDeExt, MutableCtx,
+        Querier, QuerierWrapper, Response, StdError, Storage, SubMsgResult, SudoCtx, Tx, TxOutcome,
     },
     serde::de::DeserializeOwned,
     std::sync::OnceLock,
@@ -72,6 +73,10 @@ pub struct ContractBuilder<
     E11 = StdError,
     E12 = StdError,
     E13 = StdError,
+    E14 = StdError,
+    E15 = StdError,
+    E16 = StdError,
+    E17 = StdError,
 > {
     instantiate_fn: InstantiateFn<M1, E1>,
     execute_fn: Option<ExecuteFn<M2, E2>>,
@@ -86,6 +91,10 @@ pub struct ContractBuilder<
     withhold_fee_fn: Option<WithholdFeeFn<E11>>,
     finalize_fee_fn: Option<FinalizeFeeFn<E12>>,
     cron_execute_fn: Option<CronExecuteFn<E13>>,
+    ibc_client_query_fn: Option<IbcClientQueryFn<E14>>,
+    ibc_packet_receive_fn: Option<IbcPacketReceiveFn<E15>>,
+    ibc_packet_ack_fn: Option<IbcPacketAckFn<E16>>,
+    ibc_packet_timeout_fn: Option<IbcPacketTimeoutFn<E17>>,
 }
 
 impl<M1, E1> ContractBuilder<M1, E1>
@@ -108,12 +117,62 @@ where
             withhold_fee_fn: None,
             finalize_fee_fn: None,
             cron_execute_fn: None,
+            ibc_client_query_fn: None,
+            ibc_packet_receive_fn: None,
+            ibc_packet_ack_fn: None,
+            ibc_packet_timeout_fn: None,
         }
     }
 }
 
-impl<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
-    ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+impl<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
+    ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
 where
     M1: DeserializeOwned + 'static,
     M2: DeserializeOwned + 'static,
@@ -133,11 +192,38 @@ where
     E11: ToString + 'static,
     E12: ToString + 'static,
     E13: ToString + 'static,
+    E14: ToString + 'static,
+    E15: ToString + 'static,
+    E16: ToString + 'static,
+    E17: ToString + 'static,
 {
     pub fn with_execute<M2A, E2A>(
         self,
         execute_fn: ExecuteFn<M2A, E2A>,
-    ) -> ContractBuilder<M1, E1, M2A, M3, M5, M6, E2A, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2A,
+        M3,
+        M5,
+        M6,
+        E2A,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
     where
         M2A: DeserializeOwned + 'static,
         E2A: ToString + 'static,
@@ -156,13 +242,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_migrate<M3A, E3A>(
         self,
         migrate_fn: MigrateFn<M3A, E3A>,
-    ) -> ContractBuilder<M1, E1, M2, M3A, M5, M6, E2, E3A, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3A,
+        M5,
+        M6,
+        E2,
+        E3A,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
     where
         M3A: DeserializeOwned + 'static,
         E3A: ToString + 'static,
@@ -181,13 +294,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_receive<E4A>(
         self,
         receive_fn: ReceiveFn<E4A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4A, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4A,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
     where
         E4A: ToString + 'static,
     {
@@ -205,13 +345,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_reply<M5A, E5A>(
         self,
         reply_fn: ReplyFn<M5A, E5A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5A, M6, E2, E3, E4, E5A, E6, E7, E8, E9, E10, E11, E12, E13>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5A,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5A,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
     where
         M5A: DeserializeOwned + 'static,
         E5A: ToString + 'static,
@@ -230,13 +397,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_query<M6A, E6A>(
         self,
         query_fn: QueryFn<M6A, E6A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6A, E2, E3, E4, E5, E6A, E7, E8, E9, E10, E11, E12, E13>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6A,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6A,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
     where
         M6A: DeserializeOwned + 'static,
         E6A: ToString + 'static,
@@ -255,14 +449,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_authenticate<E7A>(
         self,
         authenticate_fn: AuthenticateFn<E7A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7A, E8, E9, E10, E11, E12, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7A,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -277,14 +497,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_backrun<E8A>(
         self,
         backrun_fn: BackrunFn<E8A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8A, E9, E10, E11, E12, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8A,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -299,14 +545,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_bank_execute<E9A>(
         self,
         bank_execute_fn: BankExecuteFn<E9A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9A, E10, E11, E12, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9A,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -321,14 +593,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_bank_query<E10A>(
         self,
         bank_query_fn: BankQueryFn<E10A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10A, E11, E12, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10A,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -343,14 +641,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_withhold_fee<E11A>(
         self,
         withhold_fee_fn: WithholdFeeFn<E11A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11A, E12, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11A,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -365,14 +689,40 @@ where
             withhold_fee_fn: Some(withhold_fee_fn),
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_finalize_fee<E12A>(
         self,
         finalize_fee_fn: FinalizeFeeFn<E12A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12A, E13>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12A,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -387,14 +737,40 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: Some(finalize_fee_fn),
             cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
         }
     }
 
     pub fn with_cron_execute<E13A>(
         self,
         cron_execute_fn: CronExecuteFn<E13A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13A>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13A,
+        E14,
+        E15,
+        E16,
+        E17,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -409,6 +785,202 @@ where
             withhold_fee_fn: self.withhold_fee_fn,
             finalize_fee_fn: self.finalize_fee_fn,
             cron_execute_fn: Some(cron_execute_fn),
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
+        }
+    }
+
+    pub fn with_ibc_client_query<E14A>(
+        self,
+        ibc_client_query_fn: IbcClientQueryFn<E14A>,
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14A,
+        E15,
+        E16,
+        E17,
+    > {
+        ContractBuilder {
+            instantiate_fn: self.instantiate_fn,
+            execute_fn: self.execute_fn,
+            migrate_fn: self.migrate_fn,
+            receive_fn: self.receive_fn,
+            reply_fn: self.reply_fn,
+            query_fn: self.query_fn,
+            authenticate_fn: self.authenticate_fn,
+            backrun_fn: self.backrun_fn,
+            bank_execute_fn: self.bank_execute_fn,
+            bank_query_fn: self.bank_query_fn,
+            withhold_fee_fn: self.withhold_fee_fn,
+            finalize_fee_fn: self.finalize_fee_fn,
+            cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: Some(ibc_client_query_fn),
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
+        }
+    }
+
+    pub fn with_ibc_packet_receive<E15A>(
+        self,
+        ibc_packet_receive_fn: IbcPacketReceiveFn<E15A>,
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15A,
+        E16,
+        E17,
+    > {
+        ContractBuilder {
+            instantiate_fn: self.instantiate_fn,
+            execute_fn: self.execute_fn,
+            migrate_fn: self.migrate_fn,
+            receive_fn: self.receive_fn,
+            reply_fn: self.reply_fn,
+            query_fn: self.query_fn,
+            authenticate_fn: self.authenticate_fn,
+            backrun_fn: self.backrun_fn,
+            bank_execute_fn: self.bank_execute_fn,
+            bank_query_fn: self.bank_query_fn,
+            withhold_fee_fn: self.withhold_fee_fn,
+            finalize_fee_fn: self.finalize_fee_fn,
+            cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: Some(ibc_packet_receive_fn),
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
+        }
+    }
+
+    pub fn with_ibc_packet_ack<E16A>(
+        self,
+        ibc_packet_ack_fn: IbcPacketAckFn<E16A>,
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16A,
+        E17,
+    > {
+        ContractBuilder {
+            instantiate_fn: self.instantiate_fn,
+            execute_fn: self.execute_fn,
+            migrate_fn: self.migrate_fn,
+            receive_fn: self.receive_fn,
+            reply_fn: self.reply_fn,
+            query_fn: self.query_fn,
+            authenticate_fn: self.authenticate_fn,
+            backrun_fn: self.backrun_fn,
+            bank_execute_fn: self.bank_execute_fn,
+            bank_query_fn: self.bank_query_fn,
+            withhold_fee_fn: self.withhold_fee_fn,
+            finalize_fee_fn: self.finalize_fee_fn,
+            cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: Some(ibc_packet_ack_fn),
+            ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
+        }
+    }
+
+    pub fn with_ibc_packet_timeout<E17A>(
+        self,
+        ibc_packet_timeout_fn: IbcPacketTimeoutFn<E17A>,
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17A,
+    > {
+        ContractBuilder {
+            instantiate_fn: self.instantiate_fn,
+            execute_fn: self.execute_fn,
+            migrate_fn: self.migrate_fn,
+            receive_fn: self.receive_fn,
+            reply_fn: self.reply_fn,
+            query_fn: self.query_fn,
+            authenticate_fn: self.authenticate_fn,
+            backrun_fn: self.backrun_fn,
+            bank_execute_fn: self.bank_execute_fn,
+            bank_query_fn: self.bank_query_fn,
+            withhold_fee_fn: self.withhold_fee_fn,
+            finalize_fee_fn: self.finalize_fee_fn,
+            cron_execute_fn: self.cron_execute_fn,
+            ibc_client_query_fn: self.ibc_client_query_fn,
+            ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+            ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+            ibc_packet_timeout_fn: Some(ibc_packet_timeout_fn),
         }
     }
 
@@ -429,6 +1001,14 @@ where
                 withhold_fee_fn: self.withhold_fee_fn,
                 finalize_fee_fn: self.finalize_fee_fn,
                 cron_execute_fn: self.cron_execute_fn,
+                ibc_client_query_fn: self.ibc_client_query_fn,
+                ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+                ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+                ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
+                ibc_client_query_fn: self.ibc_client_query_fn,
+                ibc_packet_receive_fn: self.ibc_packet_receive_fn,
+                ibc_packet_ack_fn: self.ibc_packet_ack_fn,
+                ibc_packet_timeout_fn: self.ibc_packet_timeout_fn,
             }));
 
         ContractWrapper { index }
@@ -437,7 +1017,30 @@ where
 
 // ----------------------------------- impl ------------------------------------
 
-struct ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13> {
+struct ContractImpl<
+    M1,
+    M2,
+    M3,
+    M5,
+    M6,
+    E1,
+    E2,
+    E3,
+    E4,
+    E5,
+    E6,
+    E7,
+    E8,
+    E9,
+    E10,
+    E11,
+    E12,
+    E13,
+    E14,
+    E15,
+    E16,
+    E17,
+> {
     instantiate_fn: InstantiateFn<M1, E1>,
     execute_fn: Option<ExecuteFn<M2, E2>>,
     migrate_fn: Option<MigrateFn<M3, E3>>,
@@ -451,10 +1054,60 @@ struct ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10,
     withhold_fee_fn: Option<WithholdFeeFn<E11>>,
     finalize_fee_fn: Option<FinalizeFeeFn<E12>>,
     cron_execute_fn: Option<CronExecuteFn<E13>>,
+    ibc_client_query_fn: Option<IbcClientQueryFn<E14>>,
+    ibc_packet_receive_fn: Option<IbcPacketReceiveFn<E15>>,
+    ibc_packet_ack_fn: Option<IbcPacketAckFn<E16>>,
+    ibc_packet_timeout_fn: Option<IbcPacketTimeoutFn<E17>>,
 }
 
-impl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13> Contract
-    for ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+impl<
+        M1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E1,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    > Contract
+    for ContractImpl<
+        M1,
+        M2,
+        M3,
+        M5,
+        M6,
+        E1,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+        E14,
+        E15,
+        E16,
+        E17,
+    >
 where
     M1: DeserializeOwned,
     M2: DeserializeOwned,
@@ -474,6 +1127,10 @@ where
     E11: ToString,
     E12: ToString,
     E13: ToString,
+    E14: ToString,
+    E15: ToString,
+    E16: ToString,
+    E17: ToString,
 {
     fn instantiate(
         &self,
@@ -709,4 +1366,76 @@ where
 
         Ok(res.into_generic_result())
     }
+
+    fn ibc_client_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcClientQuery,
+    ) -> VmResult<GenericResult<IbcClientQueryResponse>> {
+        let Some(ibc_client_query_fn) = &self.ibc_client_query_fn else {
+            return Err(VmError::function_not_found("ibc_client_query"));
+        };
+
+        let immutable_ctx = make_immutable_ctx!(ctx, storage, api, querier);
+        let res = ibc_client_query_fn(immutable_ctx, msg);
+
+        Ok(res.into_generic_result())
+    }
+
+    fn ibc_packet_receive(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketReceiveMsg,
+    ) -> VmResult<GenericResult<Response>> {
+        let Some(ibc_packet_receive_fn) = &self.ibc_packet_receive_fn else {
+            return Err(VmError::function_not_found("ibc_packet_receive"));
+        };
+
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
+        let res = ibc_packet_receive_fn(mutable_ctx, msg);
+
+        Ok(res.into_generic_result())
+    }
+
+    fn ibc_packet_ack(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketAckMsg,
+    ) -> VmResult<GenericResult<Response>> {
+        let Some(ibc_packet_ack_fn) = &self.ibc_packet_ack_fn else {
+            return Err(VmError::function_not_found("ibc_packet_ack"));
+        };
+
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
+        let res = ibc_packet_ack_fn(mutable_ctx, msg);
+
+        Ok(res.into_generic_result())
+    }
+
+    fn ibc_packet_timeout(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketTimeoutMsg,
+    ) -> VmResult<GenericResult<Response>> {
+        let Some(ibc_packet_timeout_fn) = &self.ibc_packet_timeout_fn else {
+            return Err(VmError::function_not_found("ibc_packet_timeout"));
+        };
+
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
+        let res = ibc_packet_timeout_fn(mutable_ctx, msg);
+
+        Ok(res.into_generic_result())
+    }
 }
@@ -6,8 +6,9 @@ use {
     crate::VmResult,
     grug_types::{
         Api, AuthCtx, AuthResponse, BankMsg, BankQuery, BankQueryResponse, Context, GenericResult,
-        ImmutableCtx, Json, MutableCtx, Querier, Response, Storage, SubMsgResult, SudoCtx, Tx,
-        TxOutcome,
+        IbcClientQuery, IbcClientQueryResponse, IbcPacketAckMsg, IbcPacketReceiveMsg,
+        IbcPacketTimeoutMsg, ImmutableCtx, Json, MutableCtx, Querier, Response, Storage,
+        SubMsgResult, SudoCtx, Tx, TxOutcome,
     },
 };
 
@@ -128,6 +129,42 @@ pub trait Contract {
         api: &dyn Api,
         querier: &dyn Querier,
     ) -> VmResult<GenericResult<Response>>;
+
+    fn ibc_client_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcClientQuery,
+    ) -> VmResult<GenericResult<IbcClientQueryResponse>>;
+
+    fn ibc_packet_receive(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketReceiveMsg,
+    ) -> VmResult<GenericResult<Response>>;
+
+    fn ibc_packet_ack(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketAckMsg,
+    ) -> VmResult<GenericResult<Response>>;
+
+    fn ibc_packet_timeout(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcPacketTimeoutMsg,
+    ) -> VmResult<GenericResult<Response>>;
 }
 
 // Trait alias is unstable:
@@ -159,3 +196,11 @@ pub type WithholdFeeFn<E> = Box<dyn Fn(AuthCtx, Tx) -> Result<Response, E> + Sen
 pub type FinalizeFeeFn<E> = Box<dyn Fn(AuthCtx, Tx, TxOutcome) -> Result<Response, E> + Send + Sync>;
 
 pub type CronExecuteFn<E> = Box<dyn Fn(SudoCtx) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcClientQueryFn<E> = Box<dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<IbcClientQueryResponse, E> + Send + Sync>;
+
+pub type IbcPacketReceiveFn<E> = Box<dyn Fn(MutableCtx, IbcPacketReceiveMsg) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcPacketAckFn<E> = Box<dyn Fn(MutableCtx, IbcPacketAckMsg) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcPacketTimeoutFn<E> = Box<dyn Fn(MutableCtx, IbcPacketTimeoutMsg) -> Result<Response, E> + Send + Sync>;
@@ -8,7 +8,7 @@ use {
 ///
 /// This doesn't include `allocate` and `deallocate`, which are only relevant
 /// for the `WasmVm`.
-pub const KNOWN_FUNCTIONS: [&str; 13] = [
+pub const KNOWN_FUNCTIONS: [&str; 17] = [
     "instantate",
     "execute",
     "migrate",
@@ -22,6 +22,10 @@ pub const KNOWN_FUNCTIONS: [&str; 13] = [
     "withhold_fee",
     "finalize_fee",
     "cron_execute",
+    "ibc_client_query",
+    "ibc_packet_receive",
+    "ibc_packet_ack",
+    "ibc_packet_timeout",
 ];
 
 #[derive(Default, Clone)]
@@ -203,6 +207,50 @@ impl Instance for RustInstance {
                 )?;
                 res.to_borsh_vec()
             },
+            "ibc_client_query" => {
+                let msg = param.deserialize_borsh()?;
+                let res = contract.ibc_client_query(
+                    ctx.clone(),
+                    &self.storage,
+                    &MockApi,
+                    &self.querier,
+                    msg,
+                )?;
+                res.to_borsh_vec()
+            },
+            "ibc_packet_receive" => {
+                let msg = param.deserialize_borsh()?;
+                let res = contract.ibc_packet_receive(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    &self.querier,
+                    msg,
+                )?;
+                res.to_borsh_vec()
+            },
+            "ibc_packet_ack" => {
+                let msg = param.deserialize_borsh()?;
+                let res = contract.ibc_packet_ack(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    &self.querier,
+                    msg,
+                )?;
+                res.to_borsh_vec()
+            },
+            "ibc_packet_timeout" => {
+                let msg = param.deserialize_borsh()?;
+                let res = contract.ibc_packet_timeout(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    &self.querier,
+                    msg,
+                )?;
+                res.to_borsh_vec()
+            },
             _ if KNOWN_FUNCTIONS.contains(&name) => {
                 return Err(VmError::incorrect_number_of_inputs(name, 1));
             },
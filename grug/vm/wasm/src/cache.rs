@@ -1,5 +1,5 @@
 use {
-    crate::VmResult,
+    crate::{DiskCache, VmResult},
     clru::CLruCache,
     grug_app::Shared,
     grug_types::Hash256,
@@ -15,15 +15,26 @@ use {
 type Data = (Module, Engine);
 
 /// Statistics about the usage of the cache instance.
+///
+/// `hits` and `misses` are of the in-memory cache. `disk_hits` and
+/// `disk_misses` are of the on-disk cache, which is only looked up upon a miss
+/// of the in-memory cache.
 #[derive(Default, Debug, Clone, Copy)]
 pub struct Metrics {
     pub hits: usize,
     pub misses: usize,
+    pub disk_hits: usize,
+    pub disk_misses: usize,
 }
 
 impl Metrics {
     pub fn new() -> Self {
-        Self { hits: 0, misses: 0 }
+        Self {
+            hits: 0,
+            misses: 0,
+            disk_hits: 0,
+            disk_misses: 0,
+        }
     }
 
     pub fn increment_hits(&mut self) {
@@ -36,17 +47,34 @@ impl Metrics {
         // Same as above, use saturating add to avoid panicking on overflow.
         self.misses = self.misses.saturating_add(1);
     }
+
+    pub fn increment_disk_hits(&mut self) {
+        self.disk_hits = self.disk_hits.saturating_add(1);
+    }
+
+    pub fn increment_disk_misses(&mut self) {
+        self.disk_misses = self.disk_misses.saturating_add(1);
+    }
 }
 
 /// An in-memory cache for wasm modules, so that they don't need to be re-built
 /// every time the same contract is called.
+///
+/// Modules are keyed by the code hash together with the gas cost per operation
+/// they were metered with, so that a change in gas costs doesn't result in
+/// stale modules being used.
+///
+/// Optionally, the cache can be backed by a [`DiskCache`](crate::DiskCache),
+/// in which case modules not found in memory are looked up on disk before
+/// being built, and modules built are persisted to disk.
 #[derive(Clone)]
 pub struct Cache {
     inner: Shared<CacheInner>,
+    disk: Option<DiskCache>,
 }
 
 struct CacheInner {
-    lru_cache: CLruCache<Hash256, Data>,
+    lru_cache: CLruCache<(Hash256, u64), Data>,
     metrics: Metrics,
 }
 
@@ -58,37 +86,104 @@ impl Cache {
                 lru_cache: CLruCache::new(capacity),
                 metrics: Metrics::new(),
             }),
+            disk: None,
         }
     }
 
-    /// Attempt to get a cached module by hash. If not found, build the module
-    /// using the given method, insert the built module into the cache, and
-    /// return the module.
-    pub fn get_or_build_with<B>(&self, code_hash: Hash256, builder: B) -> VmResult<Data>
+    /// Back the cache by the given on-disk cache.
+    pub fn with_disk_cache(mut self, disk: DiskCache) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    /// Return the statistics about the usage of the cache.
+    pub fn metrics(&self) -> Metrics {
+        self.inner.read_with(|inner| inner.metrics)
+    }
+
+    /// Attempt to get a cached module by hash and gas cost per operation,
+    /// first from memory, then from disk. If not found, build the module using
+    /// the given method, insert the built module into the cache, and return
+    /// the module.
+    ///
+    /// Errors from the on-disk cache are logged but otherwise ignored, as they
+    /// don't prevent the module from being built.
+    pub fn get_or_build_with<B>(
+        &self,
+        code_hash: Hash256,
+        gas_per_operation: u64,
+        builder: B,
+    ) -> VmResult<Data>
     where
         B: FnOnce() -> VmResult<Data>,
     {
         self.inner.write_with(|mut inner| {
-            match inner.lru_cache.get(&code_hash).cloned() {
+            match inner
+                .lru_cache
+                .get(&(code_hash, gas_per_operation))
+                .cloned()
+            {
                 // Cache hit - simply clone the cached data and return.
                 Some(data) => {
                     inner.metrics.increment_hits();
 
                     Ok(data)
                 },
-                // Cache miss - build the module using the given builder method;
-                // insert both the module and engine to the cache.
+                // Cache miss - load the module from disk, or if not found
+                // there, build it using the given builder method; insert both
+                // the module and engine to the cache.
                 None => {
-                    let data = builder()?;
-
-                    inner.lru_cache.put(code_hash, data.clone());
                     inner.metrics.increment_misses();
 
+                    let data = match self.load_from_disk(code_hash, gas_per_operation) {
+                        Some(data) => {
+                            inner.metrics.increment_disk_hits();
+
+                            data
+                        },
+                        None => {
+                            if self.disk.is_some() {
+                                inner.metrics.increment_disk_misses();
+                            }
+
+                            let data = builder()?;
+
+                            self.store_to_disk(code_hash, gas_per_operation, &data.0);
+
+                            data
+                        },
+                    };
+
+                    inner
+                        .lru_cache
+                        .put((code_hash, gas_per_operation), data.clone());
+
                     Ok(data)
                 },
             }
         })
     }
+
+    fn load_from_disk(&self, code_hash: Hash256, gas_per_operation: u64) -> Option<Data> {
+        let disk = self.disk.as_ref()?;
+
+        disk.load(code_hash, gas_per_operation)
+            .unwrap_or_else(|err| {
+                tracing::warn!(err = err.to_string(), "Failed to load module from disk");
+
+                None
+            })
+    }
+
+    fn store_to_disk(&self, code_hash: Hash256, gas_per_operation: u64, module: &Module) {
+        let Some(disk) = &self.disk else {
+            return;
+        };
+
+        if let Err(err) = disk.store(code_hash, gas_per_operation, module) {
+            tracing::warn!(err = err.to_string(), "Failed to store module to disk");
+        }
+    }
 }
 
 // ----------------------------------- tests -----------------------------------
@@ -96,9 +191,9 @@ impl Cache {
 #[cfg(test)]
 mod tests {
     use {
-        crate::{Cache, VmResult},
+        crate::{Cache, DiskCache, VmResult},
         grug_types::HashExt,
-        std::num::NonZeroUsize,
+        std::{fs, num::NonZeroUsize},
         wasmer::{Engine, Module, Singlepass},
     };
 
@@ -117,18 +212,18 @@ mod tests {
         // Build the 1st contract. Should be a cache miss, and the data is
         // inserted into the cache.
         let hash1 = CONTRACT.hash256();
-        cache.get_or_build_with(hash1, builder).unwrap();
+        cache.get_or_build_with(hash1, 1, builder).unwrap();
 
         // Build the 2nd contract. Should also be a cache miss, and the data is
         // inserted. Data of the previous build should have been removed,
         // because the cache only has a capacity of 1.
         let hash2 = b"jake".hash256();
-        cache.get_or_build_with(hash2, builder).unwrap();
+        cache.get_or_build_with(hash2, 1, builder).unwrap();
 
         // Cache should have had 2 misses, with hash2 cached but hash1 not.
         cache.inner.read_with(|inner| {
-            assert!(!inner.lru_cache.contains(&hash1));
-            assert!(inner.lru_cache.contains(&hash2));
+            assert!(!inner.lru_cache.contains(&(hash1, 1)));
+            assert!(inner.lru_cache.contains(&(hash2, 1)));
             assert_eq!(inner.lru_cache.len(), 1);
             assert_eq!(inner.metrics.hits, 0);
             assert_eq!(inner.metrics.misses, 2);
@@ -142,14 +237,95 @@ mod tests {
         // Build the same contract twice. 1st time should be a cache miss, 2nd
         // time should be a cache hit.
         let hash = CONTRACT.hash256();
-        cache.get_or_build_with(hash, builder).unwrap();
-        cache.get_or_build_with(hash, builder).unwrap();
+        cache.get_or_build_with(hash, 1, builder).unwrap();
+        cache.get_or_build_with(hash, 1, builder).unwrap();
 
         cache.inner.read_with(|inner| {
-            assert!(inner.lru_cache.contains(&hash));
+            assert!(inner.lru_cache.contains(&(hash, 1)));
             assert_eq!(inner.lru_cache.len(), 1);
             assert_eq!(inner.metrics.hits, 1);
             assert_eq!(inner.metrics.misses, 1);
         });
     }
+
+    #[test]
+    fn gas_cost_changed() {
+        let cache = Cache::new(NonZeroUsize::new(2).unwrap());
+
+        // Build the same contract with two different gas costs per operation.
+        // Both should be cache misses, as the module built with one cost can't
+        // be used with the other.
+        let hash = CONTRACT.hash256();
+        cache.get_or_build_with(hash, 1, builder).unwrap();
+        cache.get_or_build_with(hash, 2, builder).unwrap();
+
+        cache.inner.read_with(|inner| {
+            assert!(inner.lru_cache.contains(&(hash, 1)));
+            assert!(inner.lru_cache.contains(&(hash, 2)));
+            assert_eq!(inner.lru_cache.len(), 2);
+            assert_eq!(inner.metrics.hits, 0);
+            assert_eq!(inner.metrics.misses, 2);
+        });
+    }
+
+    #[test]
+    fn load_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskCache::open(dir.path()).unwrap();
+        let hash = CONTRACT.hash256();
+
+        // Build the contract. Should be a miss both in memory and on disk, and
+        // the module is persisted to disk.
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap()).with_disk_cache(disk.clone());
+        cache.get_or_build_with(hash, 1, builder).unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.disk_hits, 0);
+        assert_eq!(metrics.disk_misses, 1);
+
+        // Simulate a restart by creating a new cache with the same directory.
+        // The module should be loaded from disk, without being built.
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap()).with_disk_cache(disk);
+        cache
+            .get_or_build_with(hash, 1, || panic!("module should be loaded from disk"))
+            .unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.misses, 1);
+        assert_eq!(metrics.disk_hits, 1);
+        assert_eq!(metrics.disk_misses, 0);
+    }
+
+    #[test]
+    fn corrupted_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskCache::open(dir.path()).unwrap();
+        let hash = CONTRACT.hash256();
+
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap()).with_disk_cache(disk.clone());
+        cache.get_or_build_with(hash, 1, builder).unwrap();
+
+        // Tamper with the module persisted on disk.
+        for entry in fs::read_dir(dir.path()).unwrap() {
+            for file in fs::read_dir(entry.unwrap().path()).unwrap() {
+                let path = file.unwrap().path();
+                let mut bytes = fs::read(&path).unwrap();
+                *bytes.last_mut().unwrap() ^= 1;
+                fs::write(path, bytes).unwrap();
+            }
+        }
+
+        // The tampered module should fail the integrity check, so the module
+        // is built again.
+        let cache = Cache::new(NonZeroUsize::new(1).unwrap()).with_disk_cache(disk.clone());
+        cache.get_or_build_with(hash, 1, builder).unwrap();
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.disk_hits, 0);
+        assert_eq!(metrics.disk_misses, 1);
+
+        // The module built should have replaced the tampered one.
+        assert!(disk.load(hash, 1).unwrap().is_some());
+    }
 }
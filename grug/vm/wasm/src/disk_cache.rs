@@ -0,0 +1,141 @@
+use {
+    crate::{build_engine, engine_fingerprint, VmError, VmResult},
+    grug_types::{Hash256, HashExt},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+    wasmer::{Engine, Module},
+};
+
+/// Version of the layout of the on-disk cache.
+///
+/// Bump this whenever the layout of the directory or the files within it
+/// changes, such that caches written in the old layout are never read.
+const CACHE_FORMAT: u32 = 1;
+
+/// An on-disk cache for compiled wasm modules, so that they don't need to be
+/// re-compiled every time the node restarts.
+///
+/// Modules are stored in a subdirectory named by the version of the cache
+/// format. Within the subdirectory, each module is stored in a file named by
+/// its code hash and a fingerprint of the engine configuration it was compiled
+/// with (the Wasmer version, the target, and the middlewares and limits), such
+/// that modules compiled differently are never loaded.
+///
+/// Each file consists of the SHA-256 hash of the serialized module, followed by
+/// the serialized module itself. The hash is verified when loading the module,
+/// so that a file corrupted e.g. by a crash halfway through writing is never
+/// deserialized.
+///
+/// The hash is not a MAC, so it doesn't protect against deliberate tampering.
+/// Since deserializing a module amounts to loading native code, the cache
+/// directory must only be writable by the node operator.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    /// Open the cache in the given directory, creating the directory if it
+    /// doesn't exist.
+    pub fn open<P>(dir: P) -> VmResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref().join(format!("v{CACHE_FORMAT}"));
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self { dir })
+    }
+
+    /// Load a compiled module, together with the engine to run it with, by
+    /// code hash and gas cost per operation. `None` if not found.
+    ///
+    /// A file that fails the integrity check, or can't be deserialized, is
+    /// deleted, and `None` is returned, such that the module is re-compiled.
+    pub fn load(
+        &self,
+        code_hash: Hash256,
+        gas_per_operation: u64,
+    ) -> VmResult<Option<(Module, Engine)>> {
+        let path = self.module_path(code_hash, gas_per_operation);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path)?;
+
+        // Split the file into the checksum and the serialized module. A file
+        // too short to contain a checksum is considered corrupted.
+        let (checksum, serialized) = bytes.split_at(Hash256::LENGTH.min(bytes.len()));
+
+        if serialized.hash256().as_ref() != checksum {
+            tracing::warn!(
+                path = path.display().to_string(),
+                "Cached module failed integrity check; deleting"
+            );
+
+            fs::remove_file(&path)?;
+
+            return Ok(None);
+        }
+
+        let engine = build_engine(gas_per_operation);
+
+        // Safety: the bytes were produced by `Module::serialize` with the same
+        // engine configuration, which is ensured by the fingerprint in the file
+        // name, and weren't corrupted since, which is ensured by the checksum.
+        // The checksum doesn't detect tampering; we rely on the cache directory
+        // being writable only by the node operator.
+        match unsafe { Module::deserialize(&engine, serialized) } {
+            Ok(module) => Ok(Some((module, engine))),
+            Err(err) => {
+                tracing::warn!(
+                    path = path.display().to_string(),
+                    err = err.to_string(),
+                    "Failed to deserialize cached module; deleting"
+                );
+
+                fs::remove_file(&path)?;
+
+                Ok(None)
+            },
+        }
+    }
+
+    /// Store a compiled module by code hash and gas cost per operation.
+    pub fn store(
+        &self,
+        code_hash: Hash256,
+        gas_per_operation: u64,
+        module: &Module,
+    ) -> VmResult<()> {
+        let serialized = module
+            .serialize()
+            .map_err(|err| VmError::Serialization(err.to_string()))?;
+
+        let mut bytes = Vec::with_capacity(Hash256::LENGTH + serialized.len());
+        bytes.extend_from_slice(&serialized.hash256());
+        bytes.extend_from_slice(&serialized);
+
+        // Write to a temporary file first, then rename it, so that a module is
+        // never found half-written.
+        let path = self.module_path(code_hash, gas_per_operation);
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)?;
+
+        Ok(())
+    }
+
+    fn module_path(&self, code_hash: Hash256, gas_per_operation: u64) -> PathBuf {
+        self.dir.join(format!(
+            "{code_hash}-{}.module",
+            engine_fingerprint(gas_per_operation)
+        ))
+    }
+}
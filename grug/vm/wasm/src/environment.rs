@@ -1,7 +1,7 @@
 use {
     crate::{Iterator, VmError, VmResult},
     grug_app::{GasTracker, QuerierProvider, StorageProvider},
-    grug_types::{Record, StdError},
+    grug_types::{GasCategory, Record, StdError},
     std::{collections::HashMap, ptr::NonNull},
     wasmer::{AsStoreMut, AsStoreRef, Instance, Memory, MemoryView, Value},
     wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints},
@@ -205,7 +205,8 @@ impl Environment {
             // return the result as-is.
             (result, MeteringPoints::Remaining(remaining)) => {
                 let consumed = self.gas_checkpoint - remaining;
-                self.gas_tracker.consume(consumed, name)?;
+                self.gas_tracker
+                    .consume(consumed, GasCategory::Wasm, name)?;
                 self.gas_checkpoint = remaining;
 
                 Ok(result?)
@@ -224,7 +225,8 @@ impl Environment {
                 // all `u64::MAX` gas units have been depleted) this would
                 // overflow. However this should never happen in practice (the
                 // call would run an exceedingly long time to start with).
-                self.gas_tracker.consume(self.gas_checkpoint, name)?;
+                self.gas_tracker
+                    .consume(self.gas_checkpoint, GasCategory::Wasm, name)?;
                 self.gas_checkpoint = 0;
 
                 Err(StdError::OutOfGas {
@@ -246,6 +248,7 @@ impl Environment {
         &mut self,
         store: &mut S,
         external: u64,
+        category: GasCategory,
         comment: &'static str,
     ) -> VmResult<()>
     where
@@ -254,10 +257,16 @@ impl Environment {
         let instance = self.get_wasmer_instance()?;
         match get_remaining_points(store, instance) {
             MeteringPoints::Remaining(remaining) => {
-                // gas_checkpoint can't be less than remaining
-                // compute consumed equals to the gas consumed since the last update + external gas
-                let consumed = self.gas_checkpoint - remaining + external;
-                self.gas_tracker.consume(consumed, comment)?;
+                // gas_checkpoint can't be less than remaining.
+                // Consume the gas consumed by Wasm since the last update, then
+                // the external gas. These are consumed separately, such that
+                // they can be told apart when profiling.
+                self.gas_tracker.consume(
+                    self.gas_checkpoint - remaining,
+                    GasCategory::Wasm,
+                    "wasm",
+                )?;
+                self.gas_tracker.consume(external, category, comment)?;
 
                 // If there is a limit on gas_tracker, update the remaining points in the store
                 if let Some(remaining) = self.gas_tracker.remaining() {
@@ -279,9 +288,11 @@ impl Environment {
 #[cfg(test)]
 mod test {
     use {
-        crate::{Environment, Iterator, VmError, VmResult, WasmVm, GAS_PER_OPERATION},
+        crate::{Environment, Iterator, VmError, VmResult, WasmVm},
         grug_app::{GasTracker, QuerierProviderImpl, Shared, StorageProvider},
-        grug_types::{BlockInfo, Hash256, MockStorage, Order, StdError, Storage, Timestamp},
+        grug_types::{
+            BlockInfo, GasCosts, Hash256, MockStorage, Order, StdError, Storage, Timestamp,
+        },
         std::sync::Arc,
         test_case::test_case,
         wasmer::{
@@ -309,7 +320,9 @@ mod test {
         // Compile the contract; create Wasmer store and instance.
         let (store, instance) = {
             let mut compiler = Singlepass::new();
-            compiler.push_middleware(Arc::new(Metering::new(0, |_| GAS_PER_OPERATION)));
+            compiler.push_middleware(Arc::new(Metering::new(0, |_| {
+                GasCosts::DEFAULT.wasm_operation
+            })));
 
             let engine = Engine::from(compiler);
             let module = Module::new(&engine, wat).unwrap();
@@ -528,14 +541,16 @@ mod test {
     fn external_gas_consumption() {
         let (mut env, mut store, _instance) = setup_test(MOCK_WAT, Some(100));
 
-        env.consume_external_gas(&mut store, 10, "comment").unwrap();
+        env.consume_external_gas(&mut store, 10, GasCategory::Crypto, "comment")
+            .unwrap();
         assert_eq!(env.gas_tracker.remaining(), Some(90));
 
-        env.consume_external_gas(&mut store, 90, "comment").unwrap();
+        env.consume_external_gas(&mut store, 90, GasCategory::Crypto, "comment")
+            .unwrap();
         assert_eq!(env.gas_tracker.remaining(), Some(0));
 
         let err = env
-            .consume_external_gas(&mut store, 1, "comment")
+            .consume_external_gas(&mut store, 1, GasCategory::Crypto, "comment")
             .unwrap_err();
 
         assert!(matches!(
@@ -1,7 +1,7 @@
 use {
     grug_app::AppError,
     grug_types::StdError,
-    std::string::FromUtf8Error,
+    std::{io, string::FromUtf8Error},
     thiserror::Error,
     wasmer::{CompileError, ExportError, InstantiationError, MemoryAccessError, RuntimeError},
 };
@@ -23,12 +23,18 @@ pub enum VmError {
     #[error(transparent)]
     Runtime(#[from] RuntimeError),
 
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
     // The wasmer `CompileError` and `InstantiateError` are big (56 and 128 bytes,
     // respectively). We get a clippy warning if we wrap them directly here in
     // VmError (result_large_err). To avoid this, we cast them to strings instead.
     #[error("failed to instantiate Wasm module: {0}")]
     Instantiation(String),
 
+    #[error("failed to serialize Wasm module: {0}")]
+    Serialization(String),
+
     #[error("Wasmer memory not set in Environment")]
     WasmerMemoryNotSet,
 
@@ -1,7 +1,8 @@
 use {
     crate::{read_from_memory, write_to_memory, Environment, Iterator, VmError, VmResult},
-    grug_app::GAS_COSTS,
-    grug_types::{decode_sections, Addr, BorshDeExt, BorshSerExt, Query, Record, Storage},
+    grug_types::{
+        decode_sections, Addr, BorshDeExt, BorshSerExt, GasCategory, Query, Record, Storage,
+    },
     tracing::info,
     wasmer::FunctionEnvMut,
 };
@@ -15,13 +16,19 @@ pub fn db_read(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<u3
         Some(value) => {
             env.consume_external_gas(
                 &mut store,
-                GAS_COSTS.db_read.cost(value.len()),
+                env.gas_tracker.costs().db_read.cost(value.len()),
+                GasCategory::StorageRead,
                 "db_read/found",
             )?;
             write_to_memory(env, &mut store, &value)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_read.cost(0), "db_read/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_read.cost(0),
+                GasCategory::StorageRead,
+                "db_read/not_found",
+            )?;
             // If the record doesn't exist, return a zero pointer.
             Ok(0)
         },
@@ -50,7 +57,12 @@ pub fn db_scan(
     let order = order.try_into()?;
     let iterator = Iterator::new(min, max, order);
 
-    env.consume_external_gas(&mut store, GAS_COSTS.db_scan, "db_scan")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().db_scan,
+        GasCategory::StorageRead,
+        "db_scan",
+    )?;
 
     Ok(env.add_iterator(iterator))
 }
@@ -60,16 +72,25 @@ pub fn db_next(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> VmResul
 
     match env.advance_iterator(iterator_id)? {
         Some((key, value)) => {
+            let costs = env.gas_tracker.costs();
+            let gas_cost = costs.db_next + costs.db_read.cost(key.len() + value.len());
+
             env.consume_external_gas(
                 &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(key.len() + value.len()),
+                gas_cost,
+                GasCategory::StorageRead,
                 "db_next/found",
             )?;
 
             write_to_memory(env, &mut store, &encode_record((key, value)))
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                GasCategory::StorageRead,
+                "db_next/not_found",
+            )?;
 
             Ok(0)
         },
@@ -81,16 +102,25 @@ pub fn db_next_key(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> VmR
 
     match env.advance_iterator(iterator_id)? {
         Some((key, _)) => {
+            let costs = env.gas_tracker.costs();
+            let gas_cost = costs.db_next + costs.db_read.cost(key.len());
+
             env.consume_external_gas(
                 &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(key.len()),
+                gas_cost,
+                GasCategory::StorageRead,
                 "db_next_key/found",
             )?;
 
             write_to_memory(env, &mut store, &key)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next_key/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                GasCategory::StorageRead,
+                "db_next_key/not_found",
+            )?;
 
             Ok(0)
         },
@@ -102,16 +132,25 @@ pub fn db_next_value(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> V
 
     match env.advance_iterator(iterator_id)? {
         Some((_, value)) => {
+            let costs = env.gas_tracker.costs();
+            let gas_cost = costs.db_next + costs.db_read.cost(value.len());
+
             env.consume_external_gas(
                 &mut store,
-                GAS_COSTS.db_next + GAS_COSTS.db_read.cost(value.len()),
+                gas_cost,
+                GasCategory::StorageRead,
                 "db_next_value/found",
             )?;
 
             write_to_memory(env, &mut store, &value)
         },
         None => {
-            env.consume_external_gas(&mut store, GAS_COSTS.db_next, "db_next_value/not_found")?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().db_next,
+                GasCategory::StorageRead,
+                "db_next_value/not_found",
+            )?;
 
             Ok(0)
         },
@@ -133,11 +172,13 @@ pub fn db_write(mut fe: FunctionEnvMut<Environment>, key_ptr: u32, value_ptr: u3
     let key = read_from_memory(env, &store, key_ptr)?;
     let value = read_from_memory(env, &store, value_ptr)?;
 
-    let gas_cost = GAS_COSTS
+    let gas_cost = env
+        .gas_tracker
+        .costs()
         .db_write
         .cost(env.storage.namespace().len() + key.len() + value.len());
 
-    env.consume_external_gas(&mut store, gas_cost, "db_write")?;
+    env.consume_external_gas(&mut store, gas_cost, GasCategory::StorageWrite, "db_write")?;
 
     env.storage.write(&key, &value);
 
@@ -177,7 +218,12 @@ pub fn db_remove(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<
 
     env.storage.remove(&key);
     env.clear_iterators();
-    env.consume_external_gas(&mut store, GAS_COSTS.db_remove, "storage_remove")
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().db_remove,
+        GasCategory::StorageWrite,
+        "storage_remove",
+    )
 }
 
 pub fn db_remove_range(
@@ -204,7 +250,12 @@ pub fn db_remove_range(
 
     env.storage.remove_range(min.as_deref(), max.as_deref());
     env.clear_iterators();
-    env.consume_external_gas(&mut store, GAS_COSTS.db_remove, "storage_remove_range")
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().db_remove,
+        GasCategory::StorageWrite,
+        "storage_remove_range",
+    )
 }
 
 pub fn debug(mut fe: FunctionEnvMut<Environment>, addr_ptr: u32, msg_ptr: u32) -> VmResult<()> {
@@ -250,7 +301,12 @@ pub fn secp256k1_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.secp256k1_verify, "secp256k1_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().secp256k1_verify,
+        GasCategory::Crypto,
+        "secp256k1_verify",
+    )?;
 
     match grug_crypto::secp256k1_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -270,7 +326,12 @@ pub fn secp256r1_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.secp256k1_verify, "secp256r1_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().secp256k1_verify,
+        GasCategory::Crypto,
+        "secp256r1_verify",
+    )?;
 
     match grug_crypto::secp256r1_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -298,7 +359,8 @@ pub fn secp256k1_pubkey_recover(
 
     env.consume_external_gas(
         &mut store,
-        GAS_COSTS.secp256k1_pubkey_recover,
+        env.gas_tracker.costs().secp256k1_pubkey_recover,
+        GasCategory::Crypto,
         "secp256k1_pubkey_recover",
     )?;
 
@@ -316,6 +378,31 @@ pub fn secp256k1_pubkey_recover(
     Ok((error_code as u64) << 32 | (ptr as u64))
 }
 
+pub fn secp256k1_schnorr_verify(
+    mut fe: FunctionEnvMut<Environment>,
+    msg_hash_ptr: u32,
+    sig_ptr: u32,
+    pk_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let msg_hash = read_from_memory(env, &store, msg_hash_ptr)?;
+    let sig = read_from_memory(env, &store, sig_ptr)?;
+    let pk = read_from_memory(env, &store, pk_ptr)?;
+
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().secp256k1_schnorr_verify,
+        GasCategory::Crypto,
+        "secp256k1_schnorr_verify",
+    )?;
+
+    match grug_crypto::secp256k1_schnorr_verify(&msg_hash, &sig, &pk) {
+        Ok(()) => Ok(0),
+        Err(err) => Ok(err.into_error_code()),
+    }
+}
+
 pub fn ed25519_verify(
     mut fe: FunctionEnvMut<Environment>,
     msg_hash_ptr: u32,
@@ -328,7 +415,12 @@ pub fn ed25519_verify(
     let sig = read_from_memory(env, &store, sig_ptr)?;
     let pk = read_from_memory(env, &store, pk_ptr)?;
 
-    env.consume_external_gas(&mut store, GAS_COSTS.ed25519_verify, "ed25519_verify")?;
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().ed25519_verify,
+        GasCategory::Crypto,
+        "ed25519_verify",
+    )?;
 
     match grug_crypto::ed25519_verify(&msg_hash, &sig, &pk) {
         Ok(()) => Ok(0),
@@ -336,6 +428,31 @@ pub fn ed25519_verify(
     }
 }
 
+pub fn ed25519_pure_verify(
+    mut fe: FunctionEnvMut<Environment>,
+    msg_ptr: u32,
+    sig_ptr: u32,
+    pk_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let msg = read_from_memory(env, &store, msg_ptr)?;
+    let sig = read_from_memory(env, &store, sig_ptr)?;
+    let pk = read_from_memory(env, &store, pk_ptr)?;
+
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker.costs().ed25519_pure_verify.cost(msg.len()),
+        GasCategory::Crypto,
+        "ed25519_pure_verify",
+    )?;
+
+    match grug_crypto::ed25519_pure_verify(&msg, &sig, &pk) {
+        Ok(()) => Ok(0),
+        Err(err) => Ok(err.into_error_code()),
+    }
+}
+
 pub fn ed25519_batch_verify(
     mut fe: FunctionEnvMut<Environment>,
     prehash_msgs_ptr: u32,
@@ -354,7 +471,11 @@ pub fn ed25519_batch_verify(
 
     env.consume_external_gas(
         &mut store,
-        GAS_COSTS.ed25519_batch_verify.cost(prehash_msgs.len()),
+        env.gas_tracker
+            .costs()
+            .ed25519_batch_verify
+            .cost(prehash_msgs.len()),
+        GasCategory::Crypto,
         "ed25519_batch_verify",
     )?;
 
@@ -364,6 +485,103 @@ pub fn ed25519_batch_verify(
     }
 }
 
+pub fn bls12_381_verify(
+    mut fe: FunctionEnvMut<Environment>,
+    msg_ptr: u32,
+    dst_ptr: u32,
+    sig_ptr: u32,
+    pk_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let msg = read_from_memory(env, &store, msg_ptr)?;
+    let dst = read_from_memory(env, &store, dst_ptr)?;
+    let sig = read_from_memory(env, &store, sig_ptr)?;
+    let pk = read_from_memory(env, &store, pk_ptr)?;
+
+    // The message and domain separation tag are charged per byte, as they're
+    // hashed to a curve point.
+    let costs = env.gas_tracker.costs();
+    let gas = costs
+        .bls12_381_verify
+        .saturating_add(costs.bls12_381_hash_to_curve.cost(msg.len() + dst.len()));
+
+    env.consume_external_gas(&mut store, gas, GasCategory::Crypto, "bls12_381_verify")?;
+
+    match grug_crypto::bls12_381_verify(&msg, &dst, &sig, &pk) {
+        Ok(()) => Ok(0),
+        Err(err) => Ok(err.into_error_code()),
+    }
+}
+
+pub fn bls12_381_aggregate_verify(
+    mut fe: FunctionEnvMut<Environment>,
+    msg_ptr: u32,
+    dst_ptr: u32,
+    sig_ptr: u32,
+    pks_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let msg = read_from_memory(env, &store, msg_ptr)?;
+    let dst = read_from_memory(env, &store, dst_ptr)?;
+    let sig = read_from_memory(env, &store, sig_ptr)?;
+    let pks = read_from_memory(env, &store, pks_ptr)?;
+
+    let pks = decode_sections(&pks);
+
+    let costs = env.gas_tracker.costs();
+    let gas = costs
+        .bls12_381_aggregate_verify
+        .cost(pks.len())
+        .saturating_add(costs.bls12_381_hash_to_curve.cost(msg.len() + dst.len()));
+
+    env.consume_external_gas(
+        &mut store,
+        gas,
+        GasCategory::Crypto,
+        "bls12_381_aggregate_verify",
+    )?;
+
+    match grug_crypto::bls12_381_aggregate_verify(&msg, &dst, &sig, &pks) {
+        Ok(()) => Ok(0),
+        Err(err) => Ok(err.into_error_code()),
+    }
+}
+
+pub fn bls12_381_pairing_equality(
+    mut fe: FunctionEnvMut<Environment>,
+    ps_ptr: u32,
+    qs_ptr: u32,
+    r_ptr: u32,
+    s_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let ps = read_from_memory(env, &store, ps_ptr)?;
+    let qs = read_from_memory(env, &store, qs_ptr)?;
+    let r = read_from_memory(env, &store, r_ptr)?;
+    let s = read_from_memory(env, &store, s_ptr)?;
+
+    let ps = decode_sections(&ps);
+    let qs = decode_sections(&qs);
+
+    env.consume_external_gas(
+        &mut store,
+        env.gas_tracker
+            .costs()
+            .bls12_381_pairing_equality
+            .cost(ps.len()),
+        GasCategory::Crypto,
+        "bls12_381_pairing_equality",
+    )?;
+
+    match grug_crypto::bls12_381_pairing_equality(&ps, &qs, &r, &s) {
+        Ok(()) => Ok(0),
+        Err(err) => Ok(err.into_error_code()),
+    }
+}
+
 macro_rules! impl_hash_method {
     ($hasher:ident, $name:literal) => {
         pub fn $hasher(mut fe: FunctionEnvMut<Environment>, data_ptr: u32) -> VmResult<u32> {
@@ -372,7 +590,12 @@ macro_rules! impl_hash_method {
             let data = read_from_memory(env, &store, data_ptr)?;
             let hash = grug_crypto::$hasher(&data);
 
-            env.consume_external_gas(&mut store, GAS_COSTS.$hasher.cost(data.len()), $name)?;
+            env.consume_external_gas(
+                &mut store,
+                env.gas_tracker.costs().$hasher.cost(data.len()),
+                GasCategory::Crypto,
+                $name,
+            )?;
 
             write_to_memory(env, &mut store, &hash)
         }
@@ -412,15 +635,14 @@ mod tests {
     use {
         crate::{
             db_read, db_remove, db_remove_range, db_scan, db_write, debug, read_from_memory,
-            write_to_memory, Environment, VmResult, WasmVm, GAS_PER_OPERATION,
-        },
-        grug_app::{
-            GasTracker, QuerierProviderImpl, Shared, StorageProvider, APP_CONFIG, GAS_COSTS,
+            write_to_memory, Environment, VmResult, WasmVm,
         },
+        grug_app::{GasTracker, QuerierProviderImpl, Shared, StorageProvider, APP_CONFIG},
         grug_crypto::{Identity256, Identity512},
         grug_types::{
-            encode_sections, json, Addr, BlockInfo, BorshDeExt, BorshSerExt, GenericResult,
-            Hash256, MockStorage, Order, Query, QueryResponse, ResultExt, Storage, Timestamp,
+            encode_sections, json, Addr, BlockInfo, BorshDeExt, BorshSerExt, GasCosts,
+            GenericResult, Hash256, MockStorage, Order, Query, QueryResponse, ResultExt, Storage,
+            Timestamp,
         },
         rand::rngs::OsRng,
         std::{fmt::Debug, sync::Arc},
@@ -479,7 +701,9 @@ mod tests {
         // Compile the contract; create Wasmer store and instance.
         let (mut store, instance) = {
             let mut compiler = Singlepass::new();
-            compiler.push_middleware(Arc::new(Metering::new(0, |_| GAS_PER_OPERATION)));
+            compiler.push_middleware(Arc::new(Metering::new(0, |_| {
+                GasCosts::DEFAULT.wasm_operation
+            })));
 
             let engine = Engine::from(compiler);
             let module = Module::new(&engine, TESTER_CONTRACT).unwrap();
@@ -489,31 +713,36 @@ mod tests {
             // Import functions are not used but need to be defined.
             let import_obj = imports! {
                 "env" => {
-                    "db_read"                  => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "db_scan"                  => Function::new_typed(&mut store, |_: u32, _: u32, _: i32|       -> u32 { 0 }),
-                    "db_next"                  => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "db_next_key"              => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "db_next_value"            => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "db_write"                 => Function::new_typed(&mut store, |_: u32, _: u32|                      {   }),
-                    "db_remove"                => Function::new_typed(&mut store, |_: u32|                              {   }),
-                    "db_remove_range"          => Function::new_typed(&mut store, |_: u32, _: u32|                      {   }),
-                    "secp256k1_verify"         => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|       -> u32 { 0 }),
-                    "secp256r1_verify"         => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|       -> u32 { 0 }),
-                    "secp256k1_pubkey_recover" => Function::new_typed(&mut store, |_: u32, _: u32, _: u8, _: u8| -> u64 { 0 }),
-                    "ed25519_verify"           => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|       -> u32 { 0 }),
-                    "ed25519_batch_verify"     => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|       -> u32 { 0 }),
-                    "sha2_256"                 => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "sha2_512"                 => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "sha2_512_truncated"       => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "sha3_256"                 => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "sha3_512"                 => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "sha3_512_truncated"       => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "keccak256"                => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "blake2s_256"              => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "blake2b_512"              => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "blake3"                   => Function::new_typed(&mut store, |_: u32|                       -> u32 { 0 }),
-                    "debug"                    => Function::new_typed(&mut store, |_: u32, _: u32|                      {   }),
-                    "query_chain"              => Function::new_typed(&mut store, |_: u32,|                      -> u32 { 0 }),
+                    "db_read"                    => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "db_scan"                    => Function::new_typed(&mut store, |_: u32, _: u32, _: i32|         -> u32 { 0 }),
+                    "db_next"                    => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "db_next_key"                => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "db_next_value"              => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "db_write"                   => Function::new_typed(&mut store, |_: u32, _: u32|                        {   }),
+                    "db_remove"                  => Function::new_typed(&mut store, |_: u32|                                {   }),
+                    "db_remove_range"            => Function::new_typed(&mut store, |_: u32, _: u32|                        {   }),
+                    "secp256k1_verify"           => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "secp256r1_verify"           => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "secp256k1_pubkey_recover"   => Function::new_typed(&mut store, |_: u32, _: u32, _: u8, _: u8|   -> u64 { 0 }),
+                    "secp256k1_schnorr_verify"   => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "ed25519_verify"             => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "ed25519_pure_verify"        => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "ed25519_batch_verify"       => Function::new_typed(&mut store, |_: u32, _: u32, _: u32|         -> u32 { 0 }),
+                    "bls12_381_verify"           => Function::new_typed(&mut store, |_: u32, _: u32, _: u32, _: u32| -> u32 { 0 }),
+                    "bls12_381_aggregate_verify" => Function::new_typed(&mut store, |_: u32, _: u32, _: u32, _: u32| -> u32 { 0 }),
+                    "bls12_381_pairing_equality" => Function::new_typed(&mut store, |_: u32, _: u32, _: u32, _: u32| -> u32 { 0 }),
+                    "sha2_256"                   => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "sha2_512"                   => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "sha2_512_truncated"         => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "sha3_256"                   => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "sha3_512"                   => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "sha3_512_truncated"         => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "keccak256"                  => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "blake2s_256"                => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "blake2b_512"                => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "blake3"                     => Function::new_typed(&mut store, |_: u32|                         -> u32 { 0 }),
+                    "debug"                      => Function::new_typed(&mut store, |_: u32, _: u32|                        {   }),
+                    "query_chain"                => Function::new_typed(&mut store, |_: u32,|                        -> u32 { 0 }),
                 },
             };
 
@@ -756,7 +985,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        let cost = GAS_COSTS
+        let cost = GasCosts::DEFAULT
             .db_write
             .cost(NAMESPACE_CONTRACT.len() + k.len() + v.len());
 
@@ -787,7 +1016,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        assert_eq!(gas_consumed, GAS_COSTS.db_remove);
+        assert_eq!(gas_consumed, GasCosts::DEFAULT.db_remove);
     }
 
     // ---------------------------- db_remove_range ----------------------------
@@ -849,7 +1078,7 @@ mod tests {
 
         let gas_consumed = suite.env_mut().gas_tracker.used() - gas_pre;
 
-        assert_eq!(gas_consumed, GAS_COSTS.db_remove);
+        assert_eq!(gas_consumed, GasCosts::DEFAULT.db_remove);
     }
 
     // -------------------------------- debug ----------------------------------
@@ -940,6 +1169,21 @@ mod tests {
         }
     }
 
+    fn generate_secp256k1_schnorr_verify_request() -> VerifyTest {
+        use k256::schnorr::SigningKey;
+
+        let sk = SigningKey::random(&mut OsRng);
+        let msg_hash = grug_crypto::sha2_256(MSG);
+        let sig = sk.sign_raw(&msg_hash, &[0; 32]).unwrap();
+
+        VerifyTest {
+            pk: sk.verifying_key().to_bytes().to_vec(),
+            sig: sig.to_bytes().to_vec(),
+            msg_hash: msg_hash.to_vec(),
+            wrong_msg: grug_crypto::sha2_256(WRONG_MSG).to_vec(),
+        }
+    }
+
     fn generate_ed25519_verify_request() -> VerifyTest {
         use ed25519_dalek::{DigestSigner, SigningKey, VerifyingKey};
 
@@ -956,6 +1200,21 @@ mod tests {
         }
     }
 
+    fn generate_ed25519_pure_verify_request() -> VerifyTest {
+        use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let vk = VerifyingKey::from(&sk);
+        let sig = sk.sign(MSG);
+
+        VerifyTest {
+            pk: vk.to_bytes().to_vec(),
+            sig: sig.to_bytes().to_vec(),
+            msg_hash: MSG.to_vec(),
+            wrong_msg: WRONG_MSG.to_vec(),
+        }
+    }
+
     #[test_case(
         crate::secp256k1_verify,
         generate_secp256k1_verify_request;
@@ -966,11 +1225,21 @@ mod tests {
         generate_secp256r1_verify_request;
         "secp256kr_verify"
     )]
+    #[test_case(
+        crate::secp256k1_schnorr_verify,
+        generate_secp256k1_schnorr_verify_request;
+        "secp256k1_schnorr_verify"
+    )]
     #[test_case(
         crate::ed25519_verify,
         generate_ed25519_verify_request;
         "ed25519_verify"
     )]
+    #[test_case(
+        crate::ed25519_pure_verify,
+        generate_ed25519_pure_verify_request;
+        "ed25519_pure_verify"
+    )]
     fn verify_works<V, G>(verify: V, generate: G)
     where
         V: Fn(FunctionEnvMut<Environment>, u32, u32, u32) -> VmResult<u32>,
@@ -1,4 +1,5 @@
 mod cache;
+mod disk_cache;
 mod environment;
 mod error;
 mod gatekeeper;
@@ -12,6 +13,6 @@ mod tunables;
 mod vm;
 
 pub use {
-    cache::*, environment::*, error::*, gatekeeper::*, imports::*, iterator::*, memory::*,
-    region::*, tunables::*, vm::*,
+    cache::*, disk_cache::*, environment::*, error::*, gatekeeper::*, imports::*, iterator::*,
+    memory::*, region::*, tunables::*, vm::*,
 };
@@ -1,14 +1,16 @@
 use {
     crate::{
-        blake2b_512, blake2s_256, blake3, db_next, db_next_key, db_next_value, db_read, db_remove,
-        db_remove_range, db_scan, db_write, debug, ed25519_batch_verify, ed25519_verify, keccak256,
-        query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify,
-        sha2_256, sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated,
-        write_to_memory, Cache, Environment, Gatekeeper, LimitingTunables, VmError, VmResult,
+        blake2b_512, blake2s_256, blake3, bls12_381_aggregate_verify, bls12_381_pairing_equality,
+        bls12_381_verify, db_next, db_next_key, db_next_value, db_read, db_remove, db_remove_range,
+        db_scan, db_write, debug, ed25519_batch_verify, ed25519_pure_verify, ed25519_verify,
+        keccak256, query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_schnorr_verify,
+        secp256k1_verify, secp256r1_verify, sha2_256, sha2_512, sha2_512_truncated, sha3_256,
+        sha3_512, sha3_512_truncated, write_to_memory, Cache, DiskCache, Environment, Gatekeeper,
+        LimitingTunables, Metrics, VmError, VmResult,
     },
     grug_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
-    grug_types::{BorshSerExt, Context, Hash256},
-    std::{num::NonZeroUsize, sync::Arc},
+    grug_types::{BorshSerExt, Context, GasCosts, Hash256, HashExt},
+    std::{collections::BTreeSet, num::NonZeroUsize, sync::Arc},
     wasmer::{
         imports, sys::BaseTunables, CompilerConfig, Engine, Function, FunctionEnv, Module,
         NativeEngineExt, Singlepass, Store, StoreMut, Target, WASM_PAGE_SIZE,
@@ -16,9 +18,6 @@ use {
     wasmer_middlewares::{metering::set_remaining_points, Metering},
 };
 
-/// Gas cost per Wasmer operation.
-pub const GAS_PER_OPERATION: u64 = 1;
-
 /// Maximum number of chained queries.
 ///
 /// E.g. contract A queries contract B; when handling this query, contract B
@@ -55,14 +54,33 @@ const _: () = assert!(MAX_MEMORY_PAGES < wasmer::WASM_MAX_PAGES);
 #[derive(Clone)]
 pub struct WasmVm {
     cache: Option<Cache>,
+    /// Hashes of the codes that are compiled without the cache.
+    uncached_codes: Arc<BTreeSet<Hash256>>,
 }
 
 impl WasmVm {
     pub fn new(cache_capacity: usize) -> Self {
         Self {
             cache: NonZeroUsize::new(cache_capacity).map(Cache::new),
+            uncached_codes: Arc::new(BTreeSet::new()),
         }
     }
+
+    /// Persist compiled modules in the given on-disk cache, such that they
+    /// don't need to be re-compiled after the node restarts.
+    ///
+    /// The on-disk cache backs the in-memory one, so it isn't used if the
+    /// in-memory cache is disabled, i.e. if the cache capacity is zero.
+    pub fn with_disk_cache(mut self, disk_cache: DiskCache) -> Self {
+        self.cache = self.cache.map(|cache| cache.with_disk_cache(disk_cache));
+        self
+    }
+
+    /// Return the statistics about the usage of the module cache. `None` if
+    /// the cache is disabled.
+    pub fn cache_metrics(&self) -> Option<Metrics> {
+        self.cache.as_ref().map(Cache::metrics)
+    }
 }
 
 impl Vm for WasmVm {
@@ -83,12 +101,24 @@ impl Vm for WasmVm {
             return Err(VmError::ExceedMaxQueryDepth);
         }
 
-        let (module, engine) = if let Some(cache) = &self.cache {
+        // The gas cost per operation is part of the chain config, and is
+        // compiled into the module by the metering middleware. A module built
+        // with a different cost can't be reused.
+        let gas_per_operation = gas_tracker.costs().wasm_operation;
+
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| !self.uncached_codes.contains(&code_hash));
+
+        let (module, engine) = if let Some(cache) = cache {
             // Attempt to fetch a pre-built Wasmer module from the cache.
             // If not found, build it and insert it into the cache.
-            cache.get_or_build_with(code_hash, || compile_wasmer(code))?
+            cache.get_or_build_with(code_hash, gas_per_operation, || {
+                compile_wasmer(code, gas_per_operation)
+            })?
         } else {
-            compile_wasmer(code)?
+            compile_wasmer(code, gas_per_operation)?
         };
 
         // Compute the amount of gas left for this call. This will be used as
@@ -120,31 +150,36 @@ impl Vm for WasmVm {
         );
         let import_obj = imports! {
             "env" => {
-                "db_read"                  => Function::new_typed_with_env(&mut store, &fe, db_read),
-                "db_scan"                  => Function::new_typed_with_env(&mut store, &fe, db_scan),
-                "db_next"                  => Function::new_typed_with_env(&mut store, &fe, db_next),
-                "db_next_key"              => Function::new_typed_with_env(&mut store, &fe, db_next_key),
-                "db_next_value"            => Function::new_typed_with_env(&mut store, &fe, db_next_value),
-                "db_write"                 => Function::new_typed_with_env(&mut store, &fe, db_write),
-                "db_remove"                => Function::new_typed_with_env(&mut store, &fe, db_remove),
-                "db_remove_range"          => Function::new_typed_with_env(&mut store, &fe, db_remove_range),
-                "secp256k1_verify"         => Function::new_typed_with_env(&mut store, &fe, secp256k1_verify),
-                "secp256r1_verify"         => Function::new_typed_with_env(&mut store, &fe, secp256r1_verify),
-                "secp256k1_pubkey_recover" => Function::new_typed_with_env(&mut store, &fe, secp256k1_pubkey_recover),
-                "ed25519_verify"           => Function::new_typed_with_env(&mut store, &fe, ed25519_verify),
-                "ed25519_batch_verify"     => Function::new_typed_with_env(&mut store, &fe, ed25519_batch_verify),
-                "sha2_256"                 => Function::new_typed_with_env(&mut store, &fe, sha2_256),
-                "sha2_512"                 => Function::new_typed_with_env(&mut store, &fe, sha2_512),
-                "sha2_512_truncated"       => Function::new_typed_with_env(&mut store, &fe, sha2_512_truncated),
-                "sha3_256"                 => Function::new_typed_with_env(&mut store, &fe, sha3_256),
-                "sha3_512"                 => Function::new_typed_with_env(&mut store, &fe, sha3_512),
-                "sha3_512_truncated"       => Function::new_typed_with_env(&mut store, &fe, sha3_512_truncated),
-                "keccak256"                => Function::new_typed_with_env(&mut store, &fe, keccak256),
-                "blake2s_256"              => Function::new_typed_with_env(&mut store, &fe, blake2s_256),
-                "blake2b_512"              => Function::new_typed_with_env(&mut store, &fe, blake2b_512),
-                "blake3"                   => Function::new_typed_with_env(&mut store, &fe, blake3),
-                "debug"                    => Function::new_typed_with_env(&mut store, &fe, debug),
-                "query_chain"              => Function::new_typed_with_env(&mut store, &fe, query_chain),
+                "db_read"                    => Function::new_typed_with_env(&mut store, &fe, db_read),
+                "db_scan"                    => Function::new_typed_with_env(&mut store, &fe, db_scan),
+                "db_next"                    => Function::new_typed_with_env(&mut store, &fe, db_next),
+                "db_next_key"                => Function::new_typed_with_env(&mut store, &fe, db_next_key),
+                "db_next_value"              => Function::new_typed_with_env(&mut store, &fe, db_next_value),
+                "db_write"                   => Function::new_typed_with_env(&mut store, &fe, db_write),
+                "db_remove"                  => Function::new_typed_with_env(&mut store, &fe, db_remove),
+                "db_remove_range"            => Function::new_typed_with_env(&mut store, &fe, db_remove_range),
+                "secp256k1_verify"           => Function::new_typed_with_env(&mut store, &fe, secp256k1_verify),
+                "secp256r1_verify"           => Function::new_typed_with_env(&mut store, &fe, secp256r1_verify),
+                "secp256k1_pubkey_recover"   => Function::new_typed_with_env(&mut store, &fe, secp256k1_pubkey_recover),
+                "secp256k1_schnorr_verify"   => Function::new_typed_with_env(&mut store, &fe, secp256k1_schnorr_verify),
+                "ed25519_verify"             => Function::new_typed_with_env(&mut store, &fe, ed25519_verify),
+                "ed25519_pure_verify"        => Function::new_typed_with_env(&mut store, &fe, ed25519_pure_verify),
+                "ed25519_batch_verify"       => Function::new_typed_with_env(&mut store, &fe, ed25519_batch_verify),
+                "bls12_381_verify"           => Function::new_typed_with_env(&mut store, &fe, bls12_381_verify),
+                "bls12_381_aggregate_verify" => Function::new_typed_with_env(&mut store, &fe, bls12_381_aggregate_verify),
+                "bls12_381_pairing_equality" => Function::new_typed_with_env(&mut store, &fe, bls12_381_pairing_equality),
+                "sha2_256"                   => Function::new_typed_with_env(&mut store, &fe, sha2_256),
+                "sha2_512"                   => Function::new_typed_with_env(&mut store, &fe, sha2_512),
+                "sha2_512_truncated"         => Function::new_typed_with_env(&mut store, &fe, sha2_512_truncated),
+                "sha3_256"                   => Function::new_typed_with_env(&mut store, &fe, sha3_256),
+                "sha3_512"                   => Function::new_typed_with_env(&mut store, &fe, sha3_512),
+                "sha3_512_truncated"         => Function::new_typed_with_env(&mut store, &fe, sha3_512_truncated),
+                "keccak256"                  => Function::new_typed_with_env(&mut store, &fe, keccak256),
+                "blake2s_256"                => Function::new_typed_with_env(&mut store, &fe, blake2s_256),
+                "blake2b_512"                => Function::new_typed_with_env(&mut store, &fe, blake2b_512),
+                "blake3"                     => Function::new_typed_with_env(&mut store, &fe, blake3),
+                "debug"                      => Function::new_typed_with_env(&mut store, &fe, debug),
+                "query_chain"                => Function::new_typed_with_env(&mut store, &fe, query_chain),
             }
         };
 
@@ -166,9 +201,37 @@ impl Vm for WasmVm {
             fe,
         })
     }
+
+    fn warm_up(&mut self, code: &[u8], code_hash: Hash256, gas_costs: &GasCosts) -> VmResult<()> {
+        // Compile the module and insert it into the cache. Without a cache,
+        // there's nothing to be done ahead of time.
+        if let Some(cache) = &self.cache {
+            let gas_per_operation = gas_costs.wasm_operation;
+
+            cache.get_or_build_with(code_hash, gas_per_operation, || {
+                compile_wasmer(code, gas_per_operation)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn without_caching(mut self, code_hashes: BTreeSet<Hash256>) -> Self {
+        self.uncached_codes = Arc::new(code_hashes);
+        self
+    }
 }
 
-fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
+fn compile_wasmer(code: &[u8], gas_per_operation: u64) -> VmResult<(Module, Engine)> {
+    let engine = build_engine(gas_per_operation);
+    let module = Module::new(&engine, code)?;
+
+    Ok((module, engine))
+}
+
+/// Build the engine to compile modules with, or to run them with once they're
+/// compiled.
+pub(crate) fn build_engine(gas_per_operation: u64) -> Engine {
     let mut compiler = Singlepass::new();
 
     // Set up the gas metering middleware.
@@ -182,7 +245,7 @@ fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
     //
     // Also, compiling the module doesn't cost gas, so setting the limit
     // to zero won't raise out of gas errors.
-    let metering = Metering::new(0, |_| GAS_PER_OPERATION);
+    let metering = Metering::new(0, move |_| gas_per_operation);
     compiler.push_middleware(Arc::new(metering));
 
     // Set up the `Gatekeeper`. This rejects certain Wasm operators that
@@ -199,9 +262,25 @@ fn compile_wasmer(code: &[u8]) -> VmResult<(Module, Engine)> {
     let tunables = LimitingTunables::new(base, MAX_MEMORY_PAGES);
     engine.set_tunables(tunables);
 
-    let module = Module::new(&engine, code)?;
+    engine
+}
 
-    Ok((module, engine))
+/// Return a hash that identifies the configuration of the engine built by
+/// [`build_engine`] with the given gas cost per operation.
+///
+/// Modules compiled by engines of different configurations must not be used
+/// interchangeably, as they are instrumented differently. Therefore, anything
+/// that affects how a module is compiled should be included here.
+pub(crate) fn engine_fingerprint(gas_per_operation: u64) -> Hash256 {
+    format!(
+        "wasmer={};target={};metering={};gatekeeper={:?};canonicalize_nans=true;max_memory_pages={}",
+        wasmer::VERSION,
+        Target::default().triple(),
+        gas_per_operation,
+        Gatekeeper::default(),
+        MAX_MEMORY_PAGES,
+    )
+    .hash256()
 }
 
 // --------------------------------- instance ----------------------------------
@@ -7,10 +7,10 @@ use {
         QueryRecoverSecp256k1Request, QueryVerifyEd25519BatchRequest, QueryVerifyEd25519Request,
         QueryVerifySecp256k1Request, QueryVerifySecp256r1Request,
     },
-    grug_testing::{TestAccounts, TestBuilder, TestSuite},
+    grug_testing::{TestAccount, TestAccounts, TestBuilder, TestSuite},
     grug_types::{
-        Addr, Binary, Coins, Denom, GenericResult, Message, QueryRequest, ResultExt,
-        VerificationError,
+        Addr, Binary, Coins, Denom, GasProfile, GenericResult, Message, NonEmpty, QueryRequest,
+        ResultExt, Signer, VerificationError,
     },
     grug_vm_wasm::{VmError, WasmVm},
     rand::rngs::OsRng,
@@ -146,6 +146,141 @@ fn message_stack_overflow() {
         .should_fail_with_error(AppError::ExceedMaxMessageDepth);
 }
 
+// -------------------------------- gas profile --------------------------------
+
+/// Profile a transaction sending a single message, and check that the profile
+/// accounts for all the gas used, whether the transaction succeeds or not.
+fn profile_message(
+    suite: &TestSuite<MemDb, WasmVm>,
+    sender: &TestAccount,
+    msg: Message,
+) -> GasProfile {
+    let unsigned_tx = sender
+        .unsigned_transaction(NonEmpty::new_unchecked(vec![msg]), &suite.chain_id)
+        .unwrap();
+
+    let (outcome, profile) = suite.profile_tx(unsigned_tx);
+
+    assert_eq!(profile.total(), outcome.gas_used);
+
+    profile
+}
+
+/// Find the call to the given entry point among the children of a call.
+fn find_call<'a>(call: &'a GasProfile, name: &str) -> &'a GasProfile {
+    call.children
+        .iter()
+        .find(|child| child.name == name)
+        .unwrap_or_else(|| panic!("call `{name}` not found in `{}`", call.name))
+}
+
+#[test]
+fn profiling_gas_by_category() {
+    let (suite, accounts, _) = setup_test();
+
+    let profile = profile_message(
+        &suite,
+        &accounts["sender"],
+        Message::transfer(
+            accounts["owner"].address,
+            Coins::one(DENOM.clone(), 1).unwrap(),
+        )
+        .unwrap(),
+    );
+
+    // The account hashes the sign bytes, loads its public key, and increments
+    // its sequence.
+    let authenticate = find_call(&profile, "authenticate");
+    assert!(authenticate.gas.wasm > 0);
+    assert!(authenticate.gas.storage_read > 0);
+    assert!(authenticate.gas.storage_write > 0);
+    assert!(authenticate.gas.crypto > 0);
+
+    // The bank moves the coins without any crypto operation.
+    let bank_execute = find_call(&profile, "bank_execute");
+    assert!(bank_execute.gas.wasm > 0);
+    assert!(bank_execute.gas.storage_read > 0);
+    assert!(bank_execute.gas.storage_write > 0);
+    assert_eq!(bank_execute.gas.crypto, 0);
+
+    // The receiver is notified of the transfer.
+    let receive = find_call(&profile, "receive");
+    assert_eq!(receive.contract, Some(accounts["owner"].address));
+    assert!(receive.gas.wasm > 0);
+    assert_eq!(receive.gas.crypto, 0);
+}
+
+#[test]
+fn profiling_gas_across_submessages() {
+    let (suite, accounts, tester) = setup_test();
+
+    // The tester executes itself through submessages until the max message
+    // depth is reached.
+    let profile = profile_message(
+        &suite,
+        &accounts["sender"],
+        Message::execute(
+            tester,
+            &grug_tester::ExecuteMsg::StackOverflow {},
+            Coins::new(),
+        )
+        .unwrap(),
+    );
+
+    let mut call = find_call(&profile, "execute");
+    let mut depth = 0;
+
+    assert_eq!(call.msg_depth, 0);
+
+    // Each submessage is attributed its own Wasm gas, nested under the call
+    // that emitted it.
+    while let Some(child) = call.children.iter().find(|child| child.name == "execute") {
+        assert_eq!(child.contract, Some(tester));
+        assert_eq!(child.msg_depth, call.msg_depth + 1);
+        assert_eq!(child.query_depth, 0);
+        assert!(child.gas.wasm > 0);
+        assert_eq!(child.gas.crypto, 0);
+
+        call = child;
+        depth += 1;
+    }
+
+    assert!(depth > 1);
+}
+
+#[test]
+fn profiling_gas_across_queries() {
+    let (suite, accounts, tester) = setup_test();
+
+    // The tester queries itself while executing. The query fails, as it
+    // attempts to write to the storage.
+    let profile = profile_message(
+        &suite,
+        &accounts["sender"],
+        Message::execute(
+            tester,
+            &grug_tester::ExecuteMsg::ForceWriteOnQuery {
+                key: "larry".to_string(),
+                value: "engineer".to_string(),
+            },
+            Coins::new(),
+        )
+        .unwrap(),
+    );
+
+    let execute = find_call(&profile, "execute");
+    assert!(execute.gas.wasm > 0);
+
+    // The query is attributed its own Wasm gas, at the same message depth as
+    // the call that made it. It doesn't get to write anything.
+    let query = find_call(execute, "query");
+    assert_eq!(query.contract, Some(tester));
+    assert_eq!(query.msg_depth, 0);
+    assert_eq!(query.query_depth, 1);
+    assert!(query.gas.wasm > 0);
+    assert_eq!(query.gas.storage_write, 0);
+}
+
 // ------------------------------- crypto tests --------------------------------
 
 const MSG: &[u8] = b"finger but hole";